@@ -37,8 +37,8 @@ target/release/storagext-cli --sr25519-key "$PROVIDER" market add-balance 250000
 wait
 
 target/release/storagext-cli --sr25519-key "//Charlie" storage-provider register "peer_id"
-target/release/storagext-cli --sr25519-key "//Charlie" proofs set-porep-verifying-key @2KiB.porep.vk.scale
-target/release/storagext-cli --sr25519-key "//Charlie" proofs set-post-verifying-key @2KiB.post.vk.scale
+target/release/storagext-cli --sr25519-key "//Alice" proofs set-porep-verifying-key @2KiB.porep.vk.scale
+target/release/storagext-cli --sr25519-key "//Alice" proofs set-post-verifying-key @2KiB.post.vk.scale
 
 DEAL_JSON=$(
     jq -n \
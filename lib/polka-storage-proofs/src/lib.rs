@@ -14,6 +14,9 @@ pub mod post;
 #[cfg(feature = "std")]
 pub mod porep;
 
+#[cfg(feature = "std")]
+pub mod update;
+
 #[cfg(feature = "std")]
 pub mod zero_reader;
 #[cfg(feature = "std")]
@@ -8,7 +8,9 @@ use blstrs::Bls12;
 use filecoin_proofs::{DefaultPieceHasher, SectorShapeBase};
 use primitives::proofs::RegisteredSealProof;
 use rand::rngs::OsRng;
-use storage_proofs_core::{compound_proof::CompoundProof, proof::ProofScheme};
+use storage_proofs_core::{
+    api_version::ApiFeature, compound_proof::CompoundProof, proof::ProofScheme,
+};
 use storage_proofs_porep::stacked::StackedDrg;
 
 use crate::types::Commitment;
@@ -54,21 +56,51 @@ pub enum PoRepError {
     EmptySector,
     #[error("failed to load groth16 parameters from path: {0}, because {1}")]
     FailedToLoadGrothParameters(std::path::PathBuf, std::io::Error),
+    #[error("failed to convert proof: {0}")]
+    ProofConversion(#[from] crate::FromBytesError),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
 }
 
-fn seal_to_config(seal_proof: RegisteredSealProof) -> filecoin_proofs::PoRepConfig {
-    match seal_proof {
-        RegisteredSealProof::StackedDRG2KiBV1P1 => {
-            // https://github.com/filecoin-project/rust-filecoin-proofs-api/blob/b44e7cecf2a120aa266b6886628e869ba67252af/src/registry.rs#L308
-            let sector_size = 1 << 11;
-            let porep_id = [0u8; 32];
-            let api_version = storage_proofs_core::api_version::ApiVersion::V1_2_0;
+/// Builds the `rust-fil-proofs` configuration of the seal proof.
+///
+/// All of the supported sector sizes, 2KiB, 8MiB and 512MiB, share the [`SectorShapeBase`] tree shape.
+///
+/// References:
+/// * <https://github.com/filecoin-project/rust-filecoin-proofs-api/blob/b44e7cecf2a120aa266b6886628e869ba67252af/src/registry.rs#L308>
+pub(crate) fn seal_to_config(seal_proof: RegisteredSealProof) -> filecoin_proofs::PoRepConfig {
+    let sector_size = seal_proof.sector_size().bytes();
+    // Must match the PoRep ID used by the verifier in `pallet-proofs`.
+    let mut porep_id = [0u8; 32];
+    porep_id[0..8].copy_from_slice(&proof_id(seal_proof).to_le_bytes());
+    let api_version = storage_proofs_core::api_version::ApiVersion::V1_2_0;
 
-            filecoin_proofs::PoRepConfig::new_groth16(sector_size, porep_id, api_version)
-        }
+    if seal_proof.is_non_interactive() {
+        filecoin_proofs::PoRepConfig::new_groth16_with_features(
+            sector_size,
+            porep_id,
+            api_version,
+            vec![ApiFeature::NonInteractivePoRep],
+        )
+        .expect("non-interactive PoRep to be a valid feature for all of the supported sector sizes")
+    } else {
+        filecoin_proofs::PoRepConfig::new_groth16(sector_size, porep_id, api_version)
+    }
+}
+
+/// Registered proof ID of the seal proof, it's the first part of the PoRep ID.
+///
+/// Reference:
+/// * <https://github.com/filecoin-project/rust-filecoin-proofs-api/blob/b44e7cecf2a120aa266b6886628e869ba67252af/src/registry.rs#L52>
+fn proof_id(seal_proof: RegisteredSealProof) -> u64 {
+    match seal_proof {
+        RegisteredSealProof::StackedDRG2KiBV1P1 => 0,
+        RegisteredSealProof::StackedDRG8MiBV1P1 => 1,
+        RegisteredSealProof::StackedDRG512MiBV1P1 => 2,
+        RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep => 15,
+        RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep => 16,
+        RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => 17,
     }
 }
@@ -16,7 +16,7 @@ use primitives::{
     proofs::RegisteredSealProof,
     sector::SectorNumber,
 };
-use storage_proofs_core::{compound_proof, compound_proof::CompoundProof};
+use storage_proofs_core::{api_version::ApiFeature, compound_proof, compound_proof::CompoundProof};
 use storage_proofs_porep::stacked::{self, StackedCompound, StackedDrg};
 
 use super::{seal_to_config, PoRepError};
@@ -248,6 +248,7 @@ impl Sealer {
     /// - `sector_id` - id of a sector, must match between Proving and Verification.
     /// - `ticket` - randomness seed, must match between Proving and Verification.
     /// - `seed` - randomness seed, must match between Proving and Verification.
+    ///   Non-interactive seal proofs don't have a seed, it must be `None`.
     /// - `pre_commit` - CommR and CommD produced by `precommit_sector`.
     /// - `piece_infos` - list of pieces contained in the `replica_path`.
     ///
@@ -308,7 +309,11 @@ impl Sealer {
                 comm_r: comm_r_safe,
             }),
             k: None,
-            seed: Some(seed),
+            // Non-interactive challenges are derived from the replica instead.
+            seed: (!self
+                .porep_config
+                .feature_enabled(ApiFeature::NonInteractivePoRep))
+            .then_some(seed),
         };
 
         let compound_setup_params = compound_proof::SetupParams {
@@ -407,6 +412,25 @@ impl From<PreCommitOutput> for filecoin_proofs::SealPreCommitOutput {
     }
 }
 
+/// Batches the proofs of many sectors, generated by [`Sealer::prove_sector`],
+/// into a single proof to be submitted with a batched prove commit.
+///
+/// The sectors' proofs are placed one after another, in the given order,
+/// which must match the order of the sectors in the batched prove commit.
+pub fn batch_sector_proofs(sector_proofs: &[Vec<BlstrsProof>]) -> Result<Vec<u8>, PoRepError> {
+    let mut batch = Vec::new();
+    for proof in sector_proofs.iter().flatten() {
+        let proof = SubstrateProof::try_from(proof.clone())?;
+        let mut proof_bytes = [0u8; crate::PROOF_BYTES];
+        proof
+            .into_bytes(&mut proof_bytes)
+            .expect("buffer to fit a whole proof");
+        batch.extend_from_slice(&proof_bytes);
+    }
+
+    Ok(batch)
+}
+
 #[cfg(test)]
 mod test {
     use std::io::Cursor;
@@ -416,6 +440,7 @@ mod test {
     use rstest::rstest;
 
     use super::*;
+    use crate::PrimeCurveAffine;
 
     #[test]
     fn filler_pieces_for_sizes() {
@@ -475,6 +500,26 @@ mod test {
         assert_eq!(data_commd, pieces_commd)
     }
 
+    #[test]
+    fn batches_sector_proofs_in_order() {
+        let proof = |scalar: u64| BlstrsProof {
+            a: (blstrs::G1Affine::generator() * blstrs::Scalar::from(scalar)).into(),
+            b: (blstrs::G2Affine::generator() * blstrs::Scalar::from(scalar)).into(),
+            c: (blstrs::G1Affine::generator() * blstrs::Scalar::from(scalar + 1)).into(),
+        };
+        let sector_proofs = vec![vec![proof(1)], vec![proof(2)], vec![proof(3)]];
+
+        let batch = batch_sector_proofs(&sector_proofs).unwrap();
+
+        assert_eq!(batch.len(), 3 * crate::PROOF_BYTES);
+        for (chunk, proofs) in batch.chunks(crate::PROOF_BYTES).zip(sector_proofs) {
+            assert_eq!(
+                SubstrateProof::from_bytes(chunk).unwrap(),
+                SubstrateProof::try_from(proofs[0].clone()).unwrap()
+            );
+        }
+    }
+
     /// Generates a piece of `size` and a PieceInfo for it
     fn piece_with_random_data(size: PaddedBytesAmount) -> (Vec<u8>, PieceInfo) {
         let rng = &mut XorShiftRng::from_seed(filecoin_proofs::TEST_SEED);
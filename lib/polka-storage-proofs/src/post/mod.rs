@@ -55,7 +55,7 @@ pub struct ReplicaInfo {
 }
 
 /// Generates Windowed PoSt for a replica.
-/// Only supports 2KiB sectors.
+/// Supports the sector sizes sharing the [`SectorShapeBase`] tree shape, i.e. 2KiB, 8MiB and 512MiB.
 ///
 /// References:
 /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/api/window_post.rs#L100>
@@ -140,12 +140,14 @@ pub fn generate_window_post(
 /// * <https://github.com/filecoin-project/rust-filecoin-proofs-api/blob/b44e7cecf2a120aa266b6886628e869ba67252af/src/registry.rs#L644>
 fn seal_to_config(seal_proof: RegisteredPoStProof) -> filecoin_proofs::PoStConfig {
     match seal_proof {
-        RegisteredPoStProof::StackedDRGWindow2KiBV1P1 => {
+        RegisteredPoStProof::StackedDRGWindow2KiBV1P1
+        | RegisteredPoStProof::StackedDRGWindow8MiBV1P1
+        | RegisteredPoStProof::StackedDRGWindow512MiBV1P1 => {
             filecoin_proofs::PoStConfig {
                 sector_size: filecoin_proofs::SectorSize(seal_proof.sector_size().bytes()),
                 challenge_count: filecoin_proofs::WINDOW_POST_CHALLENGE_COUNT,
                 // https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/filecoin-proofs/src/constants.rs#L104
-                sector_count: 2,
+                sector_count: seal_proof.sector_count(),
                 typ: PoStType::Window,
                 priority: true,
                 api_version: storage_proofs_core::api_version::ApiVersion::V1_2_0,
@@ -0,0 +1,157 @@
+//! Replica update (a.k.a. SnapDeals), puts data into committed capacity sectors without sealing them again.
+//!
+//! This module should work only as a facade separating our codebase from `rust-fil-proofs`.
+
+use std::path::Path;
+
+use bellperson::groth16;
+use blstrs::Bls12;
+use filecoin_hashers::Domain;
+use filecoin_proofs::{
+    as_safe_commitment, DefaultPieceDomain, PoRepConfig, SectorShapeBase, SectorUpdateConfig,
+};
+use primitives::{
+    commitment::{piece::PieceInfo, CommD, CommR, Commitment},
+    proofs::RegisteredSealProof,
+};
+use rand::rngs::OsRng;
+use storage_proofs_core::{compound_proof, compound_proof::CompoundProof};
+use storage_proofs_update::{EmptySectorUpdateCompound, PublicInputs, PublicParams, SetupParams};
+
+use crate::porep::{seal_to_config, PoRepError};
+
+/// Generates parameters for proving and verifying replica updates.
+/// It should be called once and then reused across provers and the verifier.
+/// Verifying Key is only needed for verification (no_std), rest of the params are required for proving (std).
+pub fn generate_random_groth16_parameters(
+    seal_proof: RegisteredSealProof,
+) -> Result<groth16::Parameters<Bls12>, PoRepError> {
+    let config = SectorUpdateConfig::from_porep_config(&seal_to_config(seal_proof));
+    let public_params = PublicParams::from_sector_size(u64::from(config.sector_size));
+
+    let circuit = EmptySectorUpdateCompound::<SectorShapeBase>::blank_circuit(&public_params);
+
+    Ok(groth16::generate_random_parameters::<Bls12, _, _>(
+        circuit, &mut OsRng,
+    )?)
+}
+
+pub struct ReplicaUpdater {
+    porep_config: PoRepConfig,
+}
+
+impl ReplicaUpdater {
+    pub fn new(seal_proof: RegisteredSealProof) -> Self {
+        Self {
+            porep_config: seal_to_config(seal_proof),
+        }
+    }
+
+    /// Encodes the data contained in `staged_data` into the replica of a committed capacity sector,
+    /// the new replica is written into `new_replica`.
+    /// Outputs the new CommR and CommD.
+    ///
+    /// # Arguments
+    /// - `new_replica` - a path where the updated replica will be written.
+    /// - `new_cache` - cache directory of the updated replica.
+    /// - `sector_key` - the replica of the committed capacity sector, i.e. the sealed sector.
+    /// - `sector_key_cache` - cache directory of the committed capacity sector.
+    /// - `staged_data` - unsealed sector with all of the pieces, padded to the sector size.
+    /// - `piece_infos` - list of pieces contained in the `staged_data`.
+    ///
+    /// # References:
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/api/update.rs>
+    pub fn encode_into<P: AsRef<Path>>(
+        &self,
+        new_replica: P,
+        new_cache: P,
+        sector_key: P,
+        sector_key_cache: P,
+        staged_data: P,
+        piece_infos: &[PieceInfo],
+    ) -> Result<(Commitment<CommR>, Commitment<CommD>), PoRepError> {
+        let piece_infos = piece_infos
+            .iter()
+            .copied()
+            .map(filecoin_proofs::PieceInfo::from)
+            .collect::<Vec<_>>();
+
+        let encoded = filecoin_proofs::encode_into::<SectorShapeBase>(
+            &self.porep_config,
+            new_replica.as_ref(),
+            new_cache.as_ref(),
+            sector_key.as_ref(),
+            sector_key_cache.as_ref(),
+            staged_data.as_ref(),
+            &piece_infos,
+        )?;
+
+        Ok((
+            Commitment::from(encoded.comm_r_new),
+            Commitment::from(encoded.comm_d_new),
+        ))
+    }
+
+    /// Generates a zk-SNARK proof guaranteeing that `replica` is the committed capacity sector `sector_key`
+    /// updated with the data committed to by `comm_d_new`.
+    /// Produces a proof for each of the update partitions.
+    ///
+    /// # Arguments:
+    /// - `proving_parameters` - Groth16 params generated by [`generate_random_groth16_parameters`].
+    /// - `comm_r_old` - CommR of the committed capacity sector.
+    /// - `comm_r_new`, `comm_d_new` - commitments produced by [`ReplicaUpdater::encode_into`].
+    ///
+    /// # References:
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/api/update.rs>
+    pub fn prove<P: AsRef<Path>>(
+        &self,
+        proving_parameters: &groth16::MappedParameters<Bls12>,
+        comm_r_old: Commitment<CommR>,
+        comm_r_new: Commitment<CommR>,
+        comm_d_new: Commitment<CommD>,
+        sector_key: P,
+        sector_key_cache: P,
+        replica: P,
+        replica_cache: P,
+    ) -> Result<Vec<groth16::Proof<Bls12>>, PoRepError> {
+        let vanilla_proofs = filecoin_proofs::generate_partition_proofs::<SectorShapeBase>(
+            &self.porep_config,
+            comm_r_old.raw(),
+            comm_r_new.raw(),
+            comm_d_new.raw(),
+            sector_key.as_ref(),
+            sector_key_cache.as_ref(),
+            replica.as_ref(),
+            replica_cache.as_ref(),
+        )?;
+
+        let config = SectorUpdateConfig::from_porep_config(&self.porep_config);
+        let public_inputs = PublicInputs {
+            k: 0,
+            comm_r_old: as_safe_commitment(&comm_r_old.raw(), "comm_r_old")?,
+            comm_d_new: DefaultPieceDomain::try_from_bytes(&comm_d_new.raw())?,
+            comm_r_new: as_safe_commitment(&comm_r_new.raw(), "comm_r_new")?,
+            h: config.h,
+        };
+
+        let compound_setup_params = compound_proof::SetupParams {
+            vanilla_params: SetupParams {
+                sector_bytes: u64::from(config.sector_size),
+            },
+            partitions: Some(usize::from(config.update_partitions)),
+            priority: false,
+        };
+        let compound_public_params =
+            EmptySectorUpdateCompound::<SectorShapeBase>::setup(&compound_setup_params)?;
+
+        let groth_proofs = EmptySectorUpdateCompound::<SectorShapeBase>::circuit_proofs(
+            &public_inputs,
+            vanilla_proofs,
+            &compound_public_params.vanilla_params,
+            proving_parameters,
+            compound_public_params.priority,
+        )?;
+
+        Ok(groth_proofs)
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeSet;
 
 use cid::Cid;
 use maat::*;
-use primitives::sector::SectorSize;
+use primitives::{proofs::RegisteredSealProof, sector::SectorSize};
 use storagext::{
     clients::ProofsClientExt,
     runtime::runtime_types::{
@@ -98,7 +98,7 @@ where
     assert_eq!(balance_entry.locked, 0);
 }
 
-async fn set_porep_verifying_key<Keypair>(client: &storagext::Client, charlie: &Keypair)
+async fn set_porep_verifying_key<Keypair>(client: &storagext::Client, sudo: &Keypair)
 where
     Keypair: subxt::tx::Signer<PolkaStorageConfig>,
 {
@@ -109,7 +109,12 @@ where
     let default_porep_verifying_key = VerifyingKey::from_hex(default_porep_verifying_key).unwrap();
 
     let result = client
-        .set_porep_verifying_key(charlie, default_porep_verifying_key, true)
+        .set_porep_verifying_key(
+            sudo,
+            RegisteredSealProof::StackedDRG2KiBV1P1,
+            default_porep_verifying_key,
+            true,
+        )
         .await
         .unwrap()
         .unwrap();
@@ -119,7 +124,16 @@ where
         .find::<storagext::runtime::proofs::events::PoRepVerifyingKeyChanged>()
     {
         let event = event.unwrap();
-        assert_eq!(event.who, charlie.account_id().clone().into());
+        assert_eq!(event.seal_proof, RegisteredSealProof::StackedDRG2KiBV1P1);
+        assert_eq!(event.version, 1);
+
+        let key_info = client
+            .porep_verifying_key_info(RegisteredSealProof::StackedDRG2KiBV1P1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(key_info.hash, event.hash);
+        assert_eq!(key_info.version, event.version);
     }
 }
 
@@ -225,7 +239,7 @@ where
         Cid::try_from("bagboea4b5abcaqolcsygu5o756srf7l4pzzagml5r3wa3o6ahoo5vixummsev6rf").unwrap();
 
     let sectors_pre_commit_info = vec![SectorPreCommitInfo {
-        seal_proof: primitives::proofs::RegisteredSealProof::StackedDRG2KiBV1P1,
+        seal_proof: RegisteredSealProof::StackedDRG2KiBV1P1,
         sector_number: 1.into(),
         sealed_cid,
         deal_ids: vec![0],
@@ -416,7 +430,7 @@ async fn real_world_use_case() {
     let charlie_kp = pair_signer_from_str::<Sr25519Pair>("//Charlie");
 
     register_storage_provider(&client, &charlie_kp).await;
-    set_porep_verifying_key(&client, &charlie_kp).await;
+    set_porep_verifying_key(&client, &alice_kp).await;
 
     // Add balance to Charlie
     let balance = 12_500_000_000;
@@ -10,6 +10,7 @@
 pub use pallet::*;
 
 mod error;
+pub mod migrations;
 
 #[cfg(test)]
 mod mock;
@@ -28,8 +29,9 @@ pub mod pallet {
         pallet_prelude::*,
         sp_runtime::{
             traits::{AccountIdConversion, CheckedAdd, CheckedSub, Hash, IdentifyAccount, Verify},
-            ArithmeticError, BoundedBTreeMap, RuntimeDebug,
+            ArithmeticError, BoundedBTreeMap, Perbill, RuntimeDebug,
         },
+        storage,
         traits::{
             Currency,
             ExistenceRequirement::{AllowDeath, KeepAlive},
@@ -44,13 +46,16 @@ pub mod pallet {
             piece::{PaddedPieceSize, PieceInfo},
             CommP, Commitment, CommitmentError,
         },
-        pallets::{ActiveDeal, ActiveSector, Market, SectorDeal, StorageProviderValidation},
+        pallets::{
+            ActiveDeal, ActiveSector, DealCancellation, DealExtension, DealQuote, Market,
+            MarketBalance, SectorDeal, StorageProviderValidation,
+        },
         proofs::RegisteredSealProof,
         sector::{SectorNumber, SectorSize},
-        DealId, MAX_DEALS_PER_SECTOR, MAX_SECTORS_PER_CALL,
+        DealId, MAX_DEALS_PER_PAGE, MAX_DEALS_PER_SECTOR, MAX_SECTORS_PER_CALL,
     };
     use scale_info::TypeInfo;
-    use sp_arithmetic::traits::BaseArithmetic;
+    use sp_arithmetic::traits::{BaseArithmetic, One, Saturating, Zero};
     use sp_std::vec::Vec;
 
     use crate::error::*;
@@ -85,7 +90,10 @@ pub mod pallet {
         type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
 
         /// Storage Provider trait implementation for SP validation to validate that given account id's are registered as SP.
-        type StorageProviderValidation: StorageProviderValidation<Self::AccountId>;
+        type StorageProviderValidation: StorageProviderValidation<
+            Self::AccountId,
+            BlockNumberFor<Self>,
+        >;
 
         /// How many deals can be published in a single batch of `publish_storage_deals`.
         #[pallet::constant]
@@ -108,6 +116,23 @@ pub mod pallet {
         /// Having this number too big can affect block production.
         #[pallet::constant]
         type MaxDealsPerBlock: Get<u32>;
+
+        /// How many blocks pass between the automatic settlements of an active deal's payments.
+        /// Must be non-zero.
+        ///
+        /// Active deals are also settled at their `end_block`, when they're completed.
+        #[pallet::constant]
+        type DealSettlementInterval: Get<BlockNumberFor<Self>>;
+
+        /// How many deals can be scheduled to be settled at a given block. Maximum.
+        /// Those deals are settled by Hook::<T>::on_finalize, bounding the work done in a single block.
+        #[pallet::constant]
+        type MaxDealSettlementsPerBlock: Get<u32>;
+
+        /// Share of the provider collateral paid to the client,
+        /// when the provider cancels a published deal without the client's consent.
+        #[pallet::constant]
+        type DealCancellationFee: Get<Perbill>;
     }
 
     /// Stores balances info for both Storage Providers and Storage Users
@@ -294,7 +319,11 @@ pub mod pallet {
         pub client_signature: OffchainSignature,
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// [`BalanceTable`] is used to store balances for Storage Market Participants.
@@ -345,6 +374,18 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Stores the Active Deals whose payments are settled at each Block.
+    /// Deals are scheduled when activated, [`Config::DealSettlementInterval`] after their start,
+    /// and rescheduled by [`Hooks::on_finalize`] after each settlement, until they're completed at their `end_block`.
+    #[pallet::storage]
+    pub type DealSettlementsForBlock<T: Config> = StorageMap<
+        _,
+        _,
+        BlockNumberFor<T>,
+        BoundedBTreeSet<DealId, T::MaxDealSettlementsPerBlock>,
+        ValueQuery,
+    >;
+
     /// Holds a mapping from ([`Provider`] [`SectorNumber`]) to its respective [`DealId`]s.
     #[pallet::storage]
     pub type SectorDeals<T: Config> = StorageMap<
@@ -354,6 +395,19 @@ pub mod pallet {
         BoundedVec<DealId, ConstU32<MAX_DEALS_PER_SECTOR>>,
     >;
 
+    /// Indexes the deals stored in [`Proposals`] by their client.
+    /// Deals are indexed when published and removed from the index along with their proposal,
+    /// when they're completed, terminated, cancelled or expired (not activated in time).
+    #[pallet::storage]
+    pub type ClientDeals<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, DealId, ()>;
+
+    /// Indexes the deals stored in [`Proposals`] by their storage provider.
+    /// Maintained like [`ClientDeals`].
+    #[pallet::storage]
+    pub type ProviderDeals<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, DealId, ()>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -380,6 +434,13 @@ pub mod pallet {
             /// Deal IDs for those that were not successfully settled along with the respective error.
             unsuccessful: BoundedVec<(DealId, DealSettlementError), MaxSettleDeals<T>>,
         },
+        /// Deal payment was settled automatically by [`Hooks::on_finalize`].
+        DealPaymentSettled {
+            deal_id: DealId,
+            client: T::AccountId,
+            provider: T::AccountId,
+            amount: BalanceOf<T>,
+        },
         /// Deal was slashed.
         /// It means that the `provider_collateral` was burned and the entire client's lockup returned.
         ///
@@ -404,6 +465,24 @@ pub mod pallet {
             provider: T::AccountId,
         },
 
+        /// A published deal was cancelled by its storage provider, before being activated.
+        DealCancelled {
+            deal_id: DealId,
+            client: T::AccountId,
+            provider: T::AccountId,
+            /// Part of the provider collateral paid to the client, zero if the client consented to the cancellation.
+            fee: BalanceOf<T>,
+        },
+
+        /// An active deal was extended, with the client's agreement.
+        DealExtended {
+            deal_id: DealId,
+            client: T::AccountId,
+            provider: T::AccountId,
+            end_block: BlockNumberFor<T>,
+            storage_price_per_block: BalanceOf<T>,
+        },
+
         /// Batch of published deals.
         DealsPublished {
             provider: T::AccountId,
@@ -512,6 +591,16 @@ pub mod pallet {
         DealDurationOutOfBounds,
         /// Deal's piece_cid is invalid.
         InvalidPieceCid,
+        /// Deal can only be cancelled while it's [`DealState::Published`], before being activated.
+        DealNotCancellable,
+        /// Client's signature did not match the client's public key and the [`DealCancellation`].
+        WrongClientSignatureOnCancellation,
+        /// Client's signature did not match the client's public key and the [`DealExtension`].
+        WrongClientSignatureOnExtension,
+        /// Deal can only be extended to end after its current `end_block`.
+        DealEndNotExtended,
+        /// Deal has reached its `end_block`, it can't be extended anymore.
+        DealEnded,
     }
 
     /// Extrinsics exposed by the pallet
@@ -582,6 +671,9 @@ pub mod pallet {
         /// * The deal's last update is after the current block, meaning the deal's last update is in the future.
         ///   The returned error is [`DealSettlementError::FutureLastUpdate`].
         /// * The deal is not active
+        ///
+        /// Active deals are also settled automatically, every [`Config::DealSettlementInterval`] blocks and at their `end_block`,
+        /// this call allows settling them in between.
         pub fn settle_deal_payments(
             origin: OriginFor<T>,
             // The original `deals` structure is a bitfield from fvm-ipld-bitfield
@@ -597,112 +689,15 @@ pub mod pallet {
             let mut successful = BoundedVec::<_, MaxSettleDeals<T>>::new();
             let mut unsuccessful = BoundedVec::<_, MaxSettleDeals<T>>::new();
 
+            // SAFETY: Pushing always succeeds because the upper bound on the vecs should be the same as the input vec
             for deal_id in deal_ids {
-                // If the deal is not found, we register an error and move on
-                // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1225-L1231
-                let Some(mut deal_proposal) = Proposals::<T>::get(deal_id) else {
-                    log::error!(target: LOG_TARGET, "deal not found — deal_id: {}", deal_id);
-                    // SAFETY: Always succeeds because the upper bound on the vecs should be the same as the input vec
-                    let _ = unsuccessful.try_push((deal_id, DealSettlementError::DealNotFound));
-                    continue;
-                };
-
-                // Deal isn't possibly valid yet
-                // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1255-L1264
-                if deal_proposal.start_block > current_block {
-                    // SAFETY: Always succeeds because the upper bound on the vecs should be the same as the input vec
-                    let _ = unsuccessful.try_push((deal_id, DealSettlementError::EarlySettlement));
-                    continue;
-                }
-
-                // If the deal is not active (i.e. unpublished or published), there's nothing to settle
-                // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1225-L1231
-                let DealState::Active(ref mut active_deal_state) = deal_proposal.state else {
-                    // If a deal is not published, there's nothing to settle
-                    // If a deal is published, but not active, it's supposed to be removed by cron/hooks
-
-                    // NOTE(@jmg-duarte,28/06/2024): maybe we should handle deals where deal_proposal.start_block < current_block — i.e. expired
-
-                    // SAFETY: Always succeeds because the upper bound on the vecs should be the same as the input vec
-                    let _ = unsuccessful.try_push((deal_id, DealSettlementError::DealNotActive));
-                    continue;
-                };
-
-                // If the last updated block is in the future, return an error
-                if let Some(last_updated_block) = active_deal_state.last_updated_block {
-                    if last_updated_block > current_block {
-                        log::error!(target: LOG_TARGET,
-                            "last_updated_block for deal is in the future — deal_id: {}, last_updated_block: {:?}",
-                            deal_id,
-                            last_updated_block
-                        );
-                        // SAFETY: Always succeeds because the upper bound on the vecs should be the same as the input vec
-                        let _ =
-                            unsuccessful.try_push((deal_id, DealSettlementError::FutureLastUpdate));
-                        continue;
+                match Self::settle_deal(deal_id, current_block)? {
+                    Ok((settled, _)) => {
+                        let _ = successful.try_push(settled);
                     }
-                }
-
-                // If we never settled, the duration starts at `start_block`
-                let last_settled_block = active_deal_state
-                    .last_updated_block
-                    .unwrap_or(deal_proposal.start_block);
-
-                if last_settled_block > deal_proposal.end_block {
-                    // If the code reaches this, it's a big whoops
-                    log::error!(target: LOG_TARGET, "the last settled block cannot be bigger than the end block — last_settled_block: {:?}, end_block: {:?}",
-                        last_settled_block, deal_proposal.end_block);
-                    return Err(DispatchError::Corruption);
-                }
-
-                let (block_to_settle, complete_deal) = {
-                    if current_block >= deal_proposal.end_block {
-                        // The deal has been completed, as such, we'll remove it later on
-                        (deal_proposal.end_block, true)
-                    } else {
-                        (current_block, false)
+                    Err(e) => {
+                        let _ = unsuccessful.try_push((deal_id, e));
                     }
-                };
-
-                // If an error happens when converting here we have more to worry about than completing all settlements
-                let deal_settlement_amount: BalanceOf<T> = {
-                    // There's no great way to avoid the repeated code without macros or more generics magic
-                    // ArithmeticError::Overflow used as `duration` and `storage_price_per_block` can only be positive
-                    let duration: u128 = (block_to_settle - last_settled_block)
-                        .try_into()
-                        .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))?;
-                    let storage_price_per_block: u128 = deal_proposal
-                        .storage_price_per_block
-                        .try_into()
-                        .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))?;
-
-                    (duration * storage_price_per_block)
-                        .try_into()
-                        .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))
-                }?;
-
-                perform_storage_payment::<T>(
-                    &deal_proposal.client,
-                    &deal_proposal.provider,
-                    deal_settlement_amount,
-                )?;
-
-                // SAFETY: Always succeeds because the upper bound on the vecs should be the same as the input vec
-                let _ = successful.try_push(SettledDealData {
-                    deal_id,
-                    client: deal_proposal.client.clone(),
-                    provider: deal_proposal.provider.clone(),
-                    amount: deal_settlement_amount,
-                });
-
-                // NOTE(@jmg-duarte,28/06/2024): Maybe emit an event when the table is updated?
-                if complete_deal {
-                    unlock_funds::<T>(&deal_proposal.provider, deal_proposal.provider_collateral)?;
-                    Proposals::<T>::remove(deal_id);
-                } else {
-                    // Otherwise, we update the proposal — `last_updated_block`
-                    active_deal_state.last_updated_block = Some(current_block);
-                    Proposals::<T>::insert(deal_id, deal_proposal);
                 }
             }
 
@@ -718,6 +713,7 @@ pub mod pallet {
         /// It saves valid deals as [`DealState::Published`] and locks up client fees and provider's collaterals.
         /// Locked up balances cannot be withdrawn until a deal is terminated.
         /// All of the deals must belong to a single Storage Provider.
+        /// They can be published by the Storage Provider's owner, worker or control addresses.
         /// It is permissive, if some of the deals are correct and some are not, it emits events for valid deals.
         /// On success emits [`Event::<T>::DealPublished`] for each successful deal.
         pub fn publish_storage_deals(
@@ -732,11 +728,9 @@ pub mod pallet {
                 T::MaxDeals,
             >,
         ) -> DispatchResult {
-            let provider = ensure_signed(origin)?;
-            ensure!(
-                T::StorageProviderValidation::is_registered_storage_provider(&provider),
-                Error::<T>::StorageProviderNotRegistered
-            );
+            let caller = ensure_signed(origin)?;
+            let provider = T::StorageProviderValidation::storage_provider_owner(&caller)
+                .ok_or(Error::<T>::StorageProviderNotRegistered)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
             let (valid_deals, total_provider_lockup) =
                 Self::validate_deals(provider.clone(), deals, current_block)?;
@@ -764,6 +758,7 @@ pub mod pallet {
                 })?;
                 DealsForBlock::<T>::insert(deal.start_block, deals_for_block);
                 Proposals::<T>::insert(deal_id, deal.clone());
+                Self::index_deal(deal_id, &deal);
 
                 // Only deposit the event after storing everything
                 // force_push is ok since the bound is the same as the input one
@@ -784,6 +779,207 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Cancel a published deal, before it's activated.
+        /// It can be called by the Storage Provider's owner, worker or control addresses.
+        ///
+        /// The client's lockup and the provider's collateral are unlocked.
+        /// If `client_signature` is a valid signature of the deal's [`DealCancellation::signing_payload`] by the client,
+        /// the cancellation was mutually agreed and the whole collateral is returned to the provider.
+        /// Otherwise, the provider forfeits [`Config::DealCancellationFee`] of the collateral, which is paid to the client.
+        /// On success emits [`Event::<T>::DealCancelled`].
+        pub fn cancel_storage_deal(
+            origin: OriginFor<T>,
+            deal_id: DealId,
+            client_signature: Option<T::OffchainSignature>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let provider = T::StorageProviderValidation::storage_provider_owner(&caller)
+                .ok_or(Error::<T>::StorageProviderNotRegistered)?;
+
+            let proposal = Proposals::<T>::get(deal_id).ok_or(Error::<T>::DealNotFound)?;
+            ensure!(proposal.provider == provider, Error::<T>::InvalidProvider);
+            ensure!(
+                proposal.state == DealState::Published,
+                Error::<T>::DealNotCancellable
+            );
+
+            let fee = match client_signature {
+                Some(client_signature) => {
+                    let cancellation = DealCancellation {
+                        genesis_hash: Self::genesis_hash(),
+                        deal_id,
+                    };
+                    Self::validate_signature(
+                        &cancellation.signing_payload(),
+                        &client_signature,
+                        &proposal.client,
+                    )
+                    .map_err(|_| Error::<T>::WrongClientSignatureOnCancellation)?;
+                    Zero::zero()
+                }
+                None => T::DealCancellationFee::get().mul_floor(proposal.provider_collateral),
+            };
+
+            let mut pending_proposals = PendingProposals::<T>::get();
+            ensure!(
+                pending_proposals.remove(&Self::hash_proposal(&proposal)),
+                Error::<T>::DealNotPending
+            );
+            PendingProposals::<T>::set(pending_proposals);
+
+            // The deal won't need to be checked for activation at its start block anymore
+            let mut deals_for_block = DealsForBlock::<T>::take(&proposal.start_block);
+            deals_for_block.remove(&deal_id);
+            if !deals_for_block.is_empty() {
+                DealsForBlock::<T>::insert(&proposal.start_block, deals_for_block);
+            }
+            Proposals::<T>::remove(deal_id);
+            Self::unindex_deal(deal_id, &proposal);
+
+            // PRE-COND: the total storage fee was computed and locked when the deal was published
+            let client_fee: BalanceOf<T> = proposal
+                .total_storage_fee()
+                .ok_or(ArithmeticError::Overflow)?
+                .try_into()
+                .map_err(|_| ArithmeticError::Overflow)?;
+            unlock_funds::<T>(&proposal.client, client_fee)?;
+            unlock_funds::<T>(
+                &proposal.provider,
+                proposal.provider_collateral.saturating_sub(fee),
+            )?;
+            // Moves the fee from the provider's locked collateral to the client's free balance
+            perform_storage_payment::<T>(&proposal.provider, &proposal.client, fee)?;
+
+            Self::deposit_event(Event::<T>::DealCancelled {
+                deal_id,
+                client: proposal.client,
+                provider: proposal.provider,
+                fee,
+            });
+
+            Ok(())
+        }
+
+        /// Extend an active deal, with a new end block and price per block.
+        /// It can be called by the Storage Provider's owner, worker or control addresses,
+        /// `client_signature` must be the client's signature of the matching [`DealExtension::signing_payload`].
+        ///
+        /// The deal is settled up to the current block at its previous price, the new price applies from then on.
+        /// The client's lockup is adjusted to the new remaining storage fee, locking the extra funds if it increased.
+        /// The sector hosting the deal must not expire before the new end block.
+        /// On success emits [`Event::<T>::DealExtended`].
+        pub fn extend_deal(
+            origin: OriginFor<T>,
+            deal_id: DealId,
+            new_end_block: BlockNumberFor<T>,
+            new_storage_price_per_block: BalanceOf<T>,
+            client_signature: T::OffchainSignature,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let provider = T::StorageProviderValidation::storage_provider_owner(&caller)
+                .ok_or(Error::<T>::StorageProviderNotRegistered)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            let proposal = Proposals::<T>::get(deal_id).ok_or(Error::<T>::DealNotFound)?;
+            ensure!(proposal.provider == provider, Error::<T>::InvalidProvider);
+            let DealState::Active(ref active_deal_state) = proposal.state else {
+                return Err(Error::<T>::DealIsNotActive.into());
+            };
+            ensure!(current_block < proposal.end_block, Error::<T>::DealEnded);
+            ensure!(
+                new_end_block > proposal.end_block,
+                Error::<T>::DealEndNotExtended
+            );
+            ensure!(
+                new_end_block - proposal.start_block <= T::MaxDealDuration::get(),
+                Error::<T>::DealDurationOutOfBounds
+            );
+
+            let extension = DealExtension {
+                genesis_hash: Self::genesis_hash(),
+                deal_id,
+                end_block: new_end_block,
+                storage_price_per_block: new_storage_price_per_block,
+            };
+            Self::validate_signature(
+                &extension.signing_payload(),
+                &client_signature,
+                &proposal.client,
+            )
+            .map_err(|_| Error::<T>::WrongClientSignatureOnExtension)?;
+
+            let sector_expiry = T::StorageProviderValidation::sector_expiration(
+                &provider,
+                active_deal_state.sector_number,
+            )
+            .ok_or_else(|| {
+                log::error!(target: LOG_TARGET, "extend_deal: sector {:?} of deal {} not found", active_deal_state.sector_number, deal_id);
+                Error::<T>::DealPreconditionFailed
+            })?;
+            ensure!(
+                sector_expiry >= new_end_block,
+                Error::<T>::SectorExpiresBeforeDeal
+            );
+
+            // Blocks up to now are paid at the previous price, so the new price only applies to the extension onwards
+            if proposal.start_block <= current_block {
+                match Self::settle_deal(deal_id, current_block)? {
+                    Ok((settled, _)) => Self::deposit_event(Event::<T>::DealPaymentSettled {
+                        deal_id: settled.deal_id,
+                        client: settled.client,
+                        provider: settled.provider,
+                        amount: settled.amount,
+                    }),
+                    Err(e) => {
+                        log::error!(target: LOG_TARGET, "extend_deal: failed to settle deal {}: {:?}", deal_id, e);
+                        return Err(Error::<T>::DealPreconditionFailed.into());
+                    }
+                }
+            }
+
+            // Settlement updates the deal, so it needs to be fetched again
+            let mut proposal = Proposals::<T>::get(deal_id).ok_or(Error::<T>::DealNotFound)?;
+            let DealState::Active(ref active_deal_state) = proposal.state else {
+                return Err(Error::<T>::DealIsNotActive.into());
+            };
+            let payment_start_block =
+                calculate_start_block(proposal.start_block, active_deal_state.last_updated_block);
+
+            // The client's lockup covers the storage from the last payment until the deal's end
+            let locked_storage_fee = calculate_storage_price::<T>(
+                proposal.end_block - payment_start_block,
+                proposal.storage_price_per_block,
+            )?;
+            let extended_storage_fee = calculate_storage_price::<T>(
+                new_end_block - payment_start_block,
+                new_storage_price_per_block,
+            )?;
+            if extended_storage_fee > locked_storage_fee {
+                let extra_fee = extended_storage_fee - locked_storage_fee;
+                ensure!(
+                    BalanceTable::<T>::get(&proposal.client).free >= extra_fee,
+                    Error::<T>::InsufficientFreeFunds
+                );
+                lock_funds::<T>(&proposal.client, extra_fee)?;
+            } else {
+                unlock_funds::<T>(&proposal.client, locked_storage_fee - extended_storage_fee)?;
+            }
+
+            proposal.end_block = new_end_block;
+            proposal.storage_price_per_block = new_storage_price_per_block;
+            Proposals::<T>::insert(deal_id, proposal.clone());
+
+            Self::deposit_event(Event::<T>::DealExtended {
+                deal_id,
+                client: proposal.client,
+                provider: proposal.provider,
+                end_block: new_end_block,
+                storage_price_per_block: new_storage_price_per_block,
+            });
+
+            Ok(())
+        }
     }
 
     /// Functions exposed by the pallet
@@ -796,6 +992,11 @@ pub mod pallet {
             T::PalletId::get().into_account_truncating()
         }
 
+        /// Hash of the genesis block, binding the client's signed consents to this chain.
+        pub fn genesis_hash() -> T::Hash {
+            System::<T>::block_hash(BlockNumberFor::<T>::zero())
+        }
+
         /// Validates the signature of the given data with the provided signer's account ID.
         ///
         /// # Errors
@@ -828,6 +1029,121 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Returns a page of the client's deals, with at most `limit` (capped at [`MAX_DEALS_PER_PAGE`]) deal IDs.
+        ///
+        /// The deals are returned in a stable but unspecified order, the next page starts after the last deal ID of the previous one,
+        /// passed as `start_after`. The deal ID doesn't need to be indexed anymore, it's only used as a position.
+        pub fn client_deals(
+            client: &T::AccountId,
+            start_after: Option<DealId>,
+            limit: u32,
+        ) -> BoundedVec<DealId, ConstU32<MAX_DEALS_PER_PAGE>> {
+            let deal_ids = match start_after {
+                Some(deal_id) => ClientDeals::<T>::iter_key_prefix_from(
+                    client,
+                    ClientDeals::<T>::hashed_key_for(client, deal_id),
+                ),
+                None => ClientDeals::<T>::iter_key_prefix(client),
+            };
+            Self::deals_page(deal_ids, limit)
+        }
+
+        /// Returns a page of the storage provider's deals, see [`Pallet::client_deals`].
+        pub fn provider_deals(
+            provider: &T::AccountId,
+            start_after: Option<DealId>,
+            limit: u32,
+        ) -> BoundedVec<DealId, ConstU32<MAX_DEALS_PER_PAGE>> {
+            let deal_ids = match start_after {
+                Some(deal_id) => ProviderDeals::<T>::iter_key_prefix_from(
+                    provider,
+                    ProviderDeals::<T>::hashed_key_for(provider, deal_id),
+                ),
+                None => ProviderDeals::<T>::iter_key_prefix(provider),
+            };
+            Self::deals_page(deal_ids, limit)
+        }
+
+        /// Validates the signed deal proposal like [`Pallet::publish_storage_deals`] would, in the current block,
+        /// returning the storage fee locked from the client and the provider collateral, without publishing it.
+        pub fn quote_deal(
+            deal: ClientDealProposal<
+                T::AccountId,
+                BalanceOf<T>,
+                BlockNumberFor<T>,
+                T::OffchainSignature,
+            >,
+        ) -> Result<DealQuote<BalanceOf<T>>, DispatchError> {
+            let provider = deal.proposal.provider.clone();
+            ensure!(
+                T::StorageProviderValidation::is_registered_storage_provider(&provider),
+                Error::<T>::StorageProviderNotRegistered
+            );
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            // Validation marks the deal as pending, it's rolled back since the deal isn't published.
+            let (valid_deals, provider_collateral) = storage::with_transaction(|| {
+                storage::TransactionOutcome::Rollback(Self::validate_deals(
+                    provider,
+                    BoundedVec::truncate_from(Vec::from([deal])),
+                    current_block,
+                ))
+            })?;
+
+            // PRE-COND: a single deal is validated and it doesn't overflow, validated by `validate_deals`
+            let storage_fee = valid_deals
+                .first()
+                .and_then(|deal| deal.total_storage_fee())
+                .and_then(|fee| fee.try_into().ok())
+                .ok_or(Error::<T>::UnexpectedValidationError)?;
+
+            Ok(DealQuote {
+                storage_fee,
+                provider_collateral,
+            })
+        }
+
+        /// Returns the free and locked funds of the market participant.
+        pub fn balance(account: &T::AccountId) -> MarketBalance<BalanceOf<T>> {
+            let balance = BalanceTable::<T>::get(account);
+            MarketBalance {
+                free: balance.free,
+                locked: balance.locked,
+            }
+        }
+
+        /// Returns the payment that settling the deal in the current block would pay to the storage provider,
+        /// nothing is claimable before the deal starts or while it's not active.
+        ///
+        /// Returns [`None`] if the deal doesn't exist or its state is inconsistent.
+        pub fn claimable_settlement(deal_id: DealId) -> Option<BalanceOf<T>> {
+            let deal_proposal = Proposals::<T>::get(deal_id)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            match Self::deal_settlement_amount(deal_id, &deal_proposal, current_block) {
+                Ok(Ok((amount, _))) => Some(amount),
+                Ok(Err(
+                    DealSettlementError::EarlySettlement | DealSettlementError::DealNotActive,
+                )) => Some(Zero::zero()),
+                Ok(Err(e)) => {
+                    log::error!(target: LOG_TARGET, "claimable_settlement: deal {} cannot be settled: {:?}", deal_id, e);
+                    None
+                }
+                Err(e) => {
+                    log::error!(target: LOG_TARGET, "claimable_settlement: deal {} cannot be settled: {:?}", deal_id, e);
+                    None
+                }
+            }
+        }
+
+        fn deals_page(
+            deal_ids: impl Iterator<Item = DealId>,
+            limit: u32,
+        ) -> BoundedVec<DealId, ConstU32<MAX_DEALS_PER_PAGE>> {
+            let limit = limit.min(MAX_DEALS_PER_PAGE) as usize;
+            BoundedVec::truncate_from(deal_ids.take(limit).collect())
+        }
+
         /// <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1370>
         fn compute_commd<'a>(
             proposals: impl Iterator<Item = &'a DealProposalOf<T>>,
@@ -974,6 +1290,218 @@ pub mod pallet {
             ret
         }
 
+        /// Indexes the deal by its client and storage provider.
+        fn index_deal(deal_id: DealId, deal: &DealProposalOf<T>) {
+            ClientDeals::<T>::insert(&deal.client, deal_id, ());
+            ProviderDeals::<T>::insert(&deal.provider, deal_id, ());
+        }
+
+        /// Removes the deal from the client and storage provider indexes, once its proposal is removed.
+        fn unindex_deal(deal_id: DealId, deal: &DealProposalOf<T>) {
+            ClientDeals::<T>::remove(&deal.client, deal_id);
+            ProviderDeals::<T>::remove(&deal.provider, deal_id);
+        }
+
+        /// Computes the payment for an active deal, from its last settlement up to the `current_block`,
+        /// along with whether the deal is completed by it, i.e. the `current_block` reached its `end_block`.
+        ///
+        /// Returns the reason why the deal could not be settled, like [`Pallet::settle_deal`], nothing is changed.
+        fn deal_settlement_amount(
+            deal_id: DealId,
+            deal_proposal: &DealProposalOf<T>,
+            current_block: BlockNumberFor<T>,
+        ) -> Result<Result<(BalanceOf<T>, bool), DealSettlementError>, DispatchError> {
+            // Deal isn't possibly valid yet
+            // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1255-L1264
+            if deal_proposal.start_block > current_block {
+                return Ok(Err(DealSettlementError::EarlySettlement));
+            }
+
+            // If the deal is not active (i.e. unpublished or published), there's nothing to settle
+            // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1225-L1231
+            let DealState::Active(ref active_deal_state) = deal_proposal.state else {
+                // If a deal is not published, there's nothing to settle
+                // If a deal is published, but not active, it's supposed to be removed by cron/hooks
+
+                // NOTE(@jmg-duarte,28/06/2024): maybe we should handle deals where deal_proposal.start_block < current_block — i.e. expired
+                return Ok(Err(DealSettlementError::DealNotActive));
+            };
+
+            // If the last updated block is in the future, return an error
+            if let Some(last_updated_block) = active_deal_state.last_updated_block {
+                if last_updated_block > current_block {
+                    log::error!(target: LOG_TARGET,
+                        "last_updated_block for deal is in the future — deal_id: {}, last_updated_block: {:?}",
+                        deal_id,
+                        last_updated_block
+                    );
+                    return Ok(Err(DealSettlementError::FutureLastUpdate));
+                }
+            }
+
+            // If we never settled, the duration starts at `start_block`
+            let last_settled_block = active_deal_state
+                .last_updated_block
+                .unwrap_or(deal_proposal.start_block);
+
+            if last_settled_block > deal_proposal.end_block {
+                // If the code reaches this, it's a big whoops
+                log::error!(target: LOG_TARGET, "the last settled block cannot be bigger than the end block — last_settled_block: {:?}, end_block: {:?}",
+                    last_settled_block, deal_proposal.end_block);
+                return Err(DispatchError::Corruption);
+            }
+
+            let (block_to_settle, complete_deal) = {
+                if current_block >= deal_proposal.end_block {
+                    // The deal has been completed, as such, we'll remove it later on
+                    (deal_proposal.end_block, true)
+                } else {
+                    (current_block, false)
+                }
+            };
+
+            // If an error happens when converting here we have more to worry about than completing all settlements
+            let deal_settlement_amount: BalanceOf<T> = {
+                // There's no great way to avoid the repeated code without macros or more generics magic
+                // ArithmeticError::Overflow used as `duration` and `storage_price_per_block` can only be positive
+                let duration: u128 = (block_to_settle - last_settled_block)
+                    .try_into()
+                    .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))?;
+                let storage_price_per_block: u128 = deal_proposal
+                    .storage_price_per_block
+                    .try_into()
+                    .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))?;
+
+                (duration * storage_price_per_block)
+                    .try_into()
+                    .map_err(|_| DispatchError::Arithmetic(ArithmeticError::Overflow))
+            }?;
+
+            Ok(Ok((deal_settlement_amount, complete_deal)))
+        }
+
+        /// Settles the payment of an active deal, from its last settlement up to the `current_block`.
+        /// Once the deal reaches its `end_block` it's completed, the provider collateral is unlocked and the deal is removed.
+        ///
+        /// Returns the settled payment along with the deal's `end_block`, or the reason why the deal could not be settled.
+        /// Fails only when the deal's state is inconsistent or the payment can't be performed.
+        fn settle_deal(
+            deal_id: DealId,
+            current_block: BlockNumberFor<T>,
+        ) -> Result<
+            Result<(SettledDealData<T>, BlockNumberFor<T>), DealSettlementError>,
+            DispatchError,
+        > {
+            // If the deal is not found, we register an error and move on
+            // https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/market/src/lib.rs#L1225-L1231
+            let Some(mut deal_proposal) = Proposals::<T>::get(deal_id) else {
+                log::error!(target: LOG_TARGET, "deal not found — deal_id: {}", deal_id);
+                return Ok(Err(DealSettlementError::DealNotFound));
+            };
+
+            let (deal_settlement_amount, complete_deal) =
+                match Self::deal_settlement_amount(deal_id, &deal_proposal, current_block)? {
+                    Ok(settlement) => settlement,
+                    Err(e) => return Ok(Err(e)),
+                };
+
+            // PRE-COND: `deal_settlement_amount` only succeeds for active deals
+            let DealState::Active(ref mut active_deal_state) = deal_proposal.state else {
+                return Ok(Err(DealSettlementError::DealNotActive));
+            };
+
+            perform_storage_payment::<T>(
+                &deal_proposal.client,
+                &deal_proposal.provider,
+                deal_settlement_amount,
+            )?;
+
+            let settled = SettledDealData {
+                deal_id,
+                client: deal_proposal.client.clone(),
+                provider: deal_proposal.provider.clone(),
+                amount: deal_settlement_amount,
+            };
+            let end_block = deal_proposal.end_block;
+
+            // NOTE(@jmg-duarte,28/06/2024): Maybe emit an event when the table is updated?
+            if complete_deal {
+                unlock_funds::<T>(&deal_proposal.provider, deal_proposal.provider_collateral)?;
+                // The completed deal is no longer part of the sector, it's not terminated along with it.
+                SectorDeals::<T>::mutate_exists(
+                    (&deal_proposal.provider, active_deal_state.sector_number),
+                    |deal_ids| {
+                        if let Some(ids) = deal_ids {
+                            ids.retain(|id| *id != deal_id);
+                            if ids.is_empty() {
+                                *deal_ids = None;
+                            }
+                        }
+                    },
+                );
+                Proposals::<T>::remove(deal_id);
+                Self::unindex_deal(deal_id, &deal_proposal);
+            } else {
+                // Otherwise, we update the proposal — `last_updated_block`
+                active_deal_state.last_updated_block = Some(current_block);
+                Proposals::<T>::insert(deal_id, deal_proposal);
+            }
+
+            Ok(Ok((settled, end_block)))
+        }
+
+        /// Schedules the deal's payment to be settled by [`Hooks::on_finalize`] at `block`,
+        /// if the block is full, it's scheduled in the closest block after it with room.
+        ///
+        /// Every active deal is always scheduled, a full block only delays its settlement.
+        pub(crate) fn schedule_deal_settlement(deal_id: DealId, block: BlockNumberFor<T>) {
+            let mut settlement_block = block;
+            while !DealSettlementsForBlock::<T>::mutate(settlement_block, |deal_ids| {
+                deal_ids.try_insert(deal_id).is_ok()
+            }) {
+                log::debug!(target: LOG_TARGET, "block {:?} is full, trying to schedule the settlement of deal {} in the next one", settlement_block, deal_id);
+                settlement_block = settlement_block.saturating_add(One::one());
+            }
+        }
+
+        /// Settles the payments of the deals scheduled for the `current_block` in [`DealSettlementsForBlock`],
+        /// each deal is scheduled again for its next settlement, until it's completed at its `end_block`.
+        ///
+        /// Deals that were settled manually up to their end, or terminated, in the meantime are skipped.
+        /// Deals whose settlement failed are retried [`Config::DealSettlementInterval`] blocks later.
+        fn settle_scheduled_deals(current_block: BlockNumberFor<T>) {
+            let deal_ids = DealSettlementsForBlock::<T>::take(current_block);
+            for deal_id in deal_ids {
+                // A failed settlement must not leave the balances partially updated.
+                match storage::with_storage_layer(|| Self::settle_deal(deal_id, current_block)) {
+                    Ok(Ok((settled, end_block))) => {
+                        if current_block < end_block {
+                            let next_settlement = current_block
+                                .saturating_add(T::DealSettlementInterval::get())
+                                .min(end_block);
+                            Self::schedule_deal_settlement(deal_id, next_settlement);
+                        }
+                        Self::deposit_event(Event::<T>::DealPaymentSettled {
+                            deal_id: settled.deal_id,
+                            client: settled.client,
+                            provider: settled.provider,
+                            amount: settled.amount,
+                        });
+                    }
+                    Ok(Err(e)) => {
+                        log::debug!(target: LOG_TARGET, "on_finalize: skipping the settlement of deal {}: {:?}", deal_id, e);
+                    }
+                    Err(e) => {
+                        log::error!(target: LOG_TARGET, "on_finalize: failed to settle deal {}, retrying later: {:?}", deal_id, e);
+                        Self::schedule_deal_settlement(
+                            deal_id,
+                            current_block.saturating_add(T::DealSettlementInterval::get()),
+                        );
+                    }
+                }
+            }
+        }
+
         fn sanity_check(
             deal: &ClientDealProposal<
                 T::AccountId,
@@ -1292,6 +1820,13 @@ pub mod pallet {
                         client: proposal.client.clone(),
                         provider: proposal.provider.clone(),
                     });
+                    Self::schedule_deal_settlement(
+                        deal_id,
+                        proposal
+                            .start_block
+                            .saturating_add(T::DealSettlementInterval::get())
+                            .min(proposal.end_block),
+                    );
                     Proposals::<T>::insert(deal_id, proposal);
                 }
 
@@ -1419,6 +1954,7 @@ pub mod pallet {
 
                     // Remove completed deal
                     let _ = Proposals::<T>::remove(deal_id);
+                    Self::unindex_deal(deal_id, &deal_proposal);
 
                     Self::deposit_event(Event::<T>::DealTerminated {
                         deal_id,
@@ -1435,10 +1971,17 @@ pub mod pallet {
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
             // TODO(@th7nder,#77,26/06/2024): set proper weights according to what does the `on_finalize` do
-            // return placeholder for now
             // the correct way: get number of deals for a given block from DealsForBlock
             // and then calculate weights according to the actions performed in on_finalize
-            T::DbWeight::get().reads(1)
+            //
+            // Up to `MaxDealSettlementsPerBlock` deals are settled by `on_finalize`,
+            // each one reads and writes its proposal, the client's and the provider's balances and its next settlement block.
+            let settlements = u64::from(T::MaxDealSettlementsPerBlock::get());
+            T::DbWeight::get().reads(2).saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(4, 4)
+                    .saturating_mul(settlements),
+            )
         }
 
         /// When deals are published in [`publish_storage_deals`], they're added to the `DealsForBlock::<T>::get(current_block)` data structure.
@@ -1446,8 +1989,12 @@ pub mod pallet {
         /// If it did not happen, when [`on_finalize`] reaches `current_block`, it gets Deals that were supposed to be `DealState::Active` from `DealForBlock`.
         /// If they are not `DealState::Active`, hook slashes the Storage Provider and returns all of the funds to the Client.
         ///
+        /// Before that, the payments of the active deals scheduled in `DealSettlementsForBlock::<T>::get(current_block)` are settled.
+        ///
         /// *This function should not fail at any point, if it fails, it's a bug.*
         fn on_finalize(current_block: BlockNumberFor<T>) {
+            Self::settle_scheduled_deals(current_block);
+
             let deal_ids = DealsForBlock::<T>::get(&current_block);
             if deal_ids.is_empty() {
                 log::info!(target: LOG_TARGET, "on_finalize: no deals to process in block: {:?}", current_block);
@@ -1519,6 +2066,7 @@ pub mod pallet {
 
                 // Deal has been processed, no need to process it twice.
                 Proposals::<T>::remove(&deal_id);
+                Self::unindex_deal(deal_id, &proposal);
                 // PRE-COND: all deals in DealsPerBlock are published.
                 // All Published deals are hashed and added to [`PendingProposals`].
                 let _ = pending_proposals.remove(&Self::hash_proposal(&proposal));
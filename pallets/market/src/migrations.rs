@@ -0,0 +1,94 @@
+//! Storage migrations for the market pallet.
+
+/// Schedules the automatic payment settlements of the deals activated before they were introduced.
+pub mod v1 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, sp_runtime::traits::Saturating,
+        traits::UncheckedOnRuntimeUpgrade,
+    };
+
+    use crate::{Config, DealState, Pallet, Proposals, LOG_TARGET};
+
+    /// Schedules every active deal stored in [`Proposals`] in [`crate::DealSettlementsForBlock`].
+    ///
+    /// A deal is scheduled [`Config::DealSettlementInterval`] blocks after it was last settled, or after it started,
+    /// capped at its `end_block`. Deals whose settlement is already due are settled at the upgrade block.
+    pub struct UncheckedMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut deals = 0u64;
+            let mut scheduled = 0u64;
+
+            for (deal_id, proposal) in Proposals::<T>::iter() {
+                deals += 1;
+                let DealState::Active(ref active_deal_state) = proposal.state else {
+                    continue;
+                };
+
+                let last_settlement = active_deal_state
+                    .last_updated_block
+                    .unwrap_or(proposal.start_block);
+                let next_settlement = last_settlement
+                    .saturating_add(T::DealSettlementInterval::get())
+                    .min(proposal.end_block)
+                    .max(current_block);
+                Pallet::<T>::schedule_deal_settlement(deal_id, next_settlement);
+                scheduled += 1;
+            }
+
+            log::info!(target: LOG_TARGET, "Scheduled the settlements of {} active deals", scheduled);
+
+            // Every deal is read, and each active deal reads and writes its settlement block
+            T::DbWeight::get().reads_writes(deals.saturating_add(scheduled), scheduled)
+        }
+    }
+
+    /// [`UncheckedMigrateV0ToV1`] which only runs when the on-chain storage version is 0.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Indexes the deals published before they were tracked by client and storage provider.
+pub mod v2 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    };
+
+    use crate::{ClientDeals, Config, Pallet, Proposals, ProviderDeals, LOG_TARGET};
+
+    /// Indexes every deal stored in [`Proposals`].
+    pub struct UncheckedMigrateV1ToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            for (deal_id, proposal) in Proposals::<T>::iter() {
+                ClientDeals::<T>::insert(&proposal.client, deal_id, ());
+                ProviderDeals::<T>::insert(&proposal.provider, deal_id, ());
+                migrated += 1;
+            }
+
+            log::info!(target: LOG_TARGET, "Indexed {} deals", migrated);
+
+            // Each of the deals is read and written into both indexes
+            T::DbWeight::get().reads_writes(migrated, 2 * migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV1ToV2`] which only runs when the on-chain storage version is 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
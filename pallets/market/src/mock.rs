@@ -6,11 +6,13 @@ use frame_support::{
     PalletId,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
-use primitives::proofs::RegisteredPoStProof;
+use primitives::{
+    pallets::StorageProviderValidation, proofs::RegisteredPoStProof, sector::SectorNumber,
+};
 use sp_core::Pair;
 use sp_runtime::{
     traits::{ConstU32, ConstU64, IdentifyAccount, IdentityLookup, Verify, Zero},
-    AccountId32, BuildStorage, MultiSignature, MultiSigner,
+    AccountId32, BuildStorage, MultiSignature, MultiSigner, Perbill,
 };
 
 use crate::{self as pallet_market, BalanceOf, ClientDealProposal, DealProposal};
@@ -52,6 +54,9 @@ impl pallet_balances::Config for Test {
 parameter_types! {
     // Market Pallet
     pub const MarketPalletId: PalletId = PalletId(*b"spMarket");
+    pub const DealCancellationFee: Perbill = Perbill::from_percent(20);
+    // Static so tests can set the expiration of the sectors hosting the deals
+    pub static SectorExpiration: BlockNumber = 120;
 
     // Storage Provider Pallet
     pub const WPoStPeriodDeadlines: u64 = 10;
@@ -68,6 +73,15 @@ parameter_types! {
     pub const PreCommitChallengeDelay: BlockNumber = 1 * MINUTES;
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L299>
     pub const AddressedSectorsMax: u64 = 25_000;
+    pub const WorkerKeyChangeDelay: BlockNumber = 10 * MINUTES;
+    pub const ExpectedBlockReward: u64 = 10;
+    // Power of 10 2KiB sectors, the deposit is 1 and the pledge is 2 until it's exceeded
+    pub const BaselinePower: u128 = 10 * 2048;
+    pub const PreCommitDepositProjectionPeriod: BlockNumber = 1;
+    pub const InitialPledgeProjectionPeriod: BlockNumber = 2;
+    // The fee is 1 and the penalty is 2 per faulty 2KiB sector, under the baseline
+    pub const ContinuedFaultProjectionPeriod: BlockNumber = 1;
+    pub const UndeclaredFaultProjectionPeriod: BlockNumber = 2;
 }
 
 impl crate::Config for Test {
@@ -76,11 +90,32 @@ impl crate::Config for Test {
     type Currency = Balances;
     type OffchainSignature = Signature;
     type OffchainPublic = AccountPublic;
-    type StorageProviderValidation = StorageProvider;
+    type StorageProviderValidation = StorageProviderValidationMock;
     type MaxDeals = ConstU32<32>;
     type MinDealDuration = ConstU64<2>;
     type MaxDealDuration = ConstU64<30>;
     type MaxDealsPerBlock = ConstU32<32>;
+    type DealSettlementInterval = ConstU64<5>;
+    type MaxDealSettlementsPerBlock = ConstU32<32>;
+    type DealCancellationFee = DealCancellationFee;
+}
+
+/// Storage provider validation backed by the [`StorageProvider`] pallet,
+/// except for the sectors, which all expire at [`SectorExpiration`] as the tests don't prove them.
+pub struct StorageProviderValidationMock;
+
+impl StorageProviderValidation<AccountId, BlockNumber> for StorageProviderValidationMock {
+    fn is_registered_storage_provider(storage_provider: &AccountId) -> bool {
+        StorageProvider::is_registered_storage_provider(storage_provider)
+    }
+
+    fn storage_provider_owner(account: &AccountId) -> Option<AccountId> {
+        StorageProvider::storage_provider_owner(account)
+    }
+
+    fn sector_expiration(_: &AccountId, _: SectorNumber) -> Option<BlockNumber> {
+        Some(SectorExpiration::get())
+    }
 }
 
 /// Randomness generator used by tests.
@@ -138,10 +173,19 @@ impl pallet_storage_provider::Config for Test {
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L295>
     type AddressedPartitionsMax = MaxPartitionsPerDeadline;
     type AddressedSectorsMax = AddressedSectorsMax;
+    type WorkerKeyChangeDelay = WorkerKeyChangeDelay;
+    type ExpectedBlockReward = ExpectedBlockReward;
+    type BaselinePower = BaselinePower;
+    type PreCommitDepositProjectionPeriod = PreCommitDepositProjectionPeriod;
+    type InitialPledgeProjectionPeriod = InitialPledgeProjectionPeriod;
+    type ContinuedFaultProjectionPeriod = ContinuedFaultProjectionPeriod;
+    type UndeclaredFaultProjectionPeriod = UndeclaredFaultProjectionPeriod;
 }
 
 impl pallet_proofs::Config for Test {
     type RuntimeEvent = RuntimeEvent;
+    type UpdateOrigin = frame_system::EnsureRoot<AccountIdOf<Test>>;
+    type WeightInfo = ();
 }
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -1,29 +1,36 @@
 use core::str::FromStr;
+use std::collections::BTreeSet;
 
 use cid::Cid;
+use codec::Encode;
 use frame_support::{
     assert_err, assert_noop, assert_ok,
     pallet_prelude::{ConstU32, Get},
-    sp_runtime::{bounded_vec, ArithmeticError, DispatchError, TokenError},
-    traits::Currency,
+    sp_runtime::{bounded_vec, ArithmeticError, BoundedBTreeSet, DispatchError, TokenError},
+    traits::{Currency, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
     BoundedVec,
 };
 use primitives::{
     commitment::{CommP, Commitment},
-    pallets::{ActiveDeal, ActiveSector, Market as MarketTrait, SectorDeal},
+    pallets::{
+        ActiveDeal, ActiveSector, DealCancellation, DealExtension, DealQuote,
+        Market as MarketTrait, MarketBalance, SectorDeal,
+    },
     proofs::RegisteredSealProof,
     sector::SectorNumber,
     DealId, MAX_DEALS_PER_SECTOR,
 };
 use sp_core::H256;
-use sp_runtime::AccountId32;
+use sp_runtime::{AccountId32, MultiSignature};
 
 use crate::{
     error::DealSettlementError,
+    migrations::{v1::MigrateV0ToV1, v2::MigrateV1ToV2},
     mock::*,
     pallet::{lock_funds, slash_and_burn, unlock_funds},
-    ActiveDealState, BalanceEntry, BalanceTable, Config, DealState, DealsForBlock, Error, Event,
-    PendingProposals, Proposals, PublishedDeal, SectorDeals, SettledDealData,
+    ActiveDealState, BalanceEntry, BalanceTable, Config, DealSettlementsForBlock, DealState,
+    DealsForBlock, Error, Event, PendingProposals, Proposals, PublishedDeal, SectorDeals,
+    SettledDealData,
 };
 #[test]
 fn initial_state() {
@@ -204,7 +211,7 @@ fn fails_to_withdraw_balance() {
             Error::<Test>::InsufficientFreeFunds
         );
 
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -394,7 +401,7 @@ fn publish_storage_deals_fails_different_providers() {
             ),
             Error::<Test>::ProposalsPublishedByIncorrectStorageProvider
         );
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -420,7 +427,7 @@ fn publish_storage_deals_fails_client_not_enough_funds_for_second_deal() {
             ),
             Error::<Test>::InsufficientFreeFunds
         );
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -448,7 +455,7 @@ fn publish_storage_deals_fails_provider_not_enough_funds_for_second_deal() {
             ),
             Error::<Test>::InsufficientFreeFunds
         );
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -474,7 +481,7 @@ fn publish_storage_deals_fails_duplicate_deal_in_message() {
             ),
             Error::<Test>::DuplicateDeal
         );
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -979,6 +986,7 @@ fn verifies_deals_on_block_finalization() {
         );
         // After exceeding Bob's deal start_block,
         // Storage Provider should be slashed for Bob's amount and Bob refunded.
+        // In the meantime, Alice's deal was settled every 5 blocks and completed at its end block.
         run_to_block(bob_start_block + 1);
         assert_eq!(
             BalanceTable::<Test>::get(account::<Test>(BOB)),
@@ -987,19 +995,40 @@ fn verifies_deals_on_block_finalization() {
                 locked: 0
             }
         );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 10,
+                locked: 0
+            }
+        );
         assert_eq!(
             BalanceTable::<Test>::get(account::<Test>(PROVIDER)),
             BalanceEntry::<u64> {
-                free: 35,
-                // 40 (locked) - 15 (lost collateral) = 25
-                locked: 25
+                // 35 (free) + 50 (Alice's payment) + 25 (unlocked collateral) = 110
+                free: 110,
+                // 40 (locked) - 25 (unlocked collateral) - 15 (lost collateral) = 0
+                locked: 0
             }
         );
 
         assert!(!DealsForBlock::<Test>::get(&bob_start_block).contains(&bob_deal_id));
+        assert!(!Proposals::<Test>::contains_key(alice_deal_id));
         assert_eq!(
             events(),
             [
+                RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                    deal_id: alice_deal_id,
+                    client: account::<Test>(ALICE),
+                    provider: account::<Test>(PROVIDER),
+                    amount: 25,
+                }),
+                RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                    deal_id: alice_deal_id,
+                    client: account::<Test>(ALICE),
+                    provider: account::<Test>(PROVIDER),
+                    amount: 25,
+                }),
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded { amount: 15 }),
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Withdraw {
                     who: Market::account_id(),
@@ -1016,6 +1045,955 @@ fn verifies_deals_on_block_finalization() {
     });
 }
 
+/// Publishes a deal between Alice and the provider, from block 100 to 110.
+fn publish_alice_deal() -> DealId {
+    register_storage_provider(account::<Test>(PROVIDER));
+    let alice_proposal = DealProposalBuilder::<Test>::default()
+        .start_block(100)
+        .end_block(110)
+        .storage_price_per_block(5)
+        .provider_collateral(25)
+        .signed(ALICE);
+
+    let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(ALICE)), 60);
+    let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(PROVIDER)), 75);
+    assert_ok!(Market::publish_storage_deals(
+        RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+        bounded_vec![alice_proposal],
+    ));
+    System::reset_events();
+
+    0
+}
+
+/// Publishes and activates a deal between Alice and the provider, from block 100 to 110.
+fn publish_and_activate_alice_deal() -> DealId {
+    publish_alice_deal();
+    assert_ok!(Market::activate_deals(
+        &account::<Test>(PROVIDER),
+        bounded_vec![SectorDealBuilder::default()
+            .deal_ids(bounded_vec![0])
+            .build()],
+        true,
+    ));
+    System::reset_events();
+
+    0
+}
+
+#[test]
+fn settles_active_deals_on_interval() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        // First settlement is scheduled an interval after the start
+        assert!(DealSettlementsForBlock::<Test>::get(105).contains(&deal_id));
+
+        run_to_block(106);
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                amount: 25,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 10,
+                locked: 25, // 50 - 5 * 5 (price per block * n blocks)
+            }
+        );
+        assert!(!DealSettlementsForBlock::<Test>::contains_key(105));
+        // Next settlement is capped at the end block
+        assert!(DealSettlementsForBlock::<Test>::get(110).contains(&deal_id));
+
+        run_to_block(111);
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                amount: 25,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 10,
+                locked: 0
+            }
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(PROVIDER)),
+            BalanceEntry::<u64> {
+                free: 125, // 50 (from 75 - collateral) + 25 (returned collateral) + 5 * 10 (price per block * n blocks)
+                locked: 0
+            }
+        );
+        // The deal is completed, there's nothing else to settle
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+        assert!(!SectorDeals::<Test>::contains_key((
+            account::<Test>(PROVIDER),
+            SectorNumber::from(1)
+        )));
+        assert_eq!(DealSettlementsForBlock::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn settles_active_deals_after_manual_settlement() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        run_to_block(103);
+        assert_ok!(Market::settle_deal_payments(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            bounded_vec![deal_id]
+        ));
+        System::reset_events();
+
+        // Only the blocks since the manual settlement are paid
+        run_to_block(106);
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                amount: 10,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 10,
+                locked: 25,
+            }
+        );
+    });
+}
+
+#[test]
+fn skips_settlement_of_terminated_deals() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        run_to_block(103);
+        assert_ok!(Market::on_sectors_terminate(
+            &account::<Test>(PROVIDER),
+            bounded_vec![1.into()],
+        ));
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+        System::reset_events();
+
+        run_to_block(111);
+        assert!(events().is_empty());
+        assert_eq!(DealSettlementsForBlock::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn schedules_settlement_in_next_block_with_room() {
+    new_test_ext().execute_with(|| {
+        let max_settlements: u32 = <Test as Config>::MaxDealSettlementsPerBlock::get();
+        let full_block = (0..max_settlements)
+            .map(|id| DealId::from(id) + 1000)
+            .collect::<BTreeSet<_>>();
+        DealSettlementsForBlock::<Test>::insert(
+            105,
+            BoundedBTreeSet::try_from(full_block).unwrap(),
+        );
+
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert!(!DealSettlementsForBlock::<Test>::get(105).contains(&deal_id));
+        assert!(DealSettlementsForBlock::<Test>::get(106).contains(&deal_id));
+    });
+}
+
+#[test]
+fn schedules_settlement_after_many_full_blocks() {
+    new_test_ext().execute_with(|| {
+        let max_settlements: u32 = <Test as Config>::MaxDealSettlementsPerBlock::get();
+        let full_block = (0..max_settlements)
+            .map(|id| DealId::from(id) + 1000)
+            .collect::<BTreeSet<_>>();
+        for block in 105..205 {
+            DealSettlementsForBlock::<Test>::insert(
+                block,
+                BoundedBTreeSet::try_from(full_block.clone()).unwrap(),
+            );
+        }
+
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert!(DealSettlementsForBlock::<Test>::get(205).contains(&deal_id));
+    });
+}
+
+#[test]
+fn reschedules_failed_settlement() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        // The client's locked funds can't cover the payment, so the settlement fails
+        BalanceTable::<Test>::mutate(account::<Test>(ALICE), |balance| balance.locked = 0);
+
+        run_to_block(106);
+        assert!(events().is_empty());
+        assert!(Proposals::<Test>::contains_key(deal_id));
+        // The settlement is retried an interval later
+        assert!(DealSettlementsForBlock::<Test>::get(110).contains(&deal_id));
+
+        BalanceTable::<Test>::mutate(account::<Test>(ALICE), |balance| balance.locked = 50);
+        run_to_block(111);
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                amount: 50,
+            })]
+        );
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+    });
+}
+
+/// Signs the client's consent to cancel the deal on the chain with the given genesis hash.
+fn sign_cancellation(client: &str, genesis_hash: H256, deal_id: DealId) -> MultiSignature {
+    let cancellation = DealCancellation {
+        genesis_hash,
+        deal_id,
+    };
+    sign(&key_pair(client), &cancellation.signing_payload())
+}
+
+#[test]
+fn cancel_storage_deal_with_client_consent() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+        let client_signature = sign_cancellation(ALICE, System::block_hash(0), deal_id);
+
+        assert_ok!(Market::cancel_storage_deal(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            deal_id,
+            Some(client_signature),
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealCancelled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                fee: 0,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 60,
+                locked: 0
+            }
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(PROVIDER)),
+            BalanceEntry::<u64> {
+                free: 75,
+                locked: 0
+            }
+        );
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+        assert!(PendingProposals::<Test>::get().is_empty());
+        assert!(!DealsForBlock::<Test>::contains_key(100));
+    });
+}
+
+#[test]
+fn migrates_active_deals_into_settlements() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        // Deals activated before the upgrade had no settlements scheduled
+        let _ = DealSettlementsForBlock::<Test>::clear(u32::MAX, None);
+        StorageVersion::new(0).put::<Market>();
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Market::on_chain_storage_version(), 1);
+        // The first settlement is scheduled an interval after the start
+        assert!(DealSettlementsForBlock::<Test>::get(105).contains(&deal_id));
+
+        let _ = DealSettlementsForBlock::<Test>::clear(u32::MAX, None);
+        run_to_block(107);
+        StorageVersion::new(0).put::<Market>();
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+        // A settlement already due is scheduled at the upgrade block
+        assert!(DealSettlementsForBlock::<Test>::get(107).contains(&deal_id));
+    });
+}
+
+#[test]
+fn cancel_storage_deal_forfeits_fee() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+
+        assert_ok!(Market::cancel_storage_deal(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            deal_id,
+            None,
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealCancelled {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                fee: 5,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 65, // 60 + 5 (20% of the provider collateral)
+                locked: 0
+            }
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(PROVIDER)),
+            BalanceEntry::<u64> {
+                free: 70, // 75 - 5 (20% of the provider collateral)
+                locked: 0
+            }
+        );
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+        assert!(PendingProposals::<Test>::get().is_empty());
+        assert!(!DealsForBlock::<Test>::contains_key(100));
+
+        // The cancelled deal is not slashed at its start block
+        run_to_block(101);
+        assert!(events().is_empty());
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_wrong_client_signature() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+        let client_signature = sign_cancellation(BOB, System::block_hash(0), deal_id);
+
+        assert_noop!(
+            Market::cancel_storage_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                Some(client_signature),
+            ),
+            Error::<Test>::WrongClientSignatureOnCancellation
+        );
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_client_signature_for_other_chain() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+        let client_signature = sign_cancellation(ALICE, H256::repeat_byte(0xff), deal_id);
+
+        assert_noop!(
+            Market::cancel_storage_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                Some(client_signature),
+            ),
+            Error::<Test>::WrongClientSignatureOnCancellation
+        );
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_client_signature_without_domain_tag() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+        let cancellation = DealCancellation {
+            genesis_hash: System::block_hash(0),
+            deal_id,
+        };
+        let client_signature = sign(&key_pair(ALICE), &Encode::encode(&cancellation));
+
+        assert_noop!(
+            Market::cancel_storage_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                Some(client_signature),
+            ),
+            Error::<Test>::WrongClientSignatureOnCancellation
+        );
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_not_provider() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+
+        assert_noop!(
+            Market::cancel_storage_deal(
+                RuntimeOrigin::signed(account::<Test>(ALICE)),
+                deal_id,
+                None,
+            ),
+            Error::<Test>::StorageProviderNotRegistered
+        );
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_active_deal() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert_noop!(
+            Market::cancel_storage_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                None,
+            ),
+            Error::<Test>::DealNotCancellable
+        );
+    });
+}
+
+#[test]
+fn cancel_storage_deal_fails_deal_not_found() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account::<Test>(PROVIDER));
+
+        assert_noop!(
+            Market::cancel_storage_deal(RuntimeOrigin::signed(account::<Test>(PROVIDER)), 0, None,),
+            Error::<Test>::DealNotFound
+        );
+    });
+}
+
+/// Signs the client's agreement to extend the deal.
+fn sign_extension(
+    client: &str,
+    deal_id: DealId,
+    end_block: u64,
+    storage_price_per_block: u64,
+) -> MultiSignature {
+    let extension = DealExtension {
+        genesis_hash: System::block_hash(0),
+        deal_id,
+        end_block,
+        storage_price_per_block,
+    };
+    sign(&key_pair(client), &extension.signing_payload())
+}
+
+#[test]
+fn extend_deal_settles_and_locks_extra_funds() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        assert_ok!(Market::add_balance(
+            RuntimeOrigin::signed(account::<Test>(ALICE)),
+            40
+        ));
+        run_to_block(104);
+        System::reset_events();
+
+        assert_ok!(Market::extend_deal(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            deal_id,
+            115,
+            6,
+            sign_extension(ALICE, deal_id, 115, 6),
+        ));
+
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Market(Event::<Test>::DealPaymentSettled {
+                    deal_id,
+                    client: account::<Test>(ALICE),
+                    provider: account::<Test>(PROVIDER),
+                    amount: 20, // 4 blocks at the previous price
+                }),
+                RuntimeEvent::Market(Event::<Test>::DealExtended {
+                    deal_id,
+                    client: account::<Test>(ALICE),
+                    provider: account::<Test>(PROVIDER),
+                    end_block: 115,
+                    storage_price_per_block: 6,
+                })
+            ]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 14,   // 50 - (66 - 30) extra funds locked
+                locked: 66, // 11 blocks * 6 (new price per block)
+            }
+        );
+        assert_eq!(
+            Proposals::<Test>::get(deal_id),
+            Some(
+                DealProposalBuilder::<Test>::default()
+                    .start_block(100)
+                    .end_block(115)
+                    .storage_price_per_block(6)
+                    .provider_collateral(25)
+                    .state(DealState::Active(ActiveDealState {
+                        sector_number: 1.into(),
+                        sector_start_block: 1,
+                        last_updated_block: Some(104),
+                        slash_block: None,
+                    }))
+                    .unsigned()
+            )
+        );
+
+        // The deal keeps being settled automatically until its new end
+        run_to_block(116);
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 14,
+                locked: 0
+            }
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(PROVIDER)),
+            BalanceEntry::<u64> {
+                free: 161, // 50 (from 75 - collateral) + 25 (returned collateral) + 20 + 66 (payments)
+                locked: 0
+            }
+        );
+        assert!(!Proposals::<Test>::contains_key(deal_id));
+    });
+}
+
+#[test]
+fn extend_deal_unlocks_funds_with_lower_price() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        // The deal hasn't started yet, so there's nothing to settle
+        assert_ok!(Market::extend_deal(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            deal_id,
+            112,
+            4,
+            sign_extension(ALICE, deal_id, 112, 4),
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::Market(Event::<Test>::DealExtended {
+                deal_id,
+                client: account::<Test>(ALICE),
+                provider: account::<Test>(PROVIDER),
+                end_block: 112,
+                storage_price_per_block: 4,
+            })]
+        );
+        assert_eq!(
+            BalanceTable::<Test>::get(account::<Test>(ALICE)),
+            BalanceEntry::<u64> {
+                free: 12,   // 10 + (50 - 48) unlocked funds
+                locked: 48, // 12 blocks * 4 (new price per block)
+            }
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_sector_expires_before_deal() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        SectorExpiration::set(112);
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                115,
+                5,
+                sign_extension(ALICE, deal_id, 115, 5),
+            ),
+            Error::<Test>::SectorExpiresBeforeDeal
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_wrong_client_signature() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                115,
+                5,
+                // Signed for a different price
+                sign_extension(ALICE, deal_id, 115, 1),
+            ),
+            Error::<Test>::WrongClientSignatureOnExtension
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_client_signature_for_other_chain() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+        let extension = DealExtension {
+            genesis_hash: H256::repeat_byte(0xff),
+            deal_id,
+            end_block: 115,
+            storage_price_per_block: 5,
+        };
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                115,
+                5,
+                sign(&key_pair(ALICE), &extension.signing_payload()),
+            ),
+            Error::<Test>::WrongClientSignatureOnExtension
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_end_not_extended() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                110,
+                5,
+                sign_extension(ALICE, deal_id, 110, 5),
+            ),
+            Error::<Test>::DealEndNotExtended
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_not_active() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                115,
+                5,
+                sign_extension(ALICE, deal_id, 115, 5),
+            ),
+            Error::<Test>::DealIsNotActive
+        );
+    });
+}
+
+#[test]
+fn extend_deal_fails_insufficient_funds() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert_noop!(
+            Market::extend_deal(
+                RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+                deal_id,
+                115,
+                10,
+                sign_extension(ALICE, deal_id, 115, 10),
+            ),
+            Error::<Test>::InsufficientFreeFunds
+        );
+    });
+}
+
+/// Returns all of the client's indexed deals.
+fn indexed_client_deals(client: &'static str) -> BTreeSet<DealId> {
+    Market::client_deals(&account::<Test>(client), None, u32::MAX)
+        .into_iter()
+        .collect()
+}
+
+/// Returns all of the provider's indexed deals.
+fn indexed_provider_deals() -> BTreeSet<DealId> {
+    Market::provider_deals(&account::<Test>(PROVIDER), None, u32::MAX)
+        .into_iter()
+        .collect()
+}
+
+/// Publishes two deals for Alice and one for Bob, with IDs 0, 1 and 2 respectively.
+fn publish_alice_and_bob_deals() {
+    register_storage_provider(account::<Test>(PROVIDER));
+    let alice_proposal = DealProposalBuilder::<Test>::default().signed(ALICE);
+    let alice_second_proposal = DealProposalBuilder::<Test>::default()
+        .piece_size(37)
+        .signed(ALICE);
+    let bob_proposal = DealProposalBuilder::<Test>::default()
+        .client(BOB)
+        .start_block(130)
+        .end_block(135)
+        .storage_price_per_block(10)
+        .provider_collateral(15)
+        .signed(BOB);
+
+    let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(ALICE)), 100);
+    let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(BOB)), 70);
+    let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(PROVIDER)), 75);
+    assert_ok!(Market::publish_storage_deals(
+        RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+        bounded_vec![alice_proposal, alice_second_proposal, bob_proposal]
+    ));
+    System::reset_events();
+}
+
+#[test]
+fn indexes_published_deals() {
+    new_test_ext().execute_with(|| {
+        publish_alice_and_bob_deals();
+
+        assert_eq!(indexed_client_deals(ALICE), BTreeSet::from([0, 1]));
+        assert_eq!(indexed_client_deals(BOB), BTreeSet::from([2]));
+        assert_eq!(indexed_provider_deals(), BTreeSet::from([0, 1, 2]));
+        assert!(indexed_client_deals(PROVIDER).is_empty());
+    });
+}
+
+#[test]
+fn pages_through_indexed_deals() {
+    new_test_ext().execute_with(|| {
+        publish_alice_and_bob_deals();
+        let provider = account::<Test>(PROVIDER);
+
+        let first_page = Market::provider_deals(&provider, None, 2);
+        assert_eq!(first_page.len(), 2);
+        let second_page = Market::provider_deals(&provider, first_page.last().copied(), 2);
+        assert_eq!(second_page.len(), 1);
+        assert!(Market::provider_deals(&provider, second_page.last().copied(), 2).is_empty());
+
+        let deal_ids: BTreeSet<_> = first_page.into_iter().chain(second_page).collect();
+        assert_eq!(deal_ids, BTreeSet::from([0, 1, 2]));
+    });
+}
+
+#[test]
+fn keeps_activated_deals_indexed() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_and_activate_alice_deal();
+
+        assert_eq!(indexed_client_deals(ALICE), BTreeSet::from([deal_id]));
+        assert_eq!(indexed_provider_deals(), BTreeSet::from([deal_id]));
+    });
+}
+
+#[test]
+fn unindexes_completed_deals() {
+    new_test_ext().execute_with(|| {
+        publish_and_activate_alice_deal();
+
+        run_to_block(111);
+        assert!(indexed_client_deals(ALICE).is_empty());
+        assert!(indexed_provider_deals().is_empty());
+    });
+}
+
+#[test]
+fn unindexes_terminated_deals() {
+    new_test_ext().execute_with(|| {
+        publish_and_activate_alice_deal();
+
+        assert_ok!(Market::on_sectors_terminate(
+            &account::<Test>(PROVIDER),
+            bounded_vec![1.into()],
+        ));
+        assert!(indexed_client_deals(ALICE).is_empty());
+        assert!(indexed_provider_deals().is_empty());
+    });
+}
+
+#[test]
+fn unindexes_cancelled_deals() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+
+        assert_ok!(Market::cancel_storage_deal(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            deal_id,
+            None,
+        ));
+        assert!(indexed_client_deals(ALICE).is_empty());
+        assert!(indexed_provider_deals().is_empty());
+    });
+}
+
+#[test]
+fn unindexes_expired_deals() {
+    new_test_ext().execute_with(|| {
+        publish_alice_deal();
+
+        // The deal wasn't activated by its start block
+        run_to_block(101);
+        assert!(indexed_client_deals(ALICE).is_empty());
+        assert!(indexed_provider_deals().is_empty());
+    });
+}
+
+#[test]
+fn migrates_deals_into_indexes() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<Market>();
+        Proposals::<Test>::insert(0, DealProposalBuilder::<Test>::default().unsigned());
+        Proposals::<Test>::insert(
+            1,
+            DealProposalBuilder::<Test>::default()
+                .client(BOB)
+                .unsigned(),
+        );
+
+        MigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Market::on_chain_storage_version(), 2);
+        assert_eq!(indexed_client_deals(ALICE), BTreeSet::from([0]));
+        assert_eq!(indexed_client_deals(BOB), BTreeSet::from([1]));
+        assert_eq!(indexed_provider_deals(), BTreeSet::from([0, 1]));
+    });
+}
+
+#[test]
+fn quote_deal() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account::<Test>(PROVIDER));
+        let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(ALICE)), 60);
+        let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(PROVIDER)), 75);
+        let deal = DealProposalBuilder::<Test>::default().signed(ALICE);
+
+        assert_eq!(
+            Market::quote_deal(deal.clone()),
+            Ok(DealQuote {
+                storage_fee: 50,
+                provider_collateral: 25,
+            })
+        );
+        // The quoted deal isn't published, so it can still be published
+        assert!(PendingProposals::<Test>::get().is_empty());
+        assert_ok!(Market::publish_storage_deals(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            bounded_vec![deal]
+        ));
+    });
+}
+
+#[test]
+fn quote_deal_fails_sp_not_registered() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            Market::quote_deal(DealProposalBuilder::<Test>::default().signed(ALICE)),
+            Err(Error::<Test>::StorageProviderNotRegistered.into())
+        );
+    });
+}
+
+#[test]
+fn quote_deal_fails_invalid_signature() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account::<Test>(PROVIDER));
+        let mut deal = DealProposalBuilder::<Test>::default().signed(ALICE);
+        deal.proposal.piece_size = 1337;
+
+        assert_eq!(
+            Market::quote_deal(deal),
+            Err(Error::<Test>::WrongClientSignatureOnProposal.into())
+        );
+    });
+}
+
+#[test]
+fn quote_deal_fails_insufficient_funds() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account::<Test>(PROVIDER));
+        let _ = Market::add_balance(RuntimeOrigin::signed(account::<Test>(ALICE)), 49);
+
+        assert_eq!(
+            Market::quote_deal(DealProposalBuilder::<Test>::default().signed(ALICE)),
+            Err(Error::<Test>::InsufficientFreeFunds.into())
+        );
+    });
+}
+
+#[test]
+fn balance_summary() {
+    new_test_ext().execute_with(|| {
+        publish_alice_deal();
+
+        assert_eq!(
+            Market::balance(&account::<Test>(ALICE)),
+            MarketBalance {
+                free: 10,
+                locked: 50
+            }
+        );
+        assert_eq!(
+            Market::balance(&account::<Test>(BOB)),
+            MarketBalance { free: 0, locked: 0 }
+        );
+    });
+}
+
+#[test]
+fn claimable_settlement() {
+    new_test_ext().execute_with(|| {
+        let deal_id = publish_alice_deal();
+        // Published deals have nothing to settle
+        assert_eq!(Market::claimable_settlement(deal_id), Some(0));
+
+        assert_ok!(Market::activate_deals(
+            &account::<Test>(PROVIDER),
+            bounded_vec![SectorDealBuilder::default()
+                .deal_ids(bounded_vec![deal_id])
+                .build()],
+            true,
+        ));
+        // The deal hasn't started yet
+        assert_eq!(Market::claimable_settlement(deal_id), Some(0));
+
+        run_to_block(103);
+        assert_eq!(Market::claimable_settlement(deal_id), Some(15)); // 5 * 3 (price per block * n blocks)
+
+        assert_ok!(Market::settle_deal_payments(
+            RuntimeOrigin::signed(account::<Test>(PROVIDER)),
+            bounded_vec![deal_id]
+        ));
+        assert_eq!(Market::claimable_settlement(deal_id), Some(0));
+    });
+}
+
+#[test]
+fn claimable_settlement_deal_not_found() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Market::claimable_settlement(0), None);
+    });
+}
+
 #[test]
 fn settle_deal_payments_not_found() {
     new_test_ext().execute_with(|| {
@@ -1541,7 +2519,7 @@ fn on_sector_terminate_unknown_deals() {
             bounded_vec![0.into()],
         ));
 
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -1563,7 +2541,7 @@ fn on_sector_terminate_deal_not_found() {
             Error::<Test>::DealNotFound
         );
 
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -1590,7 +2568,7 @@ fn on_sector_terminate_invalid_caller() {
             bounded_vec![sector_number]
         ),);
 
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -1622,7 +2600,7 @@ fn on_sector_terminate_not_active() {
             Error::<Test>::DealIsNotActive
         );
 
-        assert_eq!(events(), []);
+        assert!(events().is_empty());
     });
 }
 
@@ -0,0 +1,144 @@
+//! Benchmarking setup for pallet-proofs
+#![cfg(feature = "runtime-benchmarks")]
+
+use frame_benchmarking::v2::*;
+
+#[benchmarks]
+mod benchmarks {
+    use codec::Decode;
+    use frame_support::{pallet_prelude::ConstU32, sp_runtime::BoundedBTreeMap};
+    use primitives::{
+        pallets::ProofVerification,
+        proofs::{
+            PublicReplicaInfo, PublicSealInfo, RegisteredPoStProof, RegisteredSealProof, Ticket,
+        },
+        sector::SectorNumber,
+        MAX_BATCHED_SECTORS, MAX_PARTITIONS_PER_POST, MAX_SECTORS_PER_PROOF,
+    };
+
+    use super::*;
+    use crate::{
+        crypto::groth16::{Bls12, Proof, VerifyingKey},
+        post, Config, Pallet, PoRepVerifyingKey, PoStVerifyingKey, Vec,
+    };
+
+    // Generated with `polka-storage-provider-client proofs post-params` and `proofs post`
+    // for a single 2KiB sector, proof generation takes too long to be done in the benchmark.
+    const VERIFYING_KEY: &[u8] = include_bytes!("../fixtures/post_2KiB.vk.scale");
+    const PROOF: &[u8] = include_bytes!("../fixtures/post_2KiB.proof");
+    const COMM_R: [u8; 32] = [
+        0x4a, 0xfb, 0x35, 0xf8, 0x2a, 0x95, 0xa1, 0x01, 0x87, 0xa9, 0x13, 0xbc, 0x14, 0x52, 0x0d,
+        0x9a, 0x1d, 0x17, 0x33, 0x28, 0x26, 0x5b, 0x30, 0x1b, 0x5d, 0xcf, 0x44, 0x0e, 0xf2, 0x58,
+        0x39, 0x50,
+    ];
+    const SECTOR_NUMBER: u32 = 77;
+    const RANDOMNESS: Ticket = [1u8; 32];
+    const POST_TYPE: RegisteredPoStProof = RegisteredPoStProof::StackedDRGWindow2KiBV1P1;
+
+    // Generated with `polka-storage-provider utils porep-params` and `utils po-rep`
+    // for a single 2KiB sector, the same sector used by the PoRep tests.
+    const POREP_VERIFYING_KEY: &[u8] = include_bytes!("../fixtures/porep_2KiB.vk.scale");
+    const POREP_PROOF: &[u8] = include_bytes!("../fixtures/porep_2KiB.proof");
+    const POREP_COMM_D: [u8; 32] = [
+        0x12, 0x9c, 0x75, 0x62, 0xbb, 0x0c, 0x18, 0x95, 0x44, 0xf5, 0xdc, 0xcd, 0x36, 0x5f, 0xea,
+        0xec, 0x21, 0x41, 0xea, 0xb4, 0x58, 0x09, 0x7a, 0x5c, 0xa8, 0x42, 0x9c, 0x10, 0x9d, 0x15,
+        0x44, 0x21,
+    ];
+    const PROVER_ID: [u8; 32] = [0u8; 32];
+    const TICKET: Ticket = [12u8; 32];
+    const SEED: Ticket = [13u8; 32];
+    const SEAL_PROOF: RegisteredSealProof = RegisteredSealProof::StackedDRG2KiBV1P1;
+
+    type Replicas =
+        BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>;
+
+    /// Sets the PoSt verifying key and returns `p` partitions proven by the same proof.
+    /// Each proof is combined with a different random scalar, so a batch of copies is as costly as distinct proofs.
+    fn setup_partitions<T: Config>(p: u32) -> (Vec<Replicas>, Vec<u8>) {
+        let vkey = VerifyingKey::<Bls12>::decode(&mut &VERIFYING_KEY[..])
+            .expect("fixture to be a valid verifying key");
+        PoStVerifyingKey::<T>::insert(POST_TYPE, vkey);
+
+        let mut replicas = Replicas::new();
+        replicas
+            .try_insert(
+                SectorNumber::new(SECTOR_NUMBER).expect("sector number to be valid"),
+                PublicReplicaInfo { comm_r: COMM_R },
+            )
+            .expect("a single replica to fit");
+        let partitions = (0..p).map(|_| replicas.clone()).collect();
+        let proof = PROOF.repeat(p as usize);
+
+        (partitions, proof)
+    }
+
+    /// Sets the PoRep verifying key and returns `s` copies of the same sector, proven by the same proof.
+    fn setup_sectors<T: Config>(s: u32) -> (Vec<PublicSealInfo>, Vec<u8>) {
+        let vkey = VerifyingKey::<Bls12>::decode(&mut &POREP_VERIFYING_KEY[..])
+            .expect("fixture to be a valid verifying key");
+        PoRepVerifyingKey::<T>::insert(SEAL_PROOF, vkey);
+
+        let sector = PublicSealInfo {
+            sector: SectorNumber::new(SECTOR_NUMBER).expect("sector number to be valid"),
+            comm_r: COMM_R,
+            comm_d: POREP_COMM_D,
+            ticket: TICKET,
+            seed: SEED,
+        };
+        let sectors = (0..s).map(|_| sector.clone()).collect();
+        let proof = POREP_PROOF.repeat(s as usize);
+
+        (sectors, proof)
+    }
+
+    /// Verifies every partition's proof with its own pairing check, the path used before batching.
+    #[benchmark]
+    fn verify_post_single(p: Linear<1, MAX_PARTITIONS_PER_POST>) {
+        let (partitions, proof) = setup_partitions::<T>(p);
+        let proof_scheme = post::ProofScheme::setup(POST_TYPE);
+
+        #[block]
+        {
+            // Reads the key like `verify_post` does, so both benchmarks are comparable.
+            let vkey = PoStVerifyingKey::<T>::get(POST_TYPE).expect("key to be set");
+            for (replicas, proof) in partitions.iter().zip(proof.chunks(PROOF.len())) {
+                let proof = Proof::<Bls12>::decode(&mut &proof[..]).expect("proof to be valid");
+                proof_scheme
+                    .verify(RANDOMNESS, replicas, vkey.clone(), &proof)
+                    .expect("proof to be verified");
+            }
+        }
+    }
+
+    /// Verifies all of the partitions' proofs in a single batch.
+    #[benchmark]
+    fn verify_post(p: Linear<1, MAX_PARTITIONS_PER_POST>) {
+        let (partitions, proof) = setup_partitions::<T>(p);
+        let partitions = partitions.try_into().expect("partitions to be bounded");
+        let proof = proof.try_into().expect("proof to be bounded");
+
+        #[block]
+        {
+            <Pallet<T> as ProofVerification>::verify_post(POST_TYPE, RANDOMNESS, partitions, proof)
+                .expect("proofs to be verified");
+        }
+    }
+
+    /// Verifies all of the sectors' proofs of a batched prove commit in a single batch.
+    #[benchmark]
+    fn verify_batch_porep(s: Linear<1, MAX_BATCHED_SECTORS>) {
+        let (sectors, proof) = setup_sectors::<T>(s);
+        let sectors = sectors.try_into().expect("sectors to be bounded");
+        let proof = proof.try_into().expect("proof to be bounded");
+
+        #[block]
+        {
+            <Pallet<T> as ProofVerification>::verify_batch_porep(
+                PROVER_ID, SEAL_PROOF, sectors, proof,
+            )
+            .expect("proofs to be verified");
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
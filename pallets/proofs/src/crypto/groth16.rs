@@ -4,10 +4,14 @@ use core::ops::{AddAssign, Neg};
 
 use codec::{Decode, Encode};
 pub use polka_storage_proofs::{Bls12, PrimeField, Proof, Scalar as Fr, VerifyingKey};
-use polka_storage_proofs::{Curve, MillerLoopResult, MultiMillerLoop, PrimeCurveAffine};
+use polka_storage_proofs::{Curve, Field, MillerLoopResult, MultiMillerLoop, PrimeCurveAffine};
 use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
 
-use crate::Vec;
+use crate::{vec, Vec};
+
+/// Domain separation tag of the transcript the batch verification randomizers are derived from.
+const BATCH_VERIFICATION_DST: &[u8] = b"polka-storage-groth16-batch";
 
 /// The prepared verifying key needed in a Groth16 verification.
 ///
@@ -90,6 +94,108 @@ pub fn verify_proof<E: MultiMillerLoop>(
     }
 }
 
+/// Verifies a batch of Groth16 ZK-SNARK proofs, generated with the same verifying key, at once.
+///
+/// Each proof is combined with its own random scalar `r`, resulting in a single verification equation:
+/// sum(r * A * B) + sum(r * inputs) * (-gamma) + sum(r * C) * (-delta) = sum(r) * alpha * beta
+/// which takes a Miller loop per proof, plus two, and a single final exponentiation,
+/// instead of three Miller loops and a final exponentiation per proof.
+/// The public inputs are combined as well, so each element of `ic` is multiplied only once.
+///
+/// The random scalars must not be known before the proofs are generated,
+/// otherwise invalid proofs could be crafted to cancel each other out, see [`derive_batch_randomizers`].
+///
+/// References:
+/// - <https://github.com/zkcrypto/bellman/blob/main/groth16/src/verifier/batch.rs>
+pub fn verify_proofs_batch<E: MultiMillerLoop>(
+    vk: VerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[Vec<E::Fr>],
+    randomizers: &[E::Fr],
+) -> Result<(), VerificationError> {
+    // An empty batch would trivially satisfy the verification equation.
+    if proofs.is_empty() || proofs.len() != public_inputs.len() || proofs.len() != randomizers.len()
+    {
+        return Err(VerificationError::InvalidProof);
+    }
+
+    let pvk = prepare_verifying_key(vk);
+
+    if public_inputs
+        .iter()
+        .any(|inputs| (inputs.len() + 1) != pvk.ic.len())
+    {
+        return Err(VerificationError::InvalidVerifyingKey);
+    }
+
+    let mut randomizers_sum = E::Fr::ZERO;
+    let mut combined_inputs = vec![E::Fr::ZERO; pvk.ic.len() - 1];
+    let mut combined_c = E::G1Affine::identity().to_curve();
+    let mut randomized_a = Vec::with_capacity(proofs.len());
+    let mut prepared_b = Vec::with_capacity(proofs.len());
+
+    for ((proof, inputs), r) in proofs.iter().zip(public_inputs).zip(randomizers) {
+        randomizers_sum += r;
+        for (combined, input) in combined_inputs.iter_mut().zip(inputs) {
+            *combined += *input * r;
+        }
+        AddAssign::<&E::G1>::add_assign(&mut combined_c, &(proof.c * r));
+        randomized_a.push((proof.a * r).to_affine());
+        prepared_b.push(E::G2Prepared::from(proof.b));
+    }
+
+    let mut acc = pvk.ic[0] * randomizers_sum;
+
+    for (i, b) in combined_inputs.iter().zip(pvk.ic.iter().skip(1)) {
+        AddAssign::<&E::G1>::add_assign(&mut acc, &(*b * i));
+    }
+
+    let acc = acc.to_affine();
+    let combined_c = combined_c.to_affine();
+
+    let mut terms = randomized_a
+        .iter()
+        .zip(prepared_b.iter())
+        .collect::<Vec<_>>();
+    terms.push((&acc, &pvk.neg_gamma_g2));
+    terms.push((&combined_c, &pvk.neg_delta_g2));
+
+    if pvk.alpha_g1_beta_g2 * randomizers_sum == E::multi_miller_loop(&terms).final_exponentiation()
+    {
+        Ok(())
+    } else {
+        Err(VerificationError::InvalidProof)
+    }
+}
+
+/// Derives the random scalars the proofs are combined with in [`verify_proofs_batch`].
+///
+/// The scalars are derived from the hash of the proofs and their public inputs,
+/// so they can only be known after the proofs have been generated.
+/// They are 128 bits long, keeping the chance of an invalid batch being accepted negligible.
+pub fn derive_batch_randomizers(proofs: &[Proof<Bls12>], public_inputs: &[Vec<Fr>]) -> Vec<Fr> {
+    let mut transcript = Sha256::new().chain_update(BATCH_VERIFICATION_DST);
+    for proof in proofs {
+        transcript.update(proof.encode());
+    }
+    for input in public_inputs.iter().flatten() {
+        transcript.update(input.to_repr());
+    }
+    let transcript = transcript.finalize();
+
+    (0..proofs.len() as u64)
+        .map(|index| {
+            let hash = Sha256::new()
+                .chain_update(transcript)
+                .chain_update(index.to_le_bytes())
+                .finalize();
+            let low = u64::from_le_bytes(hash[0..8].try_into().expect("hash to have 32 bytes"));
+            let high = u64::from_le_bytes(hash[8..16].try_into().expect("hash to have 32 bytes"));
+            Fr::from_raw([low, high, 0, 0])
+        })
+        .collect()
+}
+
 /// Possible error types in a failed Groth16 ZK-SNARK verification.
 #[derive(Clone, Debug, Decode, Eq, Encode, PartialEq, TypeInfo)]
 pub enum VerificationError {
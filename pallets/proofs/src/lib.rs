@@ -8,11 +8,16 @@ pub(crate) use alloc::{vec, vec::Vec};
 
 pub use pallet::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarks;
 mod crypto;
 mod fr32;
 mod graphs;
+pub mod migrations;
 mod porep;
 mod post;
+mod update;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -24,52 +29,120 @@ mod tests;
 pub mod pallet {
     pub const LOG_TARGET: &'static str = "runtime::proofs";
 
-    use frame_support::{pallet_prelude::*, sp_runtime::BoundedBTreeMap};
+    use frame_support::{pallet_prelude::*, sp_runtime::BoundedBTreeMap, Hashable};
     use frame_system::pallet_prelude::*;
     use primitives::{
         commitment::RawCommitment,
-        pallets::ProofVerification,
-        proofs::{ProverId, PublicReplicaInfo, RegisteredPoStProof, RegisteredSealProof, Ticket},
+        pallets::{ProofVerification, VerifyingKeyInfo},
+        proofs::{
+            ProverId, PublicReplicaInfo, PublicSealInfo, RegisteredPoStProof, RegisteredSealProof,
+            RegisteredUpdateProof, Ticket,
+        },
         sector::SectorNumber,
-        MAX_POST_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS_PER_PROOF,
+        MAX_BATCHED_SECTORS, MAX_BATCH_PROOF_BYTES, MAX_PARTITIONS_PER_POST, MAX_POST_PROOF_BYTES,
+        MAX_REPLICA_UPDATE_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS_PER_PROOF,
+        MAX_WINDOWED_POST_PROOF_BYTES,
     };
 
     use crate::{
         crypto::groth16::{Bls12, Proof, VerifyingKey},
-        porep, post,
+        porep, post, update,
+        weights::WeightInfo,
     };
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Origin allowed to set the verifying keys, i.e. root or a collective.
+        /// Changing a key affects the verification of the proofs of the whole network.
+        type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Weight information of the proof verification, see [`ProofVerification::verify_post_weight`]
+        /// and [`ProofVerification::verify_batch_porep_weight`].
+        type WeightInfo: WeightInfo;
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
-    /// Verifying Key for verifying all of the PoRep proofs generated for 2KiB sectors.
-    /// One per runtime.
+    /// Verifying Keys for verifying all of the PoRep proofs, one per seal proof type.
+    ///
+    /// They should be set via some kind of trusted setup procedure.
+    /// Test key can be generated via `polka-storage-provider-client utils porep-params`.
+    #[pallet::storage]
+    pub type PoRepVerifyingKey<T: Config> =
+        StorageMap<_, _, RegisteredSealProof, VerifyingKey<Bls12>, OptionQuery>;
+
+    /// Verifying Keys for verifying all of the non-interactive PoRep proofs, one per seal proof type.
     ///
-    /// It should be set via some kind of trusted setup procedure.
-    /// /// Test key can be generated via `polka-storage-provider-client utils porep-params`.
-    /// To support more sector sizes for proofs, this data structure would need to be a Map from Sector Size to a Verifying Key.
+    /// Non-interactive proofs use a different circuit than the interactive ones, so they need their own keys.
+    /// They should be set via some kind of trusted setup procedure.
+    /// Test key can be generated via `polka-storage-provider-client utils porep-params --seal-proof 2KiB-NI`.
     #[pallet::storage]
-    pub type PoRepVerifyingKey<T: Config> = StorageValue<_, VerifyingKey<Bls12>, OptionQuery>;
+    pub type NiPoRepVerifyingKey<T: Config> =
+        StorageMap<_, _, RegisteredSealProof, VerifyingKey<Bls12>, OptionQuery>;
 
-    /// Verifying Key for verifying all of the PoSt proofs generated for 2KiB sectors.
-    /// One per runtime.
+    /// Verifying Keys for verifying all of the PoSt proofs, one per PoSt proof type.
     ///
-    /// It should be set via some kind of trusted setup procedure.
+    /// They should be set via some kind of trusted setup procedure.
     /// Test key can be generated via `polka-storage-provider-client utils post-params`.
-    /// To support more sector sizes for proofs, this data structure would need to be a Map from Sector Size to a Verifying Key.
     #[pallet::storage]
-    pub type PoStVerifyingKey<T: Config> = StorageValue<_, VerifyingKey<Bls12>, OptionQuery>;
+    pub type PoStVerifyingKey<T: Config> =
+        StorageMap<_, _, RegisteredPoStProof, VerifyingKey<Bls12>, OptionQuery>;
+
+    /// Verifying Keys for verifying all of the replica update (SnapDeals) proofs, one per update proof type.
+    ///
+    /// They should be set via some kind of trusted setup procedure.
+    /// Test key can be generated via `polka-storage-provider-client proofs update-params`.
+    #[pallet::storage]
+    pub type ReplicaUpdateVerifyingKey<T: Config> =
+        StorageMap<_, _, RegisteredUpdateProof, VerifyingKey<Bls12>, OptionQuery>;
+
+    /// Hash and version of the PoRep verifying keys, one per seal proof type.
+    ///
+    /// Holds the information of both interactive and non-interactive keys, as their seal proof types differ.
+    #[pallet::storage]
+    pub type PoRepVerifyingKeyInfo<T: Config> =
+        StorageMap<_, _, RegisteredSealProof, VerifyingKeyInfo, OptionQuery>;
+
+    /// Hash and version of the PoSt verifying keys, one per PoSt proof type.
+    #[pallet::storage]
+    pub type PoStVerifyingKeyInfo<T: Config> =
+        StorageMap<_, _, RegisteredPoStProof, VerifyingKeyInfo, OptionQuery>;
+
+    /// Hash and version of the replica update verifying keys, one per update proof type.
+    #[pallet::storage]
+    pub type ReplicaUpdateVerifyingKeyInfo<T: Config> =
+        StorageMap<_, _, RegisteredUpdateProof, VerifyingKeyInfo, OptionQuery>;
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        PoRepVerifyingKeyChanged { who: T::AccountId },
-        PoStVerifyingKeyChanged { who: T::AccountId },
+        PoRepVerifyingKeyChanged {
+            seal_proof: RegisteredSealProof,
+            hash: [u8; 32],
+            version: u32,
+        },
+        NiPoRepVerifyingKeyChanged {
+            seal_proof: RegisteredSealProof,
+            hash: [u8; 32],
+            version: u32,
+        },
+        PoStVerifyingKeyChanged {
+            post_type: RegisteredPoStProof,
+            hash: [u8; 32],
+            version: u32,
+        },
+        ReplicaUpdateVerifyingKeyChanged {
+            update_proof: RegisteredUpdateProof,
+            hash: [u8; 32],
+            version: u32,
+        },
     }
 
     #[pallet::error]
@@ -79,6 +152,12 @@ pub mod pallet {
         MissingPoRepVerifyingKey,
         InvalidPoRepProof,
         InvalidVerifyingKey,
+        MissingReplicaUpdateVerifyingKey,
+        InvalidReplicaUpdateProof,
+        MissingNiPoRepVerifyingKey,
+        /// The seal proof type can't be verified this way,
+        /// non-interactive proofs are verified without a seed and interactive ones need it.
+        InvalidSealProofType,
         /// Returned in case of failed conversion, i.e. in `bytes_into_fr()`.
         Conversion,
     }
@@ -87,41 +166,153 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         pub fn set_porep_verifying_key(
             origin: OriginFor<T>,
+            seal_proof: RegisteredSealProof,
             verifying_key: crate::Vec<u8>,
         ) -> DispatchResult {
-            let caller = ensure_signed(origin)?;
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(!seal_proof.is_non_interactive(), {
+                log::error!(target: LOG_TARGET, "Non-interactive seal proof {:?} needs a non-interactive PoRep verifying key", seal_proof);
+                Error::<T>::InvalidSealProofType
+            });
             let vkey =
                 VerifyingKey::<Bls12>::decode(&mut verifying_key.as_slice()).map_err(|e| {
                     log::error!(target: LOG_TARGET, "failed to parse PoRep verifying key {:?}", e);
                     Error::<T>::Conversion
                 })?;
 
-            PoRepVerifyingKey::<T>::set(Some(vkey));
+            let VerifyingKeyInfo { hash, version } =
+                Self::next_key_info(PoRepVerifyingKeyInfo::<T>::get(seal_proof), &vkey);
+            PoRepVerifyingKey::<T>::insert(seal_proof, vkey);
+            PoRepVerifyingKeyInfo::<T>::insert(seal_proof, VerifyingKeyInfo { hash, version });
+
+            Self::deposit_event(Event::PoRepVerifyingKeyChanged {
+                seal_proof,
+                hash,
+                version,
+            });
+
+            Ok(())
+        }
 
-            Self::deposit_event(Event::PoRepVerifyingKeyChanged { who: caller });
+        pub fn set_ni_porep_verifying_key(
+            origin: OriginFor<T>,
+            seal_proof: RegisteredSealProof,
+            verifying_key: crate::Vec<u8>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(seal_proof.is_non_interactive(), {
+                log::error!(target: LOG_TARGET, "Interactive seal proof {:?} needs an interactive PoRep verifying key", seal_proof);
+                Error::<T>::InvalidSealProofType
+            });
+            let vkey =
+                VerifyingKey::<Bls12>::decode(&mut verifying_key.as_slice()).map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse NI-PoRep verifying key {:?}", e);
+                    Error::<T>::Conversion
+                })?;
+
+            let VerifyingKeyInfo { hash, version } =
+                Self::next_key_info(PoRepVerifyingKeyInfo::<T>::get(seal_proof), &vkey);
+            NiPoRepVerifyingKey::<T>::insert(seal_proof, vkey);
+            PoRepVerifyingKeyInfo::<T>::insert(seal_proof, VerifyingKeyInfo { hash, version });
+
+            Self::deposit_event(Event::NiPoRepVerifyingKeyChanged {
+                seal_proof,
+                hash,
+                version,
+            });
 
             Ok(())
         }
 
         pub fn set_post_verifying_key(
             origin: OriginFor<T>,
+            post_type: RegisteredPoStProof,
             verifying_key: crate::Vec<u8>,
         ) -> DispatchResult {
-            let caller = ensure_signed(origin)?;
+            T::UpdateOrigin::ensure_origin(origin)?;
             let vkey =
                 VerifyingKey::<Bls12>::decode(&mut verifying_key.as_slice()).map_err(|e| {
                     log::error!(target: LOG_TARGET, "failed to parse PoSt verifying key {:?}", e);
                     Error::<T>::Conversion
                 })?;
 
-            PoStVerifyingKey::<T>::set(Some(vkey));
+            let VerifyingKeyInfo { hash, version } =
+                Self::next_key_info(PoStVerifyingKeyInfo::<T>::get(post_type), &vkey);
+            PoStVerifyingKey::<T>::insert(post_type, vkey);
+            PoStVerifyingKeyInfo::<T>::insert(post_type, VerifyingKeyInfo { hash, version });
 
-            Self::deposit_event(Event::PoStVerifyingKeyChanged { who: caller });
+            Self::deposit_event(Event::PoStVerifyingKeyChanged {
+                post_type,
+                hash,
+                version,
+            });
+
+            Ok(())
+        }
+
+        pub fn set_replica_update_verifying_key(
+            origin: OriginFor<T>,
+            update_proof: RegisteredUpdateProof,
+            verifying_key: crate::Vec<u8>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            let vkey =
+                VerifyingKey::<Bls12>::decode(&mut verifying_key.as_slice()).map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse replica update verifying key {:?}", e);
+                    Error::<T>::Conversion
+                })?;
+
+            let VerifyingKeyInfo { hash, version } =
+                Self::next_key_info(ReplicaUpdateVerifyingKeyInfo::<T>::get(update_proof), &vkey);
+            ReplicaUpdateVerifyingKey::<T>::insert(update_proof, vkey);
+            ReplicaUpdateVerifyingKeyInfo::<T>::insert(
+                update_proof,
+                VerifyingKeyInfo { hash, version },
+            );
+
+            Self::deposit_event(Event::ReplicaUpdateVerifyingKeyChanged {
+                update_proof,
+                hash,
+                version,
+            });
 
             Ok(())
         }
     }
 
+    impl<T: Config> Pallet<T> {
+        /// Returns the hash and version of the PoRep verifying key of the seal proof type,
+        /// interactive or not.
+        pub fn porep_verifying_key_info(
+            seal_proof: RegisteredSealProof,
+        ) -> Option<VerifyingKeyInfo> {
+            PoRepVerifyingKeyInfo::<T>::get(seal_proof)
+        }
+
+        /// Returns the hash and version of the PoSt verifying key of the PoSt proof type.
+        pub fn post_verifying_key_info(post_type: RegisteredPoStProof) -> Option<VerifyingKeyInfo> {
+            PoStVerifyingKeyInfo::<T>::get(post_type)
+        }
+
+        /// Returns the hash and version of the replica update verifying key of the update proof type.
+        pub fn replica_update_verifying_key_info(
+            update_proof: RegisteredUpdateProof,
+        ) -> Option<VerifyingKeyInfo> {
+            ReplicaUpdateVerifyingKeyInfo::<T>::get(update_proof)
+        }
+
+        /// Computes the information of a newly set key, its version follows the one of the replaced key.
+        pub(crate) fn next_key_info(
+            previous: Option<VerifyingKeyInfo>,
+            vkey: &VerifyingKey<Bls12>,
+        ) -> VerifyingKeyInfo {
+            VerifyingKeyInfo {
+                hash: vkey.blake2_256(),
+                version: previous.map_or(1, |info| info.version.saturating_add(1)),
+            }
+        }
+    }
+
     impl<T: Config> ProofVerification for Pallet<T> {
         fn verify_porep(
             prover_id: ProverId,
@@ -133,6 +324,10 @@ pub mod pallet {
             seed: Ticket,
             proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
         ) -> DispatchResult {
+            ensure!(!seal_proof.is_non_interactive(), {
+                log::error!(target: LOG_TARGET, "Non-interactive seal proof {:?} can't be verified with a seed", seal_proof);
+                Error::<T>::InvalidSealProofType
+            });
             let proof_len = proof.len();
             ensure!(proof_len >= seal_proof.proof_size(), {
                 log::error!(
@@ -142,57 +337,226 @@ pub mod pallet {
                 );
                 Error::<T>::InvalidPoRepProof
             });
-            let proof = Proof::<Bls12>::decode(&mut proof.as_slice()).map_err(|e| {
-                log::error!(target: LOG_TARGET, "failed to parse PoRep proof {:?}", e);
-                Error::<T>::Conversion
-            })?;
             let proof_scheme = porep::ProofScheme::setup(seal_proof);
+            // The proof consists of a proof for each of the partitions, one after another.
+            let mut proof_bytes = proof.as_slice();
+            let proofs = (0..proof_scheme.partitions())
+                .map(|_| Proof::<Bls12>::decode(&mut proof_bytes))
+                .collect::<Result<crate::Vec<_>, _>>()
+                .map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse PoRep proof {:?}", e);
+                    Error::<T>::Conversion
+                })?;
 
-            let vkey = PoRepVerifyingKey::<T>::get().ok_or(Error::<T>::MissingPoRepVerifyingKey)?;
+            let vkey = PoRepVerifyingKey::<T>::get(seal_proof)
+                .ok_or(Error::<T>::MissingPoRepVerifyingKey)?;
             log::info!(target: LOG_TARGET, "Verifying PoRep proof for sector: {}...", sector);
             proof_scheme
                 .verify(
-                    &comm_r, &comm_d, &prover_id, sector, &ticket, &seed, vkey, &proof,
+                    &comm_r,
+                    &comm_d,
+                    &prover_id,
+                    sector,
+                    &ticket,
+                    Some(&seed),
+                    vkey,
+                    &proofs,
+                )
+                .map_err(Into::<Error<T>>::into)?;
+
+            Ok(())
+        }
+
+        fn verify_ni_porep(
+            prover_id: ProverId,
+            seal_proof: RegisteredSealProof,
+            comm_r: RawCommitment,
+            comm_d: RawCommitment,
+            sector: SectorNumber,
+            ticket: Ticket,
+            proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
+        ) -> DispatchResult {
+            ensure!(seal_proof.is_non_interactive(), {
+                log::error!(target: LOG_TARGET, "Interactive seal proof {:?} can't be verified without a seed", seal_proof);
+                Error::<T>::InvalidSealProofType
+            });
+            let proof_len = proof.len();
+            ensure!(proof_len >= seal_proof.proof_size(), {
+                log::error!(
+                    target: LOG_TARGET,
+                    "NI-PoRep proof submission does not contain enough bytes. Expected minimum length is {} got {}",
+                    seal_proof.proof_size(), proof_len
+                );
+                Error::<T>::InvalidPoRepProof
+            });
+            let proof_scheme = porep::ProofScheme::setup(seal_proof);
+            // The proof consists of a proof for each of the partitions, one after another.
+            let mut proof_bytes = proof.as_slice();
+            let proofs = (0..proof_scheme.partitions())
+                .map(|_| Proof::<Bls12>::decode(&mut proof_bytes))
+                .collect::<Result<crate::Vec<_>, _>>()
+                .map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse NI-PoRep proof {:?}", e);
+                    Error::<T>::Conversion
+                })?;
+
+            let vkey = NiPoRepVerifyingKey::<T>::get(seal_proof)
+                .ok_or(Error::<T>::MissingNiPoRepVerifyingKey)?;
+            log::info!(target: LOG_TARGET, "Verifying NI-PoRep proof for sector: {}...", sector);
+            proof_scheme
+                .verify(
+                    &comm_r, &comm_d, &prover_id, sector, &ticket, None, vkey, &proofs,
                 )
                 .map_err(Into::<Error<T>>::into)?;
 
             Ok(())
         }
 
+        fn verify_batch_porep(
+            prover_id: ProverId,
+            seal_proof: RegisteredSealProof,
+            sectors: BoundedVec<PublicSealInfo, ConstU32<MAX_BATCHED_SECTORS>>,
+            proof: BoundedVec<u8, ConstU32<MAX_BATCH_PROOF_BYTES>>,
+        ) -> DispatchResult {
+            ensure!(!seal_proof.is_non_interactive(), {
+                log::error!(target: LOG_TARGET, "Non-interactive seal proof {:?} can't be batched", seal_proof);
+                Error::<T>::InvalidSealProofType
+            });
+            ensure!(!sectors.is_empty(), {
+                log::error!(target: LOG_TARGET, "Batched PoRep proof does not contain any sectors");
+                Error::<T>::InvalidPoRepProof
+            });
+            let proof_len = proof.len();
+            let expected_len = seal_proof.proof_size() * sectors.len();
+            ensure!(proof_len == expected_len, {
+                log::error!(
+                    target: LOG_TARGET,
+                    "Batched PoRep proof has an unexpected length. Expected length is {} got {}",
+                    expected_len, proof_len
+                );
+                Error::<T>::InvalidPoRepProof
+            });
+            let proof_scheme = porep::ProofScheme::setup(seal_proof);
+            // The batch consists of the proofs of each of the sectors, one after another.
+            let mut proof_bytes = proof.as_slice();
+            let proofs = (0..sectors.len() * proof_scheme.partitions())
+                .map(|_| Proof::<Bls12>::decode(&mut proof_bytes))
+                .collect::<Result<crate::Vec<_>, _>>()
+                .map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse batched PoRep proof {:?}", e);
+                    Error::<T>::Conversion
+                })?;
+
+            let vkey = PoRepVerifyingKey::<T>::get(seal_proof)
+                .ok_or(Error::<T>::MissingPoRepVerifyingKey)?;
+            log::info!(target: LOG_TARGET, "Verifying batched PoRep proof for {} sectors...", sectors.len());
+            proof_scheme
+                .verify_batch(&prover_id, &sectors, vkey, &proofs)
+                .map_err(Into::<Error<T>>::into)?;
+
+            Ok(())
+        }
+
         fn verify_post(
             post_type: RegisteredPoStProof,
             randomness: Ticket,
-            replicas: BoundedBTreeMap<
-                SectorNumber,
-                PublicReplicaInfo,
-                ConstU32<MAX_SECTORS_PER_PROOF>,
+            partitions: BoundedVec<
+                BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>,
+                ConstU32<MAX_PARTITIONS_PER_POST>,
             >,
-            proof: BoundedVec<u8, ConstU32<MAX_POST_PROOF_BYTES>>,
+            proof: BoundedVec<u8, ConstU32<MAX_WINDOWED_POST_PROOF_BYTES>>,
         ) -> DispatchResult {
-            let replica_count = replicas.len();
-            ensure!(replica_count <= post_type.sector_count(), {
+            ensure!(!partitions.is_empty(), {
+                log::error!(target: LOG_TARGET, "PoSt proof does not contain any partitions");
+                Error::<T>::InvalidPoStProof
+            });
+            for replicas in &partitions {
+                let replica_count = replicas.len();
+                ensure!(replica_count <= post_type.sector_count(), {
+                    log::error!(
+                        target: LOG_TARGET,
+                        "Got more replicas than expected. Expected max replicas = {}, submitted replicas = {replica_count}",
+                        post_type.sector_count()
+                    );
+                    Error::<T>::InvalidPoStProof
+                });
+            }
+            let proof_len = proof.len();
+            let expected_len = MAX_POST_PROOF_BYTES as usize * partitions.len();
+            ensure!(proof_len == expected_len, {
                 log::error!(
                     target: LOG_TARGET,
-                    "Got more replicas than expected. Expected max replicas = {}, submitted replicas = {replica_count}",
-                    post_type.sector_count()
+                    "PoSt proof has an unexpected length. Expected length is {} got {}",
+                    expected_len, proof_len
                 );
                 Error::<T>::InvalidPoStProof
             });
-            let proof = Proof::<Bls12>::decode(&mut proof.as_slice()).map_err(|e| {
-                log::error!(target: LOG_TARGET, "failed to parse PoSt proof {:?}", e);
-                Error::<T>::Conversion
-            })?;
+            // The proof consists of a proof for each of the partitions, one after another.
+            let mut proof_bytes = proof.as_slice();
+            let proofs = (0..partitions.len())
+                .map(|_| Proof::<Bls12>::decode(&mut proof_bytes))
+                .collect::<Result<crate::Vec<_>, _>>()
+                .map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse PoSt proof {:?}", e);
+                    Error::<T>::Conversion
+                })?;
             let proof_scheme = post::ProofScheme::setup(post_type);
 
-            let vkey = PoStVerifyingKey::<T>::get().ok_or(Error::<T>::MissingPoStVerifyingKey)?;
+            let vkey =
+                PoStVerifyingKey::<T>::get(post_type).ok_or(Error::<T>::MissingPoStVerifyingKey)?;
             proof_scheme
-                .verify(randomness, replicas.clone(), vkey, proof)
+                .verify_batch(randomness, &partitions, vkey, &proofs)
                 .map_err(|e| {
-                    log::warn!(target: LOG_TARGET, "failed to verify PoSt proof: {:?}, for replicas: {:?}", e, replicas);
+                    log::warn!(target: LOG_TARGET, "failed to verify PoSt proof: {:?}, for partitions: {:?}", e, partitions);
                     Error::<T>::InvalidPoStProof
                 })?;
 
             Ok(())
         }
+
+        fn verify_batch_porep_weight(sectors: u32) -> Weight {
+            T::WeightInfo::verify_batch_porep(sectors)
+        }
+
+        fn verify_post_weight(partitions: u32) -> Weight {
+            T::WeightInfo::verify_post(partitions)
+        }
+
+        fn verify_replica_update(
+            update_proof: RegisteredUpdateProof,
+            comm_r_old: RawCommitment,
+            comm_r_new: RawCommitment,
+            comm_d_new: RawCommitment,
+            proof: BoundedVec<u8, ConstU32<MAX_REPLICA_UPDATE_PROOF_BYTES>>,
+        ) -> DispatchResult {
+            let proof_len = proof.len();
+            ensure!(proof_len >= update_proof.proof_size(), {
+                log::error!(
+                    target: LOG_TARGET,
+                    "Replica update proof submission does not contain enough bytes. Expected minimum length is {} got {}",
+                    update_proof.proof_size(), proof_len
+                );
+                Error::<T>::InvalidReplicaUpdateProof
+            });
+            let proof_scheme = update::ProofScheme::setup(update_proof);
+            // The update proof consists of a proof for each of the partitions, one after another.
+            let mut proof_bytes = proof.as_slice();
+            let proofs = (0..proof_scheme.partitions())
+                .map(|_| Proof::<Bls12>::decode(&mut proof_bytes))
+                .collect::<Result<crate::Vec<_>, _>>()
+                .map_err(|e| {
+                    log::error!(target: LOG_TARGET, "failed to parse replica update proof {:?}", e);
+                    Error::<T>::Conversion
+                })?;
+
+            let vkey = ReplicaUpdateVerifyingKey::<T>::get(update_proof)
+                .ok_or(Error::<T>::MissingReplicaUpdateVerifyingKey)?;
+            log::info!(target: LOG_TARGET, "Verifying replica update proof...");
+            proof_scheme
+                .verify(&comm_r_old, &comm_r_new, &comm_d_new, vkey, &proofs)
+                .map_err(Into::<Error<T>>::into)?;
+
+            Ok(())
+        }
     }
 }
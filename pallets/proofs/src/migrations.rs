@@ -0,0 +1,191 @@
+//! Storage migrations for the proofs pallet.
+
+/// Migrates the single verifying keys into the per proof type maps.
+pub mod v1 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    };
+    use primitives::proofs::{RegisteredPoStProof, RegisteredSealProof};
+
+    use crate::{
+        crypto::groth16::{Bls12, VerifyingKey},
+        Config, NiPoRepVerifyingKey, Pallet, PoRepVerifyingKey, PoStVerifyingKey, LOG_TARGET,
+    };
+
+    /// Storage items as they were before the migration, only 2KiB sectors were supported.
+    pub(crate) mod v0 {
+        use super::*;
+
+        #[frame_support::storage_alias]
+        pub type PoRepVerifyingKey<T: Config> =
+            StorageValue<Pallet<T>, VerifyingKey<Bls12>, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type NiPoRepVerifyingKey<T: Config> =
+            StorageValue<Pallet<T>, VerifyingKey<Bls12>, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type PoStVerifyingKey<T: Config> =
+            StorageValue<Pallet<T>, VerifyingKey<Bls12>, OptionQuery>;
+    }
+
+    /// Moves the previously set keys under the 2KiB proof types.
+    pub struct UncheckedMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            if let Some(vkey) = v0::PoRepVerifyingKey::<T>::take() {
+                PoRepVerifyingKey::<T>::insert(RegisteredSealProof::StackedDRG2KiBV1P1, vkey);
+                migrated += 1;
+            }
+            if let Some(vkey) = v0::NiPoRepVerifyingKey::<T>::take() {
+                NiPoRepVerifyingKey::<T>::insert(
+                    RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep,
+                    vkey,
+                );
+                migrated += 1;
+            }
+            if let Some(vkey) = v0::PoStVerifyingKey::<T>::take() {
+                PoStVerifyingKey::<T>::insert(RegisteredPoStProof::StackedDRGWindow2KiBV1P1, vkey);
+                migrated += 1;
+            }
+
+            log::info!(target: LOG_TARGET, "Migrated {} verifying keys", migrated);
+
+            // Each of the keys is read, and if present, removed and written into the map
+            T::DbWeight::get().reads_writes(3, 2 * migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV0ToV1`] which only runs when the on-chain storage version is 0.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Stores the hash and version of the verifying keys set before they were tracked.
+pub mod v2 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    };
+
+    use super::v3::v2::{ReplicaUpdateVerifyingKey, ReplicaUpdateVerifyingKeyInfo};
+    use crate::{
+        Config, NiPoRepVerifyingKey, Pallet, PoRepVerifyingKey, PoRepVerifyingKeyInfo,
+        PoStVerifyingKey, PoStVerifyingKeyInfo, LOG_TARGET,
+    };
+
+    /// Tracks every key already set as its first version.
+    pub struct UncheckedMigrateV1ToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            for (seal_proof, vkey) in
+                PoRepVerifyingKey::<T>::iter().chain(NiPoRepVerifyingKey::<T>::iter())
+            {
+                PoRepVerifyingKeyInfo::<T>::insert(
+                    seal_proof,
+                    Pallet::<T>::next_key_info(None, &vkey),
+                );
+                migrated += 1;
+            }
+            for (post_type, vkey) in PoStVerifyingKey::<T>::iter() {
+                PoStVerifyingKeyInfo::<T>::insert(
+                    post_type,
+                    Pallet::<T>::next_key_info(None, &vkey),
+                );
+                migrated += 1;
+            }
+            if let Some(vkey) = ReplicaUpdateVerifyingKey::<T>::get() {
+                ReplicaUpdateVerifyingKeyInfo::<T>::put(Pallet::<T>::next_key_info(None, &vkey));
+                migrated += 1;
+            }
+
+            log::info!(target: LOG_TARGET, "Stored the information of {} verifying keys", migrated);
+
+            // Each of the keys is read and its information written, the replica update key is always read
+            T::DbWeight::get().reads_writes(migrated + 1, migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV1ToV2`] which only runs when the on-chain storage version is 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Migrates the single replica update verifying key into the per update proof type map.
+pub mod v3 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    };
+    use primitives::{pallets::VerifyingKeyInfo, proofs::RegisteredUpdateProof};
+
+    use crate::{
+        crypto::groth16::{Bls12, VerifyingKey},
+        Config, Pallet, ReplicaUpdateVerifyingKey, ReplicaUpdateVerifyingKeyInfo, LOG_TARGET,
+    };
+
+    /// Storage items as they were before the migration, only 2KiB sectors could be updated.
+    pub(crate) mod v2 {
+        use super::*;
+
+        #[frame_support::storage_alias]
+        pub type ReplicaUpdateVerifyingKey<T: Config> =
+            StorageValue<Pallet<T>, VerifyingKey<Bls12>, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type ReplicaUpdateVerifyingKeyInfo<T: Config> =
+            StorageValue<Pallet<T>, VerifyingKeyInfo, OptionQuery>;
+    }
+
+    /// Moves the previously set replica update key, and its information, under the 2KiB update proof type.
+    pub struct UncheckedMigrateV2ToV3<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV2ToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            if let Some(vkey) = v2::ReplicaUpdateVerifyingKey::<T>::take() {
+                ReplicaUpdateVerifyingKey::<T>::insert(
+                    RegisteredUpdateProof::StackedDRG2KiBV1,
+                    vkey,
+                );
+                migrated += 1;
+            }
+            if let Some(info) = v2::ReplicaUpdateVerifyingKeyInfo::<T>::take() {
+                ReplicaUpdateVerifyingKeyInfo::<T>::insert(
+                    RegisteredUpdateProof::StackedDRG2KiBV1,
+                    info,
+                );
+                migrated += 1;
+            }
+
+            log::info!(target: LOG_TARGET, "Migrated {} replica update verifying key items", migrated);
+
+            // Both items are read, and if present, removed and written into the map
+            T::DbWeight::get().reads_writes(2, 2 * migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV2ToV3`] which only runs when the on-chain storage version is 2.
+    pub type MigrateV2ToV3<T> = VersionedMigration<
+        2,
+        3,
+        UncheckedMigrateV2ToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -1,5 +1,5 @@
 use frame_support::derive_impl;
-use frame_system::{mocking::MockBlock, GenesisConfig};
+use frame_system::{mocking::MockBlock, EnsureRoot, GenesisConfig};
 use sp_runtime::BuildStorage;
 
 // Configure a mock runtime to test the pallet.
@@ -33,6 +33,8 @@ impl frame_system::Config for Test {
 
 impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
+    type UpdateOrigin = EnsureRoot<u64>;
+    type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
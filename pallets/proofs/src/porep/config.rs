@@ -1,6 +1,6 @@
 use bls12_381::Scalar as Fr;
 use num_bigint::BigUint;
-use primitives::proofs::RegisteredSealProof;
+use primitives::proofs::{RegisteredSealProof, Ticket};
 use sha2::{Digest, Sha256};
 
 use crate::Vec;
@@ -15,7 +15,16 @@ pub type PoRepID = [u8; 32];
 pub struct Config {
     porep_id: PoRepID,
     nodes: usize,
-    challenges: InteractiveChallenges,
+    partitions: usize,
+    challenges: Challenges,
+}
+
+/// The way challenges are generated, depends on whether the seal proof is interactive.
+/// References:
+/// * <https://github.com/filecoin-project/rust-fil-proofs/blob/master/storage-proofs-porep/src/stacked/vanilla/challenges.rs>
+pub enum Challenges {
+    Interactive(InteractiveChallenges),
+    NonInteractive(NonInteractiveChallenges),
 }
 
 /// References:
@@ -63,14 +72,66 @@ impl InteractiveChallenges {
     }
 }
 
+/// Challenges derived from the replica itself, used by non-interactive PoRep.
+/// There's no interactive seed, the challenges are bound to CommR instead,
+/// which can't be known before the sector has been sealed.
+/// References:
+/// * <https://github.com/filecoin-project/rust-fil-proofs/blob/master/storage-proofs-porep/src/stacked/vanilla/challenges.rs>
+pub struct NonInteractiveChallenges {
+    challenges_per_partition: usize,
+}
+
+impl NonInteractiveChallenges {
+    /// Domain separation tag, so the challenges can't collide with the interactive ones.
+    const TAG: &'static [u8] = b"filecoin.io|PoRep|1|NonInteractive|1";
+
+    pub fn new(partitions: usize, minimum_total_challenges: usize) -> Self {
+        let challenges_per_partition = usize::div_ceil(minimum_total_challenges, partitions);
+        Self {
+            challenges_per_partition,
+        }
+    }
+
+    /// Returns the porep challenges for partition `k`.
+    pub fn derive(&self, sector_nodes: usize, replica_id: &Fr, comm_r: &Fr, k: u8) -> Vec<usize> {
+        let hasher = Sha256::new()
+            .chain_update(Self::TAG)
+            .chain_update(replica_id.to_bytes())
+            .chain_update(comm_r.to_bytes());
+
+        (0..self.challenges_per_partition)
+            .map(|i| {
+                let j: u32 = ((self.challenges_per_partition * k as usize) + i) as u32;
+
+                let hash = hasher.clone().chain_update(j.to_le_bytes()).finalize();
+
+                let bigint = BigUint::from_bytes_le(hash.as_ref());
+                bigint_to_challenge(bigint, sector_nodes)
+            })
+            .collect()
+    }
+}
+
 impl Config {
     pub fn new(seal_proof: RegisteredSealProof) -> Self {
         let partitions = partitions(seal_proof);
+        let challenges = if seal_proof.is_non_interactive() {
+            Challenges::NonInteractive(NonInteractiveChallenges::new(
+                partitions,
+                minimum_challenges(seal_proof),
+            ))
+        } else {
+            Challenges::Interactive(InteractiveChallenges::new(
+                partitions,
+                minimum_challenges(seal_proof),
+            ))
+        };
         Self {
             porep_id: porep_id(seal_proof),
             // PRE-COND: sector size must be divisible by 32
             nodes: (seal_proof.sector_size().bytes() / 32) as usize,
-            challenges: InteractiveChallenges::new(partitions, minimum_challenges(seal_proof)),
+            partitions,
+            challenges,
         }
     }
 
@@ -78,6 +139,12 @@ impl Config {
         self.porep_id
     }
 
+    /// Number of partitions of a proof, each one of them is a separate Groth16 proof,
+    /// with its own challenges.
+    pub fn partitions(&self) -> usize {
+        self.partitions
+    }
+
     /// Expected number of nodes in the base Depth Robust Graph
     /// References:
     /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/parameters.rs#L89>
@@ -85,8 +152,24 @@ impl Config {
         self.nodes
     }
 
-    pub fn challenges(&self, leaves: usize, replica_id: &Fr, seed: &[u8; 32], k: u8) -> Vec<usize> {
-        self.challenges.derive(leaves, replica_id, seed, k)
+    /// Returns the challenges for partition `k`.
+    /// Interactive challenges require the `seed`, returns [`None`] when it's missing.
+    pub fn challenges(
+        &self,
+        leaves: usize,
+        replica_id: &Fr,
+        comm_r: &Fr,
+        seed: Option<&Ticket>,
+        k: u8,
+    ) -> Option<Vec<usize>> {
+        match &self.challenges {
+            Challenges::Interactive(challenges) => {
+                Some(challenges.derive(leaves, replica_id, seed?, k))
+            }
+            Challenges::NonInteractive(challenges) => {
+                Some(challenges.derive(leaves, replica_id, comm_r, k))
+            }
+        }
     }
 }
 
@@ -117,22 +200,42 @@ pub fn porep_id(seal_proof: RegisteredSealProof) -> PoRepID {
 fn proof_id(seal_proof: RegisteredSealProof) -> u64 {
     match seal_proof {
         RegisteredSealProof::StackedDRG2KiBV1P1 => 0,
+        RegisteredSealProof::StackedDRG8MiBV1P1 => 1,
+        RegisteredSealProof::StackedDRG512MiBV1P1 => 2,
+        // Non-interactive types get their own ID, so their graph and parameters are never shared with the interactive ones.
+        RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep => 15,
+        RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep => 16,
+        RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => 17,
     }
 }
 
 /// Reference:
 /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/constants.rs#L65>
+/// * <https://github.com/filecoin-project/rust-fil-proofs/blob/master/filecoin-proofs/src/constants.rs> (`get_porep_non_interactive_partitions`)
 fn partitions(seal_proof: RegisteredSealProof) -> usize {
     match seal_proof {
-        RegisteredSealProof::StackedDRG2KiBV1P1 => 1,
+        RegisteredSealProof::StackedDRG2KiBV1P1
+        | RegisteredSealProof::StackedDRG8MiBV1P1
+        | RegisteredSealProof::StackedDRG512MiBV1P1 => 1,
+        RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep
+        | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep
+        | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => 2,
     }
 }
 
 /// Reference:
 /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/5a0523ae1ddb73b415ce2fa819367c7989aaf73f/filecoin-proofs/src/constants.rs#L123>
-fn minimum_challenges(seal_proof: RegisteredSealProof) -> usize {
+/// * <https://github.com/filecoin-project/rust-fil-proofs/blob/master/filecoin-proofs/src/constants.rs> (`get_porep_non_interactive_minimum_challenges`)
+pub(super) fn minimum_challenges(seal_proof: RegisteredSealProof) -> usize {
     match seal_proof {
-        RegisteredSealProof::StackedDRG2KiBV1P1 => 2,
+        RegisteredSealProof::StackedDRG2KiBV1P1
+        | RegisteredSealProof::StackedDRG8MiBV1P1
+        | RegisteredSealProof::StackedDRG512MiBV1P1 => 2,
+        // Without a seed, the prover could grind CommR until it gets favourable challenges,
+        // more challenges are required to make that impractical.
+        RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep
+        | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep
+        | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => 4,
     }
 }
 
@@ -3,14 +3,15 @@ mod config;
 use config::{Config, PoRepID};
 use primitives::{
     commitment::RawCommitment,
-    proofs::{ProverId, RegisteredSealProof, Ticket},
+    proofs::{ProverId, PublicSealInfo, RegisteredSealProof, Ticket},
     sector::SectorNumber,
 };
 use sha2::{Digest, Sha256};
 
 use crate::{
     crypto::groth16::{
-        verify_proof, Bls12, Fr, PrimeField, Proof, VerificationError, VerifyingKey,
+        derive_batch_randomizers, verify_proof, verify_proofs_batch, Bls12, Fr, PrimeField, Proof,
+        VerificationError, VerifyingKey,
     },
     fr32,
     graphs::{
@@ -117,7 +118,8 @@ pub struct Tau {
 pub struct PublicInputs {
     replica_id: ReplicaId,
     tau: Tau,
-    seed: Ticket,
+    /// Interactive randomness, non-interactive proofs don't have any.
+    seed: Option<Ticket>,
 }
 
 impl ProofScheme {
@@ -133,6 +135,14 @@ impl ProofScheme {
         Self { config, graph }
     }
 
+    /// Number of partitions of a sector's proof, i.e. the number of Groth16 proofs it's made of.
+    pub fn partitions(&self) -> usize {
+        self.config.partitions()
+    }
+
+    /// Verifies the proof of a sector, made of one Groth16 proof per partition, in partition order.
+    /// A proof with a single partition is checked on its own, the partitions of a bigger one are checked in a batch.
+    ///
     /// References:
     /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/storage-proofs-core/src/compound_proof.rs#L148>
     pub fn verify(
@@ -142,26 +152,83 @@ impl ProofScheme {
         prover_id: &ProverId,
         sector: SectorNumber,
         ticket: &Ticket,
-        seed: &Ticket,
+        seed: Option<&Ticket>,
         vk: VerifyingKey<Bls12>,
-        proof: &Proof<Bls12>,
+        proofs: &[Proof<Bls12>],
     ) -> Result<(), ProofError> {
+        if proofs.len() != self.partitions() {
+            return Err(ProofError::InvalidProof);
+        }
+        let public_inputs =
+            self.sector_public_inputs(comm_r, comm_d, prover_id, sector, ticket, seed)?;
+
+        if let ([proof], [public_inputs]) = (proofs, public_inputs.as_slice()) {
+            return verify_proof(vk, proof, public_inputs).map_err(Into::<ProofError>::into);
+        }
+        let randomizers = derive_batch_randomizers(proofs, &public_inputs);
+
+        verify_proofs_batch(vk, proofs, &public_inputs, &randomizers)
+            .map_err(Into::<ProofError>::into)
+    }
+
+    /// Verifies the proofs of many sectors at once, `proofs` must be in the same order as `sectors`,
+    /// with the partitions of each sector's proof one after another.
+    pub fn verify_batch(
+        &self,
+        prover_id: &ProverId,
+        sectors: &[PublicSealInfo],
+        vk: VerifyingKey<Bls12>,
+        proofs: &[Proof<Bls12>],
+    ) -> Result<(), ProofError> {
+        if sectors.len() * self.partitions() != proofs.len() {
+            return Err(ProofError::InvalidProof);
+        }
+
+        let mut public_inputs = Vec::with_capacity(proofs.len());
+        for sector in sectors {
+            public_inputs.extend(self.sector_public_inputs(
+                &sector.comm_r,
+                &sector.comm_d,
+                prover_id,
+                sector.sector,
+                &sector.ticket,
+                Some(&sector.seed),
+            )?);
+        }
+        let randomizers = derive_batch_randomizers(proofs, &public_inputs);
+
+        verify_proofs_batch(vk, proofs, &public_inputs, &randomizers)
+            .map_err(Into::<ProofError>::into)
+    }
+
+    /// Generates the public inputs of every partition of a sector's proof.
+    fn sector_public_inputs(
+        &self,
+        comm_r: &RawCommitment,
+        comm_d: &RawCommitment,
+        prover_id: &ProverId,
+        sector: SectorNumber,
+        ticket: &Ticket,
+        seed: Option<&Ticket>,
+    ) -> Result<Vec<Vec<Fr>>, ProofError> {
         let comm_d_fr = fr32::bytes_into_fr(comm_d).map_err(|_| ProofError::Conversion)?;
         let comm_r_fr = fr32::bytes_into_fr(comm_r).map_err(|_| ProofError::Conversion)?;
 
         let replica_id = self.generate_replica_id(prover_id, sector, ticket, comm_d);
-        let public_inputs = PublicInputs {
-            replica_id,
-            tau: Tau {
-                comm_d: comm_d_fr,
-                comm_r: comm_r_fr,
-            },
-            seed: *seed,
-        };
 
-        let public_inputs = self.generate_public_inputs(public_inputs, None)?;
-
-        verify_proof(vk, proof, public_inputs.as_slice()).map_err(Into::<ProofError>::into)
+        (0..self.partitions())
+            .map(|k| {
+                let public_inputs = PublicInputs {
+                    replica_id,
+                    tau: Tau {
+                        comm_d: comm_d_fr,
+                        comm_r: comm_r_fr,
+                    },
+                    seed: seed.copied(),
+                };
+                self.generate_public_inputs(public_inputs, Some(k))
+            })
+            .collect()
     }
 
     /// References:
@@ -200,7 +267,10 @@ impl ProofScheme {
             seed,
         } = public_inputs;
         let leaves = self.graph.size();
-        let challenges = self.config.challenges(leaves, &replica_id, &seed, k as u8);
+        let challenges = self
+            .config
+            .challenges(leaves, &replica_id, &comm_r, seed.as_ref(), k as u8)
+            .ok_or(ProofError::InvalidProof)?;
 
         let mut inputs = Vec::new();
         inputs.push(replica_id);
@@ -254,7 +324,10 @@ fn generate_inclusion_input(challenge: usize) -> Fr {
 mod tests {
     use primitives::{proofs::RegisteredSealProof, sector::SectorNumber};
 
-    use super::{ProofScheme, PublicInputs, Tau};
+    use super::{
+        config::{minimum_challenges, porep_id, Config},
+        ProofScheme, PublicInputs, Tau,
+    };
 
     #[test]
     /// References:
@@ -283,6 +356,103 @@ mod tests {
         }
     }
 
+    #[test]
+    /// The proofs are generated with the `filecoin-proofs` configuration,
+    /// so the partitions and challenges must match it or valid proofs would be rejected.
+    fn config_matches_reference_impl() {
+        use filecoin_proofs::PoRepConfig;
+        use storage_proofs_core::api_version::{ApiFeature, ApiVersion};
+
+        for seal_proof in [
+            RegisteredSealProof::StackedDRG2KiBV1P1,
+            RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep,
+            RegisteredSealProof::StackedDRG8MiBV1P1,
+            RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep,
+            RegisteredSealProof::StackedDRG512MiBV1P1,
+            RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep,
+        ] {
+            let features = if seal_proof.is_non_interactive() {
+                vec![ApiFeature::NonInteractivePoRep]
+            } else {
+                vec![]
+            };
+            let reference = PoRepConfig::new_groth16_with_features(
+                seal_proof.sector_size().bytes(),
+                porep_id(seal_proof),
+                ApiVersion::V1_2_0,
+                features,
+            )
+            .unwrap();
+            let config = Config::new(seal_proof);
+
+            assert_eq!(config.partitions(), usize::from(reference.partitions));
+            assert_eq!(
+                minimum_challenges(seal_proof),
+                reference.minimum_challenges()
+            );
+            // Every partition is a Groth16 proof of 192 bytes.
+            assert_eq!(seal_proof.proof_size(), config.partitions() * 192);
+        }
+    }
+
+    #[test]
+    fn non_interactive_public_inputs_are_bound_to_comm_r() {
+        let proof_scheme = ProofScheme::setup(RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep);
+        let replica_id = proof_scheme.generate_replica_id(
+            &[77u8; 32],
+            SectorNumber::new(123).unwrap(),
+            &[10u8; 32],
+            &[15u8; 32],
+        );
+        let public_inputs = |comm_r: u8| {
+            let comm_r =
+                crate::fr32::bytes_into_fr(&crate::fr32::bytes_into_fr_repr_safe(&[comm_r; 32]))
+                    .unwrap();
+            PublicInputs {
+                replica_id,
+                tau: Tau {
+                    comm_d: replica_id,
+                    comm_r,
+                },
+                seed: None,
+            }
+        };
+
+        let inputs = proof_scheme
+            .generate_public_inputs(public_inputs(151), None)
+            .unwrap();
+        let other_inputs = proof_scheme
+            .generate_public_inputs(public_inputs(152), None)
+            .unwrap();
+
+        // Same amount of challenges, but different challenged nodes.
+        assert_eq!(inputs.len(), other_inputs.len());
+        assert_ne!(inputs[3..], other_inputs[3..]);
+    }
+
+    #[test]
+    fn interactive_public_inputs_require_seed() {
+        let proof_scheme = ProofScheme::setup(RegisteredSealProof::StackedDRG2KiBV1P1);
+        let replica_id = proof_scheme.generate_replica_id(
+            &[77u8; 32],
+            SectorNumber::new(123).unwrap(),
+            &[10u8; 32],
+            &[15u8; 32],
+        );
+        let public_inputs = PublicInputs {
+            replica_id,
+            tau: Tau {
+                comm_d: replica_id,
+                comm_r: replica_id,
+            },
+            seed: None,
+        };
+
+        assert!(proof_scheme
+            .generate_public_inputs(public_inputs, None)
+            .is_err());
+    }
+
     fn ported_generate_public_inputs(
         prover_id: &[u8; 32],
         sector_id: SectorNumber,
@@ -305,7 +475,7 @@ mod tests {
                 comm_d: comm_d_fr,
                 comm_r: comm_r_fr,
             },
-            seed: seed.clone(),
+            seed: Some(seed.clone()),
         };
 
         proof_scheme
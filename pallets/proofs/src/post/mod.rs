@@ -12,7 +12,10 @@ use primitives::{
 use sha2::{Digest, Sha256};
 
 use crate::{
-    crypto::groth16::{verify_proof, Bls12, Fr, Proof, VerificationError, VerifyingKey},
+    crypto::groth16::{
+        derive_batch_randomizers, verify_proof, verify_proofs_batch, Bls12, Fr, Proof,
+        VerificationError, VerifyingKey,
+    },
     fr32, Vec,
 };
 
@@ -40,11 +43,61 @@ impl ProofScheme {
     pub fn verify(
         &self,
         randomness: Ticket,
-        replicas: BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>,
+        replicas: &BoundedBTreeMap<
+            SectorNumber,
+            PublicReplicaInfo,
+            ConstU32<MAX_SECTORS_PER_PROOF>,
+        >,
         vk: VerifyingKey<Bls12>,
-        proof: Proof<Bls12>,
+        proof: &Proof<Bls12>,
     ) -> Result<(), ProofError> {
-        let randomness = fr32::bytes_into_fr(&randomness)
+        let inputs = self.partition_public_inputs(&randomness, replicas)?;
+        verify_proof(vk, proof, inputs.as_slice())?;
+        Ok(())
+    }
+
+    /// Verifies PoSt for many partitions at once, `proofs` must be in the same order as `partitions`.
+    ///
+    /// The proofs are combined with random scalars and checked with a single final exponentiation,
+    /// a single proof is checked on its own, as the randomization would only add work.
+    pub fn verify_batch(
+        &self,
+        randomness: Ticket,
+        partitions: &[BoundedBTreeMap<
+            SectorNumber,
+            PublicReplicaInfo,
+            ConstU32<MAX_SECTORS_PER_PROOF>,
+        >],
+        vk: VerifyingKey<Bls12>,
+        proofs: &[Proof<Bls12>],
+    ) -> Result<(), ProofError> {
+        if partitions.len() != proofs.len() {
+            return Err(ProofError::InvalidNumberOfProofs);
+        }
+        if let ([replicas], [proof]) = (partitions, proofs) {
+            return self.verify(randomness, replicas, vk, proof);
+        }
+
+        let public_inputs = partitions
+            .iter()
+            .map(|replicas| self.partition_public_inputs(&randomness, replicas))
+            .collect::<Result<Vec<_>, _>>()?;
+        let randomizers = derive_batch_randomizers(proofs, &public_inputs);
+
+        verify_proofs_batch(vk, proofs, &public_inputs, &randomizers).map_err(Into::into)
+    }
+
+    /// Generates the public inputs of a partition's proof, proving all of the `replicas`.
+    fn partition_public_inputs(
+        &self,
+        randomness: &Ticket,
+        replicas: &BoundedBTreeMap<
+            SectorNumber,
+            PublicReplicaInfo,
+            ConstU32<MAX_SECTORS_PER_PROOF>,
+        >,
+    ) -> Result<Vec<Fr>, ProofError> {
+        let randomness = fr32::bytes_into_fr(randomness)
             .map_err(|_| ProofError::Conversion)?
             .into();
 
@@ -55,13 +108,11 @@ impl ProofScheme {
         .unwrap_or(1);
 
         if required_partitions != 1 {
-            // We don't support more than 1 partition in this method right now.
+            // Each partition is proven by its own proof, the replicas must fit in a single one.
             return Err(ProofError::InvalidNumberOfProofs);
         }
 
-        // NOTE:
-        //  * This is checked after the required partitions on purpose!
-        //  * Once we support verification of multiple partitions this check should be done for every partition
+        // NOTE: This is checked after the required partitions on purpose!
         let replica_count = replicas.len();
         ensure!(
             replica_count <= self.config.challenged_sectors_per_partition,
@@ -90,9 +141,7 @@ impl ProofScheme {
             sectors: pub_sectors,
         };
 
-        let inputs = self.generate_public_inputs(public_inputs, None)?;
-        verify_proof(vk, &proof, inputs.as_slice())?;
-        Ok(())
+        self.generate_public_inputs(public_inputs, None)
     }
 
     /// References:
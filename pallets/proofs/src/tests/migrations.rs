@@ -0,0 +1,129 @@
+use codec::Decode;
+use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+use polka_storage_proofs::{Bls12, VerifyingKey};
+use primitives::{
+    pallets::VerifyingKeyInfo,
+    proofs::{RegisteredPoStProof, RegisteredSealProof, RegisteredUpdateProof},
+};
+
+use super::{porep::default_porep_verifyingkey, post::default_post_verifyingkey};
+use crate::{
+    migrations::{
+        v1::{v0, MigrateV0ToV1},
+        v2::MigrateV1ToV2,
+        v3::{v2, MigrateV2ToV3},
+    },
+    mock::*,
+    NiPoRepVerifyingKey, PoRepVerifyingKey, PoStVerifyingKey, ReplicaUpdateVerifyingKey,
+};
+
+#[test]
+fn migrates_verifying_keys_to_maps() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<ProofsModule>();
+        let porep_vk: VerifyingKey<Bls12> =
+            Decode::decode(&mut default_porep_verifyingkey().as_slice()).unwrap();
+        let post_vk: VerifyingKey<Bls12> =
+            Decode::decode(&mut default_post_verifyingkey().as_slice()).unwrap();
+        v0::PoRepVerifyingKey::<Test>::put(porep_vk.clone());
+        v0::PoStVerifyingKey::<Test>::put(post_vk.clone());
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(ProofsModule::on_chain_storage_version(), 1);
+        assert!(!v0::PoRepVerifyingKey::<Test>::exists());
+        assert!(!v0::PoStVerifyingKey::<Test>::exists());
+        assert_eq!(
+            PoRepVerifyingKey::<Test>::get(RegisteredSealProof::StackedDRG2KiBV1P1),
+            Some(porep_vk)
+        );
+        assert_eq!(
+            PoStVerifyingKey::<Test>::get(RegisteredPoStProof::StackedDRGWindow2KiBV1P1),
+            Some(post_vk)
+        );
+        // The non-interactive key was never set
+        assert_eq!(
+            NiPoRepVerifyingKey::<Test>::get(RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep),
+            None
+        );
+    });
+}
+
+#[test]
+fn migration_skipped_when_up_to_date() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<ProofsModule>();
+        let porep_vk: VerifyingKey<Bls12> =
+            Decode::decode(&mut default_porep_verifyingkey().as_slice()).unwrap();
+        v0::PoRepVerifyingKey::<Test>::put(porep_vk);
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+        assert!(v0::PoRepVerifyingKey::<Test>::exists());
+        assert_eq!(
+            PoRepVerifyingKey::<Test>::get(RegisteredSealProof::StackedDRG2KiBV1P1),
+            None
+        );
+    });
+}
+
+#[test]
+fn migrates_verifying_key_info() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<ProofsModule>();
+        let vk = default_post_verifyingkey();
+        let post_vk: VerifyingKey<Bls12> = Decode::decode(&mut vk.as_slice()).unwrap();
+        PoStVerifyingKey::<Test>::insert(RegisteredPoStProof::StackedDRGWindow2KiBV1P1, post_vk);
+
+        MigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(ProofsModule::on_chain_storage_version(), 2);
+        assert_eq!(
+            ProofsModule::post_verifying_key_info(RegisteredPoStProof::StackedDRGWindow2KiBV1P1),
+            Some(VerifyingKeyInfo {
+                hash: sp_core::blake2_256(&vk),
+                version: 1,
+            })
+        );
+        assert_eq!(
+            ProofsModule::porep_verifying_key_info(RegisteredSealProof::StackedDRG2KiBV1P1),
+            None
+        );
+    });
+}
+
+#[test]
+fn migrates_replica_update_verifying_key_to_map() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(2).put::<ProofsModule>();
+        // Any valid key will do, the migration doesn't check what it verifies
+        let vk = default_post_verifyingkey();
+        let update_vk: VerifyingKey<Bls12> = Decode::decode(&mut vk.as_slice()).unwrap();
+        let info = VerifyingKeyInfo {
+            hash: sp_core::blake2_256(&vk),
+            version: 2,
+        };
+        v2::ReplicaUpdateVerifyingKey::<Test>::put(update_vk.clone());
+        v2::ReplicaUpdateVerifyingKeyInfo::<Test>::put(info);
+
+        MigrateV2ToV3::<Test>::on_runtime_upgrade();
+
+        assert_eq!(ProofsModule::on_chain_storage_version(), 3);
+        assert!(!v2::ReplicaUpdateVerifyingKey::<Test>::exists());
+        assert!(!v2::ReplicaUpdateVerifyingKeyInfo::<Test>::exists());
+        assert_eq!(
+            ReplicaUpdateVerifyingKey::<Test>::get(RegisteredUpdateProof::StackedDRG2KiBV1),
+            Some(update_vk)
+        );
+        assert_eq!(
+            ProofsModule::replica_update_verifying_key_info(
+                RegisteredUpdateProof::StackedDRG2KiBV1
+            ),
+            Some(info)
+        );
+        assert_eq!(
+            ReplicaUpdateVerifyingKey::<Test>::get(RegisteredUpdateProof::StackedDRG8MiBV1),
+            None
+        );
+    });
+}
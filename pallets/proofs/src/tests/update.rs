@@ -0,0 +1,133 @@
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok};
+use polka_storage_proofs::{Bls12, Proof, VerifyingKey};
+use primitives::{
+    commitment::RawCommitment, pallets::ProofVerification, proofs::RegisteredUpdateProof,
+};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+use sp_runtime::BoundedVec;
+
+use crate::{mock::*, tests::TEST_SEED, Error, ReplicaUpdateVerifyingKey};
+
+/// Number of public inputs of the replica update circuit:
+/// partition index, comm_r_old, comm_d_new, comm_r_new and h_select.
+const PUBLIC_INPUTS: usize = 5;
+
+#[test]
+fn sets_replica_update_verifying_key() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            None,
+            ReplicaUpdateVerifyingKey::<Test>::get(RegisteredUpdateProof::StackedDRG2KiBV1)
+        );
+        let mut rng = XorShiftRng::from_seed(TEST_SEED);
+        let vk = Encode::encode(&VerifyingKey::<Bls12>::random(&mut rng));
+
+        assert_ok!(ProofsModule::set_replica_update_verifying_key(
+            RuntimeOrigin::root(),
+            RegisteredUpdateProof::StackedDRG2KiBV1,
+            vk.clone()
+        ));
+        let scale_vk: VerifyingKey<Bls12> = Decode::decode(&mut vk.as_slice()).unwrap();
+        assert_eq!(
+            Some(scale_vk),
+            ReplicaUpdateVerifyingKey::<Test>::get(RegisteredUpdateProof::StackedDRG2KiBV1)
+        );
+    });
+}
+
+#[test]
+fn replica_update_verification_fails_without_verifying_key() {
+    new_test_ext().execute_with(|| {
+        let mut rng = XorShiftRng::from_seed(TEST_SEED);
+        let proof = Encode::encode(&Proof::<Bls12>::random(&mut rng));
+
+        assert_noop!(
+            verify_replica_update(proof),
+            Error::<Test>::MissingReplicaUpdateVerifyingKey
+        );
+    });
+}
+
+#[test]
+fn replica_update_verification_fails_missing_verifying_key_for_sector_size() {
+    new_test_ext().execute_with(|| {
+        let mut rng = XorShiftRng::from_seed(TEST_SEED);
+        // Only the 8MiB key is set
+        assert_ok!(ProofsModule::set_replica_update_verifying_key(
+            RuntimeOrigin::root(),
+            RegisteredUpdateProof::StackedDRG8MiBV1,
+            Encode::encode(&VerifyingKey::<Bls12>::random(&mut rng))
+        ));
+        let proof = Encode::encode(&Proof::<Bls12>::random(&mut rng));
+
+        assert_noop!(
+            verify_replica_update(proof),
+            Error::<Test>::MissingReplicaUpdateVerifyingKey
+        );
+    });
+}
+
+#[test]
+fn replica_update_verification_fails_with_short_proof() {
+    new_test_ext().execute_with(|| {
+        let mut rng = XorShiftRng::from_seed(TEST_SEED);
+        let mut proof = Encode::encode(&Proof::<Bls12>::random(&mut rng));
+        proof.truncate(proof.len() - 1);
+
+        assert_noop!(
+            verify_replica_update(proof),
+            Error::<Test>::InvalidReplicaUpdateProof
+        );
+    });
+}
+
+#[test]
+fn replica_update_verification_fails() {
+    new_test_ext().execute_with(|| {
+        let mut rng = XorShiftRng::from_seed(TEST_SEED);
+        // Random verifying key, with an input commitment for each of the public inputs
+        let mut vk = VerifyingKey::<Bls12>::random(&mut rng);
+        vk.ic = vk
+            .ic
+            .iter()
+            .cycle()
+            .take(PUBLIC_INPUTS + 1)
+            .copied()
+            .collect();
+        assert_ok!(ProofsModule::set_replica_update_verifying_key(
+            RuntimeOrigin::root(),
+            RegisteredUpdateProof::StackedDRG2KiBV1,
+            Encode::encode(&vk)
+        ));
+        let proof = Encode::encode(&Proof::<Bls12>::random(&mut rng));
+
+        assert_noop!(
+            verify_replica_update(proof),
+            Error::<Test>::InvalidReplicaUpdateProof
+        );
+    });
+}
+
+fn verify_replica_update(proof: Vec<u8>) -> sp_runtime::DispatchResult {
+    <ProofsModule as ProofVerification>::verify_replica_update(
+        RegisteredUpdateProof::StackedDRG2KiBV1,
+        comm_r_old(),
+        comm_r_new(),
+        comm_d_new(),
+        BoundedVec::try_from(proof).expect("proof bytes should be valid"),
+    )
+}
+
+fn comm_r_old() -> RawCommitment {
+    [1; 32]
+}
+
+fn comm_r_new() -> RawCommitment {
+    [2; 32]
+}
+
+fn comm_d_new() -> RawCommitment {
+    [3; 32]
+}
@@ -0,0 +1,41 @@
+use primitives::proofs::RegisteredUpdateProof;
+
+/// Configuration used for replica update (a.k.a. SnapDeals) proofs.
+pub struct Config {
+    /// Number of partitions, the update proof consists of a Groth16 proof for each of them.
+    pub partitions: usize,
+    /// Selects the number of high bits (`h`) of each challenge used to generate the encoding randomness.
+    /// The circuit takes it as a public input, encoded as `1 << index`, where `index` is the position of `h`
+    /// in the list of values allowed for the sector size.
+    pub h_select: u64,
+}
+
+impl Config {
+    /// Creates a new config based on `RegisteredUpdateProof`.
+    ///
+    /// The partitions follow `partition_count`: 1 partition up to 8KiB, 2 up to 32KiB, 4 up to 16MiB and 16 for bigger sectors.
+    ///
+    /// References:
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/storage-proofs-update/src/constants.rs>
+    pub fn new(update_proof: RegisteredUpdateProof) -> Self {
+        match update_proof {
+            // Sectors up to 8KiB are proven in a single partition,
+            // sectors up to 32KiB only allow `h = 1`, which is the first allowed value.
+            RegisteredUpdateProof::StackedDRG2KiBV1 => Self {
+                partitions: 1,
+                h_select: 1 << 0,
+            },
+            // Sectors up to 16MiB are proven in 4 partitions,
+            // bigger than 32KiB sectors allow `h` in `[7, 8, 9, 10, 11, 12]`, the default `h = 10` is the fourth allowed value.
+            RegisteredUpdateProof::StackedDRG8MiBV1 => Self {
+                partitions: 4,
+                h_select: 1 << 3,
+            },
+            // Sectors bigger than 16MiB are proven in 16 partitions.
+            RegisteredUpdateProof::StackedDRG512MiBV1 => Self {
+                partitions: 16,
+                h_select: 1 << 3,
+            },
+        }
+    }
+}
@@ -0,0 +1,146 @@
+mod config;
+
+use config::Config;
+use primitives::{commitment::RawCommitment, proofs::RegisteredUpdateProof};
+
+use crate::{
+    crypto::groth16::{verify_proof, Bls12, Fr, Proof, VerificationError, VerifyingKey},
+    fr32, vec, Error, Vec,
+};
+
+pub struct ProofScheme {
+    config: Config,
+}
+
+#[derive(core::fmt::Debug)]
+pub enum ProofError {
+    /// Returned when the amount of partition proofs doesn't match the update proof type.
+    InvalidNumberOfProofs,
+    /// Returned when the given proof was invalid in a verification.
+    InvalidProof,
+    /// Returned when the given verifying key was invalid.
+    InvalidVerifyingKey,
+    /// Returned in case of failed conversion, i.e. in `bytes_into_fr()`.
+    Conversion,
+}
+
+impl From<VerificationError> for ProofError {
+    fn from(value: VerificationError) -> Self {
+        match value {
+            VerificationError::InvalidProof => ProofError::InvalidProof,
+            VerificationError::InvalidVerifyingKey => ProofError::InvalidVerifyingKey,
+        }
+    }
+}
+
+impl<T> From<ProofError> for Error<T> {
+    fn from(value: ProofError) -> Self {
+        match value {
+            ProofError::InvalidNumberOfProofs | ProofError::InvalidProof => {
+                Error::<T>::InvalidReplicaUpdateProof
+            }
+            ProofError::InvalidVerifyingKey => Error::<T>::InvalidVerifyingKey,
+            ProofError::Conversion => Error::<T>::Conversion,
+        }
+    }
+}
+
+pub struct PublicInputs {
+    comm_r_old: Fr,
+    comm_d_new: Fr,
+    comm_r_new: Fr,
+}
+
+impl ProofScheme {
+    pub fn setup(update_proof: RegisteredUpdateProof) -> Self {
+        Self {
+            config: Config::new(update_proof),
+        }
+    }
+
+    /// Number of partition proofs an update proof consists of.
+    pub fn partitions(&self) -> usize {
+        self.config.partitions
+    }
+
+    /// Verifies the partition proofs of a replica update, each of them is verified separately.
+    ///
+    /// References:
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/filecoin-proofs/src/api/update.rs>
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/storage-proofs-core/src/compound_proof.rs#L148>
+    pub fn verify(
+        &self,
+        comm_r_old: &RawCommitment,
+        comm_r_new: &RawCommitment,
+        comm_d_new: &RawCommitment,
+        vk: VerifyingKey<Bls12>,
+        proofs: &[Proof<Bls12>],
+    ) -> Result<(), ProofError> {
+        if proofs.len() != self.config.partitions {
+            return Err(ProofError::InvalidNumberOfProofs);
+        }
+
+        let public_inputs = PublicInputs {
+            comm_r_old: fr32::bytes_into_fr(comm_r_old).map_err(|_| ProofError::Conversion)?,
+            comm_d_new: fr32::bytes_into_fr(comm_d_new).map_err(|_| ProofError::Conversion)?,
+            comm_r_new: fr32::bytes_into_fr(comm_r_new).map_err(|_| ProofError::Conversion)?,
+        };
+
+        for (partition_index, proof) in proofs.iter().enumerate() {
+            let inputs = self.generate_public_inputs(&public_inputs, partition_index);
+            verify_proof(vk.clone(), proof, inputs.as_slice())?;
+        }
+
+        Ok(())
+    }
+
+    /// The challenges are derived inside of the circuit from `comm_r_new`,
+    /// so the public inputs only consist of the partition index, the commitments and `h_select`.
+    ///
+    /// References:
+    /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/storage-proofs-update/src/circuit.rs>
+    fn generate_public_inputs(
+        &self,
+        public_inputs: &PublicInputs,
+        partition_index: usize,
+    ) -> Vec<Fr> {
+        vec![
+            Fr::from(partition_index as u64),
+            public_inputs.comm_r_old,
+            public_inputs.comm_d_new,
+            public_inputs.comm_r_new,
+            Fr::from(self.config.h_select),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::proofs::RegisteredUpdateProof;
+    use storage_proofs_update::constants::{h_default, hs, partition_count};
+
+    use super::config::Config;
+
+    #[test]
+    /// The proofs are generated with the `storage-proofs-update` configuration,
+    /// so the partitions and `h` must match it or valid proofs would be rejected.
+    fn config_matches_reference_impl() {
+        for update_proof in [
+            RegisteredUpdateProof::StackedDRG2KiBV1,
+            RegisteredUpdateProof::StackedDRG8MiBV1,
+            RegisteredUpdateProof::StackedDRG512MiBV1,
+        ] {
+            let sector_nodes = update_proof.sector_size().bytes() as usize / 32;
+            let config = Config::new(update_proof);
+
+            assert_eq!(config.partitions, partition_count(sector_nodes));
+            let h_index = hs(sector_nodes)
+                .iter()
+                .position(|h| *h == h_default(sector_nodes))
+                .unwrap();
+            assert_eq!(config.h_select, 1 << h_index);
+            // Every partition is a Groth16 proof of 192 bytes.
+            assert_eq!(update_proof.proof_size(), config.partitions * 192);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Weights for pallet-proofs.
+//!
+//! TODO: The values are placeholders, they were estimated by hand from the cost of the pairings
+//! and are not benchmark results. They should be replaced by running the benchmarks on the reference hardware:
+//!
+//! ```bash
+//! polka-storage-node benchmark pallet \
+//!     --runtime target/release/wbuild/polka-storage-runtime/polka_storage_runtime.wasm \
+//!     --pallet pallet_proofs --extrinsic '*' \
+//!     --output pallets/proofs/src/weights.rs
+//! ```
+
+use core::marker::PhantomData;
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for pallet-proofs.
+pub trait WeightInfo {
+    /// Verifies the PoSt proofs of `p` partitions, one pairing check per proof.
+    fn verify_post_single(p: u32) -> Weight;
+    /// Verifies the PoSt proofs of `p` partitions in a single batch, sharing the final exponentiation.
+    fn verify_post(p: u32) -> Weight;
+    /// Verifies the PoRep proofs of `s` sectors in a single batch, sharing the final exponentiation.
+    fn verify_batch_porep(s: u32) -> Weight;
+}
+
+/// Placeholder weights for pallet-proofs, each verification reads its verifying key.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn verify_post_single(p: u32) -> Weight {
+        Weight::from_parts(45_000_000, 1_600)
+            .saturating_add(Weight::from_parts(1_350_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+    }
+    fn verify_post(p: u32) -> Weight {
+        Weight::from_parts(650_000_000, 1_600)
+            .saturating_add(Weight::from_parts(700_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+    }
+    fn verify_batch_porep(s: u32) -> Weight {
+        Weight::from_parts(650_000_000, 2_400)
+            .saturating_add(Weight::from_parts(800_000_000, 0).saturating_mul(s.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+    }
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+    fn verify_post_single(p: u32) -> Weight {
+        Weight::from_parts(45_000_000, 1_600)
+            .saturating_add(Weight::from_parts(1_350_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+    }
+    fn verify_post(p: u32) -> Weight {
+        Weight::from_parts(650_000_000, 1_600)
+            .saturating_add(Weight::from_parts(700_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+    }
+    fn verify_batch_porep(s: u32) -> Weight {
+        Weight::from_parts(650_000_000, 2_400)
+            .saturating_add(Weight::from_parts(800_000_000, 0).saturating_mul(s.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+    }
+}
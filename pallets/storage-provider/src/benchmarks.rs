@@ -1,7 +1,48 @@
 //! Benchmarking setup for pallet-storage-provider
 #![cfg(feature = "runtime-benchmarks")]
 
-#[benchmarks]
+use frame_benchmarking::v2::*;
+
+#[benchmarks(where T::PeerId: Default)]
 mod benchmarks {
-    // TODO(aidan46, no-ref, 2024-06-04): Add benchmarks for storage provider pallet.
+    use frame_support::traits::Hooks;
+    use frame_system::RawOrigin;
+    use primitives::proofs::RegisteredPoStProof;
+
+    use super::*;
+    use crate::{
+        pallet::{DeadlineChecks, PreCommitExpirations},
+        Config, Pallet,
+    };
+
+    // TODO(aidan46, no-ref, 2024-06-04): Add benchmarks for the storage provider pallet extrinsics.
+
+    /// Pre-commit expirations and deadlines are scheduled by block,
+    /// the hook only processes the `k` storage providers with work due in the block.
+    /// Its weight must not depend on the amount of registered storage providers `p`.
+    #[benchmark]
+    fn on_finalize(p: Linear<1, 1_000>, k: Linear<0, 1_000>) {
+        let current_block = frame_system::Pallet::<T>::block_number();
+        for i in 0..p {
+            let storage_provider: T::AccountId = account("storage_provider", i, 0);
+            Pallet::<T>::register_storage_provider(
+                RawOrigin::Signed(storage_provider.clone()).into(),
+                T::PeerId::default(),
+                RegisteredPoStProof::StackedDRGWindow2KiBV1P1,
+            )
+            .expect("storage provider to be registered");
+
+            if i < k {
+                DeadlineChecks::<T>::insert(current_block, &storage_provider, ());
+                PreCommitExpirations::<T>::insert(current_block, &storage_provider, ());
+            }
+        }
+
+        #[block]
+        {
+            Pallet::<T>::on_finalize(current_block);
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::Test);
 }
@@ -1,6 +1,9 @@
 extern crate alloc;
 
-use alloc::{collections::BTreeSet, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 
 use codec::{Decode, Encode};
 use frame_support::{pallet_prelude::*, sp_runtime::BoundedBTreeMap};
@@ -21,6 +24,22 @@ pub use assignment::assign_deadlines;
 
 const LOG_TARGET: &'static str = "runtime::storage_provider::deadline";
 
+/// Argument used for the `compact_partitions` extrinsic
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct CompactPartitionsParams {
+    pub deadline: u64,
+    pub partitions: BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+}
+
+/// Argument used for the `move_partitions` extrinsic
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct MovePartitionsParams {
+    pub orig_deadline: u64,
+    /// The deadline the partitions are moved to, it must be due before the original deadline.
+    pub dest_deadline: u64,
+    pub partitions: BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+}
+
 /// Deadline holds the state for all sectors due at a specific deadline.
 ///
 /// A deadline exists along side 47 other deadlines (1 for every 30 minutes in a day).
@@ -37,7 +56,7 @@ where
         ConstU32<MAX_PARTITIONS_PER_DEADLINE>,
     >,
 
-    /// Maps blocks to partitions that _may_ have sectors about to expire — i.e. just before or in that block.
+    /// Maps blocks to the partitions that _may_ have sectors about to expire — i.e. just before or in that block.
     /// The expiration happens either on-time or early because faults.
     ///
     /// Filecoin has another expiration mapping in the Partition struct which maps the a block to sectors that are on-time or expired (due to being faulty).
@@ -46,8 +65,8 @@ where
     ///
     /// Getting the information about a partition that has sectors that are about to expire you need to get the current deadline from the storage provider state.
     /// `let current_deadline_block = storage_provider_state.current_deadline;`
-    /// With the current deadline we can then get the partition number that is associated with that deadline block.
-    /// `let partition_number = deadline.expirations_blocks.get(current_deadline_block);`
+    /// With the current deadline we can then get the partition numbers that are associated with that deadline block.
+    /// `let partition_numbers = deadline.expirations_blocks.get(current_deadline_block);`
     ///
     /// Then we can get the partition information from the deadline.
     /// `let partition_to_expire = deadline.partitions.get(partition_number);`
@@ -64,8 +83,15 @@ where
     /// associated block has passed) even if they no longer have sectors
     /// expiring at that block. Sectors expiring at their given block may later be
     /// recovered, and this queue will not be updated at that time.
-    pub expirations_blocks:
-        BoundedBTreeMap<BlockNumber, PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+    ///
+    /// Several partitions can expire at the same block, so every block holds a set of partitions.
+    /// Blocks are only added when sectors are scheduled to expire at them,
+    /// so the queue is bounded by the number of sectors rather than by the number of partitions.
+    pub expirations_blocks: BoundedBTreeMap<
+        BlockNumber,
+        BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+        ConstU32<MAX_SECTORS>,
+    >,
 
     /// Partitions that have been proved by window PoSts so far during the
     /// current challenge window.
@@ -224,7 +250,7 @@ where
 
         // Next, update the expiration queue.
         for (block, partition_index) in partition_deadline_updates {
-            self.expirations_blocks.try_insert(block, partition_index).map_err(|_| {
+            self.add_expiration_partition(block, partition_index).map_err(|_| {
                 log::error!(target: LOG_TARGET, "add_sectors: Cannot update expiration queue at index {partition_idx}");
                 GeneralPalletError::DeadlineErrorCouldNotAddSectors
             })?;
@@ -266,28 +292,50 @@ where
                 e
             })?;
 
-            // Update expiration block
-            if let Some((&block, _)) = self
-                .expirations_blocks
-                .iter()
-                .find(|(_, partition_num)| partition_num == &partition_number)
-            {
-                self.expirations_blocks.remove(&block);
-                self.expirations_blocks.try_insert(fault_expiration_block, *partition_number).map_err(|_| {
-                    log::error!(target: LOG_TARGET, "record_faults: Could not insert new expiration");
-                    GeneralPalletError::DeadlineErrorFailedToUpdateFaultExpiration
-                })?;
-            } else {
-                self.expirations_blocks.try_insert(fault_expiration_block, *partition_number).map_err(|_| {
-                    log::error!(target: LOG_TARGET, "record_faults: Could not insert new expiration");
-                    GeneralPalletError::DeadlineErrorFailedToUpdateFaultExpiration
-                })?;
-            }
+            self.schedule_fault_expiration(fault_expiration_block, *partition_number)?;
         }
 
         Ok(())
     }
 
+    /// Schedules the partition in the expiration queue at the block its faulty sectors expire,
+    /// so they are terminated if they're still faulty by then.
+    ///
+    /// The partition's previous expirations are left in place, see the `expirations_blocks` docs.
+    pub fn schedule_fault_expiration(
+        &mut self,
+        fault_expiration_block: BlockNumber,
+        partition_number: PartitionNumber,
+    ) -> Result<(), GeneralPalletError> {
+        self.add_expiration_partition(fault_expiration_block, partition_number)
+            .map_err(|_| {
+                log::error!(target: LOG_TARGET, "schedule_fault_expiration: Could not insert new expiration");
+                GeneralPalletError::DeadlineErrorFailedToUpdateFaultExpiration
+            })
+    }
+
+    /// Adds the partition to the partitions expiring at the given block,
+    /// keeping the other partitions already expiring at that block.
+    fn add_expiration_partition(
+        &mut self,
+        block: BlockNumber,
+        partition_number: PartitionNumber,
+    ) -> Result<(), ()> {
+        if let Some(partitions) = self.expirations_blocks.get_mut(&block) {
+            return partitions
+                .try_insert(partition_number)
+                .map(|_| ())
+                .map_err(|_| ());
+        }
+
+        let mut partitions = BoundedBTreeSet::new();
+        partitions.try_insert(partition_number).map_err(|_| ())?;
+        self.expirations_blocks
+            .try_insert(block, partitions)
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+
     /// Sets sectors as recovering.
     /// Filecoin ref: <https://github.com/filecoin-project/builtin-actors/blob/0f205c378983ac6a08469b9f400cbb908eef64e2/actors/miner/src/deadline_state.rs#L818>
     pub fn declare_faults_recovered(
@@ -366,6 +414,271 @@ where
         Ok(())
     }
 
+    /// Replaces sectors in the given partition with sectors holding a new expiration.
+    /// The partition is scheduled in the deadline's expiration queue at the new expirations.
+    /// This functions is invoked by the `extend_sector_expiration` extrinsic.
+    ///
+    /// Reference implementation (`extend_sector_expiration_2`):
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+    pub fn replace_sectors(
+        &mut self,
+        partition_number: PartitionNumber,
+        old_sectors: &[&SectorOnChainInfo<BlockNumber>],
+        new_sectors: &[SectorOnChainInfo<BlockNumber>],
+    ) -> Result<(), GeneralPalletError> {
+        let Some(partition) = self.partitions.get_mut(&partition_number) else {
+            log::error!(target: LOG_TARGET, "replace_sectors: Cannot find partition {partition_number}");
+            return Err(GeneralPalletError::DeadlineErrorPartitionNotFound);
+        };
+
+        partition.replace_sectors(old_sectors, new_sectors)?;
+
+        // The old expirations are left in place, see the `expirations_blocks` docs.
+        for sector in new_sectors {
+            self.add_expiration_partition(sector.expiration, partition_number)
+                .map_err(|_| {
+                    log::error!(target: LOG_TARGET, "replace_sectors: Cannot update expiration queue for partition {partition_number}");
+                    GeneralPalletError::DeadlineErrorFailedToUpdateExpiration
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Compacts the given partitions, re-packing their live sectors into as few partitions as possible
+    /// and dropping the dead (terminated and expired) sectors.
+    ///
+    /// The remaining partitions are renumbered, so the partition numbers are contiguous.
+    /// Faulty sectors stay faulty, keeping their fault expiration, and declared recoveries are kept.
+    ///
+    /// # Important
+    /// * It's the caller's responsibility to make sure that this deadline is mutable.
+    /// * The partitions must not have unproven sectors nor early terminations to be processed.
+    ///
+    /// Reference implementation:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/deadline_state.rs#L637>
+    pub fn compact_partitions(
+        &mut self,
+        partition_size: u64,
+        all_sectors: &BoundedBTreeMap<
+            SectorNumber,
+            SectorOnChainInfo<BlockNumber>,
+            ConstU32<MAX_SECTORS>,
+        >,
+        partition_numbers: &BTreeSet<PartitionNumber>,
+    ) -> Result<(), GeneralPalletError> {
+        for partition_number in partition_numbers {
+            let partition = self.partitions.get(partition_number).ok_or_else(|| {
+                log::error!(target: LOG_TARGET, "compact_partitions: Cannot find partition {partition_number}");
+                GeneralPalletError::DeadlineErrorPartitionNotFound
+            })?;
+            // Re-added sectors are marked as unproven, we couldn't tell them apart anymore.
+            ensure!(partition.unproven.is_empty(), {
+                log::error!(target: LOG_TARGET, "compact_partitions: partition {partition_number} has unproven sectors");
+                GeneralPalletError::DeadlineErrorPartitionHasUnprovenSectors
+            });
+        }
+
+        let removed = self.remove_partitions(partition_numbers)?;
+
+        let mut live_sectors = Vec::new();
+        // Faulty sectors grouped by the block they expire at, early or on-time.
+        let mut faults = BTreeMap::<BlockNumber, BTreeSet<SectorNumber>>::new();
+        let mut recoveries = BTreeSet::new();
+        for partition in removed {
+            for sector_number in partition.live_sectors().iter() {
+                let sector = all_sectors.get(sector_number).ok_or_else(|| {
+                    log::error!(target: LOG_TARGET, "compact_partitions: sector {sector_number} not found in the storage provider");
+                    GeneralPalletError::DeadlineErrorSectorsNotFound
+                })?;
+                live_sectors.push(sector.clone());
+            }
+
+            for (&block, expiration_set) in partition.expirations.map.iter() {
+                for sector_number in expiration_set
+                    .on_time_sectors
+                    .iter()
+                    .chain(expiration_set.early_sectors.iter())
+                    .filter(|sector_number| partition.faults.contains(sector_number))
+                {
+                    faults.entry(block).or_default().insert(*sector_number);
+                }
+            }
+
+            recoveries.extend(partition.recoveries.iter().copied());
+        }
+
+        if live_sectors.is_empty() {
+            return Ok(());
+        }
+
+        self.add_sectors(partition_size, &live_sectors)?;
+
+        let compacted = live_sectors
+            .iter()
+            .map(|sector| sector.sector_number)
+            .collect::<BTreeSet<_>>();
+        let mut partitions = core::mem::take(&mut self.partitions).into_inner();
+        for (&partition_number, partition) in partitions.iter_mut() {
+            // The sectors were already proven in their previous partitions.
+            for sector_number in compacted.intersection(&partition.sectors) {
+                partition.unproven.remove(sector_number);
+            }
+
+            for (&fault_expiration, faulty_sectors) in faults.iter() {
+                let faulty_sectors = faulty_sectors
+                    .intersection(&partition.sectors)
+                    .copied()
+                    .collect::<BTreeSet<_>>();
+                if faulty_sectors.is_empty() {
+                    continue;
+                }
+                let faulty_sectors: BoundedBTreeSet<_, _> = faulty_sectors
+                    .try_into()
+                    .expect("a subset of the partition sectors is bounded by MAX_SECTORS");
+                partition.record_faults(all_sectors, &faulty_sectors, fault_expiration)?;
+                self.schedule_fault_expiration(fault_expiration, partition_number)?;
+            }
+
+            let recovered_sectors: BoundedBTreeSet<_, _> = recoveries
+                .intersection(&partition.sectors)
+                .copied()
+                .collect::<BTreeSet<_>>()
+                .try_into()
+                .expect("a subset of the partition sectors is bounded by MAX_SECTORS");
+            partition.declare_faults_recovered(&recovered_sectors);
+        }
+        self.partitions = BoundedBTreeMap::try_from(partitions)
+            .expect("the partitions were taken from a BoundedBTreeMap with the same bound");
+
+        Ok(())
+    }
+
+    /// Moves the given partitions, as they are, to the destination deadline.
+    ///
+    /// The moved partitions are appended after the destination deadline's partitions,
+    /// the remaining partitions of this deadline are renumbered, so the partition numbers are contiguous.
+    /// The faults, recoveries and unproven sectors of the partitions are carried over.
+    ///
+    /// # Important
+    /// * It's the caller's responsibility to make sure that both deadlines are mutable.
+    /// * The partitions must not have early terminations to be processed.
+    ///
+    /// Reference implementation:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+    pub fn move_partitions(
+        &mut self,
+        destination: &mut Deadline<BlockNumber>,
+        partition_numbers: &BTreeSet<PartitionNumber>,
+    ) -> Result<(), GeneralPalletError> {
+        for partition in self.remove_partitions(partition_numbers)? {
+            destination.add_partition(partition)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the given partitions from the deadline and returns them, in order.
+    /// The remaining partitions are renumbered, so the partition numbers are contiguous.
+    fn remove_partitions(
+        &mut self,
+        partition_numbers: &BTreeSet<PartitionNumber>,
+    ) -> Result<Vec<Partition<BlockNumber>>, GeneralPalletError> {
+        for partition_number in partition_numbers {
+            ensure!(self.partitions.contains_key(partition_number), {
+                log::error!(target: LOG_TARGET, "remove_partitions: Cannot find partition {partition_number}");
+                GeneralPalletError::DeadlineErrorPartitionNotFound
+            });
+            // The early terminations are processed by partition number.
+            ensure!(!self.early_terminations.contains(partition_number), {
+                log::error!(target: LOG_TARGET, "remove_partitions: partition {partition_number} has early terminations");
+                GeneralPalletError::DeadlineErrorPartitionHasEarlyTerminations
+            });
+        }
+
+        let mut removed = Vec::with_capacity(partition_numbers.len());
+        let mut partitions = BTreeMap::new();
+        let mut partitions_posted = BTreeSet::new();
+        let mut early_terminations = BTreeSet::new();
+        for (partition_number, partition) in core::mem::take(&mut self.partitions).into_inner() {
+            if partition_numbers.contains(&partition_number) {
+                // PRE-COND: the partition sectors were counted when they were added
+                self.live_sectors -= partition.live_sectors().len() as u64;
+                self.total_sectors -= partition.sectors.len() as u64;
+                removed.push(partition);
+                continue;
+            }
+
+            let new_partition_number = partitions.len() as PartitionNumber;
+            if self.partitions_posted.contains(&partition_number) {
+                partitions_posted.insert(new_partition_number);
+            }
+            if self.early_terminations.contains(&partition_number) {
+                early_terminations.insert(new_partition_number);
+            }
+            partitions.insert(new_partition_number, partition);
+        }
+
+        // Subsets of the previous collections, so the bounds hold.
+        self.partitions = BoundedBTreeMap::try_from(partitions)
+            .expect("the partitions were taken from a BoundedBTreeMap with the same bound");
+        self.partitions_posted = BoundedBTreeSet::try_from(partitions_posted)
+            .expect("the posted partitions are a subset of the previous ones");
+        self.early_terminations = BoundedBTreeSet::try_from(early_terminations)
+            .expect("the early terminations are a subset of the previous ones");
+
+        // Reschedule the remaining partitions under their new numbers.
+        self.expirations_blocks = BoundedBTreeMap::new();
+        let expirations = self
+            .partitions
+            .iter()
+            .flat_map(|(&partition_number, partition)| {
+                partition
+                    .expirations
+                    .map
+                    .keys()
+                    .map(move |&block| (block, partition_number))
+            })
+            .collect::<Vec<_>>();
+        for (block, partition_number) in expirations {
+            self.add_expiration_partition(block, partition_number)
+                .map_err(|_| {
+                    log::error!(target: LOG_TARGET, "remove_partitions: Cannot update expiration queue for partition {partition_number}");
+                    GeneralPalletError::DeadlineErrorFailedToUpdateExpiration
+                })?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Adds a partition, with all its state, after the last partition of the deadline.
+    fn add_partition(
+        &mut self,
+        partition: Partition<BlockNumber>,
+    ) -> Result<PartitionNumber, GeneralPalletError> {
+        let partition_number = self.partitions.len() as PartitionNumber;
+
+        for &block in partition.expirations.map.keys() {
+            self.add_expiration_partition(block, partition_number)
+                .map_err(|_| {
+                    log::error!(target: LOG_TARGET, "add_partition: Cannot update expiration queue for partition {partition_number}");
+                    GeneralPalletError::DeadlineErrorFailedToUpdateExpiration
+                })?;
+        }
+
+        // PRE-COND: there can never be more live sectors than u64, so it never overflows
+        self.live_sectors += partition.live_sectors().len() as u64;
+        self.total_sectors += partition.sectors.len() as u64;
+        self.partitions
+            .try_insert(partition_number, partition)
+            .map_err(|_| {
+                log::error!(target: LOG_TARGET, "add_partition: Cannot add partition {partition_number}, too many partitions");
+                GeneralPalletError::DeadlineErrorMaxPartitionsReached
+            })?;
+
+        Ok(partition_number)
+    }
+
     /// Pops early terminations until `max_sectors`, `max_partitions` or until there are none left
     ///
     /// Reference implementation:
@@ -424,23 +737,21 @@ where
         &mut self,
         until: BlockNumber,
     ) -> Result<Vec<PartitionNumber>, GeneralPalletError> {
-        let (to_pop, popped_partitions): (Vec<BlockNumber>, Vec<PartitionNumber>) = self
-            .expirations_blocks
-            .iter()
-            // take_while does not work here because we cannot ensure that `self.expirations_blocks` is ordered
-            .filter_map(|(&block, partition_number)| {
-                (block <= until).then(|| (block, *partition_number))
-            })
-            .unzip();
-        let mut to_pop = to_pop.into_iter().peekable();
-        if to_pop.peek().is_none() {
-            return Ok(Vec::new());
+        let mut to_pop = Vec::new();
+        // A partition can be scheduled at several of the popped blocks, it's only returned once.
+        let mut popped_partitions = BTreeSet::new();
+        // take_while does not work here because we cannot ensure that `self.expirations_blocks` is ordered
+        for (&block, partition_numbers) in self.expirations_blocks.iter() {
+            if block <= until {
+                to_pop.push(block);
+                popped_partitions.extend(partition_numbers.iter().copied());
+            }
         }
 
-        to_pop.for_each(|block_number| {
+        for block_number in to_pop {
             self.expirations_blocks.remove(&block_number);
-        });
-        Ok(popped_partitions)
+        }
+        Ok(popped_partitions.into_iter().collect())
     }
 
     /// PopExpiredSectors terminates expired sectors from all partitions.
@@ -581,6 +892,23 @@ where
         deadline.record_proven(all_sectors, partitions)?;
         Ok(())
     }
+
+    /// Moves partitions from the deadline at `orig_idx` to the deadline at `dest_idx`.
+    ///
+    /// See [`Deadline::move_partitions`].
+    pub fn move_partitions(
+        &mut self,
+        orig_idx: usize,
+        dest_idx: usize,
+        partitions: &BTreeSet<PartitionNumber>,
+    ) -> Result<(), GeneralPalletError> {
+        log::debug!(target: LOG_TARGET, "move_partitions: moving partitions {partitions:?} from deadline {orig_idx} to {dest_idx}");
+        let mut orig_deadline = self.load_deadline_mut(orig_idx)?.clone();
+        let dest_deadline = self.load_deadline_mut(dest_idx)?;
+        orig_deadline.move_partitions(dest_deadline, partitions)?;
+        self.due[orig_idx] = orig_deadline;
+        Ok(())
+    }
 }
 
 /// Holds information about deadlines like when they open and close and what deadline index they relate to.
@@ -1354,6 +1682,39 @@ mod tests {
         assert_eq!(partition.faults, sector_set::<MAX_SECTORS>(&[]));
     }
 
+    #[test]
+    fn replaced_sectors_expire_at_the_same_block() -> Result<(), GeneralPalletError> {
+        let mut deadline = Deadline::new();
+        let sectors = add_sectors(&mut deadline, true)?;
+
+        // Sector 2 in partition 0 and sector 6 in partition 1 are extended to the same block
+        for (partition_number, sector) in [(0, &sectors[1]), (1, &sectors[5])] {
+            let new_sector = SectorOnChainInfo {
+                expiration: 20,
+                ..sector.clone()
+            };
+            deadline.replace_sectors(partition_number, &[sector], &[new_sector])?;
+        }
+        assert_eq!(
+            deadline.expirations_blocks[&20]
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        let expired = deadline.pop_expired_sectors(19)?;
+        assert_eq!(
+            expired.on_time_sectors,
+            sector_set::<MAX_SECTORS>(&[1, 3, 4, 5, 7, 8, 9])
+        );
+        let expired = deadline.pop_expired_sectors(20)?;
+        assert_eq!(expired.on_time_sectors, sector_set::<MAX_SECTORS>(&[2, 6]));
+        assert_eq!(deadline.live_sectors, 0);
+
+        Ok(())
+    }
+
     #[test]
     fn cannot_pop_expired_sectors_before_proving() {
         let mut deadline = Deadline::new();
@@ -21,6 +21,8 @@ pub enum GeneralPalletError {
     PartitionErrorUnexpectedRecoveries,
     /// Emitted when trying to pop expired sectors that are already terminated.
     PartitionErrorExpiredSectorsAlreadyTerminated,
+    /// Emitted when trying to replace sectors that are not active (i.e. faulty, unproven or terminated).
+    PartitionErrorSectorsNotActive,
 
     /// Deadline error module types
     /// Emitted when the passed in deadline index supplied for `submit_windowed_post` is out of range.
@@ -47,6 +49,12 @@ pub enum GeneralPalletError {
     DeadlineErrorCouldNotAssignSectorsToDeadlines,
     /// Emitted when trying to update fault expirations fails
     DeadlineErrorFailedToUpdateFaultExpiration,
+    /// Emitted when trying to update sector expirations fails
+    DeadlineErrorFailedToUpdateExpiration,
+    /// Emitted when trying to compact or move a partition with early terminations that haven't been processed yet.
+    DeadlineErrorPartitionHasEarlyTerminations,
+    /// Emitted when trying to compact a partition with sectors that haven't been proven yet.
+    DeadlineErrorPartitionHasUnprovenSectors,
 
     /// StorageProvider module error types
     /// Happens when an SP tries to pre-commit more sectors than SECTOR_MAX.
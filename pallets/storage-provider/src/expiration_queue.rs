@@ -310,6 +310,22 @@ where
         }
     }
 
+    /// Removes active sectors from their current expiration sets and schedules
+    /// the replacement sectors at their (new) on-time expiration.
+    /// The sectors to remove must be active (non-faulty).
+    ///
+    /// https://github.com/filecoin-project/builtin-actors/blob/c3c41c5d06fe78c88d4d05eb81b749a6586a5c9f/actors/miner/src/expiration_queue.rs
+    pub fn replace_sectors(
+        &mut self,
+        old_sectors: &[&SectorOnChainInfo<BlockNumber>],
+        new_sectors: &[SectorOnChainInfo<BlockNumber>],
+    ) -> Result<(), GeneralPalletError> {
+        self.remove_active_sectors(old_sectors)?;
+        self.add_active_sectors(new_sectors)?;
+
+        Ok(())
+    }
+
     /// Pops expiration sets from the queue until the given block number.
     /// Returns an expiration set with the popped sectors, on time and early.
     pub fn pop_until(&mut self, until: BlockNumber) -> Result<ExpirationSet, GeneralPalletError> {
@@ -587,6 +603,30 @@ mod tests {
         assert_eq!(removed.early_sectors, expected_early_sectors);
     }
 
+    #[test]
+    fn replace_sectors() {
+        let mut q = ExpirationQueue::new();
+        // Add sectors to queue
+        q.add_active_sectors(&sectors()).unwrap();
+
+        // Extend sectors 1 and 3, sector 3 is moved to an already existing set
+        let old_sectors = [&sectors()[0], &sectors()[2]];
+        let new_sectors = [test_sector(20, 1), test_sector(13, 3)];
+        q.replace_sectors(&old_sectors, &new_sectors).unwrap();
+
+        // The emptied sets are removed from the queue
+        assert!(q.map.get(&2).is_none());
+        assert!(q.map.get(&7).is_none());
+        assert_eq!(
+            q.map.get(&20).unwrap().on_time_sectors,
+            sector_set::<MAX_SECTORS>(&[1])
+        );
+        assert_eq!(
+            q.map.get(&13).unwrap().on_time_sectors,
+            sector_set::<MAX_SECTORS>(&[3, 6])
+        );
+    }
+
     fn sectors() -> [SectorOnChainInfo<u64>; 6] {
         [
             test_sector(2, 1),
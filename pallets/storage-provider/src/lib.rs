@@ -22,6 +22,7 @@ mod deadline;
 mod error;
 mod expiration_queue;
 mod fault;
+pub mod migrations;
 mod partition;
 mod proofs;
 mod sector;
@@ -35,7 +36,11 @@ pub mod pallet {
 
     extern crate alloc;
 
-    use alloc::{collections::BTreeMap, vec, vec::Vec};
+    use alloc::{
+        collections::{BTreeMap, BTreeSet},
+        vec,
+        vec::Vec,
+    };
     use core::fmt::Debug;
 
     use cid::Cid;
@@ -44,7 +49,10 @@ pub mod pallet {
         dispatch::DispatchResult,
         ensure, fail,
         pallet_prelude::*,
-        sp_runtime::traits::{CheckedAdd, CheckedSub, One},
+        sp_runtime::{
+            traits::{CheckedAdd, CheckedSub, Saturating},
+            ArithmeticError, SaturatedConversion,
+        },
         traits::{
             Currency, ExistenceRequirement::KeepAlive, Imbalance, Randomness, ReservableCurrency,
             WithdrawReasons,
@@ -58,32 +66,39 @@ pub mod pallet {
     use primitives::{
         commitment::{CommD, CommR, Commitment},
         pallets::{
-            DeadlineInfo as ExternalDeadlineInfo, Market, ProofVerification,
+            DeadlineInfo as ExternalDeadlineInfo, LockedFunds, Market, PreCommittedSector,
+            ProofVerification, SectorDeal, SectorFaults, SectorInfo, StoragePower,
             StorageProviderValidation,
         },
-        proofs::{derive_prover_id, PublicReplicaInfo, RegisteredPoStProof},
+        proofs::{derive_prover_id, PublicReplicaInfo, PublicSealInfo, RegisteredPoStProof},
         randomness::{draw_randomness, AuthorVrfHistory, DomainSeparationTag},
-        sector::SectorNumber,
-        PartitionNumber, MAX_PARTITIONS_PER_DEADLINE, MAX_SEAL_PROOF_BYTES, MAX_SECTORS,
-        MAX_SECTORS_PER_CALL,
+        sector::{SectorNumber, SectorSize},
+        Multiaddr, PartitionNumber, MAX_BATCHED_SECTORS, MAX_MULTIADDRS,
+        MAX_PARTITIONS_PER_DEADLINE, MAX_PARTITIONS_PER_POST, MAX_POST_PROOF_BYTES,
+        MAX_REPLICA_UPDATE_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS, MAX_SECTORS_PER_CALL,
     };
     use scale_info::TypeInfo;
-    use sp_arithmetic::traits::Zero;
+    use sp_arithmetic::traits::{One, Zero};
 
     use crate::{
-        deadline::{deadline_is_mutable, DeadlineInfo},
+        deadline::{
+            deadline_is_mutable, CompactPartitionsParams, DeadlineInfo, MovePartitionsParams,
+        },
         fault::{
             DeclareFaultsParams, DeclareFaultsRecoveredParams, FaultDeclaration,
             RecoveryDeclaration,
         },
         proofs::{assign_proving_period_offset, SubmitWindowedPoStParams},
         sector::{
-            ProveCommitResult, ProveCommitSector, SectorOnChainInfo, SectorPreCommitInfo,
-            SectorPreCommitOnChainInfo, TerminateSectorsParams, TerminationDeclaration,
+            ExpirationExtension, ExtendSectorExpirationParams, ProveCommitBatch,
+            ProveCommitNiSector, ProveCommitResult, ProveCommitSector, ReplicaUpdate,
+            SectorOnChainInfo, SectorPreCommitInfo, SectorPreCommitOnChainInfo,
+            TerminateSectorsParams, TerminationDeclaration,
         },
         sector_map::DeadlineSectorMap,
         storage_provider::{
             calculate_first_proving_period_start, StorageProviderInfo, StorageProviderState,
+            WorkerKeyChange, MAX_CONTROL_ADDRESSES,
         },
     };
 
@@ -92,7 +107,11 @@ pub mod pallet {
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info] // Allows to define storage items without fixed size
     pub struct Pallet<T>(_);
 
@@ -246,6 +265,64 @@ pub mod pallet {
         /// The maximum number of sector numbers addressable in a single invocation
         /// (which implies also the max infos that may be loaded at once).
         type AddressedSectorsMax: Get<u64>;
+
+        /// Number of blocks that need to pass before a worker account change can be confirmed.
+        ///
+        /// The delay gives the network — and the owner — time to notice an unexpected change.
+        ///
+        /// Reference:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/policy.rs>
+        #[pallet::constant]
+        type WorkerKeyChangeDelay: Get<BlockNumberFor<Self>>;
+
+        /// Reward expected to be shared by all storage providers in each block.
+        ///
+        /// A sector is expected to earn a share of it proportional to its power,
+        /// this expected reward scales the pre-commit deposit and the initial pledge.
+        ///
+        /// Reference:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs>
+        #[pallet::constant]
+        type ExpectedBlockReward: Get<BalanceOf<Self>>;
+
+        /// Lower bound of the network power, in bytes, used to compute the expected reward of a sector.
+        ///
+        /// Without it, the first storage providers would be expected to earn the whole block reward,
+        /// requiring them to lock unreasonable amounts of funds.
+        #[pallet::constant]
+        type BaselinePower: Get<u128>;
+
+        /// Number of blocks of the expected reward of a sector locked as pre-commit deposit.
+        ///
+        /// The deposit is locked when the sector is pre-committed and released when it is proven,
+        /// if the sector is not proven in time, the deposit is slashed.
+        #[pallet::constant]
+        type PreCommitDepositProjectionPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Number of blocks of the expected reward of a sector locked as initial pledge.
+        ///
+        /// The pledge is locked when the sector is proven and released when it is terminated or expires.
+        #[pallet::constant]
+        type InitialPledgeProjectionPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Number of blocks of the expected reward of a sector charged as fee
+        /// for each proving period the sector stays faulty, declared or not.
+        ///
+        /// Reference:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs>
+        #[pallet::constant]
+        type ContinuedFaultProjectionPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Number of blocks of the expected reward of a sector charged as penalty
+        /// when the sector misses a PoSt without being declared as faulty.
+        ///
+        /// It should be higher than [`Config::ContinuedFaultProjectionPeriod`],
+        /// so declaring the faults in advance is cheaper than letting them be detected.
+        ///
+        /// Reference:
+        /// * <https://github.com/filecoin-project/specs-actors/blob/v2.3.6/actors/builtin/miner/monies.go>
+        #[pallet::constant]
+        type UndeclaredFaultProjectionPeriod: Get<BlockNumberFor<Self>>;
     }
 
     /// Need some storage type that keeps track of sectors, deadlines and terminations.
@@ -255,18 +332,55 @@ pub mod pallet {
         _,
         _,
         T::AccountId,
-        StorageProviderState<T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+        StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
     >;
 
+    /// Maps the worker and control accounts to the owner of the storage provider they act for.
+    ///
+    /// Owners are not stored here, they are the keys of [`StorageProviders`].
+    #[pallet::storage]
+    pub type StorageProviderAccounts<T: Config> = StorageMap<_, _, T::AccountId, T::AccountId>;
+
+    /// Power of each storage provider, keyed by the owner.
+    ///
+    /// Updated whenever sectors are activated, faulted, recovered, terminated or expired.
+    #[pallet::storage]
+    pub type StorageProviderPower<T: Config> =
+        StorageMap<_, _, T::AccountId, StoragePower, ValueQuery>;
+
+    /// Sum of the power of all storage providers.
+    #[pallet::storage]
+    pub type TotalPower<T: Config> = StorageValue<_, StoragePower, ValueQuery>;
+
+    /// Storage providers with pre-committed sectors expiring at a given block.
+    ///
+    /// When a block finalizes, only the storage providers scheduled for it are checked for expired pre-commits.
+    #[pallet::storage]
+    pub type PreCommitExpirations<T: Config> =
+        StorageDoubleMap<_, _, BlockNumberFor<T>, _, T::AccountId, ()>;
+
+    /// Storage providers whose current deadline closes at a given block.
+    ///
+    /// When a block finalizes, only the deadlines scheduled for it are processed,
+    /// each storage provider is then scheduled again for the closing of its next deadline.
+    #[pallet::storage]
+    pub type DeadlineChecks<T: Config> =
+        StorageDoubleMap<_, _, BlockNumberFor<T>, _, T::AccountId, ()>;
+
     #[pallet::event]
     #[pallet::generate_deposit(fn deposit_event)]
     pub enum Event<T: Config> {
         /// Emitted when a new storage provider is registered.
         StorageProviderRegistered {
             owner: T::AccountId,
-            info: StorageProviderInfo<T::PeerId>,
+            info: StorageProviderInfo<T::AccountId, T::PeerId, BlockNumberFor<T>>,
             proving_period_start: BlockNumberFor<T>,
         },
+        /// Emitted when a storage provider deregisters, its remaining locked funds are released.
+        StorageProviderDeregistered {
+            owner: T::AccountId,
+            released: BalanceOf<T>,
+        },
         /// Emitted when a storage provider pre commits some sectors.
         SectorsPreCommitted {
             /// Block at which sectors have been precommitted.
@@ -279,7 +393,7 @@ pub mod pallet {
         /// Emitted when a storage provider successfully proves pre committed sectors.
         SectorsProven {
             owner: T::AccountId,
-            sectors: BoundedVec<ProveCommitResult, ConstU32<MAX_SECTORS_PER_CALL>>,
+            sectors: BoundedVec<ProveCommitResult, ConstU32<MAX_BATCHED_SECTORS>>,
         },
         /// Emitted when a sector was pre-committed, but not proven, so it got slashed in the pre-commit hook.
         SectorsSlashed {
@@ -308,11 +422,70 @@ pub mod pallet {
                 ConstU32<MAX_PARTITIONS_PER_DEADLINE>,
             >,
         },
+        /// Emitted when an SP is charged for its faulty sectors, once their deadline is processed.
+        /// The amounts are taken from the SP's initial pledge and burned.
+        FaultsPenalized {
+            owner: T::AccountId,
+            /// Fee charged for the sectors that were already faulty, e.g. declared faults.
+            continued_fault_fee: BalanceOf<T>,
+            /// Penalty charged for the sectors that missed the PoSt without being declared as faulty.
+            undeclared_fault_penalty: BalanceOf<T>,
+        },
         /// Emitted when an SP terminates some sectors.
         SectorsTerminated {
             owner: T::AccountId,
             terminations: BoundedVec<TerminationDeclaration, ConstU32<DECLARATIONS_MAX>>,
         },
+        /// Emitted when an SP extends the expiration of some sectors.
+        SectorsExpirationExtended {
+            owner: T::AccountId,
+            extensions:
+                BoundedVec<ExpirationExtension<BlockNumberFor<T>>, ConstU32<DECLARATIONS_MAX>>,
+        },
+        /// Emitted when an SP compacts some partitions of a deadline.
+        PartitionsCompacted {
+            owner: T::AccountId,
+            deadline: u64,
+            partitions: BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+        },
+        /// Emitted when an SP moves some partitions from a deadline to another.
+        PartitionsMoved {
+            owner: T::AccountId,
+            orig_deadline: u64,
+            dest_deadline: u64,
+            partitions: BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+        },
+        /// Emitted when an SP updates the replicas of committed capacity sectors with deals.
+        ReplicasUpdated {
+            owner: T::AccountId,
+            sectors: BoundedVec<SectorNumber, ConstU32<MAX_SECTORS_PER_CALL>>,
+        },
+        /// Emitted when an SP changes its control addresses.
+        ControlAddressesChanged {
+            owner: T::AccountId,
+            control_addresses: BoundedVec<T::AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+        },
+        /// Emitted when an SP schedules a change of its worker account.
+        WorkerAddressChangeScheduled {
+            owner: T::AccountId,
+            new_worker: T::AccountId,
+            effective_at: BlockNumberFor<T>,
+        },
+        /// Emitted when an SP confirms the change of its worker account.
+        WorkerAddressChanged {
+            owner: T::AccountId,
+            worker: T::AccountId,
+        },
+        /// Emitted when an SP changes its peer ID.
+        PeerIdChanged {
+            owner: T::AccountId,
+            peer_id: T::PeerId,
+        },
+        /// Emitted when an SP changes its multiaddrs.
+        MultiaddrsChanged {
+            owner: T::AccountId,
+            multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
+        },
     }
 
     #[pallet::error]
@@ -333,7 +506,8 @@ pub mod pallet {
         InvalidProofType,
         /// Emitted when the proof is invalid
         InvalidProof,
-        /// Emitted when there is not enough funds to run an extrinsic.
+        /// Emitted when there is not enough funds to run an extrinsic,
+        /// e.g. to lock the pre-commit deposit or the initial pledge.
         NotEnoughFunds,
         /// Emitted when trying to reuse a sector number
         SectorNumberAlreadyUsed,
@@ -350,6 +524,12 @@ pub mod pallet {
         /// Emitted when a prove commit is sent after the deadline.
         /// These pre-commits will be cleaned up in the hook.
         ProveCommitAfterDeadline,
+        /// Emitted when the seal randomness of a non-interactive sector is older than `MaxProveCommitDuration`.
+        SealRandomnessTooOld,
+        /// Emitted when a batched prove commit doesn't contain any sectors.
+        NoSectorsInBatch,
+        /// Emitted when the sectors of a batched prove commit were sealed with different seal proof types.
+        BatchSealProofMismatch,
         /// Emitted when a PoSt supplied by by the SP is invalid
         PoStProofInvalid,
         /// Emitted when an error occurs when submitting PoSt.
@@ -359,6 +539,7 @@ pub mod pallet {
         CouldNotVerifySectorForPreCommit,
         /// Declared unsealed_cid for pre_commit is different from the one calculated by `Market::verify_deals_for_activation`.
         /// unsealed_cid === CommD and is calculated from piece ids of all of the deals in a sector.
+        /// Committed capacity sectors, without deals, must use the zero CommD of their seal proof.
         InvalidUnsealedCidForSector,
         /// Emitted when SP calls declare_faults and the fault cutoff is passed.
         FaultDeclarationTooLate,
@@ -370,8 +551,42 @@ pub mod pallet {
         CouldNotTerminateDeals,
         /// Tried to terminate sectors that are not mutable.
         CannotTerminateImmutableDeadline,
+        /// Tried to extend the expiration of sectors that are not mutable.
+        CannotExtendImmutableDeadline,
+        /// Tried to set a sector expiration that is not after its current expiration.
+        CannotReduceSectorExpiration,
+        /// Tried to update the replica of sectors that are not mutable.
+        CannotUpdateImmutableDeadline,
+        /// Tried to compact partitions of a deadline that is not mutable.
+        CannotCompactImmutableDeadline,
+        /// Tried to move partitions from or to a deadline that is not mutable.
+        CannotMoveImmutableDeadline,
+        /// Tried to move partitions to a deadline that is not due before the original deadline.
+        InvalidDestinationDeadline,
+        /// Tried to update the replica of a sector that already contains deals.
+        /// Only committed capacity sectors can be updated.
+        SectorNotCommittedCapacity,
+        /// Emitted when an extrinsic addresses more partitions than `AddressedPartitionsMax`.
+        TooManyPartitionsAddressed,
+        /// Emitted when an extrinsic addresses more sectors than `AddressedSectorsMax`.
+        TooManySectorsAddressed,
         /// Emitted when trying to submit PoSt with partitions containing too many sectors (>2349).
         TooManyReplicas,
+        /// Emitted when trying to use an account that is already the owner, worker or control address
+        /// of another storage provider.
+        AddressAlreadyInUse,
+        /// Emitted when trying to confirm a worker account change that was not scheduled.
+        NoPendingWorkerKeyChange,
+        /// Emitted when a storage provider tries to deregister while it still has live or pre-committed sectors.
+        StorageProviderHasSectors,
+        /// Emitted when a storage provider tries to deregister while it has early terminations pending.
+        EarlyTerminationsPending,
+        /// Emitted when a storage provider tries to deregister while it has pre-commit deposits locked.
+        PreCommitDepositsLocked,
+        /// Emitted when trying to confirm a worker account change before `WorkerKeyChangeDelay` has passed.
+        WorkerKeyChangeNotEffective,
+        /// Emitted when one of the multiaddrs passed to `change_multiaddrs` is empty.
+        InvalidMultiaddr,
         /// AuthorVRF lookup failed.
         MissingAuthorVRF,
         /// Inner pallet errors
@@ -397,6 +612,11 @@ pub mod pallet {
                 !StorageProviders::<T>::contains_key(&owner),
                 Error::<T>::StorageProviderExists
             );
+            // Ensure that the account isn't acting for another storage provider
+            ensure!(
+                !StorageProviderAccounts::<T>::contains_key(&owner),
+                Error::<T>::AddressAlreadyInUse
+            );
             let current_block = <frame_system::Pallet<T>>::block_number();
             let proving_period = T::WPoStProvingPeriod::get();
 
@@ -412,8 +632,13 @@ pub mod pallet {
                 offset,
                 proving_period,
             );
-            let info = StorageProviderInfo::new(peer_id, window_post_proof_type);
-            let state = StorageProviderState::<T::PeerId, BalanceOf<T>, BlockNumberFor<T>>::new(
+            let info = StorageProviderInfo::new(owner.clone(), peer_id, window_post_proof_type);
+            let state = StorageProviderState::<
+                T::AccountId,
+                T::PeerId,
+                BalanceOf<T>,
+                BlockNumberFor<T>,
+            >::new(
                 info.clone(),
                 local_proving_start,
                 // Always zero since we're calculating the absolute first start
@@ -421,6 +646,8 @@ pub mod pallet {
                 0,
                 T::WPoStPeriodDeadlines::get(),
             );
+            Self::schedule_deadline_check(current_block, &owner, &state)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?;
             StorageProviders::<T>::insert(&owner, state);
             // Emit event
             Self::deposit_event(Event::StorageProviderRegistered {
@@ -431,11 +658,71 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Deregisters the storage provider, removing its state and releasing its remaining locked funds.
+        ///
+        /// Only the owner can call this extrinsic, once the storage provider has no live or pre-committed sectors,
+        /// no early terminations pending and no pre-commit deposits locked.
+        /// The initial pledge is released as sectors expire or are terminated,
+        /// whatever remains of it is released here.
+        pub fn deregister_storage_provider(origin: OriginFor<T>) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            let sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            ensure!(
+                sp.pledged_sectors_count() == 0 && sp.pre_committed_sectors.is_empty(),
+                {
+                    log::error!(target: LOG_TARGET, "deregister_storage_provider: {owner:?} still has live or pre-committed sectors");
+                    Error::<T>::StorageProviderHasSectors
+                }
+            );
+            ensure!(sp.early_terminations.is_empty(), {
+                log::error!(target: LOG_TARGET, "deregister_storage_provider: {owner:?} has early terminations pending in deadlines {:?}", sp.early_terminations);
+                Error::<T>::EarlyTerminationsPending
+            });
+            ensure!(sp.pre_commit_deposits.is_zero(), {
+                log::error!(target: LOG_TARGET, "deregister_storage_provider: {owner:?} has {:?} locked as pre-commit deposits", sp.pre_commit_deposits);
+                Error::<T>::PreCommitDepositsLocked
+            });
+
+            let released = sp.initial_pledge;
+            T::Currency::unreserve(&owner, released);
+
+            // Release the addresses so they can be used by other storage providers.
+            for account in core::iter::once(&sp.info.worker).chain(&sp.info.control_addresses) {
+                StorageProviderAccounts::<T>::remove(account);
+            }
+            // The storage provider has no power left, there's nothing to subtract from the total.
+            StorageProviderPower::<T>::remove(&owner);
+            // Unschedule the storage provider, so a later registration isn't processed by the previous schedules.
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            if let Ok(current_deadline) = sp.deadline_info(
+                current_block,
+                T::WPoStPeriodDeadlines::get(),
+                T::WPoStProvingPeriod::get(),
+                T::WPoStChallengeWindow::get(),
+                T::WPoStChallengeLookBack::get(),
+                T::FaultDeclarationCutoff::get(),
+            ) {
+                // A deadline that closed already was scheduled for the current block, see `schedule_deadline_check`.
+                DeadlineChecks::<T>::remove(current_deadline.close_at.max(current_block), &owner);
+            }
+            // There are no pre-commits left, proving or slashing them already unscheduled their expirations.
+            StorageProviders::<T>::remove(&owner);
+
+            Self::deposit_event(Event::StorageProviderDeregistered { owner, released });
+            Ok(())
+        }
+
         /// The Storage Provider uses this extrinsic to pledge and seal X sectors at once.
         /// If a single sector fails to pre commit for whatever reason, the extrinsic will fail.
         ///
+        /// Sectors without deals are committed capacity (CC) sectors,
+        /// their unsealed_cid must be the zero CommD for their seal proof — `Commitment::<CommD>::zero`.
+        /// Only sectors with deals are verified by the market.
+        ///
         /// The deposit amount is calculated by `calculate_pre_commit_deposit`.
-        /// The deposited amount is locked until the sector has been terminated.
+        /// The deposited amount is locked until the sector has been proven.
         /// A hook will check pre-committed sectors `expiration` and
         /// if that sector has not been proven by that time the deposit will be slashed.
         /// Reference implementation:
@@ -447,11 +734,10 @@ pub mod pallet {
                 ConstU32<MAX_SECTORS_PER_CALL>,
             >,
         ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
             let sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
-            let sector_amount = sectors.len();
 
             // Pre-committed sectors for emitting the event.
             let mut pre_committed_sectors = BoundedVec::new();
@@ -464,12 +750,15 @@ pub mod pallet {
             let mut total_deposit = BalanceOf::<T>::zero();
             // sector deals for all pre commits
             let mut all_sector_deals = BoundedVec::new();
-            // unsealed_cids for all sectors
+            // unsealed_cids for all sectors with deals
             let mut unsealed_cids = BoundedVec::new();
-            // deal amounts for each sector
-            let mut deal_amounts = BoundedVec::new();
 
             for sector in sectors {
+                // Non-interactive sectors are onboarded with `prove_commit_ni_sectors` instead.
+                ensure!(!sector.seal_proof.is_non_interactive(), {
+                    log::error!(target: LOG_TARGET, "pre_commit_sectors: sector {} uses the non-interactive seal proof {:?}", sector.sector_number, sector.seal_proof);
+                    Error::<T>::InvalidProofType
+                });
                 // Basic pre-commit validation.
                 Self::validate_sector_for_pre_commit(&sp, &sector)?;
 
@@ -480,33 +769,21 @@ pub mod pallet {
                     sector.expiration,
                 )?;
 
-                // Validate the data commitment
-                let commd = Commitment::<CommD>::from_cid_bytes(&sector.unsealed_cid[..])
-                    .map_err(|err| {
-                        log::error!(target: LOG_TARGET, err:?; "pre_commit_sectors: invalid unsealed_cid");
-                        Error::<T>::InvalidCid
-                    })?;
-
-                // Validate the replica commitment
-                let _ = Commitment::<CommR>::from_cid_bytes(&sector.sealed_cid[..])
-                    .map_err(|err| {
-                        log::error!(target: LOG_TARGET, err:?; "pre_commit_sectors: invalid sealed_cid");
-                        Error::<T>::InvalidCid
-                    })?;
+                let commd = Self::validate_sector_commitments(&sector)?;
 
-                let deposit = calculate_pre_commit_deposit::<T>();
+                let deposit = calculate_pre_commit_deposit::<T>(sp.info.sector_size);
 
                 let sector_on_chain =
                     SectorPreCommitOnChainInfo::new(sector.clone(), deposit, current_block);
 
-                // Push deal amounts for later verification
-                deal_amounts.try_push(sector_on_chain.info.deal_ids.len()).expect("Programmer error: cannot have more that MAX_SECTORS_PER_CALL deal_amount because of previous bounds");
-                // Push all unsealed_cids and deal amount to verify later.
-                unsealed_cids.try_push(commd.cid()).expect("Programmer error: cannot have more that MAX_SECTORS_PER_CALL unsealed_cids because of previous bounds");
-                // Push all deals to verify in one go later.
-                all_sector_deals.try_push((&sector_on_chain).into()).expect(
-                    "Programmer error: sector deals cannot be more that MAX_SECTORS_PER_CALL because of previous bounds",
-                );
+                if !sector_on_chain.info.deal_ids.is_empty() {
+                    // Push all unsealed_cids to verify later.
+                    unsealed_cids.try_push(commd.cid()).expect("Programmer error: cannot have more that MAX_SECTORS_PER_CALL unsealed_cids because of previous bounds");
+                    // Push all deals to verify in one go later.
+                    all_sector_deals.try_push((&sector_on_chain).into()).expect(
+                        "Programmer error: sector deals cannot be more that MAX_SECTORS_PER_CALL because of previous bounds",
+                    );
+                }
                 // Add deposit to total deposit and push sector_on_chain to on_chain_sectors
                 // to avoid mutation of the SP for every sector.
                 total_deposit = total_deposit
@@ -521,18 +798,22 @@ pub mod pallet {
                     .expect("Programmer error: sectors should fit in this BoundedVec due to previous validation");
             }
 
-            let calculated_unsealed_cids =
-                T::Market::verify_deals_for_activation(&owner, all_sector_deals)?;
-            Self::check_commd_for_pre_commit(
-                calculated_unsealed_cids,
-                sector_amount,
-                unsealed_cids,
-                deal_amounts,
-            )?;
+            if !all_sector_deals.is_empty() {
+                let sectors_with_deals = all_sector_deals.len();
+                let calculated_unsealed_cids =
+                    T::Market::verify_deals_for_activation(&owner, all_sector_deals)?;
+                Self::check_commd_for_pre_commit(
+                    calculated_unsealed_cids,
+                    sectors_with_deals,
+                    unsealed_cids,
+                )?;
+            }
 
             // Check balance for deposit
-            let balance = T::Currency::total_balance(&owner);
-            ensure!(balance >= total_deposit, Error::<T>::NotEnoughFunds);
+            ensure!(
+                T::Currency::can_reserve(&owner, total_deposit),
+                Error::<T>::NotEnoughFunds
+            );
             T::Currency::reserve(&owner, total_deposit)?;
             StorageProviders::<T>::try_mutate(&owner, |maybe_sp| -> DispatchResult {
                 let sp = maybe_sp
@@ -545,6 +826,12 @@ pub mod pallet {
                 }
                 Ok(())
             })?;
+            // The sectors are checked by the hook once they can no longer be proven.
+            PreCommitExpirations::<T>::insert(
+                current_block + T::MaxProveCommitDuration::get(),
+                &owner,
+                (),
+            );
 
             Self::deposit_event(Event::SectorsPreCommitted {
                 block: current_block,
@@ -557,128 +844,184 @@ pub mod pallet {
 
         /// Allows the storage providers to submit proof for their pre-committed
         /// sectors.
+        ///
+        /// The deals of the proven sectors are activated, committed capacity sectors don't have any.
         pub fn prove_commit_sectors(
             origin: OriginFor<T>,
             sectors: BoundedVec<ProveCommitSector, ConstU32<MAX_SECTORS_PER_CALL>>,
         ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
-            let mut sp = StorageProviders::<T>::try_get(&owner)
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
-            // Create vectors for activating all prove commits at once.
-            let mut sector_deals = BoundedVec::new();
-            let mut new_sectors = BoundedVec::new();
-            let mut sector_numbers: BoundedVec<SectorNumber, ConstU32<MAX_SECTORS_PER_CALL>> =
-                BoundedVec::new();
+            let mut sector_numbers = Vec::with_capacity(sectors.len());
 
             for sector in sectors {
                 // Get pre-committed sector. This is the sector we are currently
                 // proving.
-                let precommit = sp
-                    .get_pre_committed_sector(sector.sector_number)
-                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
-                let prove_commit_due =
-                    precommit.pre_commit_block_number + T::MaxProveCommitDuration::get();
-                ensure!(current_block < prove_commit_due, {
-                    log::error!(target: LOG_TARGET, "prove_commit_sectors: Prove commit submitted after the deadline. {current_block:?} > {prove_commit_due:?}");
-                    Error::<T>::ProveCommitAfterDeadline
-                });
+                let precommit =
+                    Self::provable_pre_committed_sector(&sp, sector.sector_number, current_block)?;
 
                 // Validate the proof
-                validate_seal_proof::<T>(&owner, &precommit, sector.proof)?;
+                validate_seal_proof::<T>(&owner, precommit, sector.proof)?;
 
-                // Sector deals that will be activated after the sector is
-                // successfully proven.
-                sector_deals
-                    .try_push(precommit.into())
-                    .expect("Programmer error: Sector deals should fit in bound of MAX_SECTORS");
-                sector_numbers
-                    .try_push(sector.sector_number)
-                    .expect("Programmer error: Sector numbers should fit in bound of MAX_SECTORS");
-                // Sector that will be activated and required to be periodically
-                // proven
-                let new_sector =
-                    SectorOnChainInfo::from_pre_commit(precommit.info.clone(), current_block);
-                new_sectors
-                    .try_push(new_sector)
-                    .expect("Programmer error: New sectors should fit in bound of MAX_SECTORS");
+                sector_numbers.push(sector.sector_number);
             }
 
-            // Activate the deals for the sectors that will be proven. This
-            // action is not applied if Err is returned from the extrinsic.
-            let compute_commd = sector_deals.len() > 0;
-            T::Market::activate_deals(&owner, sector_deals, compute_commd)?;
+            Self::activate_proven_sectors(owner, sp, &sector_numbers, current_block)
+        }
 
-            // Activate the new sectors and remove from pre-committed sectors.
-            sector_numbers.iter().zip(&new_sectors).try_for_each(
-                |(&sector_number, new_sector)| -> Result<(), Error<T>> {
-                    // Activate the new sector
-                    sp.activate_sector(sector_number, new_sector.clone())?;
-                    // Remove sector from the pre-committed map
-                    sp.remove_pre_committed_sector(sector_number)?;
+        /// Allows the storage providers to prove many pre-committed sectors at once,
+        /// with a batch made of the seal proofs of all of them.
+        ///
+        /// The proofs are batch-verified together, which is considerably cheaper than verifying them one by one,
+        /// making it the preferred way of bulk onboarding sectors.
+        /// Unlike Filecoin's `ProveCommitBatch`, the proofs are not aggregated with SnarkPack,
+        /// so the batch grows linearly with the amount of sectors.
+        /// All of the sectors must have been sealed with the same seal proof type.
+        #[pallet::weight(T::ProofVerification::verify_batch_porep_weight(params.sector_numbers.len() as u32))]
+        pub fn prove_commit_batch(
+            origin: OriginFor<T>,
+            params: ProveCommitBatch,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
 
-                    Ok(())
-                },
+            let Some(&first_sector) = params.sector_numbers.first() else {
+                log::error!(target: LOG_TARGET, "prove_commit_batch: no sectors to prove");
+                return Err(Error::<T>::NoSectorsInBatch)?;
+            };
+            let seal_proof = sp
+                .get_pre_committed_sector(first_sector)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?
+                .info
+                .seal_proof;
+
+            let mut sectors: BoundedVec<PublicSealInfo, ConstU32<MAX_BATCHED_SECTORS>> =
+                BoundedVec::new();
+            for &sector_number in &params.sector_numbers {
+                let precommit =
+                    Self::provable_pre_committed_sector(&sp, sector_number, current_block)?;
+                ensure!(precommit.info.seal_proof == seal_proof, {
+                    log::error!(target: LOG_TARGET, "prove_commit_batch: sector {sector_number:?} was sealed with {:?}, expected {seal_proof:?}", precommit.info.seal_proof);
+                    Error::<T>::BatchSealProofMismatch
+                });
+
+                sectors
+                    .try_push(public_seal_info::<T>(&owner, precommit)?)
+                    .expect("Programmer error: sectors should fit in bound of MAX_BATCHED_SECTORS");
+            }
+
+            // Check proof size
+            let max_proof_size = seal_proof.proof_size() * sectors.len();
+            if params.batch_proof.len() > max_proof_size {
+                log::error!(target: LOG_TARGET, "batch proof size {} exceeds max {}", params.batch_proof.len(), max_proof_size);
+                return Err(Error::<T>::InvalidProof)?;
+            }
+
+            // Verify the batch of proofs
+            T::ProofVerification::verify_batch_porep(
+                derive_prover_id(&owner),
+                seal_proof,
+                sectors,
+                params.batch_proof,
             )?;
 
-            // Assign sectors to deadlines which specify when sectors needs
-            // to be proven
-            sp.assign_sectors_to_deadlines(
-                current_block,
-                new_sectors,
-                sp.info.window_post_partition_sectors,
-                T::MaxPartitionsPerDeadline::get(),
-                T::WPoStPeriodDeadlines::get(),
-                T::WPoStProvingPeriod::get(),
-                T::WPoStChallengeWindow::get(),
-                T::WPoStChallengeLookBack::get(),
-                T::FaultDeclarationCutoff::get(),
-            )
-            .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+            Self::activate_proven_sectors(owner, sp, &params.sector_numbers, current_block)
+        }
 
-            let sectors_proven = sector_numbers
-                .iter()
-                .map(|&sector_number| {
-                    // Find where the sector was placed. In worst case this goes through
-                    // all deadlines. It starts to look in the last partition of the
-                    // deadline. Usually the new sector will be there.
-                    let (deadline_idx, partition_number) = sp
-                        .deadlines
-                        .due
-                        .iter()
-                        .enumerate()
-                        .find_map(|(deadline_idx, deadline)| {
-                            deadline.partitions.iter().rev().find_map(
-                                |(partition_number, partition)| {
-                                    if partition.sectors.contains(&sector_number) {
-                                        Some((deadline_idx as u64, *partition_number))
-                                    } else {
-                                        None
-                                    }
-                                },
-                            )
-                        })
-                        .expect("sector should be assigned to a deadline");
-                    ProveCommitResult::new(sector_number, partition_number, deadline_idx)
-                })
-                .collect::<Vec<ProveCommitResult>>()
-                .try_into()
-                .expect("Programmer error: ProveCommitResult's should fit in bound of MAX_SECTORS");
+        /// Allows the storage providers to onboard sectors sealed with a non-interactive seal proof
+        /// in a single step, skipping the pre-commit and the wait for the interactive challenge.
+        ///
+        /// The challenges of non-interactive proofs are derived from the replica itself,
+        /// so no pre-commit deposit is locked and the sectors are activated right away.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0090.md>
+        pub fn prove_commit_ni_sectors(
+            origin: OriginFor<T>,
+            sectors: BoundedVec<
+                ProveCommitNiSector<BlockNumberFor<T>>,
+                ConstU32<MAX_SECTORS_PER_CALL>,
+            >,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sector_numbers = Vec::with_capacity(sectors.len());
+            // sector deals for all sectors
+            let mut all_sector_deals = BoundedVec::new();
+            // unsealed_cids for all sectors with deals
+            let mut unsealed_cids = BoundedVec::new();
 
-            StorageProviders::<T>::set(owner.clone(), Some(sp));
-            Self::deposit_event(Event::SectorsProven {
-                owner,
-                sectors: sectors_proven,
-            });
-            Ok(())
+            for ProveCommitNiSector { sector, proof } in sectors {
+                ensure!(sector.seal_proof.is_non_interactive(), {
+                    log::error!(target: LOG_TARGET, "prove_commit_ni_sectors: sector {} uses the interactive seal proof {:?}", sector.sector_number, sector.seal_proof);
+                    Error::<T>::InvalidProofType
+                });
+                // Basic validation, the sectors added below are checked as well
+                // so sector numbers can't be repeated within the call.
+                Self::validate_sector_for_pre_commit(&sp, &sector)?;
+
+                // The ticket must be recent, as it would be for interactive sectors when proven.
+                let randomness_expiry = sector
+                    .seal_randomness_height
+                    .checked_add(&T::MaxProveCommitDuration::get())
+                    .ok_or(ArithmeticError::Overflow)?;
+                ensure!(randomness_expiry > current_block, {
+                    log::error!(target: LOG_TARGET, "prove_commit_ni_sectors: seal randomness of sector {} is too old, {randomness_expiry:?} <= {current_block:?}", sector.sector_number);
+                    Error::<T>::SealRandomnessTooOld
+                });
+
+                // The sector is activated in this block.
+                Self::validate_expiration(current_block, current_block, sector.expiration)?;
+
+                let commd = Self::validate_sector_commitments(&sector)?;
+
+                validate_ni_seal_proof::<T>(&owner, &sector, proof)?;
+
+                let sector_on_chain =
+                    SectorPreCommitOnChainInfo::new(sector, BalanceOf::<T>::zero(), current_block);
+                if !sector_on_chain.info.deal_ids.is_empty() {
+                    // Push all unsealed_cids to verify later.
+                    unsealed_cids.try_push(commd.cid()).expect("Programmer error: cannot have more that MAX_SECTORS_PER_CALL unsealed_cids because of previous bounds");
+                    // Push all deals to verify in one go later.
+                    all_sector_deals.try_push((&sector_on_chain).into()).expect(
+                        "Programmer error: sector deals cannot be more that MAX_SECTORS_PER_CALL because of previous bounds",
+                    );
+                }
+
+                sector_numbers.push(sector_on_chain.info.sector_number);
+                // The sector goes through the pre-committed sectors, without a deposit,
+                // so it's activated the same way as interactive sectors.
+                sp.put_pre_committed_sector(sector_on_chain)
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+            }
+
+            if !all_sector_deals.is_empty() {
+                let sectors_with_deals = all_sector_deals.len();
+                let calculated_unsealed_cids =
+                    T::Market::verify_deals_for_activation(&owner, all_sector_deals)?;
+                Self::check_commd_for_pre_commit(
+                    calculated_unsealed_cids,
+                    sectors_with_deals,
+                    unsealed_cids,
+                )?;
+            }
+
+            Self::activate_proven_sectors(owner, sp, &sector_numbers, current_block)
         }
 
         /// The SP uses this extrinsic to submit their Proof-of-Spacetime.
+        #[pallet::weight(T::ProofVerification::verify_post_weight(windowed_post.partitions.len() as u32))]
         pub fn submit_windowed_post(
             origin: OriginFor<T>,
             windowed_post: SubmitWindowedPoStParams,
         ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
             let mut sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
@@ -697,11 +1040,32 @@ pub mod pallet {
                 }
             );
 
+            ensure!(!windowed_post.partitions.is_empty(), {
+                log::error!(target: LOG_TARGET, "submit_window_post: no partitions to prove");
+                Error::<T>::InvalidPartition
+            });
             ensure!(
-                windowed_post.proof.proof_bytes.len() <= primitives::MAX_POST_PROOF_BYTES as usize,
+                windowed_post.partitions.len() <= MAX_PARTITIONS_PER_POST as usize,
                 {
-                    log::error!("submit_window_post: invalid proof size");
-                    Error::<T>::PoStProofInvalid
+                    log::error!(
+                        target: LOG_TARGET,
+                        "submit_window_post: too many partitions {}, max {}",
+                        windowed_post.partitions.len(),
+                        MAX_PARTITIONS_PER_POST
+                    );
+                    Error::<T>::TooManyPartitionsAddressed
+                }
+            );
+
+            // There is a proof for each of the partitions, all of them have the same length.
+            let proof_len = windowed_post.proof.proof_bytes.len() / windowed_post.partitions.len();
+            ensure!(
+                proof_len > 0
+                    && proof_len <= MAX_POST_PROOF_BYTES as usize
+                    && windowed_post.proof.proof_bytes.len() % windowed_post.partitions.len() == 0,
+                {
+                    log::error!(target: LOG_TARGET, "submit_window_post: invalid proof size");
+                    Error::<T>::PoStProofInvalid
                 }
             );
 
@@ -739,40 +1103,6 @@ pub mod pallet {
                 )
                 .map_err(|e| Error::<T>::GeneralPalletError(e))?;
 
-            // TODO(@th7nder,#592, 19/11/2024): handle faulty and recovered sectors, we don't take them into account now
-            let deadlines = &sp.deadlines;
-            let mut replicas = BoundedBTreeMap::new();
-            // Take all the sectors that were assigned to all of the partitions
-            for partition in &windowed_post.partitions {
-                // Deadline is validated by `Self::validate_deadline`, so we're sure it can be used as an index.
-                let deadline = &deadlines.due[windowed_post.deadline as usize];
-                let sectors = &deadline
-                    .partitions
-                    .get(&partition)
-                    .ok_or(Error::<T>::InvalidPartition)?
-                    .sectors;
-                for sector_number in sectors {
-                    // Sectors stored in the Storage Provider struct should be consistently stored, without breaking invariants.
-                    let sector_info = &sp.sectors[sector_number];
-                    let comm_r = Commitment::<CommR>::from_cid_bytes(&sector_info.sealed_cid)
-                        .expect("CommR to be validated on pre-commit");
-                    let _ = replicas
-                        .try_insert(
-                            *sector_number,
-                            PublicReplicaInfo {
-                                comm_r: comm_r.raw(),
-                            },
-                        )
-                        .map_err(|_| Error::<T>::TooManyReplicas)?;
-                }
-            }
-
-            log::debug!(target: LOG_TARGET, "submit_windowed_post: index {:?} challenge {:?}, replicas: {:?}",
-                current_deadline.idx,
-                current_deadline.challenge,
-                replicas
-            );
-
             let entropy = owner.encode();
             // The `chain_commit_epoch` should be `current_deadline.challenge` as per:
             //
@@ -799,15 +1129,57 @@ pub mod pallet {
                 &entropy,
             )?;
 
+            // Deadline is validated by `Self::validate_deadline`, so we're sure it can be used as an index.
+            let deadline = &sp.deadlines.due[windowed_post.deadline as usize];
+            // Each partition has its own proof, all of them are verified at once.
+            // A partition's proof covers its live sectors that are not faulty, the declared recoveries were recorded above,
+            // which is the same set of sectors returned by `deadline_state` to the provers.
+            let mut partitions = BoundedVec::new();
+            for partition in &windowed_post.partitions {
+                let sectors = deadline
+                    .partitions
+                    .get(partition)
+                    .ok_or(Error::<T>::InvalidPartition)?
+                    .provable_sectors();
+                let mut replicas = BoundedBTreeMap::new();
+                for sector_number in &sectors {
+                    // Sectors stored in the Storage Provider struct should be consistently stored, without breaking invariants.
+                    let sector_info = &sp.sectors[sector_number];
+                    let comm_r = Commitment::<CommR>::from_cid_bytes(&sector_info.sealed_cid)
+                        .expect("CommR to be validated on pre-commit");
+                    let _ = replicas
+                        .try_insert(
+                            *sector_number,
+                            PublicReplicaInfo {
+                                comm_r: comm_r.raw(),
+                            },
+                        )
+                        .map_err(|_| Error::<T>::TooManyReplicas)?;
+                }
+
+                log::debug!(target: LOG_TARGET, "submit_windowed_post: index {:?} challenge {:?}, partition {:?}, replicas: {:?}",
+                    current_deadline.idx,
+                    current_deadline.challenge,
+                    partition,
+                    replicas
+                );
+
+                partitions
+                    .try_push(replicas)
+                    .expect("partitions to be validated against MAX_PARTITIONS_PER_POST");
+            }
+
             T::ProofVerification::verify_post(
                 windowed_post.proof.post_proof,
                 randomness,
-                replicas,
+                partitions,
                 windowed_post.proof.proof_bytes,
             )?;
 
             log::debug!(target: LOG_TARGET, "submit_windowed_post: proof recorded");
 
+            // Recovered sectors count towards the power again
+            Self::update_power(&owner, &sp);
             // Store new storage provider state
             StorageProviders::<T>::set(owner.clone(), Some(sp));
             Self::deposit_event(Event::ValidPoStSubmitted { owner });
@@ -820,7 +1192,7 @@ pub mod pallet {
         /// References:
         /// * <https://github.com/filecoin-project/builtin-actors/blob/82d02e58f9ef456aeaf2a6c737562ac97b22b244/actors/miner/src/lib.rs#L2648>
         pub fn declare_faults(origin: OriginFor<T>, params: DeclareFaultsParams) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
             let mut sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
@@ -890,6 +1262,7 @@ pub mod pallet {
                     .map_err(|e| Error::<T>::GeneralPalletError(e))?;
             }
 
+            Self::update_power(&owner, &sp);
             StorageProviders::<T>::set(owner.clone(), Some(sp));
             Self::deposit_event(Event::FaultsDeclared {
                 owner,
@@ -909,7 +1282,7 @@ pub mod pallet {
             origin: OriginFor<T>,
             params: DeclareFaultsRecoveredParams,
         ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
             let mut sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
@@ -987,7 +1360,7 @@ pub mod pallet {
             origin: OriginFor<T>,
             params: TerminateSectorsParams,
         ) -> DispatchResult {
-            let owner = ensure_signed(origin)?;
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
             let mut sp = StorageProviders::<T>::try_get(&owner)
                 .map_err(|_| Error::<T>::StorageProviderNotFound)?;
@@ -1009,6 +1382,7 @@ pub mod pallet {
                 .map(|(_sector_number, info)| info)
                 .cloned()
                 .collect::<Vec<_>>();
+            let pledged_sectors = sp.pledged_sectors_count();
             for (&deadline_idx, partition_sectors) in to_process.into_iter() {
                 ensure!(
                     deadline_is_mutable(
@@ -1040,134 +1414,1126 @@ pub mod pallet {
                 sp.early_terminations.insert(deadline_idx);
             }
 
+            // Release the initial pledge of the terminated sectors.
+            // TODO(@aidan46, #452, 2024-10-14): Figure out economics to apply early termination penalty.
+            let released_pledge = sp.remove_initial_pledge(pledged_sectors);
+            T::Currency::unreserve(&owner, released_pledge);
+
+            Self::process_early_terminations(current_block, &owner, &mut sp)?;
+
             // Update storage provider state
+            Self::update_power(&owner, &sp);
             StorageProviders::<T>::insert(&owner, sp);
 
-            Self::process_early_terminations(current_block, &owner)?;
-
             Self::deposit_event(Event::SectorsTerminated {
                 owner,
                 terminations: params.terminations,
             });
             Ok(())
         }
-    }
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            // TODO(@th7nder, no-ref, 2024/07/31): set proper weights
-            T::DbWeight::get().reads(1)
-        }
+        /// Extends the expiration of live sectors, so they can keep being proven without resealing.
+        ///
+        /// The extended sectors must be active (i.e. not faulty, unproven or terminated)
+        /// and their deadlines must be mutable.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+        pub fn extend_sector_expiration(
+            origin: OriginFor<T>,
+            params: ExtendSectorExpirationParams<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
 
-        fn on_finalize(current_block: BlockNumberFor<T>) {
-            Self::check_precommited_sectors(current_block);
-            Self::check_deadlines(current_block);
+            // Every declaration addresses a single partition.
+            ensure!(
+                params.extensions.len() as u64 <= T::AddressedPartitionsMax::get(),
+                {
+                    log::error!(target: LOG_TARGET, "extend_sector_expiration: too many partitions addressed, {} > {}", params.extensions.len(), T::AddressedPartitionsMax::get());
+                    Error::<T>::TooManyPartitionsAddressed
+                }
+            );
+            let sector_count = params
+                .extensions
+                .iter()
+                .map(|extension| extension.sectors.len() as u64)
+                .sum::<u64>();
+            ensure!(sector_count <= T::AddressedSectorsMax::get(), {
+                log::error!(target: LOG_TARGET, "extend_sector_expiration: too many sectors addressed, {} > {}", sector_count, T::AddressedSectorsMax::get());
+                Error::<T>::TooManySectorsAddressed
+            });
+
+            for extension in params.extensions.iter() {
+                ensure!(
+                    deadline_is_mutable(
+                        sp.proving_period_start,
+                        extension.deadline,
+                        current_block,
+                        T::WPoStPeriodDeadlines::get(),
+                        T::WPoStProvingPeriod::get(),
+                        T::WPoStChallengeWindow::get(),
+                        T::WPoStChallengeLookBack::get(),
+                        T::FaultDeclarationCutoff::get(),
+                    )
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?,
+                    {
+                        log::error!(target: LOG_TARGET, "cannot extend sectors in immutable deadline {}", extension.deadline);
+                        Error::<T>::CannotExtendImmutableDeadline
+                    }
+                );
+
+                let mut old_sectors = Vec::with_capacity(extension.sectors.len());
+                for sector_number in extension.sectors.iter() {
+                    let sector = sp.sectors.get(sector_number).ok_or_else(|| {
+                        log::error!(target: LOG_TARGET, "extend_sector_expiration: sector {sector_number} not found");
+                        Error::<T>::InvalidSector
+                    })?;
+
+                    ensure!(extension.new_expiration > sector.expiration, {
+                        log::error!(target: LOG_TARGET, "extend_sector_expiration: cannot reduce the expiration of sector {sector_number} ({:?} -> {:?})", sector.expiration, extension.new_expiration);
+                        Error::<T>::CannotReduceSectorExpiration
+                    });
+                    Self::validate_expiration(
+                        current_block,
+                        sector.activation,
+                        extension.new_expiration,
+                    )?;
+
+                    old_sectors.push(sector.clone());
+                }
+
+                let new_sectors = old_sectors
+                    .iter()
+                    .cloned()
+                    .map(|mut sector| {
+                        sector.expiration = extension.new_expiration;
+                        sector
+                    })
+                    .collect::<Vec<_>>();
+
+                let deadline = sp
+                    .deadlines
+                    .load_deadline_mut(extension.deadline as usize)
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+                deadline
+                    .replace_sectors(
+                        extension.partition,
+                        &old_sectors.iter().collect::<Vec<_>>(),
+                        &new_sectors,
+                    )
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+
+                for sector in new_sectors {
+                    if let Some(info) = sp.sectors.get_mut(&sector.sector_number) {
+                        info.expiration = sector.expiration;
+                    }
+                }
+            }
+
+            // Update storage provider state
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::SectorsExpirationExtended {
+                owner,
+                extensions: params.extensions,
+            });
+            Ok(())
         }
-    }
 
-    impl<T: Config> StorageProviderValidation<T::AccountId> for Pallet<T> {
-        fn is_registered_storage_provider(storage_provider: &T::AccountId) -> bool {
-            StorageProviders::<T>::contains_key(storage_provider)
+        /// Changes the worker and control addresses of the storage provider.
+        ///
+        /// Only the owner can call this extrinsic.
+        /// The control addresses are replaced immediately, while the worker change
+        /// needs to be confirmed with `confirm_change_worker_address`
+        /// after [`Config::WorkerKeyChangeDelay`] blocks have passed.
+        /// Passing the current worker cancels any pending worker change.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/lib.rs>
+        pub fn change_worker_address(
+            origin: OriginFor<T>,
+            new_worker: T::AccountId,
+            new_control_addresses: BoundedVec<T::AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            Self::ensure_address_available(&owner, &new_worker)?;
+            for control_address in new_control_addresses.iter() {
+                Self::ensure_address_available(&owner, control_address)?;
+            }
+
+            let old_info = sp.info.clone();
+            sp.info.control_addresses = new_control_addresses.clone();
+
+            let mut scheduled_change = None;
+            let already_pending = sp
+                .info
+                .pending_worker_key
+                .as_ref()
+                .is_some_and(|pending| pending.new_worker == new_worker);
+            if new_worker == sp.info.worker {
+                // Changing to the current worker cancels the pending change
+                sp.info.pending_worker_key = None;
+            } else if !already_pending {
+                // If the same change is already pending, the original schedule is kept
+                let effective_at = current_block
+                    .checked_add(&T::WorkerKeyChangeDelay::get())
+                    .ok_or(ArithmeticError::Overflow)?;
+                let change = WorkerKeyChange {
+                    new_worker,
+                    effective_at,
+                };
+                sp.info.pending_worker_key = Some(change.clone());
+                scheduled_change = Some(change);
+            }
+
+            Self::update_storage_provider_accounts(&owner, &old_info, &sp.info);
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::ControlAddressesChanged {
+                owner: owner.clone(),
+                control_addresses: new_control_addresses,
+            });
+            if let Some(change) = scheduled_change {
+                Self::deposit_event(Event::WorkerAddressChangeScheduled {
+                    owner,
+                    new_worker: change.new_worker,
+                    effective_at: change.effective_at,
+                });
+            }
+            Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Gets the current deadline of the storage provider.
+        /// Confirms a worker change scheduled by `change_worker_address`.
         ///
-        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
-        /// May exceptionally return [`Option::None`] when
-        /// conversion between BlockNumbers fails, but technically should never happen.
-        pub fn current_deadline(
-            storage_provider: &T::AccountId,
-        ) -> Option<ExternalDeadlineInfo<BlockNumberFor<T>>> {
-            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+        /// Only the owner can call this extrinsic and only after the change is effective.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/lib.rs>
+        pub fn confirm_change_worker_address(origin: OriginFor<T>) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
             let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
 
-            let deadline = sp
-                .deadline_info(
-                    current_block,
-                    T::WPoStPeriodDeadlines::get(),
-                    T::WPoStProvingPeriod::get(),
-                    T::WPoStChallengeWindow::get(),
-                    T::WPoStChallengeLookBack::get(),
-                    T::FaultDeclarationCutoff::get(),
-                )
-                .ok()?;
+            let old_info = sp.info.clone();
+            let pending = sp
+                .info
+                .pending_worker_key
+                .take()
+                .ok_or(Error::<T>::NoPendingWorkerKeyChange)?;
+            ensure!(current_block >= pending.effective_at, {
+                log::error!(target: LOG_TARGET, "confirm_change_worker_address: change is effective at {:?}, current block {:?}", pending.effective_at, current_block);
+                Error::<T>::WorkerKeyChangeNotEffective
+            });
+            // The account could have been taken by another storage provider in the meantime
+            Self::ensure_address_available(&owner, &pending.new_worker)?;
 
-            Some(ExternalDeadlineInfo {
-                deadline_index: deadline.idx,
-                open: deadline.is_open(),
-                challenge_block: deadline.challenge,
-                start: deadline.open_at,
-            })
+            sp.info.worker = pending.new_worker;
+            let worker = sp.info.worker.clone();
+            Self::update_storage_provider_accounts(&owner, &old_info, &sp.info);
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::WorkerAddressChanged { owner, worker });
+            Ok(())
+        }
+
+        /// Changes the libp2p identity of the storage provider.
+        ///
+        /// Can be called by the owner, the worker or any of the control addresses.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/lib.rs>
+        pub fn change_peer_id(origin: OriginFor<T>, new_peer_id: T::PeerId) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            sp.info.peer_id = new_peer_id.clone();
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::PeerIdChanged {
+                owner,
+                peer_id: new_peer_id,
+            });
+            Ok(())
         }
 
-        /// Gets the next, not yet opened, deadline of the storage provider.
-        ///
-        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
-        /// May exceptionally return [`Option::None`] when
-        /// conversion between BlockNumbers fails, but technically should never happen.
-        pub fn deadline_info(
-            storage_provider: &T::AccountId,
-            deadline_index: u64,
-        ) -> Option<ExternalDeadlineInfo<BlockNumberFor<T>>> {
-            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
-            let current_block = <frame_system::Pallet<T>>::block_number();
+        /// Replaces the multiaddrs clients use to dial the storage provider.
+        ///
+        /// Can be called by the owner, the worker or any of the control addresses.
+        /// Passing an empty list removes all the multiaddrs.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/lib.rs>
+        pub fn change_multiaddrs(
+            origin: OriginFor<T>,
+            new_multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            ensure!(
+                new_multiaddrs.iter().all(|multiaddr| !multiaddr.is_empty()),
+                {
+                    log::error!(target: LOG_TARGET, "change_multiaddrs: multiaddrs cannot be empty");
+                    Error::<T>::InvalidMultiaddr
+                }
+            );
+
+            sp.info.multiaddrs = new_multiaddrs.clone();
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::MultiaddrsChanged {
+                owner,
+                multiaddrs: new_multiaddrs,
+            });
+            Ok(())
+        }
+
+        /// Puts deals into committed capacity sectors, by proving that their replicas
+        /// were updated with the deals' data, without sealing the sectors again.
+        ///
+        /// The updated sectors must be active and their deadlines must be mutable.
+        /// The sectors keep their expiration, which must outlast the deals.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0019.md>
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+        pub fn prove_replica_updates(
+            origin: OriginFor<T>,
+            updates: BoundedVec<ReplicaUpdate, ConstU32<MAX_SECTORS_PER_CALL>>,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            // Sector deals and unsealed_cids to verify all updates at once.
+            let mut sector_deals = BoundedVec::new();
+            let mut unsealed_cids = BoundedVec::new();
+            let mut sector_numbers: BoundedVec<SectorNumber, ConstU32<MAX_SECTORS_PER_CALL>> =
+                BoundedVec::new();
+
+            for update in updates.iter() {
+                ensure!(
+                    deadline_is_mutable(
+                        sp.proving_period_start,
+                        update.deadline,
+                        current_block,
+                        T::WPoStPeriodDeadlines::get(),
+                        T::WPoStProvingPeriod::get(),
+                        T::WPoStChallengeWindow::get(),
+                        T::WPoStChallengeLookBack::get(),
+                        T::FaultDeclarationCutoff::get(),
+                    )
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?,
+                    {
+                        log::error!(target: LOG_TARGET, "cannot update sectors in immutable deadline {}", update.deadline);
+                        Error::<T>::CannotUpdateImmutableDeadline
+                    }
+                );
+
+                let sector = sp.sectors.get(&update.sector_number).ok_or_else(|| {
+                    log::error!(target: LOG_TARGET, "prove_replica_updates: sector {} not found", update.sector_number);
+                    Error::<T>::InvalidSector
+                })?;
+
+                // Only sectors without any data can be updated.
+                let commd = Commitment::<CommD>::from_cid_bytes(&sector.unsealed_cid[..])
+                    .map_err(|err| {
+                        log::error!(target: LOG_TARGET, err:?; "prove_replica_updates: invalid unsealed_cid of sector {}", update.sector_number);
+                        Error::<T>::InvalidCid
+                    })?;
+                ensure!(commd == Commitment::<CommD>::zero(sector.seal_proof), {
+                    log::error!(target: LOG_TARGET, "prove_replica_updates: sector {} is not a committed capacity sector", update.sector_number);
+                    Error::<T>::SectorNotCommittedCapacity
+                });
+
+                let new_commd = Commitment::<CommD>::from_cid_bytes(&update.new_unsealed_cid[..])
+                    .map_err(|err| {
+                        log::error!(target: LOG_TARGET, err:?; "prove_replica_updates: invalid new_unsealed_cid");
+                        Error::<T>::InvalidCid
+                    })?;
+
+                // The sector must be active, i.e. not faulty or terminated.
+                // Its expiration doesn't change, only the replica is replaced.
+                let mut new_sector = sector.clone();
+                new_sector.sealed_cid = update.new_sealed_cid.clone();
+                new_sector.unsealed_cid = update.new_unsealed_cid.clone();
+                let deadline = sp
+                    .deadlines
+                    .load_deadline_mut(update.deadline as usize)
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+                deadline
+                    .replace_sectors(update.partition, &[sector], &[new_sector.clone()])
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+
+                validate_replica_update_proof::<T>(sector, update)?;
+
+                sector_deals
+                    .try_push(SectorDeal {
+                        sector_number: update.sector_number,
+                        sector_expiry: sector.expiration,
+                        sector_type: sector.seal_proof,
+                        deal_ids: update.deal_ids.clone(),
+                    })
+                    .expect("Programmer error: sector deals should fit in bound of MAX_SECTORS_PER_CALL");
+                unsealed_cids.try_push(new_commd.cid()).expect(
+                    "Programmer error: unsealed_cids should fit in bound of MAX_SECTORS_PER_CALL",
+                );
+                sector_numbers.try_push(update.sector_number).expect(
+                    "Programmer error: sector numbers should fit in bound of MAX_SECTORS_PER_CALL",
+                );
+                if let Some(info) = sp.sectors.get_mut(&update.sector_number) {
+                    *info = new_sector;
+                }
+            }
+
+            // The deals must match the data in the updated replicas.
+            let calculated_unsealed_cids =
+                T::Market::verify_deals_for_activation(&owner, sector_deals.clone())?;
+            Self::check_commd_for_pre_commit(
+                calculated_unsealed_cids,
+                sector_numbers.len(),
+                unsealed_cids,
+            )?;
+            T::Market::activate_deals(&owner, sector_deals, true)?;
+
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::ReplicasUpdated {
+                owner,
+                sectors: sector_numbers,
+            });
+            Ok(())
+        }
+
+        /// Compacts partitions of a deadline, re-packing their live sectors into as few partitions as possible.
+        ///
+        /// The terminated and expired sectors are dropped from the partitions,
+        /// so they don't need to be considered when proving the deadline anymore.
+        /// Faulty sectors keep their fault expiration and declared recoveries are kept.
+        /// The remaining partitions of the deadline are renumbered.
+        ///
+        /// The deadline must be mutable and the partitions must not have unproven sectors
+        /// nor early terminations waiting to be processed.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+        pub fn compact_partitions(
+            origin: OriginFor<T>,
+            params: CompactPartitionsParams,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            ensure!(
+                params.partitions.len() as u64 <= T::AddressedPartitionsMax::get(),
+                {
+                    log::error!(target: LOG_TARGET, "compact_partitions: too many partitions addressed, {} > {}", params.partitions.len(), T::AddressedPartitionsMax::get());
+                    Error::<T>::TooManyPartitionsAddressed
+                }
+            );
+            ensure!(
+                deadline_is_mutable(
+                    sp.proving_period_start,
+                    params.deadline,
+                    current_block,
+                    T::WPoStPeriodDeadlines::get(),
+                    T::WPoStProvingPeriod::get(),
+                    T::WPoStChallengeWindow::get(),
+                    T::WPoStChallengeLookBack::get(),
+                    T::FaultDeclarationCutoff::get(),
+                )
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?,
+                {
+                    log::error!(target: LOG_TARGET, "cannot compact partitions in immutable deadline {}", params.deadline);
+                    Error::<T>::CannotCompactImmutableDeadline
+                }
+            );
+
+            let partition_size = sp.info.window_post_partition_sectors;
+            let deadline = sp
+                .deadlines
+                .load_deadline_mut(params.deadline as usize)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+            deadline
+                .compact_partitions(partition_size, &sp.sectors, &params.partitions)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::PartitionsCompacted {
+                owner,
+                deadline: params.deadline,
+                partitions: params.partitions,
+            });
+            Ok(())
+        }
+
+        /// Moves partitions, with all their sectors, from a deadline to another.
+        ///
+        /// Faults, recoveries and unproven sectors are carried over to the destination deadline.
+        /// The moved partitions are added after the partitions of the destination deadline
+        /// and the remaining partitions of the original deadline are renumbered.
+        ///
+        /// Both deadlines must be mutable and the destination deadline must be due before the original one,
+        /// so the moved sectors are proven again within a proving period.
+        /// The partitions must not have early terminations waiting to be processed.
+        ///
+        /// References:
+        /// * <https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0070.md>
+        /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/lib.rs>
+        pub fn move_partitions(
+            origin: OriginFor<T>,
+            params: MovePartitionsParams,
+        ) -> DispatchResult {
+            let owner = Self::resolve_owner(ensure_signed(origin)?)?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let mut sp = StorageProviders::<T>::try_get(&owner)
+                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
+
+            ensure!(
+                params.partitions.len() as u64 <= T::AddressedPartitionsMax::get(),
+                {
+                    log::error!(target: LOG_TARGET, "move_partitions: too many partitions addressed, {} > {}", params.partitions.len(), T::AddressedPartitionsMax::get());
+                    Error::<T>::TooManyPartitionsAddressed
+                }
+            );
+            for deadline_idx in [params.orig_deadline, params.dest_deadline] {
+                ensure!(
+                    deadline_is_mutable(
+                        sp.proving_period_start,
+                        deadline_idx,
+                        current_block,
+                        T::WPoStPeriodDeadlines::get(),
+                        T::WPoStProvingPeriod::get(),
+                        T::WPoStChallengeWindow::get(),
+                        T::WPoStChallengeLookBack::get(),
+                        T::FaultDeclarationCutoff::get(),
+                    )
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?,
+                    {
+                        log::error!(target: LOG_TARGET, "cannot move partitions from or to immutable deadline {}", deadline_idx);
+                        Error::<T>::CannotMoveImmutableDeadline
+                    }
+                );
+            }
+
+            // The sectors must not skip their proof, otherwise they'd go unproven for longer than a proving period.
+            let next_open_at = |deadline_idx| {
+                DeadlineInfo::new(
+                    current_block,
+                    sp.proving_period_start,
+                    deadline_idx,
+                    T::WPoStPeriodDeadlines::get(),
+                    T::WPoStProvingPeriod::get(),
+                    T::WPoStChallengeWindow::get(),
+                    T::WPoStChallengeLookBack::get(),
+                    T::FaultDeclarationCutoff::get(),
+                )
+                .and_then(DeadlineInfo::next_not_elapsed)
+                .map(|deadline| deadline.open_at)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))
+            };
+            ensure!(
+                next_open_at(params.dest_deadline)? < next_open_at(params.orig_deadline)?,
+                {
+                    log::error!(target: LOG_TARGET, "move_partitions: deadline {} is not due before deadline {}", params.dest_deadline, params.orig_deadline);
+                    Error::<T>::InvalidDestinationDeadline
+                }
+            );
+
+            sp.deadlines
+                .move_partitions(
+                    params.orig_deadline as usize,
+                    params.dest_deadline as usize,
+                    &params.partitions,
+                )
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+
+            StorageProviders::<T>::insert(&owner, sp);
+
+            Self::deposit_event(Event::PartitionsMoved {
+                owner,
+                orig_deadline: params.orig_deadline,
+                dest_deadline: params.dest_deadline,
+                partitions: params.partitions,
+            });
+            Ok(())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Accounts for the work done by [`Hooks::on_finalize`], bounded by the storage providers scheduled for the block.
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let deadline_checks = DeadlineChecks::<T>::iter_key_prefix(n).count() as u64;
+            let pre_commit_expirations =
+                PreCommitExpirations::<T>::iter_key_prefix(n).count() as u64;
+            let scheduled = deadline_checks.saturating_add(pre_commit_expirations);
+
+            // Counting the schedules here and draining them in `on_finalize`.
+            db_weight
+                .reads_writes(scheduled.saturating_mul(2), scheduled)
+                // The storage provider, its power and balance are read and written,
+                // then its next deadline is scheduled.
+                .saturating_add(db_weight.reads_writes(4, 5).saturating_mul(deadline_checks))
+                // The storage provider and its balance are read and written, the deposit is burned.
+                .saturating_add(
+                    db_weight
+                        .reads_writes(2, 3)
+                        .saturating_mul(pre_commit_expirations),
+                )
+        }
+
+        fn on_finalize(current_block: BlockNumberFor<T>) {
+            Self::check_precommited_sectors(current_block);
+            Self::check_deadlines(current_block);
+        }
+    }
+
+    impl<T: Config> StorageProviderValidation<T::AccountId, BlockNumberFor<T>> for Pallet<T> {
+        fn is_registered_storage_provider(storage_provider: &T::AccountId) -> bool {
+            StorageProviders::<T>::contains_key(storage_provider)
+        }
+
+        fn storage_provider_owner(account: &T::AccountId) -> Option<T::AccountId> {
+            Self::resolve_owner(account.clone()).ok()
+        }
+
+        fn sector_expiration(
+            storage_provider: &T::AccountId,
+            sector_number: SectorNumber,
+        ) -> Option<BlockNumberFor<T>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            sp.sectors
+                .get(&sector_number)
+                .map(|sector| sector.expiration)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Resolves the owner of the storage provider the account acts for.
+        ///
+        /// The account can be the owner itself, the worker or one of the control addresses.
+        pub fn resolve_owner(account: T::AccountId) -> Result<T::AccountId, Error<T>> {
+            if StorageProviders::<T>::contains_key(&account) {
+                return Ok(account);
+            }
+            StorageProviderAccounts::<T>::get(&account).ok_or(Error::<T>::StorageProviderNotFound)
+        }
+
+        /// Checks that the account is not the owner, worker or control address of another storage provider.
+        fn ensure_address_available(
+            owner: &T::AccountId,
+            account: &T::AccountId,
+        ) -> Result<(), Error<T>> {
+            if account == owner {
+                return Ok(());
+            }
+            let acting_for_other = StorageProviderAccounts::<T>::get(account)
+                .is_some_and(|account_owner| &account_owner != owner);
+            ensure!(
+                !StorageProviders::<T>::contains_key(account) && !acting_for_other,
+                {
+                    log::error!(target: LOG_TARGET, "account {account:?} is already in use by another storage provider");
+                    Error::<T>::AddressAlreadyInUse
+                }
+            );
+            Ok(())
+        }
+
+        /// Updates the [`StorageProviderAccounts`] index after the storage provider's addresses changed.
+        fn update_storage_provider_accounts(
+            owner: &T::AccountId,
+            old_info: &StorageProviderInfo<T::AccountId, T::PeerId, BlockNumberFor<T>>,
+            new_info: &StorageProviderInfo<T::AccountId, T::PeerId, BlockNumberFor<T>>,
+        ) {
+            for account in core::iter::once(&old_info.worker).chain(&old_info.control_addresses) {
+                StorageProviderAccounts::<T>::remove(account);
+            }
+            for account in core::iter::once(&new_info.worker)
+                .chain(&new_info.control_addresses)
+                .filter(|account| *account != owner)
+            {
+                StorageProviderAccounts::<T>::insert(account, owner);
+            }
+        }
+
+        /// Gets the current deadline of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        /// May exceptionally return [`Option::None`] when
+        /// conversion between BlockNumbers fails, but technically should never happen.
+        pub fn current_deadline(
+            storage_provider: &T::AccountId,
+        ) -> Option<ExternalDeadlineInfo<BlockNumberFor<T>>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            let deadline = sp
+                .deadline_info(
+                    current_block,
+                    T::WPoStPeriodDeadlines::get(),
+                    T::WPoStProvingPeriod::get(),
+                    T::WPoStChallengeWindow::get(),
+                    T::WPoStChallengeLookBack::get(),
+                    T::FaultDeclarationCutoff::get(),
+                )
+                .ok()?;
+
+            Some(ExternalDeadlineInfo {
+                deadline_index: deadline.idx,
+                open: deadline.is_open(),
+                challenge_block: deadline.challenge,
+                start: deadline.open_at,
+            })
+        }
+
+        /// Gets the next, not yet opened, deadline of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        /// May exceptionally return [`Option::None`] when
+        /// conversion between BlockNumbers fails, but technically should never happen.
+        pub fn deadline_info(
+            storage_provider: &T::AccountId,
+            deadline_index: u64,
+        ) -> Option<ExternalDeadlineInfo<BlockNumberFor<T>>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            let deadline = DeadlineInfo::new(
+                current_block,
+                sp.proving_period_start,
+                deadline_index,
+                T::WPoStPeriodDeadlines::get(),
+                T::WPoStProvingPeriod::get(),
+                T::WPoStChallengeWindow::get(),
+                T::WPoStChallengeLookBack::get(),
+                T::FaultDeclarationCutoff::get(),
+            )
+            .and_then(DeadlineInfo::next_not_opened)
+            .ok()?;
+
+            Some(ExternalDeadlineInfo {
+                deadline_index: deadline.idx,
+                open: deadline.is_open(),
+                challenge_block: deadline.challenge,
+                start: deadline.open_at,
+            })
+        }
+
+        /// Returns snapshot information about the deadline, i.e. which sectors are assigned to which partitions.
+        /// When the deadline has not opened yet (deadline_start - WPoStChallengeWindow), it can change!
+        ///
+        /// The sectors of each partition are the ones to be proven by a Windowed PoSt,
+        /// i.e. its live sectors that are not faulty, or that are declared as recovered.
+        pub fn deadline_state(
+            storage_provider: &T::AccountId,
+            deadline_index: u64,
+        ) -> Option<primitives::pallets::DeadlineState> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            let deadline_index: usize = deadline_index.try_into().ok()?;
+
+            if deadline_index >= sp.deadlines.due.len() {
+                log::warn!(
+                    "tried to get non existing deadline: {}/{}",
+                    deadline_index,
+                    sp.deadlines.due.len()
+                );
+                return None;
+            }
+
+            let deadline = &sp.deadlines.due[deadline_index];
+            let mut partitions = BoundedBTreeMap::new();
+            for (partition_number, partition) in deadline.partitions.iter() {
+                partitions
+                    .try_insert(
+                        *partition_number,
+                        primitives::pallets::PartitionState {
+                            sectors: partition.provable_sectors(),
+                        },
+                    )
+                    .ok()?;
+            }
+
+            Some(primitives::pallets::DeadlineState { partitions })
+        }
+
+        /// Returns the peer ID and multiaddrs clients use to reach the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        pub fn peer_info(
+            storage_provider: &T::AccountId,
+        ) -> Option<primitives::pallets::StorageProviderPeerInfo<T::PeerId>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+
+            Some(primitives::pallets::StorageProviderPeerInfo {
+                peer_id: sp.info.peer_id,
+                multiaddrs: sp.info.multiaddrs,
+            })
+        }
+
+        /// Returns the power of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        pub fn storage_provider_power(storage_provider: &T::AccountId) -> Option<StoragePower> {
+            StorageProviders::<T>::contains_key(storage_provider)
+                .then(|| StorageProviderPower::<T>::get(storage_provider))
+        }
+
+        /// Returns the total power of the network.
+        pub fn total_power() -> StoragePower {
+            TotalPower::<T>::get()
+        }
+
+        /// Returns the on-chain information about a proven sector and where it is assigned.
+        ///
+        /// If there is no Storage Provider of given AccountId or it has no such sector returns [`Option::None`].
+        pub fn sector_info(
+            storage_provider: &T::AccountId,
+            sector_number: SectorNumber,
+        ) -> Option<SectorInfo<BlockNumberFor<T>>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            let sector = sp.sectors.get(&sector_number)?;
+
+            let (deadline_index, partition_number) =
+                sp.deadlines
+                    .due
+                    .iter()
+                    .enumerate()
+                    .find_map(|(deadline_index, deadline)| {
+                        deadline
+                            .partitions
+                            .iter()
+                            .find(|(_, partition)| partition.sectors.contains(&sector_number))
+                            .map(|(partition_number, _)| (deadline_index as u64, *partition_number))
+                    })?;
+
+            Some(SectorInfo {
+                sector_number,
+                seal_proof: sector.seal_proof,
+                sealed_cid: sector.sealed_cid.clone(),
+                unsealed_cid: sector.unsealed_cid.clone(),
+                activation: sector.activation,
+                expiration: sector.expiration,
+                deadline_index,
+                partition_number,
+            })
+        }
+
+        /// Returns the faulty, recovering and terminated sectors of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        pub fn sector_faults(storage_provider: &T::AccountId) -> Option<SectorFaults> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+
+            let mut faulty = BTreeSet::new();
+            let mut recovering = BTreeSet::new();
+            let mut terminated = BTreeSet::new();
+            for partition in sp
+                .deadlines
+                .due
+                .iter()
+                .flat_map(|deadline| deadline.partitions.values())
+            {
+                faulty.extend(partition.faults.iter().copied());
+                recovering.extend(partition.recoveries.iter().copied());
+                terminated.extend(partition.terminated.iter().copied());
+            }
+
+            Some(SectorFaults {
+                faulty: faulty.try_into().ok()?,
+                recovering: recovering.try_into().ok()?,
+                terminated: terminated.try_into().ok()?,
+            })
+        }
+
+        /// Returns the pre-committed sectors of the storage provider, along with the block by which they must be proven.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        pub fn pre_committed_sectors(
+            storage_provider: &T::AccountId,
+        ) -> Option<
+            BoundedVec<PreCommittedSector<BalanceOf<T>, BlockNumberFor<T>>, ConstU32<MAX_SECTORS>>,
+        > {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+
+            let pre_committed_sectors = sp
+                .pre_committed_sectors
+                .values()
+                .map(|pre_commit| PreCommittedSector {
+                    sector_number: pre_commit.info.sector_number,
+                    pre_commit_deposit: pre_commit.pre_commit_deposit,
+                    pre_commit_block_number: pre_commit.pre_commit_block_number,
+                    prove_commit_due: pre_commit.pre_commit_block_number
+                        + T::MaxProveCommitDuration::get(),
+                    expiration: pre_commit.info.expiration,
+                })
+                .collect::<Vec<_>>();
+
+            BoundedVec::try_from(pre_committed_sectors).ok()
+        }
+
+        /// Returns the next deadline in which the storage provider needs to submit a Windowed PoSt,
+        /// i.e. the earliest not elapsed deadline with live sectors that have not been proven yet.
+        ///
+        /// If there is no Storage Provider of given AccountId or it has no sectors to prove returns [`Option::None`].
+        /// May exceptionally return [`Option::None`] when
+        /// conversion between BlockNumbers fails, but technically should never happen.
+        pub fn next_deadline(
+            storage_provider: &T::AccountId,
+        ) -> Option<ExternalDeadlineInfo<BlockNumberFor<T>>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            let mut next: Option<DeadlineInfo<BlockNumberFor<T>>> = None;
+            for (deadline_index, deadline) in sp.deadlines.due.iter().enumerate() {
+                if deadline.live_sectors == 0 {
+                    continue;
+                }
+
+                let mut info = DeadlineInfo::new(
+                    current_block,
+                    sp.proving_period_start,
+                    deadline_index as u64,
+                    T::WPoStPeriodDeadlines::get(),
+                    T::WPoStProvingPeriod::get(),
+                    T::WPoStChallengeWindow::get(),
+                    T::WPoStChallengeLookBack::get(),
+                    T::FaultDeclarationCutoff::get(),
+                )
+                .and_then(DeadlineInfo::next_not_elapsed)
+                .ok()?;
+
+                // All partitions were already proven in the open window, the next window is the one that matters
+                let all_posted = deadline
+                    .partitions
+                    .keys()
+                    .all(|partition| deadline.partitions_posted.contains(partition));
+                if info.is_open() && all_posted {
+                    info = info.next().ok()?;
+                }
+
+                if next
+                    .as_ref()
+                    .map_or(true, |next| info.open_at < next.open_at)
+                {
+                    next = Some(info);
+                }
+            }
+
+            next.map(|deadline| ExternalDeadlineInfo {
+                deadline_index: deadline.idx,
+                open: deadline.is_open(),
+                challenge_block: deadline.challenge,
+                start: deadline.open_at,
+            })
+        }
+
+        /// Returns the funds locked by the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        pub fn locked_funds(storage_provider: &T::AccountId) -> Option<LockedFunds<BalanceOf<T>>> {
+            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
+
+            Some(LockedFunds {
+                pre_commit_deposits: sp.pre_commit_deposits,
+                initial_pledge: sp.initial_pledge,
+            })
+        }
+
+        /// Gets a pre-committed sector, making sure it can still be proven.
+        fn provable_pre_committed_sector(
+            sp: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+            sector_number: SectorNumber,
+            current_block: BlockNumberFor<T>,
+        ) -> Result<&SectorPreCommitOnChainInfo<BalanceOf<T>, BlockNumberFor<T>>, Error<T>>
+        {
+            let precommit = sp
+                .get_pre_committed_sector(sector_number)
+                .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+            let prove_commit_due =
+                precommit.pre_commit_block_number + T::MaxProveCommitDuration::get();
+            ensure!(current_block < prove_commit_due, {
+                log::error!(target: LOG_TARGET, "prove_commit_sectors: Prove commit submitted after the deadline. {current_block:?} > {prove_commit_due:?}");
+                Error::<T>::ProveCommitAfterDeadline
+            });
+
+            Ok(precommit)
+        }
+
+        /// Activates the pre-committed sectors whose proofs have been verified.
+        ///
+        /// The sectors' deals are activated, the pre-commit deposits are replaced by the initial pledge
+        /// and the sectors are assigned to deadlines, where they'll need to be periodically proven.
+        fn activate_proven_sectors(
+            owner: T::AccountId,
+            mut sp: StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+            sector_numbers: &[SectorNumber],
+            current_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            // Create vectors for activating all prove commits at once.
+            let mut sector_deals: Vec<SectorDeal<BlockNumberFor<T>>> = Vec::new();
+            let mut new_sectors = BoundedVec::new();
+            // Pre-commit deposits released once the sectors are proven.
+            let mut released_deposit = BalanceOf::<T>::zero();
+            // Blocks at which the proven sectors' pre-commits were scheduled to expire.
+            let mut prove_commit_dues = Vec::with_capacity(sector_numbers.len());
+
+            for &sector_number in sector_numbers {
+                let precommit = sp
+                    .get_pre_committed_sector(sector_number)
+                    .map_err(|e| Error::<T>::GeneralPalletError(e))?;
+                prove_commit_dues
+                    .push(precommit.pre_commit_block_number + T::MaxProveCommitDuration::get());
+
+                released_deposit = released_deposit
+                    .checked_add(&precommit.pre_commit_deposit)
+                    .ok_or(ArithmeticError::Overflow)?;
+
+                // Sector deals that will be activated after the sector is
+                // successfully proven. Committed capacity sectors have no deals to activate.
+                if !precommit.info.deal_ids.is_empty() {
+                    sector_deals.push(precommit.into());
+                }
+                // Sector that will be activated and required to be periodically
+                // proven
+                let new_sector =
+                    SectorOnChainInfo::from_pre_commit(precommit.info.clone(), current_block);
+                new_sectors
+                    .try_push(new_sector)
+                    .expect("Programmer error: New sectors should fit in bound of MAX_SECTORS");
+            }
+
+            // Activate the deals for the sectors that will be proven. This
+            // action is not applied if Err is returned from the extrinsic.
+            // The market activates the deals of up to MAX_SECTORS_PER_CALL sectors at once.
+            let mut sector_deals = sector_deals.into_iter();
+            loop {
+                let chunk = sector_deals
+                    .by_ref()
+                    .take(MAX_SECTORS_PER_CALL as usize)
+                    .collect::<Vec<_>>();
+                if chunk.is_empty() {
+                    break;
+                }
+                T::Market::activate_deals(&owner, BoundedVec::truncate_from(chunk), true)?;
+            }
+
+            // Activate the new sectors and remove from pre-committed sectors.
+            sector_numbers.iter().zip(&new_sectors).try_for_each(
+                |(&sector_number, new_sector)| -> Result<(), Error<T>> {
+                    // Activate the new sector
+                    sp.activate_sector(sector_number, new_sector.clone())?;
+                    // Remove sector from the pre-committed map
+                    sp.remove_pre_committed_sector(sector_number)?;
+
+                    Ok(())
+                },
+            )?;
+            Self::unschedule_pre_commit_expirations(&owner, &sp, prove_commit_dues);
+
+            // Release the pre-commit deposits and lock the initial pledge of the proven sectors.
+            let initial_pledge = calculate_initial_pledge::<T>(sp.info.sector_size)
+                .saturating_mul((sector_numbers.len() as u32).into());
+            sp.remove_pre_commit_deposit(released_deposit)?;
+            T::Currency::unreserve(&owner, released_deposit);
+            ensure!(T::Currency::can_reserve(&owner, initial_pledge), {
+                log::error!(target: LOG_TARGET, "prove_commit_sectors: not enough funds to lock the initial pledge {initial_pledge:?}");
+                Error::<T>::NotEnoughFunds
+            });
+            T::Currency::reserve(&owner, initial_pledge)?;
+            sp.add_initial_pledge(initial_pledge)?;
 
-            let deadline = DeadlineInfo::new(
+            // Assign sectors to deadlines which specify when sectors needs
+            // to be proven
+            sp.assign_sectors_to_deadlines(
                 current_block,
-                sp.proving_period_start,
-                deadline_index,
+                new_sectors,
+                sp.info.window_post_partition_sectors,
+                T::MaxPartitionsPerDeadline::get(),
                 T::WPoStPeriodDeadlines::get(),
                 T::WPoStProvingPeriod::get(),
                 T::WPoStChallengeWindow::get(),
                 T::WPoStChallengeLookBack::get(),
                 T::FaultDeclarationCutoff::get(),
             )
-            .and_then(DeadlineInfo::next_not_opened)
-            .ok()?;
-
-            Some(ExternalDeadlineInfo {
-                deadline_index: deadline.idx,
-                open: deadline.is_open(),
-                challenge_block: deadline.challenge,
-                start: deadline.open_at,
-            })
-        }
+            .map_err(|e| Error::<T>::GeneralPalletError(e))?;
 
-        /// Returns snapshot information about the deadline, i.e. which sectors are assigned to which partitions.
-        /// When the deadline has not opened yet (deadline_start - WPoStChallengeWindow), it can change!
-        pub fn deadline_state(
-            storage_provider: &T::AccountId,
-            deadline_index: u64,
-        ) -> Option<primitives::pallets::DeadlineState> {
-            let sp = StorageProviders::<T>::try_get(storage_provider).ok()?;
-            let deadline_index: usize = deadline_index.try_into().ok()?;
+            let sectors_proven = sector_numbers
+                .iter()
+                .map(|&sector_number| {
+                    // Find where the sector was placed. In worst case this goes through
+                    // all deadlines. It starts to look in the last partition of the
+                    // deadline. Usually the new sector will be there.
+                    let (deadline_idx, partition_number) = sp
+                        .deadlines
+                        .due
+                        .iter()
+                        .enumerate()
+                        .find_map(|(deadline_idx, deadline)| {
+                            deadline.partitions.iter().rev().find_map(
+                                |(partition_number, partition)| {
+                                    if partition.sectors.contains(&sector_number) {
+                                        Some((deadline_idx as u64, *partition_number))
+                                    } else {
+                                        None
+                                    }
+                                },
+                            )
+                        })
+                        .expect("sector should be assigned to a deadline");
+                    ProveCommitResult::new(sector_number, partition_number, deadline_idx)
+                })
+                .collect::<Vec<ProveCommitResult>>()
+                .try_into()
+                .expect("Programmer error: ProveCommitResult's should fit in bound of MAX_BATCHED_SECTORS");
 
-            if deadline_index >= sp.deadlines.due.len() {
-                log::warn!(
-                    "tried to get non existing deadline: {}/{}",
-                    deadline_index,
-                    sp.deadlines.due.len()
-                );
-                return None;
-            }
+            Self::update_power(&owner, &sp);
+            StorageProviders::<T>::set(owner.clone(), Some(sp));
+            Self::deposit_event(Event::SectorsProven {
+                owner,
+                sectors: sectors_proven,
+            });
+            Ok(())
+        }
 
-            let deadline = &sp.deadlines.due[deadline_index];
-            let mut partitions = BoundedBTreeMap::new();
-            for (partition_number, partition) in deadline.partitions.iter() {
-                partitions
-                    .try_insert(
-                        *partition_number,
-                        primitives::pallets::PartitionState {
-                            sectors: partition.live_sectors(),
-                        },
-                    )
-                    .ok()?;
+        /// Recomputes the power of the storage provider from its state,
+        /// applying the difference to the total power.
+        fn update_power(
+            owner: &T::AccountId,
+            state: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+        ) {
+            let new_power = state.power();
+            let old_power = StorageProviderPower::<T>::get(owner);
+            if new_power == old_power {
+                return;
             }
 
-            Some(primitives::pallets::DeadlineState { partitions })
+            TotalPower::<T>::mutate(|total| {
+                total.raw_byte_power = total
+                    .raw_byte_power
+                    .saturating_sub(old_power.raw_byte_power)
+                    .saturating_add(new_power.raw_byte_power);
+                total.quality_adj_power = total
+                    .quality_adj_power
+                    .saturating_sub(old_power.quality_adj_power)
+                    .saturating_add(new_power.quality_adj_power);
+            });
+            StorageProviderPower::<T>::insert(owner, new_power);
         }
 
         fn validate_expiration(
@@ -1226,7 +2592,8 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Goes through all of the registered storage providers and checks if they have any expired pre committed sectors.
+        /// Goes through the storage providers with pre-commits expiring in the current block, scheduled in [`PreCommitExpirations`],
+        /// and checks if they have any expired pre committed sectors.
         /// If there are any sectors that are expired the total deposit amount for all those sectors will be slashed.
         ///
         /// References:
@@ -1235,24 +2602,25 @@ pub mod pallet {
         fn check_precommited_sectors(current_block: BlockNumberFor<T>) {
             const LOG_TARGET: &'static str = "runtime::storage_provider::check_precommited_sectors";
 
-            // TODO(@th7nder,31/07/2024): this approach is suboptimal, as it's time complexity is O(StorageProviders * PreCommitedSectors).
-            // We can reduce this by indexing pre-committed sectors by BlockNumber in which they're supposed to be activated in PreCommit and remove them in ProveCommit.
             log::info!(target: LOG_TARGET, "checking pre_commited_sectors for block: {:?}", current_block);
 
-            // We cannot modify storage map while inside `iter_keys()` as docs say it's undefined results.
-            // And we can use `alloc::Vec`, because it's bounded by StorageProviders data structure anyways.
-            let storage_providers: Vec<_> = StorageProviders::<T>::iter_keys().collect();
+            // Storage providers whose pre-commits were proven in the meantime are scheduled as well,
+            // they're skipped when no expired sectors are detected.
+            // We can use `alloc::Vec`, because it's bounded by StorageProviders data structure anyways.
+            let storage_providers: Vec<_> = PreCommitExpirations::<T>::drain_prefix(current_block)
+                .map(|(storage_provider, ())| storage_provider)
+                .collect();
             for storage_provider in storage_providers {
                 log::info!(target: LOG_TARGET, "checking storage provider {:?}", storage_provider);
                 let Ok(mut state) = StorageProviders::<T>::try_get(storage_provider.clone()) else {
-                    log::error!(target: LOG_TARGET, "catastrophe, couldn't find a storage provider based on key. it should have been there...");
+                    log::info!(target: LOG_TARGET, "storage provider {:?} not found, it was deregistered", storage_provider);
                     continue;
                 };
 
                 let (expired, slash_amount) =
                     Self::detect_expired_precommit_sectors(current_block, &state);
                 if expired.is_empty() {
-                    return;
+                    continue;
                 }
 
                 let mut removed_sectors = BoundedVec::new();
@@ -1296,7 +2664,7 @@ pub mod pallet {
         /// Returns an array of expired sector numbers and the total deposit to be slashed.
         fn detect_expired_precommit_sectors(
             curr_block: BlockNumberFor<T>,
-            state: &StorageProviderState<T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+            state: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
         ) -> (
             BoundedVec<SectorNumber, ConstU32<MAX_SECTORS>>,
             BalanceOf<T>,
@@ -1306,9 +2674,9 @@ pub mod pallet {
             let mut to_be_slashed = BalanceOf::<T>::zero();
 
             for (sector_number, sector) in &state.pre_committed_sectors {
-                // Expiration marks the time for a block when it was supposed to be proven by `prove_commit` ultimately.
+                // A sector must be proven by `prove_commit` within `MaxProveCommitDuration` after it was pre-committed.
                 // If it's still in `pre_commited_sectors` and `curr_block` is past this time, it means it was not.
-                if curr_block >= sector.info.expiration {
+                if curr_block >= sector.pre_commit_block_number + T::MaxProveCommitDuration::get() {
                     let Ok(()) = expired_sectors.try_push(*sector_number) else {
                         log::error!(target: LOG_TARGET, "detect_expired_precommit_sectors: invariant violated, expired_sectors bounded_vec's capacity < state.pre_committed_sectors capacity, sector: {}", sector_number);
                         continue;
@@ -1324,17 +2692,21 @@ pub mod pallet {
             (expired_sectors, to_be_slashed)
         }
 
-        /// Goes through each Storage Provider and its current deadline.
+        /// Goes through each Storage Provider whose current deadline closes in the current block, scheduled in [`DeadlineChecks`].
         ///
         /// If the deadline elapsed (current_block >= deadline.close_at) it checks all of the partitions and their sectors.
         /// If a proof for a partition has not been submitted, all sectors in the partition are marked as faulty.
         /// A deadline is checked once every [`T::WPoStProvingPeriod`]. If a Partition was marked as faulty in a deadline (deadline_idx, proving_period_idx),
         /// it's rechecked in the next [`T::WPoStProvingPeriod`] in the next deadline (deadline_idx, proving_period_idx + 1).
-        /// `pre_commit_deposit` is slashed by 1 for each partition for each proving period a partition is faulty.
+        /// Sectors that were already faulty — e.g. declared as faulty — are charged the continued fault fee,
+        /// sectors that are detected as faulty are charged the larger undeclared fault penalty instead.
+        /// Both are taken from the storage provider's initial pledge, see [`Pallet::charge_fault_penalty`].
+        /// Once the deadline is processed, the storage provider's power is updated with the faulty and expired sectors
+        /// and the storage provider is scheduled for the closing of its next deadline.
         ///
-        /// TODO:
-        /// - If a partition is faulty for too long [`T::FaultMaxAge`], it needs to be be terminated. (#165, #167)
-        /// - A proper slashing mechanism `pre_commit_deposit` and calculation. (#187)
+        /// Faulty sectors are scheduled to expire [`T::FaultMaxAge`] after they're detected.
+        /// If they're still faulty by then, they're terminated when their deadline is processed
+        /// and their deals are terminated through `Market::on_sectors_terminate`.
         ///
         /// Reference implementation:
         /// * <https://github.com/filecoin-project/builtin-actors/blob/82d02e58f9ef456aeaf2a6c737562ac97b22b244/actors/miner/src/state.rs#L1128>
@@ -1343,24 +2715,19 @@ pub mod pallet {
             const LOG_TARGET: &'static str = "runtime::storage_provider::check_deadlines";
             log::info!(target: LOG_TARGET, "block: {:?}", current_block);
 
-            // We cannot modify storage map while inside `iter_keys()` as docs say it's undefined results.
+            // We cannot modify storage map while inside `drain_prefix()` as docs say it's undefined results,
+            // the storage providers are scheduled again while being processed.
             // And we can use `alloc::Vec`, because it's bounded by StorageProviders data structure anyways.
-            let storage_providers: Vec<_> = StorageProviders::<T>::iter_keys().collect();
-            // TODO(@th7nder,13/08/2024): this approach is suboptimal, as it's time complexity is O(StorageProviders * PreCommitedSectors).
-            // We can reduce this by indexing pre-committed sectors by BlockNumber in which they're supposed to be activated in PreCommit and remove them in ProveCommit.
+            let storage_providers: Vec<_> = DeadlineChecks::<T>::drain_prefix(current_block)
+                .map(|(storage_provider, ())| storage_provider)
+                .collect();
             for storage_provider in storage_providers {
                 log::info!(target: LOG_TARGET, "block: {:?}, checking storage provider {:?}", current_block, storage_provider);
                 let Ok(mut state) = StorageProviders::<T>::try_get(storage_provider.clone()) else {
-                    log::error!(target: LOG_TARGET, "missing storage provider {:?} (should have been added before)", storage_provider);
+                    log::info!(target: LOG_TARGET, "block: {:?}, storage provider {:?} not found, it was deregistered", current_block, storage_provider);
                     continue;
                 };
 
-                if current_block < state.proving_period_start {
-                    log::info!(target: LOG_TARGET, "skipping checking sp: {:?} on block: {:?} < proving_start {:?}, because it hasn't started yet.",
-                    storage_provider, current_block, state.proving_period_start);
-                    continue;
-                }
-
                 let Ok(current_deadline) = state.deadline_info(
                     current_block,
                     T::WPoStPeriodDeadlines::get(),
@@ -1373,17 +2740,19 @@ pub mod pallet {
                     continue;
                 };
 
-                if !current_deadline.period_started() {
-                    log::info!(target: LOG_TARGET, "block: {:?}, period for deadline {:?}, sp {:?} has not yet started...", current_block, current_deadline.idx, storage_provider);
-                    continue;
-                }
-
-                if !current_deadline.has_elapsed() {
+                // Storage providers are scheduled for the closing of their deadline,
+                // if it's not elapsed yet, they're scheduled again.
+                if !current_deadline.period_started() || !current_deadline.has_elapsed() {
                     log::info!(target: LOG_TARGET,
                     "block: {:?}, deadline {:?} for sp {:?} not yet elapsed. open_at: {:?} < current {:?} < close_at {:?}",
                     current_block,
                     current_deadline.idx, storage_provider, current_deadline.open_at, current_block, current_deadline.close_at
                     );
+                    if let Err(e) =
+                        Self::schedule_deadline_check(current_block, &storage_provider, &state)
+                    {
+                        log::error!(target: LOG_TARGET, "block: {:?}, failed to schedule deadline check for sp {:?}: {:?}", current_block, storage_provider, e);
+                    }
                     continue;
                 }
 
@@ -1402,6 +2771,12 @@ pub mod pallet {
                 };
 
                 let mut faulty_partitions_amount = 0;
+                // Sectors that were faulty before the deadline was processed
+                let mut continued_faults = 0u32;
+                // Sectors that missed the PoSt without being declared as faulty
+                let mut undeclared_faults = 0u32;
+                // Faulty sectors are terminated if they're still faulty after `FaultMaxAge`
+                let fault_expiration_block = current_deadline.last() + T::FaultMaxAge::get();
                 // Create collection for fault partitions, 1 event per SP
                 let mut faulty_partitions: BTreeMap<
                     PartitionNumber,
@@ -1411,6 +2786,8 @@ pub mod pallet {
                     if partition.sectors.len() == 0 {
                         continue;
                     }
+                    // Faults that weren't recovered pay the fee, whether the partition was proven or not.
+                    continued_faults += partition.faults.len() as u32;
                     // WindowPoSt Proof was submitted for a partition.
                     if deadline.partitions_posted.contains(&partition_number) {
                         continue;
@@ -1419,7 +2796,6 @@ pub mod pallet {
                     log::debug!(target: LOG_TARGET, "block: {:?}, going through partition: {:?}", current_block, partition);
 
                     // Mark all Sectors in a partition as faulty
-                    let fault_expiration_block = current_deadline.last() + T::FaultMaxAge::get();
                     let Ok(new_faults) = partition.record_faults(
                         &state.sectors,
                         &partition.sectors.clone(),
@@ -1430,24 +2806,28 @@ pub mod pallet {
                         continue;
                     };
 
-                    if let Err(e) =
-                        Self::process_early_terminations(current_block, &storage_provider)
-                    {
-                        log::error!(target: LOG_TARGET, "could not process early terminations for {storage_provider:?}: {e:?}");
-                        continue;
-                    }
                     log::info!(target: LOG_TARGET, "block: {:?}, sp: {:?}, detected partition {} with {} new faults...",
                     current_block, storage_provider, partition_number, new_faults.len());
 
                     if new_faults.len() > 0 {
+                        undeclared_faults += new_faults.len() as u32;
                         faulty_partitions.insert(*partition_number, new_faults.try_into().expect("should be able to create BoundedBTreeSet due to input being bounded"));
                         faulty_partitions_amount += 1;
                     }
                 }
 
-                // TODO(@th7nder,[#106,#187],08/08/2024): figure out slashing amounts (for continued faults, new faults).
+                // Schedule the termination of the new faults, the previous ones are already scheduled.
+                for partition_number in faulty_partitions.keys() {
+                    if let Err(e) = deadline
+                        .schedule_fault_expiration(fault_expiration_block, *partition_number)
+                    {
+                        log::error!(target: LOG_TARGET, "block: {:?}, failed to schedule fault expiration for partition {}, deadline: {}, sp: {:?}: {:?}",
+                            current_block, partition_number, current_deadline.idx, storage_provider, e);
+                    }
+                }
+
                 if faulty_partitions_amount > 0 {
-                    log::warn!(target: LOG_TARGET, "block: {:?}, sp: {:?}, deadline: {:?} - should have slashed {} partitions...",
+                    log::warn!(target: LOG_TARGET, "block: {:?}, sp: {:?}, deadline: {:?} - detected {} faulty partitions...",
                         current_block,
                         storage_provider,
                         current_deadline.idx,
@@ -1468,6 +2848,30 @@ pub mod pallet {
                 // Reset posted partitions, as deadline has been processed.
                 // Next processing will happen in the next proving period.
                 deadline.partitions_posted = BoundedBTreeSet::new();
+
+                // Charge the faulty sectors, the continued faults first.
+                let sector_size = state.info.sector_size;
+                let continued_fault_fee = Self::charge_fault_penalty(
+                    &storage_provider,
+                    &mut state,
+                    calculate_continued_fault_fee::<T>(sector_size)
+                        .saturating_mul(continued_faults.into()),
+                );
+                let undeclared_fault_penalty = Self::charge_fault_penalty(
+                    &storage_provider,
+                    &mut state,
+                    calculate_undeclared_fault_penalty::<T>(sector_size)
+                        .saturating_mul(undeclared_faults.into()),
+                );
+                if !continued_fault_fee.is_zero() || !undeclared_fault_penalty.is_zero() {
+                    Self::deposit_event(Event::FaultsPenalized {
+                        owner: storage_provider.clone(),
+                        continued_fault_fee,
+                        undeclared_fault_penalty,
+                    });
+                }
+
+                let pledged_sectors = state.pledged_sectors_count();
                 state
                     .advance_deadline(
                         current_block,
@@ -1478,16 +2882,77 @@ pub mod pallet {
                         T::FaultDeclarationCutoff::get(),
                     )
                     .expect("Could not advance deadline");
+                // Sectors that were faulty for longer than `FaultMaxAge` were terminated when advancing,
+                // their deals need to be terminated too.
+                if let Err(e) =
+                    Self::process_early_terminations(current_block, &storage_provider, &mut state)
+                {
+                    log::error!(target: LOG_TARGET, "could not process early terminations for {storage_provider:?}: {e:?}");
+                }
+                // Release the initial pledge of the expired sectors.
+                let released_pledge = state.remove_initial_pledge(pledged_sectors);
+                T::Currency::unreserve(&storage_provider, released_pledge);
+                Self::update_power(&storage_provider, &state);
+                if let Err(e) =
+                    Self::schedule_deadline_check(current_block, &storage_provider, &state)
+                {
+                    log::error!(target: LOG_TARGET, "block: {:?}, failed to schedule deadline check for sp {:?}: {:?}", current_block, storage_provider, e);
+                }
                 StorageProviders::<T>::insert(storage_provider, state);
             }
         }
 
+        /// Schedules the current deadline of the storage provider to be processed by [`Pallet::check_deadlines`]
+        /// in the block it closes, or in the next block if it's already closed.
+        pub(crate) fn schedule_deadline_check(
+            current_block: BlockNumberFor<T>,
+            owner: &T::AccountId,
+            state: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> Result<(), crate::error::GeneralPalletError> {
+            let current_deadline = state.deadline_info(
+                current_block,
+                T::WPoStPeriodDeadlines::get(),
+                T::WPoStProvingPeriod::get(),
+                T::WPoStChallengeWindow::get(),
+                T::WPoStChallengeLookBack::get(),
+                T::FaultDeclarationCutoff::get(),
+            )?;
+            // The hook for the current block may have already run.
+            let check_block = current_deadline
+                .close_at
+                .max(current_block + BlockNumberFor::<T>::one());
+            DeadlineChecks::<T>::insert(check_block, owner, ());
+
+            Ok(())
+        }
+
+        /// Removes the storage provider from the [`PreCommitExpirations`] of the given blocks,
+        /// unless some of its remaining pre-commits still expire in them.
+        fn unschedule_pre_commit_expirations(
+            owner: &T::AccountId,
+            state: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+            blocks: impl IntoIterator<Item = BlockNumberFor<T>>,
+        ) {
+            let remaining_dues = state
+                .pre_committed_sectors
+                .values()
+                .map(|precommit| {
+                    precommit.pre_commit_block_number + T::MaxProveCommitDuration::get()
+                })
+                .collect::<BTreeSet<_>>();
+            for block in blocks {
+                if !remaining_dues.contains(&block) {
+                    PreCommitExpirations::<T>::remove(block, owner);
+                }
+            }
+        }
+
         /// Verifies that the unsealed_cid (CommD) and checks that it matches the given unsealed CID.
+        /// Only sectors with deals are checked, committed capacity sectors are checked against the zero CommD instead.
         fn check_commd_for_pre_commit(
             calculated_unsealed_cid: BoundedVec<Option<Cid>, ConstU32<MAX_SECTORS_PER_CALL>>,
             sector_amount: usize,
             unsealed_cids: BoundedVec<Cid, ConstU32<MAX_SECTORS_PER_CALL>>,
-            deal_amounts: BoundedVec<usize, ConstU32<MAX_SECTORS_PER_CALL>>,
         ) -> Result<(), Error<T>> {
             ensure!(calculated_unsealed_cid.len() == sector_amount, {
                 log::error!(target: LOG_TARGET, "check_commd_for_pre_commit: failed to verify deals, invalid calculated_commd length: {}", calculated_unsealed_cid.len());
@@ -1495,28 +2960,55 @@ pub mod pallet {
             });
 
             for (i, unsealed_cid) in unsealed_cids.into_iter().enumerate() {
-                if deal_amounts[i] > 0 {
-                    let Some(calculated_commd) = calculated_unsealed_cid[i] else {
-                        log::error!(target: LOG_TARGET, "check_commd_for_pre_commit: commd for the deals at index {i} from verify_deals is None...");
-                        fail!(Error::<T>::CouldNotVerifySectorForPreCommit)
-                    };
+                let Some(calculated_commd) = calculated_unsealed_cid[i] else {
+                    log::error!(target: LOG_TARGET, "check_commd_for_pre_commit: commd for the deals at index {i} from verify_deals is None...");
+                    fail!(Error::<T>::CouldNotVerifySectorForPreCommit)
+                };
 
-                    ensure!(calculated_commd == unsealed_cid, {
-                        log::error!(target: LOG_TARGET, "check_commd_for_pre_commit: calculated_commd at index {i} != sector.unsealed_cid, {:?} != {:?}", calculated_commd, unsealed_cid);
-                        Error::<T>::InvalidUnsealedCidForSector
-                    });
-                }
+                ensure!(calculated_commd == unsealed_cid, {
+                    log::error!(target: LOG_TARGET, "check_commd_for_pre_commit: calculated_commd at index {i} != sector.unsealed_cid, {:?} != {:?}", calculated_commd, unsealed_cid);
+                    Error::<T>::InvalidUnsealedCidForSector
+                });
             }
             Ok(())
         }
 
+        /// Validates the data and replica commitments of a sector, returning its data commitment.
+        /// Committed capacity sectors, without deals, must only contain zeroes.
+        fn validate_sector_commitments(
+            sector: &SectorPreCommitInfo<BlockNumberFor<T>>,
+        ) -> Result<Commitment<CommD>, Error<T>> {
+            // Validate the data commitment
+            let commd = Commitment::<CommD>::from_cid_bytes(&sector.unsealed_cid[..]).map_err(
+                |err| {
+                    log::error!(target: LOG_TARGET, err:?; "validate_sector_commitments: invalid unsealed_cid");
+                    Error::<T>::InvalidCid
+                },
+            )?;
+
+            // Validate the replica commitment
+            let _ = Commitment::<CommR>::from_cid_bytes(&sector.sealed_cid[..]).map_err(|err| {
+                log::error!(target: LOG_TARGET, err:?; "validate_sector_commitments: invalid sealed_cid");
+                Error::<T>::InvalidCid
+            })?;
+
+            if sector.deal_ids.is_empty() {
+                ensure!(commd == Commitment::<CommD>::zero(sector.seal_proof), {
+                    log::error!(target: LOG_TARGET, "validate_sector_commitments: committed capacity sector {} must have the zero unsealed_cid", sector.sector_number);
+                    Error::<T>::InvalidUnsealedCidForSector
+                });
+            }
+
+            Ok(commd)
+        }
+
         /// Checks if the sectors submitted for pre-commit by the SP are valid.
         /// Checks are
         /// - Sector number limit (cannot be higher than MAX_SECTORS)
         /// - The proof type must correspond to the proof type submitted during registration.
         /// - The sector number must not be used previously.
         fn validate_sector_for_pre_commit(
-            sp: &StorageProviderState<T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+            sp: &StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
             sector: &SectorPreCommitInfo<BlockNumberFor<T>>,
         ) -> Result<(), Error<T>> {
             let sector_number = sector.sector_number;
@@ -1533,14 +3025,49 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Processes terminations for the given account (should be a registered SP).
+        /// Charges a fault fee or penalty from the storage provider's initial pledge, burning it.
+        ///
+        /// The charged amount is capped by the initial pledge, as only locked funds are used to pay it.
+        /// Returns the amount that was actually charged.
+        fn charge_fault_penalty(
+            owner: &T::AccountId,
+            state: &mut StorageProviderState<
+                T::AccountId,
+                T::PeerId,
+                BalanceOf<T>,
+                BlockNumberFor<T>,
+            >,
+            penalty: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            let charged = penalty.min(state.initial_pledge);
+            if charged.is_zero() {
+                return charged;
+            }
+
+            // PRE-COND: the initial pledge was previously reserved in prove_commit
+            if let Err(e) = slash_and_burn::<T>(owner, charged) {
+                log::error!(target: LOG_TARGET, "failed to charge fault penalty {charged:?} from {owner:?}: {e:?}");
+                return BalanceOf::<T>::zero();
+            }
+            state.initial_pledge = state.initial_pledge.saturating_sub(charged);
+
+            charged
+        }
+
+        /// Processes terminations for the given storage provider state.
         /// Clears all early terminations and calls `on_sectors_terminate` when finished.
+        ///
+        /// The state is not written to storage, that is left to the caller.
         fn process_early_terminations(
             current_block: BlockNumberFor<T>,
             owner: &T::AccountId,
+            state: &mut StorageProviderState<
+                T::AccountId,
+                T::PeerId,
+                BalanceOf<T>,
+                BlockNumberFor<T>,
+            >,
         ) -> Result<(), Error<T>> {
-            let mut state = StorageProviders::<T>::try_get(owner)
-                .map_err(|_| Error::<T>::StorageProviderNotFound)?;
             let result = state
                 .pop_early_terminations(
                     T::AddressedPartitionsMax::get(),
@@ -1578,16 +3105,71 @@ pub mod pallet {
             T::Market::on_sectors_terminate(owner, terminated_data)
                 .map_err(|_| Error::<T>::CouldNotTerminateDeals)?;
 
-            // Update storage provider state
-            StorageProviders::<T>::insert(owner, state);
-
             Ok(())
         }
     }
 
+    /// Calculates the reward a sector of the given size is expected to earn during the projection period.
+    ///
+    /// The sector earns a share of [`Config::ExpectedBlockReward`] proportional to its power,
+    /// where the network power is at least [`Config::BaselinePower`].
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L57-L72>
+    fn expected_reward_for_sector<T: Config>(
+        sector_size: SectorSize,
+        projection_period: BlockNumberFor<T>,
+    ) -> BalanceOf<T> {
+        // Verified deals are not supported, the quality adjusted power of a sector is its size.
+        let sector_power = u128::from(sector_size.bytes());
+        let network_power = TotalPower::<T>::get()
+            .quality_adj_power
+            .max(T::BaselinePower::get())
+            // Avoid dividing by zero if the baseline is misconfigured
+            .max(1);
+
+        T::ExpectedBlockReward::get()
+            .saturated_into::<u128>()
+            .saturating_mul(sector_power)
+            .saturating_mul(projection_period.saturated_into::<u128>())
+            .checked_div(network_power)
+            .unwrap_or_default()
+            .saturated_into()
+    }
+
     /// Calculate the required pre commit deposit amount
-    fn calculate_pre_commit_deposit<T: Config>() -> BalanceOf<T> {
-        BalanceOf::<T>::one() // TODO(@aidan46, #106, 2024-06-24): Set a logical value or calculation
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L111-L129>
+    fn calculate_pre_commit_deposit<T: Config>(sector_size: SectorSize) -> BalanceOf<T> {
+        expected_reward_for_sector::<T>(sector_size, T::PreCommitDepositProjectionPeriod::get())
+    }
+
+    /// Calculate the initial pledge locked for a proven sector.
+    ///
+    /// Filecoin adds a share of the network's consensus pledge on top of the expected reward,
+    /// we only lock the expected reward as there's no circulating supply target to derive it from.
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L131-L162>
+    fn calculate_initial_pledge<T: Config>(sector_size: SectorSize) -> BalanceOf<T> {
+        expected_reward_for_sector::<T>(sector_size, T::InitialPledgeProjectionPeriod::get())
+    }
+
+    /// Calculate the fee charged for a sector that stays faulty for another proving period.
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L74-L85>
+    fn calculate_continued_fault_fee<T: Config>(sector_size: SectorSize) -> BalanceOf<T> {
+        expected_reward_for_sector::<T>(sector_size, T::ContinuedFaultProjectionPeriod::get())
+    }
+
+    /// Calculate the penalty charged for a sector that missed a PoSt without being declared as faulty.
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/specs-actors/blob/v2.3.6/actors/builtin/miner/monies.go>
+    fn calculate_undeclared_fault_penalty<T: Config>(sector_size: SectorSize) -> BalanceOf<T> {
+        expected_reward_for_sector::<T>(sector_size, T::UndeclaredFaultProjectionPeriod::get())
     }
 
     /// Slashes **reserved* currency, burns it completely and settles the token amount in the chain.
@@ -1632,6 +3214,69 @@ pub mod pallet {
             return Err(Error::<T>::InvalidProof)?;
         }
 
+        let PublicSealInfo {
+            sector,
+            comm_r,
+            comm_d,
+            ticket,
+            seed,
+        } = public_seal_info::<T>(owner, precommit)?;
+
+        // Verify the porep proof
+        T::ProofVerification::verify_porep(
+            derive_prover_id(owner),
+            precommit.info.seal_proof,
+            comm_r,
+            comm_d,
+            sector,
+            ticket,
+            seed,
+            proof,
+        )
+    }
+
+    /// Verifies the non-interactive seal proof of a sector, which doesn't have an interactive seed.
+    fn validate_ni_seal_proof<T: Config>(
+        owner: &T::AccountId,
+        sector: &SectorPreCommitInfo<BlockNumberFor<T>>,
+        proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
+    ) -> Result<(), DispatchError> {
+        let max_proof_size = sector.seal_proof.proof_size();
+
+        // Check proof size
+        if proof.len() > max_proof_size {
+            log::error!(target: LOG_TARGET, "sector proof size {} exceeds max {}", proof.len(), max_proof_size);
+            return Err(Error::<T>::InvalidProof)?;
+        }
+
+        // The commitments were validated before
+        let commd = Commitment::<CommD>::from_cid_bytes(&sector.unsealed_cid[..])
+            .map_err(|_| Error::<T>::InvalidCid)?;
+        let commr = Commitment::<CommR>::from_cid_bytes(&sector.sealed_cid[..])
+            .map_err(|_| Error::<T>::InvalidCid)?;
+
+        let ticket = get_randomness::<T>(
+            DomainSeparationTag::SealRandomness,
+            sector.seal_randomness_height,
+            &owner.encode(),
+        )?;
+
+        T::ProofVerification::verify_ni_porep(
+            derive_prover_id(owner),
+            sector.seal_proof,
+            commr.raw(),
+            commd.raw(),
+            sector.sector_number,
+            ticket,
+            proof,
+        )
+    }
+
+    /// Gathers the public information required to verify the seal proof of a pre-committed sector.
+    fn public_seal_info<T: Config>(
+        owner: &T::AccountId,
+        precommit: &SectorPreCommitOnChainInfo<BalanceOf<T>, BlockNumberFor<T>>,
+    ) -> Result<PublicSealInfo, DispatchError> {
         let current_block_number = <frame_system::Pallet<T>>::block_number();
 
         // https://github.com/filecoin-project/builtin-actors/blob/a45fb87910bca74d62215b0d58ed90cf78b6c8ff/actors/miner/src/lib.rs#L4865
@@ -1676,16 +3321,54 @@ pub mod pallet {
             precommit.info.seal_randomness_height, precommit.pre_commit_block_number, interactive_block_number, hex::encode(entropy), hex::encode(randomness), hex::encode(interactive_randomness));
         log::debug!(target: LOG_TARGET, "Prover Id: {}, Sector Number: {}", hex::encode(prover_id), precommit.info.sector_number);
 
-        // Verify the porep proof
-        T::ProofVerification::verify_porep(
-            prover_id,
-            precommit.info.seal_proof,
-            commr.raw(),
-            commd.raw(),
-            precommit.info.sector_number,
-            randomness,
-            interactive_randomness,
-            proof,
+        Ok(PublicSealInfo {
+            sector: precommit.info.sector_number,
+            comm_r: commr.raw(),
+            comm_d: commd.raw(),
+            ticket: randomness,
+            seed: interactive_randomness,
+        })
+    }
+
+    fn validate_replica_update_proof<T: Config>(
+        sector: &SectorOnChainInfo<BlockNumberFor<T>>,
+        update: &ReplicaUpdate,
+    ) -> Result<(), DispatchError> {
+        let update_proof = sector.seal_proof.registered_update_proof();
+        let max_proof_size = update_proof.proof_size();
+
+        // Check proof size
+        if update.proof.len() > max_proof_size {
+            log::error!(target: LOG_TARGET, "replica update proof size {} exceeds max {}", update.proof.len(), max_proof_size);
+            return Err(Error::<T>::InvalidProof)?;
+        }
+
+        // The replica the sector was sealed into
+        let comm_r_old = Commitment::<CommR>::from_cid_bytes(&sector.sealed_cid[..])
+            .map_err(|err| {
+                log::error!(target: LOG_TARGET, err:?; "validate_replica_update_proof: invalid sealed_cid {:?}", &sector.sealed_cid);
+                Error::<T>::InvalidCid
+            })?;
+
+        // The replica containing the deals' data
+        let comm_r_new = Commitment::<CommR>::from_cid_bytes(&update.new_sealed_cid[..])
+            .map_err(|err| {
+                log::error!(target: LOG_TARGET, err:?; "validate_replica_update_proof: invalid new_sealed_cid {:?}", &update.new_sealed_cid);
+                Error::<T>::InvalidCid
+            })?;
+
+        let comm_d_new = Commitment::<CommD>::from_cid_bytes(&update.new_unsealed_cid[..])
+            .map_err(|err| {
+                log::error!(target: LOG_TARGET, err:?; "validate_replica_update_proof: invalid new_unsealed_cid {:?}", &update.new_unsealed_cid);
+                Error::<T>::InvalidCid
+            })?;
+
+        T::ProofVerification::verify_replica_update(
+            update_proof,
+            comm_r_old.raw(),
+            comm_r_new.raw(),
+            comm_d_new.raw(),
+            update.proof.clone(),
         )
     }
 
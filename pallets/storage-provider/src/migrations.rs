@@ -0,0 +1,614 @@
+//! Storage migrations for the storage provider pallet.
+//!
+//! Every migration step keeps its own copy of the stored types it changes,
+//! so it keeps working when the pallet's types change in later versions.
+//! The fields after the last changed one are carried over as raw bytes, see [`Tail`].
+
+extern crate alloc;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+use codec::{Decode, Encode, EncodeLike, Input, Output};
+
+const LOG_TARGET: &'static str = "runtime::storage_provider::migrations";
+
+/// Remaining bytes of an encoded value, carried over without being decoded.
+pub(crate) struct Tail(pub Vec<u8>);
+
+impl Decode for Tail {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let len = input
+            .remaining_len()?
+            .ok_or("the length of the stored value is always known")?;
+        let mut bytes = alloc::vec![0; len];
+        input.read(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl Encode for Tail {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        dest.write(&self.0);
+    }
+}
+
+impl EncodeLike for Tail {}
+
+/// Migrates the deadlines' expiration queues, which only kept the last partition scheduled at each block,
+/// to hold every partition expiring at a block.
+pub mod v1 {
+    use frame_support::{
+        migrations::VersionedMigration,
+        pallet_prelude::*,
+        sp_runtime::{BoundedBTreeMap, BoundedVec},
+        traits::UncheckedOnRuntimeUpgrade,
+    };
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use primitives::{
+        proofs::RegisteredPoStProof,
+        sector::{SectorNumber, SectorSize},
+        PartitionNumber, MAX_PARTITIONS_PER_DEADLINE, MAX_SECTORS,
+    };
+
+    use super::{BTreeMap, BTreeSet, Tail, Vec, LOG_TARGET};
+    use crate::{
+        pallet::BalanceOf,
+        partition::Partition,
+        sector::{SectorOnChainInfo, SectorPreCommitOnChainInfo},
+        Config, Pallet,
+    };
+
+    /// Types as they were stored before the migration.
+    pub(crate) mod v0 {
+        use super::*;
+
+        /// Only the last partition scheduled at a block was kept in the expiration queue.
+        #[derive(Decode, Encode)]
+        pub struct Deadline<BlockNumber>
+        where
+            BlockNumber: sp_runtime::traits::BlockNumber,
+        {
+            pub partitions: BoundedBTreeMap<
+                PartitionNumber,
+                Partition<BlockNumber>,
+                ConstU32<MAX_PARTITIONS_PER_DEADLINE>,
+            >,
+            pub expirations_blocks: BoundedBTreeMap<
+                BlockNumber,
+                PartitionNumber,
+                ConstU32<MAX_PARTITIONS_PER_DEADLINE>,
+            >,
+            pub partitions_posted:
+                BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+            pub early_terminations:
+                BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+            pub live_sectors: u64,
+            pub total_sectors: u64,
+        }
+
+        pub type StorageProviderState<PeerId, Balance, BlockNumber> =
+            super::StorageProviderState<PeerId, Balance, BlockNumber, Deadline<BlockNumber>>;
+    }
+
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderInfo<PeerId> {
+        pub peer_id: PeerId,
+        pub window_post_proof_type: RegisteredPoStProof,
+        pub sector_size: SectorSize,
+        pub window_post_partition_sectors: u64,
+    }
+
+    #[derive(Decode, Encode)]
+    pub struct Deadline<BlockNumber>
+    where
+        BlockNumber: sp_runtime::traits::BlockNumber,
+    {
+        pub partitions: BoundedBTreeMap<
+            PartitionNumber,
+            Partition<BlockNumber>,
+            ConstU32<MAX_PARTITIONS_PER_DEADLINE>,
+        >,
+        pub expirations_blocks: BoundedBTreeMap<
+            BlockNumber,
+            BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+            ConstU32<MAX_SECTORS>,
+        >,
+        pub partitions_posted:
+            BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+        pub early_terminations:
+            BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
+        pub live_sectors: u64,
+        pub total_sectors: u64,
+    }
+
+    /// The state up to the deadlines, the early terminations are carried over as they are.
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderState<PeerId, Balance, BlockNumber, Deadline> {
+        pub info: StorageProviderInfo<PeerId>,
+        pub sectors:
+            BoundedBTreeMap<SectorNumber, SectorOnChainInfo<BlockNumber>, ConstU32<MAX_SECTORS>>,
+        pub pre_commit_deposits: Balance,
+        pub pre_committed_sectors: BoundedBTreeMap<
+            SectorNumber,
+            SectorPreCommitOnChainInfo<Balance, BlockNumber>,
+            ConstU32<MAX_SECTORS>,
+        >,
+        pub proving_period_start: BlockNumber,
+        pub current_deadline: u64,
+        pub deadlines: BoundedVec<Deadline, ConstU32<48>>,
+        pub early_terminations: Tail,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type StorageProviders<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        StorageProviderState<
+            <T as Config>::PeerId,
+            BalanceOf<T>,
+            BlockNumberFor<T>,
+            Deadline<BlockNumberFor<T>>,
+        >,
+    >;
+
+    /// Rebuilds the expiration queue from the partitions,
+    /// restoring the partitions the previous queue lost when several of them expired at the same block.
+    fn migrate_deadline<BlockNumber>(old: v0::Deadline<BlockNumber>) -> Deadline<BlockNumber>
+    where
+        BlockNumber: sp_runtime::traits::BlockNumber + Copy,
+    {
+        let mut expirations_blocks = BTreeMap::<_, BTreeSet<_>>::new();
+        for (&partition_number, partition) in old.partitions.iter() {
+            for &block in partition.expirations.map.keys() {
+                expirations_blocks
+                    .entry(block)
+                    .or_default()
+                    .insert(partition_number);
+            }
+        }
+        let expirations_blocks = expirations_blocks
+            .into_iter()
+            .map(|(block, partitions)| {
+                let partitions = partitions
+                    .try_into()
+                    .expect("the partitions are bounded by the deadline's partitions");
+                (block, partitions)
+            })
+            .collect::<BTreeMap<_, _>>()
+            .try_into()
+            .expect("there are never more expiration blocks than sectors");
+
+        Deadline {
+            partitions: old.partitions,
+            expirations_blocks,
+            partitions_posted: old.partitions_posted,
+            early_terminations: old.early_terminations,
+            live_sectors: old.live_sectors,
+            total_sectors: old.total_sectors,
+        }
+    }
+
+    /// Rebuilds the expiration queues of every deadline of every storage provider.
+    pub struct UncheckedMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            StorageProviders::<T>::translate::<
+                v0::StorageProviderState<T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+                _,
+            >(|_, old| {
+                migrated += 1;
+
+                let deadlines = old
+                    .deadlines
+                    .into_iter()
+                    .map(migrate_deadline)
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .expect("the deadlines are migrated one to one");
+
+                Some(StorageProviderState {
+                    info: old.info,
+                    sectors: old.sectors,
+                    pre_commit_deposits: old.pre_commit_deposits,
+                    pre_committed_sectors: old.pre_committed_sectors,
+                    proving_period_start: old.proving_period_start,
+                    current_deadline: old.current_deadline,
+                    deadlines,
+                    early_terminations: old.early_terminations,
+                })
+            });
+
+            log::info!(target: LOG_TARGET, "Migrated the deadlines of {} storage providers", migrated);
+
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV0ToV1`] which only runs when the on-chain storage version is 0.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Migrates the storage providers registered before the owner and worker accounts,
+/// the owner becomes the worker, without control addresses.
+pub mod v2 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, sp_runtime::BoundedVec,
+        traits::UncheckedOnRuntimeUpgrade,
+    };
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use primitives::{proofs::RegisteredPoStProof, sector::SectorSize};
+
+    use super::{v1, Tail, LOG_TARGET};
+    use crate::{
+        storage_provider::{WorkerKeyChange, MAX_CONTROL_ADDRESSES},
+        Config, Pallet,
+    };
+
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderInfo<AccountId, PeerId, BlockNumber> {
+        pub owner: AccountId,
+        pub worker: AccountId,
+        pub control_addresses: BoundedVec<AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+        pub pending_worker_key: Option<WorkerKeyChange<AccountId, BlockNumber>>,
+        pub peer_id: PeerId,
+        pub window_post_proof_type: RegisteredPoStProof,
+        pub sector_size: SectorSize,
+        pub window_post_partition_sectors: u64,
+    }
+
+    /// The state's info, the rest of the state is carried over as it is.
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderState<Info> {
+        pub info: Info,
+        pub rest: Tail,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type StorageProviders<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        StorageProviderState<
+            StorageProviderInfo<
+                <T as frame_system::Config>::AccountId,
+                <T as Config>::PeerId,
+                BlockNumberFor<T>,
+            >,
+        >,
+    >;
+
+    /// Sets the owner as the worker, without control addresses nor a pending worker change.
+    pub struct UncheckedMigrateV1ToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            StorageProviders::<T>::translate::<
+                StorageProviderState<v1::StorageProviderInfo<T::PeerId>>,
+                _,
+            >(|owner, old| {
+                migrated += 1;
+
+                let info = StorageProviderInfo {
+                    owner: owner.clone(),
+                    worker: owner,
+                    control_addresses: BoundedVec::new(),
+                    pending_worker_key: None,
+                    peer_id: old.info.peer_id,
+                    window_post_proof_type: old.info.window_post_proof_type,
+                    sector_size: old.info.sector_size,
+                    window_post_partition_sectors: old.info.window_post_partition_sectors,
+                };
+
+                Some(StorageProviderState {
+                    info,
+                    rest: old.rest,
+                })
+            });
+
+            log::info!(target: LOG_TARGET, "Migrated the accounts of {} storage providers", migrated);
+
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV1ToV2`] which only runs when the on-chain storage version is 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Migrates the storage providers registered before the multiaddrs, they start without any.
+pub mod v3 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, sp_runtime::BoundedVec,
+        traits::UncheckedOnRuntimeUpgrade,
+    };
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use primitives::{Multiaddr, MAX_MULTIADDRS};
+
+    use super::{Tail, LOG_TARGET};
+    use crate::{
+        storage_provider::{WorkerKeyChange, MAX_CONTROL_ADDRESSES},
+        Config, Pallet,
+    };
+
+    /// Types as they were stored before the migration.
+    pub(crate) mod v2 {
+        use super::*;
+
+        /// The state up to the peer id, the rest of the state is carried over as it is.
+        #[derive(Decode, Encode)]
+        pub struct StorageProviderState<AccountId, PeerId, BlockNumber> {
+            pub owner: AccountId,
+            pub worker: AccountId,
+            pub control_addresses: BoundedVec<AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+            pub pending_worker_key: Option<WorkerKeyChange<AccountId, BlockNumber>>,
+            pub peer_id: PeerId,
+            pub rest: Tail,
+        }
+    }
+
+    /// The state up to the multiaddrs, the rest of the state is carried over as it is.
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderState<AccountId, PeerId, BlockNumber> {
+        pub owner: AccountId,
+        pub worker: AccountId,
+        pub control_addresses: BoundedVec<AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+        pub pending_worker_key: Option<WorkerKeyChange<AccountId, BlockNumber>>,
+        pub peer_id: PeerId,
+        pub multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
+        pub rest: Tail,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type StorageProviders<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        StorageProviderState<
+            <T as frame_system::Config>::AccountId,
+            <T as Config>::PeerId,
+            BlockNumberFor<T>,
+        >,
+    >;
+
+    /// Adds empty multiaddrs after the peer id.
+    pub struct UncheckedMigrateV2ToV3<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV2ToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            StorageProviders::<T>::translate::<
+                v2::StorageProviderState<T::AccountId, T::PeerId, BlockNumberFor<T>>,
+                _,
+            >(|_, old| {
+                migrated += 1;
+
+                Some(StorageProviderState {
+                    owner: old.owner,
+                    worker: old.worker,
+                    control_addresses: old.control_addresses,
+                    pending_worker_key: old.pending_worker_key,
+                    peer_id: old.peer_id,
+                    multiaddrs: BoundedVec::new(),
+                    rest: old.rest,
+                })
+            });
+
+            log::info!(target: LOG_TARGET, "Migrated the multiaddrs of {} storage providers", migrated);
+
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV2ToV3`] which only runs when the on-chain storage version is 2.
+    pub type MigrateV2ToV3<T> = VersionedMigration<
+        2,
+        3,
+        UncheckedMigrateV2ToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Migrates the storage providers registered before the initial pledge,
+/// they start without any as none was locked for their sectors.
+pub mod v4 {
+    use frame_support::{
+        migrations::VersionedMigration,
+        pallet_prelude::*,
+        sp_runtime::{BoundedBTreeMap, BoundedVec},
+        traits::UncheckedOnRuntimeUpgrade,
+    };
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use primitives::{
+        proofs::RegisteredPoStProof,
+        sector::{SectorNumber, SectorSize},
+        Multiaddr, MAX_MULTIADDRS, MAX_SECTORS,
+    };
+    use sp_arithmetic::traits::Zero;
+
+    use super::{Tail, LOG_TARGET};
+    use crate::{
+        pallet::BalanceOf,
+        sector::SectorOnChainInfo,
+        storage_provider::{WorkerKeyChange, MAX_CONTROL_ADDRESSES},
+        Config, Pallet,
+    };
+
+    /// Types as they were stored before the migration.
+    pub(crate) mod v3 {
+        use super::*;
+
+        /// The state up to the pre-commit deposits, the rest of the state is carried over as it is.
+        #[derive(Decode, Encode)]
+        pub struct StorageProviderState<AccountId, PeerId, Balance, BlockNumber> {
+            pub info: StorageProviderInfo<AccountId, PeerId, BlockNumber>,
+            pub sectors: BoundedBTreeMap<
+                SectorNumber,
+                SectorOnChainInfo<BlockNumber>,
+                ConstU32<MAX_SECTORS>,
+            >,
+            pub pre_commit_deposits: Balance,
+            pub rest: Tail,
+        }
+    }
+
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderInfo<AccountId, PeerId, BlockNumber> {
+        pub owner: AccountId,
+        pub worker: AccountId,
+        pub control_addresses: BoundedVec<AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+        pub pending_worker_key: Option<WorkerKeyChange<AccountId, BlockNumber>>,
+        pub peer_id: PeerId,
+        pub multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
+        pub window_post_proof_type: RegisteredPoStProof,
+        pub sector_size: SectorSize,
+        pub window_post_partition_sectors: u64,
+    }
+
+    /// The state up to the initial pledge, the rest of the state is carried over as it is.
+    #[derive(Decode, Encode)]
+    pub struct StorageProviderState<AccountId, PeerId, Balance, BlockNumber> {
+        pub info: StorageProviderInfo<AccountId, PeerId, BlockNumber>,
+        pub sectors:
+            BoundedBTreeMap<SectorNumber, SectorOnChainInfo<BlockNumber>, ConstU32<MAX_SECTORS>>,
+        pub pre_commit_deposits: Balance,
+        pub initial_pledge: Balance,
+        pub rest: Tail,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type StorageProviders<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        StorageProviderState<
+            <T as frame_system::Config>::AccountId,
+            <T as Config>::PeerId,
+            BalanceOf<T>,
+            BlockNumberFor<T>,
+        >,
+    >;
+
+    /// Adds an empty initial pledge after the pre-commit deposits.
+    pub struct UncheckedMigrateV3ToV4<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV3ToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0;
+
+            StorageProviders::<T>::translate::<
+                v3::StorageProviderState<T::AccountId, T::PeerId, BalanceOf<T>, BlockNumberFor<T>>,
+                _,
+            >(|_, old| {
+                migrated += 1;
+
+                Some(StorageProviderState {
+                    info: old.info,
+                    sectors: old.sectors,
+                    pre_commit_deposits: old.pre_commit_deposits,
+                    initial_pledge: BalanceOf::<T>::zero(),
+                    rest: old.rest,
+                })
+            });
+
+            log::info!(target: LOG_TARGET, "Migrated the initial pledge of {} storage providers", migrated);
+
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// [`UncheckedMigrateV3ToV4`] which only runs when the on-chain storage version is 3.
+    pub type MigrateV3ToV4<T> = VersionedMigration<
+        3,
+        4,
+        UncheckedMigrateV3ToV4<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Schedules the storage providers registered before the deadline checks and pre-commit expirations were scheduled by block.
+pub mod v5 {
+    use frame_support::{
+        migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    };
+
+    use super::LOG_TARGET;
+    use crate::{
+        pallet::{PreCommitExpirations, StorageProviders},
+        Config, Pallet,
+    };
+
+    /// Schedules the current deadline of every storage provider and the expirations of its pre-commits.
+    /// Blocks that already passed are scheduled for the current block instead.
+    pub struct UncheckedMigrateV4ToV5<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV4ToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut migrated = 0;
+            let mut scheduled = 0;
+
+            for (owner, state) in StorageProviders::<T>::iter() {
+                migrated += 1;
+
+                if let Err(e) = Pallet::<T>::schedule_deadline_check(current_block, &owner, &state)
+                {
+                    log::error!(target: LOG_TARGET, "failed to schedule deadline check for sp {:?}: {:?}", owner, e);
+                } else {
+                    scheduled += 1;
+                }
+                for precommit in state.pre_committed_sectors.values() {
+                    let prove_commit_due =
+                        precommit.pre_commit_block_number + T::MaxProveCommitDuration::get();
+                    PreCommitExpirations::<T>::insert(
+                        prove_commit_due.max(current_block),
+                        &owner,
+                        (),
+                    );
+                    scheduled += 1;
+                }
+            }
+
+            log::info!(target: LOG_TARGET, "Scheduled {} storage providers", migrated);
+
+            // Each storage provider is read, then its deadline and pre-commits are scheduled
+            T::DbWeight::get().reads_writes(migrated + 1, scheduled)
+        }
+    }
+
+    /// [`UncheckedMigrateV4ToV5`] which only runs when the on-chain storage version is 4.
+    pub type MigrateV4ToV5<T> = VersionedMigration<
+        4,
+        5,
+        UncheckedMigrateV4ToV5<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
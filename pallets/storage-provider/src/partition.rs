@@ -52,6 +52,10 @@ where
     /// All sectors mapped by the expiration. The sectors are indexed by the
     /// expiration block. An expiration may be an "on-time" scheduled
     /// expiration, or early "faulty" expiration.
+    ///
+    /// This is where the fault start of every sector is tracked: a sector becoming faulty
+    /// is rescheduled to expire early at its fault start plus `FaultMaxAge`, sectors that stay faulty
+    /// keep that expiration and recovered sectors are rescheduled back to their on-time expiration.
     pub expirations: ExpirationQueue<BlockNumber>,
 
     /// Sectors that were terminated before their committed expiration, indexed by termination block.
@@ -375,6 +379,64 @@ where
         Ok(removed)
     }
 
+    /// Active sectors are sectors that are live and neither faulty nor unproven.
+    pub fn active_sectors(&self) -> BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>> {
+        self.live_sectors()
+            .iter()
+            .filter(|s| !self.faults.contains(s) && !self.unproven.contains(s))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .try_into()
+            .expect("Sectors is bounded to MAX_SECTORS so the length can never exceed MAX_SECTORS")
+    }
+
+    /// Sectors proven by a Windowed PoSt, i.e. live sectors that are not faulty.
+    /// Faulty sectors declared as recovered are proven as well, as they stop being faulty once the proof is recorded.
+    pub fn provable_sectors(&self) -> BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>> {
+        self.live_sectors()
+            .iter()
+            .filter(|s| !self.faults.contains(s) || self.recoveries.contains(s))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .try_into()
+            .expect("Sectors is bounded to MAX_SECTORS so the length can never exceed MAX_SECTORS")
+    }
+
+    /// Number of sectors counting towards the storage provider's power, i.e. live sectors that are not faulty.
+    /// Unproven sectors count towards the power, as they are activated by `prove_commit_sectors`.
+    pub fn powered_sectors_count(&self) -> u64 {
+        self.live_sectors()
+            .iter()
+            .filter(|s| !self.faults.contains(s))
+            .count() as u64
+    }
+
+    /// Replaces a number of active sectors in this partition, rescheduling them in the expiration queue.
+    /// The replaced sectors must have the same sector numbers as the ones they replace,
+    /// used to update the expiration of a sector.
+    ///
+    /// Reference implementation:
+    /// * <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/actors/miner/src/partition_state.rs>
+    pub fn replace_sectors(
+        &mut self,
+        old_sectors: &[&SectorOnChainInfo<BlockNumber>],
+        new_sectors: &[SectorOnChainInfo<BlockNumber>],
+    ) -> Result<(), GeneralPalletError> {
+        // Ensure that all given sectors are active
+        let active_sectors = self.active_sectors();
+        ensure!(
+            old_sectors
+                .iter()
+                .all(|s| active_sectors.contains(&s.sector_number)),
+            {
+                log::error!(target: LOG_TARGET, "replace_sectors: can only replace active sectors");
+                GeneralPalletError::PartitionErrorSectorsNotActive
+            }
+        );
+
+        self.expirations.replace_sectors(old_sectors, new_sectors)
+    }
+
     pub fn record_early_terminations(
         &mut self,
         block_number: BlockNumber,
@@ -733,6 +795,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn replace_sectors() -> Result<(), GeneralPalletError> {
+        // Set up partition, using `u64` for block number because it is not relevant to this test.
+        let mut partition: Partition<u64> = Partition::new();
+        let all_sectors = sectors();
+
+        // Add sectors
+        partition.add_sectors(&all_sectors)?;
+
+        // Sectors are unproven and cannot be replaced
+        let result = partition.replace_sectors(&[&all_sectors[0]], &[test_sector(20, 1)]);
+        assert!(matches!(
+            result,
+            Err(GeneralPalletError::PartitionErrorSectorsNotActive)
+        ));
+
+        partition.activate_unproven();
+
+        let all_sectors_map = BoundedBTreeMap::try_from(
+            all_sectors
+                .iter()
+                .map(|s| (s.sector_number, s.clone()))
+                .collect::<BTreeMap<SectorNumber, SectorOnChainInfo<u64>>>(),
+        )
+        .unwrap();
+        // fault sector 2, faulty sectors cannot be replaced
+        partition.record_faults(&all_sectors_map, &sector_set(&[2]), 7)?;
+        let result = partition.replace_sectors(&[&all_sectors[1]], &[test_sector(20, 2)]);
+        assert!(matches!(
+            result,
+            Err(GeneralPalletError::PartitionErrorSectorsNotActive)
+        ));
+
+        // Replace an active sector
+        partition.replace_sectors(&[&all_sectors[0]], &[test_sector(20, 1)])?;
+        assert!(partition.expirations.map.get(&2).is_none());
+        assert_eq!(
+            partition.expirations.map.get(&20).unwrap().on_time_sectors,
+            sector_set::<MAX_SECTORS>(&[1])
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn pop_early_terminations_till_max_sectors() -> Result<(), GeneralPalletError> {
         // Set up partition, using `u64` for block number because it is not relevant to this test.
@@ -4,7 +4,8 @@ use frame_support::{
     sp_runtime::BoundedVec,
 };
 use primitives::{
-    proofs::RegisteredPoStProof, PartitionNumber, MAX_PARTITIONS_PER_DEADLINE, MAX_POST_PROOF_BYTES,
+    proofs::RegisteredPoStProof, PartitionNumber, MAX_PARTITIONS_PER_DEADLINE,
+    MAX_WINDOWED_POST_PROOF_BYTES,
 };
 use scale_info::TypeInfo;
 use sp_core::blake2_64;
@@ -15,7 +16,10 @@ pub struct PoStProof {
     /// The proof type, currently only one type is supported.
     pub post_proof: RegisteredPoStProof,
     /// The proof submission, to be checked by [`ProofVerification::verify_post`], usually [`pallet_proofs`].
-    pub proof_bytes: BoundedVec<u8, ConstU32<MAX_POST_PROOF_BYTES>>,
+    ///
+    /// Contains a proof for each of the partitions being proven, of the same length,
+    /// concatenated in the order of [`SubmitWindowedPoStParams::partitions`].
+    pub proof_bytes: BoundedVec<u8, ConstU32<MAX_WINDOWED_POST_PROOF_BYTES>>,
 }
 
 /// Parameter type for `submit_windowed_post` extrinsic.
@@ -26,7 +30,7 @@ pub struct PoStProof {
 pub struct SubmitWindowedPoStParams {
     /// The deadline index which the submission targets.
     pub deadline: u64,
-    /// The partition being proven.
+    /// The partitions being proven, at most [`primitives::MAX_PARTITIONS_PER_POST`].
     pub partitions: BoundedVec<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>>,
     /// The proof submission.
     pub proof: PoStProof,
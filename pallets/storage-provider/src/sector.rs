@@ -2,7 +2,8 @@ use codec::{Decode, Encode};
 use frame_support::{pallet_prelude::*, BoundedVec};
 use primitives::{
     pallets::SectorDeal, proofs::RegisteredSealProof, sector::SectorNumber, DealId,
-    PartitionNumber, CID_SIZE_IN_BYTES, MAX_DEALS_PER_SECTOR, MAX_SEAL_PROOF_BYTES,
+    PartitionNumber, CID_SIZE_IN_BYTES, MAX_BATCHED_SECTORS, MAX_BATCH_PROOF_BYTES,
+    MAX_DEALS_PER_SECTOR, MAX_REPLICA_UPDATE_PROOF_BYTES, MAX_SEAL_PROOF_BYTES,
     MAX_TERMINATIONS_PER_CALL,
 };
 use scale_info::TypeInfo;
@@ -117,6 +118,45 @@ pub struct ProveCommitSector {
     pub proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
 }
 
+/// Arguments passed into the `prove_commit_ni_sectors` extrinsic.
+///
+/// The sector is onboarded in a single step, so it carries the same information as a pre-commit
+/// along with its non-interactive proof.
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct ProveCommitNiSector<BlockNumber> {
+    /// The sector being onboarded, it must use a non-interactive seal proof.
+    pub sector: SectorPreCommitInfo<BlockNumber>,
+    pub proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
+}
+
+/// Arguments passed into the `prove_commit_batch` extrinsic.
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct ProveCommitBatch {
+    /// The sectors being proven, in the same order as their proofs in the batch.
+    pub sector_numbers: BoundedVec<SectorNumber, ConstU32<MAX_BATCHED_SECTORS>>,
+    /// The proofs of all the sectors, one after another.
+    pub batch_proof: BoundedVec<u8, ConstU32<MAX_BATCH_PROOF_BYTES>>,
+}
+
+/// Arguments passed into the `prove_replica_updates` extrinsic.
+///
+/// Replaces the replica of a committed capacity sector with one containing the given deals.
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct ReplicaUpdate {
+    pub sector_number: SectorNumber,
+    /// The deadline the sector is assigned to.
+    pub deadline: u64,
+    /// The partition the sector is assigned to.
+    pub partition: PartitionNumber,
+    /// CommR of the updated replica.
+    pub new_sealed_cid: BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>>,
+    /// CommD of the data in the updated replica, calculated from the pieces of the deals.
+    pub new_unsealed_cid: BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>>,
+    /// Deals that are activated once the update is proven.
+    pub deal_ids: BoundedVec<DealId, ConstU32<MAX_DEALS_PER_SECTOR>>,
+    pub proof: BoundedVec<u8, ConstU32<MAX_REPLICA_UPDATE_PROOF_BYTES>>,
+}
+
 /// Type that is emitted after a successful prove commit extrinsic.
 #[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
 pub struct ProveCommitResult {
@@ -154,3 +194,18 @@ pub struct TerminationDeclaration {
     pub partition: PartitionNumber,
     pub sectors: BoundedBTreeSet<SectorNumber, ConstU32<MAX_TERMINATIONS_PER_CALL>>,
 }
+
+/// Argument used for the `extend_sector_expiration` extrinsic
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct ExtendSectorExpirationParams<BlockNumber> {
+    pub extensions: BoundedVec<ExpirationExtension<BlockNumber>, ConstU32<DECLARATIONS_MAX>>,
+}
+
+#[derive(Clone, RuntimeDebug, Decode, Encode, PartialEq, TypeInfo)]
+pub struct ExpirationExtension<BlockNumber> {
+    pub deadline: u64,
+    pub partition: PartitionNumber,
+    pub sectors: BoundedBTreeSet<SectorNumber, ConstU32<MAX_TERMINATIONS_PER_CALL>>,
+    /// The block at which the sectors will expire, must be after their current expiration.
+    pub new_expiration: BlockNumber,
+}
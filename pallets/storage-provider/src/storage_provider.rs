@@ -8,12 +8,16 @@ use frame_support::{
     sp_runtime::{BoundedBTreeMap, BoundedVec},
 };
 use primitives::{
+    pallets::StoragePower,
     proofs::RegisteredPoStProof,
     sector::{SectorNumber, SectorSize},
-    MAX_SECTORS,
+    Multiaddr, MAX_MULTIADDRS, MAX_SECTORS,
 };
 use scale_info::TypeInfo;
-use sp_arithmetic::{traits::BaseArithmetic, ArithmeticError};
+use sp_arithmetic::{
+    traits::{BaseArithmetic, UniqueSaturatedFrom},
+    ArithmeticError,
+};
 
 use crate::{
     deadline::{assign_deadlines, deadline_is_mutable, Deadline, DeadlineInfo, Deadlines},
@@ -26,12 +30,12 @@ const LOG_TARGET: &'static str = "runtime::storage_provider::storage_provider";
 
 /// This struct holds the state of a single storage provider.
 #[derive(RuntimeDebug, Decode, Encode, TypeInfo)]
-pub struct StorageProviderState<PeerId, Balance, BlockNumber>
+pub struct StorageProviderState<AccountId, PeerId, Balance, BlockNumber>
 where
     BlockNumber: sp_runtime::traits::BlockNumber,
 {
     /// Contains static information about this storage provider
-    pub info: StorageProviderInfo<PeerId>,
+    pub info: StorageProviderInfo<AccountId, PeerId, BlockNumber>,
 
     /// Information for all proven and not-yet-garbage-collected sectors.
     pub sectors:
@@ -40,6 +44,9 @@ where
     /// Total funds locked as pre_commit_deposit
     pub pre_commit_deposits: Balance,
 
+    /// Total funds locked as initial pledge for the proven sectors.
+    pub initial_pledge: Balance,
+
     /// Sectors that have been pre-committed but not yet proven.
     pub pre_committed_sectors: BoundedBTreeMap<
         SectorNumber,
@@ -80,14 +87,16 @@ where
     pub early_terminations: BTreeSet<u64>,
 }
 
-impl<PeerId, Balance, BlockNumber> StorageProviderState<PeerId, Balance, BlockNumber>
+impl<AccountId, PeerId, Balance, BlockNumber>
+    StorageProviderState<AccountId, PeerId, Balance, BlockNumber>
 where
+    AccountId: Clone + Decode + Encode + TypeInfo,
     PeerId: Clone + Decode + Encode + TypeInfo,
     BlockNumber: sp_runtime::traits::BlockNumber + BaseArithmetic,
     Balance: BaseArithmetic,
 {
     pub fn new(
-        info: StorageProviderInfo<PeerId>,
+        info: StorageProviderInfo<AccountId, PeerId, BlockNumber>,
         period_start: BlockNumber,
         deadline_idx: u64,
         w_post_period_deadlines: u64,
@@ -96,6 +105,7 @@ where
             info,
             sectors: BoundedBTreeMap::new(),
             pre_commit_deposits: 0.into(),
+            initial_pledge: 0.into(),
             pre_committed_sectors: BoundedBTreeMap::new(),
             proving_period_start: period_start,
             current_deadline: deadline_idx,
@@ -154,6 +164,62 @@ where
         Ok(())
     }
 
+    pub fn remove_pre_commit_deposit(&mut self, amount: Balance) -> Result<(), ArithmeticError> {
+        self.pre_commit_deposits = self
+            .pre_commit_deposits
+            .checked_sub(&amount)
+            .ok_or(ArithmeticError::Underflow)?;
+        Ok(())
+    }
+
+    pub fn add_initial_pledge(&mut self, amount: Balance) -> Result<(), ArithmeticError> {
+        self.initial_pledge = self
+            .initial_pledge
+            .checked_add(&amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(())
+    }
+
+    /// Number of sectors holding an initial pledge, i.e. proven sectors that were not terminated nor expired.
+    pub fn pledged_sectors_count(&self) -> u64 {
+        self.deadlines
+            .due
+            .iter()
+            .flat_map(|deadline| deadline.partitions.values())
+            .map(|partition| partition.live_sectors().len() as u64)
+            .sum()
+    }
+
+    /// Removes the initial pledge of the sectors that stopped being live since `pledged_sectors` were counted,
+    /// returning the amount to be released.
+    ///
+    /// Each sector holds an equal share of the locked pledge, regardless of the network power when it was proven,
+    /// the whole pledge is released once there are no live sectors left.
+    pub fn remove_initial_pledge(&mut self, pledged_sectors: u64) -> Balance {
+        let remaining_sectors = self.pledged_sectors_count();
+        if remaining_sectors >= pledged_sectors {
+            return Balance::zero();
+        }
+
+        let initial_pledge = core::mem::replace(&mut self.initial_pledge, Balance::zero());
+        if remaining_sectors == 0 {
+            // Release the whole pledge, including any remainder left by the division below
+            return initial_pledge;
+        }
+
+        let released = initial_pledge
+            .checked_div(&Balance::unique_saturated_from(pledged_sectors))
+            .unwrap_or_else(Balance::zero)
+            .saturating_mul(Balance::unique_saturated_from(
+                pledged_sectors - remaining_sectors,
+            ));
+        self.initial_pledge = initial_pledge
+            .checked_sub(&released)
+            .unwrap_or_else(Balance::zero);
+
+        released
+    }
+
     /// Inserts sectors into the pre commit state.
     /// Before calling this it should be ensured that the sector number is not being reused.
     // TODO(@aidan46, #107, 2024-06-21): Allow for batch inserts.
@@ -296,6 +362,26 @@ where
         Ok(())
     }
 
+    /// Computes the power of the storage provider from the sectors assigned to its deadlines.
+    ///
+    /// Verified deals are not supported yet, so the quality adjusted power equals the raw byte power.
+    pub fn power(&self) -> StoragePower {
+        let powered_sectors: u64 = self
+            .deadlines
+            .due
+            .iter()
+            .flat_map(|deadline| deadline.partitions.values())
+            .map(|partition| partition.powered_sectors_count())
+            .sum();
+        let raw_byte_power =
+            u128::from(powered_sectors) * u128::from(self.info.sector_size.bytes());
+
+        StoragePower {
+            raw_byte_power,
+            quality_adj_power: raw_byte_power,
+        }
+    }
+
     /// Simple getter for mutable deadlines.
     pub fn get_deadlines_mut(&mut self) -> &mut Deadlines<BlockNumber> {
         &mut self.deadlines
@@ -371,11 +457,27 @@ where
     }
 }
 
+/// Maximum number of control addresses a storage provider can have.
+pub const MAX_CONTROL_ADDRESSES: u32 = 10;
+
 /// Static information about the storage provider.
-#[derive(RuntimeDebug, Clone, Copy, Decode, Encode, TypeInfo, PartialEq)]
-pub struct StorageProviderInfo<PeerId> {
+#[derive(RuntimeDebug, Clone, Decode, Encode, TypeInfo, PartialEq)]
+pub struct StorageProviderInfo<AccountId, PeerId, BlockNumber> {
+    /// Account that holds the storage provider's funds.
+    /// It is the account the storage provider was registered with and it never changes,
+    /// as such, the owner key can be kept cold.
+    pub owner: AccountId,
+    /// Account used to sign the storage provider's operational extrinsics — e.g. PoSt and commits.
+    /// It is expected to be a hot key, used by the storage provider's server.
+    pub worker: AccountId,
+    /// Additional accounts allowed to sign the storage provider's operational extrinsics.
+    pub control_addresses: BoundedVec<AccountId, ConstU32<MAX_CONTROL_ADDRESSES>>,
+    /// A pending change of the worker account, if any.
+    pub pending_worker_key: Option<WorkerKeyChange<AccountId, BlockNumber>>,
     /// Libp2p identity that should be used when connecting to this Storage Provider
     pub peer_id: PeerId,
+    /// Addresses clients can dial to upload or retrieve data from this Storage Provider.
+    pub multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
     /// The proof type used by this Storage provider for sealing sectors.
     /// Rationale: Different StorageProviders may use different proof types for sealing sectors. By storing
     /// the `window_post_proof_type`, we can ensure that the correct proof mechanisms are applied and verified
@@ -398,13 +500,28 @@ pub struct StorageProviderInfo<PeerId> {
     pub window_post_partition_sectors: u64,
 }
 
-impl<PeerId> StorageProviderInfo<PeerId> {
+impl<AccountId, PeerId, BlockNumber> StorageProviderInfo<AccountId, PeerId, BlockNumber>
+where
+    AccountId: Clone,
+{
     /// Create a new instance of StorageProviderInfo
-    pub fn new(peer_id: PeerId, window_post_proof_type: RegisteredPoStProof) -> Self {
+    ///
+    /// The worker defaults to the owner, it can be changed later with `change_worker_address`.
+    /// The multiaddrs start empty, they can be set later with `change_multiaddrs`.
+    pub fn new(
+        owner: AccountId,
+        peer_id: PeerId,
+        window_post_proof_type: RegisteredPoStProof,
+    ) -> Self {
         let sector_size = window_post_proof_type.sector_size();
         let window_post_partition_sectors = window_post_proof_type.window_post_partitions_sector();
         Self {
+            worker: owner.clone(),
+            owner,
+            control_addresses: BoundedVec::new(),
+            pending_worker_key: None,
             peer_id,
+            multiaddrs: BoundedVec::new(),
             window_post_proof_type,
             sector_size,
             window_post_partition_sectors,
@@ -412,6 +529,18 @@ impl<PeerId> StorageProviderInfo<PeerId> {
     }
 }
 
+/// A scheduled change of a storage provider's worker account.
+///
+/// Reference:
+/// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/types.rs>
+#[derive(RuntimeDebug, Clone, Decode, Encode, TypeInfo, PartialEq)]
+pub struct WorkerKeyChange<AccountId, BlockNumber> {
+    /// The new worker account.
+    pub new_worker: AccountId,
+    /// Block from which the change can be confirmed.
+    pub effective_at: BlockNumber,
+}
+
 /// Calculate the *first* proving period.
 ///
 /// *This function deviates considerably from Filecoin.*
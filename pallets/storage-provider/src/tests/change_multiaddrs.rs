@@ -0,0 +1,105 @@
+use frame_support::{assert_noop, assert_ok, sp_runtime::BoundedVec};
+use primitives::{Multiaddr, MAX_MULTIADDRS};
+use sp_core::{bounded_vec, ConstU32};
+
+use crate::{
+    pallet::{Error, Event, StorageProviders},
+    tests::{
+        account, events, new_test_ext, register_storage_provider, RuntimeEvent, RuntimeOrigin,
+        StorageProvider, Test, ALICE, BOB,
+    },
+};
+
+/// Builds a multiaddr from its string representation.
+fn multiaddr(addr: &str) -> Multiaddr {
+    BoundedVec::try_from(addr.as_bytes().to_vec()).unwrap()
+}
+
+#[test]
+fn change_multiaddrs_fails_sp_not_found() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            StorageProvider::change_multiaddrs(
+                RuntimeOrigin::signed(account(ALICE)),
+                bounded_vec![multiaddr("/ip4/127.0.0.1/tcp/8001")],
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+#[test]
+fn change_multiaddrs_fails_empty_multiaddr() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_noop!(
+            StorageProvider::change_multiaddrs(
+                RuntimeOrigin::signed(account(ALICE)),
+                bounded_vec![multiaddr("/ip4/127.0.0.1/tcp/8001"), multiaddr("")],
+            ),
+            Error::<Test>::InvalidMultiaddr
+        );
+    });
+}
+
+#[test]
+fn change_multiaddrs_success() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        let multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>> = bounded_vec![
+            multiaddr("/ip4/127.0.0.1/tcp/8001"),
+            multiaddr("/dns4/sp.example.com/tcp/8001"),
+        ];
+        assert_ok!(StorageProvider::change_multiaddrs(
+            RuntimeOrigin::signed(account(ALICE)),
+            multiaddrs.clone(),
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::MultiaddrsChanged {
+                    owner: account(ALICE),
+                    multiaddrs: multiaddrs.clone(),
+                }
+            )]
+        );
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.info.multiaddrs, multiaddrs);
+
+        // Passing no multiaddrs removes the current ones
+        assert_ok!(StorageProvider::change_multiaddrs(
+            RuntimeOrigin::signed(account(ALICE)),
+            bounded_vec![],
+        ));
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert!(sp.info.multiaddrs.is_empty());
+    });
+}
+
+#[test]
+fn peer_info_returns_peer_id_and_multiaddrs() {
+    new_test_ext().execute_with(|| {
+        assert!(StorageProvider::peer_info(&account(ALICE)).is_none());
+
+        register_storage_provider(account(ALICE));
+        let multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>> =
+            bounded_vec![multiaddr("/ip4/127.0.0.1/tcp/8001")];
+        assert_ok!(StorageProvider::change_multiaddrs(
+            RuntimeOrigin::signed(account(ALICE)),
+            multiaddrs.clone(),
+        ));
+
+        let peer_info = StorageProvider::peer_info(&account(ALICE)).unwrap();
+        assert_eq!(peer_info.peer_id.to_vec(), "storage_provider_1".as_bytes());
+        assert_eq!(peer_info.multiaddrs, multiaddrs);
+
+        // Other storage providers are not affected
+        register_storage_provider(account(BOB));
+        let peer_info = StorageProvider::peer_info(&account(BOB)).unwrap();
+        assert!(peer_info.multiaddrs.is_empty());
+    });
+}
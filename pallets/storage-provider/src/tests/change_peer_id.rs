@@ -0,0 +1,81 @@
+use frame_support::{assert_noop, assert_ok, sp_runtime::BoundedVec};
+use sp_core::bounded_vec;
+
+use crate::{
+    pallet::{Error, Event, StorageProviders},
+    tests::{
+        account, events, new_test_ext, register_storage_provider, RuntimeEvent, RuntimeOrigin,
+        StorageProvider, Test, ALICE, CHARLIE,
+    },
+};
+
+#[test]
+fn change_peer_id_fails_sp_not_found() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            StorageProvider::change_peer_id(
+                RuntimeOrigin::signed(account(ALICE)),
+                bounded_vec![0xd, 0xe, 0xa, 0xd],
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+#[test]
+fn change_peer_id_success() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        let peer_id = BoundedVec::try_from("storage_provider_2".as_bytes().to_vec()).unwrap();
+        assert_ok!(StorageProvider::change_peer_id(
+            RuntimeOrigin::signed(account(ALICE)),
+            peer_id.clone(),
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::PeerIdChanged {
+                    owner: account(ALICE),
+                    peer_id: peer_id.clone(),
+                }
+            )]
+        );
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.info.peer_id, peer_id);
+    });
+}
+
+/// Control addresses can change the peer ID on behalf of the owner.
+#[test]
+fn change_peer_id_by_control_address() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![account(CHARLIE)],
+        ));
+
+        let peer_id: BoundedVec<_, _> = bounded_vec![0xd, 0xe, 0xa, 0xd];
+        assert_ok!(StorageProvider::change_peer_id(
+            RuntimeOrigin::signed(account(CHARLIE)),
+            peer_id.clone(),
+        ));
+
+        assert_eq!(
+            events().last(),
+            Some(&RuntimeEvent::StorageProvider(
+                Event::<Test>::PeerIdChanged {
+                    owner: account(ALICE),
+                    peer_id: peer_id.clone(),
+                }
+            ))
+        );
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.info.peer_id, peer_id);
+    });
+}
@@ -0,0 +1,377 @@
+use frame_support::{assert_noop, assert_ok};
+use primitives::proofs::RegisteredPoStProof;
+use sp_core::bounded_vec;
+use sp_runtime::{ArithmeticError, DispatchError};
+
+use crate::{
+    pallet::{Error, Event, StorageProviderAccounts, StorageProviders},
+    sector::ExtendSectorExpirationParams,
+    storage_provider::WorkerKeyChange,
+    tests::{
+        account, events, new_test_ext, register_storage_provider, run_to_block,
+        DealProposalBuilder, Market, RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder,
+        StorageProvider, System, Test, WorkerKeyChangeDelay, ALICE, BOB, CHARLIE,
+    },
+};
+
+/// Schedules the change of Alice's worker to Charlie and confirms it once it's effective.
+fn change_worker_to_charlie() {
+    assert_ok!(StorageProvider::change_worker_address(
+        RuntimeOrigin::signed(account(ALICE)),
+        account(CHARLIE),
+        bounded_vec![],
+    ));
+    run_to_block(System::block_number() + WorkerKeyChangeDelay::get());
+    assert_ok!(StorageProvider::confirm_change_worker_address(
+        RuntimeOrigin::signed(account(ALICE)),
+    ));
+    System::reset_events();
+}
+
+/// Empty extension params, used to check who can call the operational extrinsics.
+fn no_extensions() -> ExtendSectorExpirationParams<u64> {
+    ExtendSectorExpirationParams {
+        extensions: bounded_vec![],
+    }
+}
+
+#[test]
+fn change_worker_address_fails_sp_not_found() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(ALICE)),
+                account(CHARLIE),
+                bounded_vec![],
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+/// Only the owner can change the addresses, not the worker nor the control addresses.
+#[test]
+fn change_worker_address_fails_not_owner() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        change_worker_to_charlie();
+
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                account(BOB),
+                bounded_vec![],
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+#[test]
+fn change_worker_address_fails_address_in_use() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        register_storage_provider(account(BOB));
+
+        // Bob is the owner of another storage provider
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(ALICE)),
+                account(BOB),
+                bounded_vec![],
+            ),
+            Error::<Test>::AddressAlreadyInUse
+        );
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(ALICE)),
+                account(ALICE),
+                bounded_vec![account(BOB)],
+            ),
+            Error::<Test>::AddressAlreadyInUse
+        );
+
+        // Charlie is Alice's worker
+        change_worker_to_charlie();
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(BOB)),
+                account(CHARLIE),
+                bounded_vec![],
+            ),
+            Error::<Test>::AddressAlreadyInUse
+        );
+    });
+}
+
+#[test]
+fn change_worker_address_fails_effective_block_overflow() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        System::set_block_number(u64::MAX);
+
+        assert_noop!(
+            StorageProvider::change_worker_address(
+                RuntimeOrigin::signed(account(ALICE)),
+                account(CHARLIE),
+                bounded_vec![],
+            ),
+            DispatchError::Arithmetic(ArithmeticError::Overflow)
+        );
+    });
+}
+
+#[test]
+fn change_worker_address_schedules_change() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(CHARLIE),
+            bounded_vec![account(BOB)],
+        ));
+
+        let effective_at = System::block_number() + WorkerKeyChangeDelay::get();
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::StorageProvider(Event::<Test>::ControlAddressesChanged {
+                    owner: account(ALICE),
+                    control_addresses: bounded_vec![account(BOB)],
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::WorkerAddressChangeScheduled {
+                    owner: account(ALICE),
+                    new_worker: account(CHARLIE),
+                    effective_at,
+                }),
+            ]
+        );
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        // The worker is only changed after the change is confirmed
+        assert_eq!(sp.info.worker, account(ALICE));
+        assert_eq!(
+            sp.info.pending_worker_key,
+            Some(WorkerKeyChange {
+                new_worker: account(CHARLIE),
+                effective_at,
+            })
+        );
+        // The control addresses are changed immediately
+        assert_eq!(sp.info.control_addresses.to_vec(), vec![account(BOB)]);
+        assert_eq!(
+            StorageProviderAccounts::<Test>::get(account(BOB)),
+            Some(account(ALICE))
+        );
+        assert_eq!(StorageProviderAccounts::<Test>::get(account(CHARLIE)), None);
+    });
+}
+
+/// Changing the worker to the current one cancels the pending change.
+#[test]
+fn change_worker_address_cancels_pending_change() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(CHARLIE),
+            bounded_vec![],
+        ));
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![],
+        ));
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.info.pending_worker_key, None);
+
+        run_to_block(System::block_number() + WorkerKeyChangeDelay::get());
+        assert_noop!(
+            StorageProvider::confirm_change_worker_address(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::NoPendingWorkerKeyChange
+        );
+    });
+}
+
+#[test]
+fn confirm_change_worker_address_fails_no_pending_change() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_noop!(
+            StorageProvider::confirm_change_worker_address(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::NoPendingWorkerKeyChange
+        );
+    });
+}
+
+#[test]
+fn confirm_change_worker_address_fails_not_effective() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(CHARLIE),
+            bounded_vec![],
+        ));
+        run_to_block(System::block_number() + WorkerKeyChangeDelay::get() - 1);
+
+        assert_noop!(
+            StorageProvider::confirm_change_worker_address(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::WorkerKeyChangeNotEffective
+        );
+    });
+}
+
+#[test]
+fn confirm_change_worker_address_success() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(CHARLIE),
+            bounded_vec![],
+        ));
+        run_to_block(System::block_number() + WorkerKeyChangeDelay::get());
+        System::reset_events();
+
+        assert_ok!(StorageProvider::confirm_change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::WorkerAddressChanged {
+                    owner: account(ALICE),
+                    worker: account(CHARLIE),
+                }
+            )]
+        );
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.info.worker, account(CHARLIE));
+        assert_eq!(sp.info.pending_worker_key, None);
+        assert_eq!(
+            StorageProviderAccounts::<Test>::get(account(CHARLIE)),
+            Some(account(ALICE))
+        );
+    });
+}
+
+/// The worker acts on behalf of the owner, while the previous worker can't anymore.
+#[test]
+fn worker_acts_for_owner() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        change_worker_to_charlie();
+
+        assert_ok!(StorageProvider::extend_sector_expiration(
+            RuntimeOrigin::signed(account(CHARLIE)),
+            no_extensions(),
+        ));
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::SectorsExpirationExtended {
+                    owner: account(ALICE),
+                    extensions: bounded_vec![],
+                }
+            )]
+        );
+
+        // Change the worker back to the owner
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![],
+        ));
+        run_to_block(System::block_number() + WorkerKeyChangeDelay::get());
+        assert_ok!(StorageProvider::confirm_change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+        ));
+
+        assert_eq!(StorageProviderAccounts::<Test>::get(account(CHARLIE)), None);
+        assert_noop!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                no_extensions(),
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+/// Control addresses can publish deals and pre-commit sectors on behalf of the owner.
+#[test]
+fn control_address_acts_for_owner() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![account(CHARLIE)],
+        ));
+
+        // Add balance to the market pallet
+        assert_ok!(Market::add_balance(
+            RuntimeOrigin::signed(account(ALICE)),
+            60
+        ));
+        assert_ok!(Market::add_balance(RuntimeOrigin::signed(account(BOB)), 70));
+
+        // The control address publishes the deal for the owner
+        let deal_proposal = DealProposalBuilder::default()
+            .client(BOB)
+            .provider(ALICE)
+            .signed(BOB);
+        assert_ok!(Market::publish_storage_deals(
+            RuntimeOrigin::signed(account(CHARLIE)),
+            bounded_vec![deal_proposal],
+        ));
+        System::reset_events();
+
+        // The control address pre-commits a sector for the owner
+        let sector = SectorPreCommitInfoBuilder::default().deals(vec![0]).build();
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(CHARLIE)),
+            bounded_vec![sector.clone()],
+        ));
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.pre_committed_sectors.len(), 1);
+        assert_eq!(
+            events().last(),
+            Some(&RuntimeEvent::StorageProvider(
+                Event::<Test>::SectorsPreCommitted {
+                    block: System::block_number(),
+                    owner: account(ALICE),
+                    sectors: bounded_vec![sector],
+                }
+            ))
+        );
+    });
+}
+
+/// Accounts acting for a storage provider can't register as storage providers.
+#[test]
+fn register_fails_worker_address() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        change_worker_to_charlie();
+
+        assert_noop!(
+            StorageProvider::register_storage_provider(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                bounded_vec![],
+                RegisteredPoStProof::StackedDRGWindow2KiBV1P1,
+            ),
+            Error::<Test>::AddressAlreadyInUse
+        );
+    });
+}
@@ -1,4 +1,25 @@
-use crate::deadline::DeadlineInfo;
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+
+use frame_support::{assert_noop, assert_ok, pallet_prelude::ConstU32};
+use primitives::{sector::SectorNumber, PartitionNumber, MAX_PARTITIONS_PER_DEADLINE};
+use sp_core::bounded_vec;
+use sp_runtime::BoundedBTreeSet;
+
+use crate::{
+    deadline::{CompactPartitionsParams, Deadline, DeadlineInfo, MovePartitionsParams},
+    error::GeneralPalletError,
+    pallet::{Error, Event, StorageProviders},
+    partition::Partition,
+    sector::{TerminateSectorsParams, TerminationDeclaration},
+    tests::{
+        account, declare_faults::setup_sp_with_many_sectors_multiple_partitions, events,
+        new_test_ext, run_to_block, sector_set, DeclareFaultsBuilder,
+        DeclareFaultsRecoveredBuilder, FaultMaxAge, RuntimeEvent, RuntimeOrigin, StorageProvider,
+        SubmitWindowedPoStBuilder, System, Test, WPoStChallengeWindow, ALICE, BOB,
+    },
+};
 
 fn default_deadline() -> DeadlineInfo<u64> {
     let block_number = 112;
@@ -59,3 +80,372 @@ fn calculates_next_deadline_when_its_2_proving_periods_behind() {
     assert_eq!(next.open_at, 220);
     assert_eq!(next.close_at, 240);
 }
+
+/// Sets up a storage provider with sectors in multiple partitions of every deadline
+/// and proves the partitions of the first deadline, so they don't have unproven sectors.
+///
+/// Deadline 0 after setup:
+///      Partition 0: sectors 0, 1
+///      Partition 1: sectors 20, 21
+///      Partition 2: sector 40
+///
+/// The current block is right after the first deadline closed.
+fn setup_proven_first_deadline() {
+    setup_sp_with_many_sectors_multiple_partitions(ALICE, BOB);
+    let proving_period_start = StorageProviders::<Test>::get(account(ALICE))
+        .unwrap()
+        .proving_period_start;
+    run_to_block(proving_period_start);
+
+    let windowed_post = SubmitWindowedPoStBuilder::default()
+        .partitions(bounded_vec![0, 1, 2])
+        .proof_bytes(vec![0x1, 0x2, 0x3])
+        .build();
+    assert_ok!(StorageProvider::submit_windowed_post(
+        RuntimeOrigin::signed(account(ALICE)),
+        windowed_post,
+    ));
+
+    // Run past the deadline, so it's mutable again
+    run_to_block(proving_period_start + WPoStChallengeWindow::get() + 1);
+    System::reset_events();
+}
+
+fn partition_set(
+    partitions: &[PartitionNumber],
+) -> BoundedBTreeSet<PartitionNumber, ConstU32<MAX_PARTITIONS_PER_DEADLINE>> {
+    BoundedBTreeSet::try_from(partitions.iter().copied().collect::<BTreeSet<_>>()).unwrap()
+}
+
+fn load_deadline(deadline_idx: usize) -> Deadline<u64> {
+    let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+    sp.deadlines.due[deadline_idx].clone()
+}
+
+/// Block at which the faulty sector expires early, if it's scheduled to.
+fn fault_expiration(partition: &Partition<u64>, sector_number: u32) -> Option<u64> {
+    let sector_number = SectorNumber::try_from(sector_number).unwrap();
+    partition
+        .expirations
+        .map
+        .iter()
+        .find(|(_, set)| set.early_sectors.contains(&sector_number))
+        .map(|(block, _)| *block)
+}
+
+fn terminate(deadline: u64, partition: u32, sectors: &[u32]) {
+    let params = TerminateSectorsParams {
+        terminations: bounded_vec![TerminationDeclaration {
+            deadline,
+            partition,
+            sectors: sector_set(sectors),
+        }],
+    };
+    assert_ok!(StorageProvider::terminate_sectors(
+        RuntimeOrigin::signed(account(ALICE)),
+        params
+    ));
+}
+
+#[test]
+fn compact_partitions_drops_dead_sectors() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+        terminate(0, 0, &[0]);
+        terminate(0, 1, &[20]);
+        System::reset_events();
+
+        let params = CompactPartitionsParams {
+            deadline: 0,
+            partitions: partition_set(&[0, 1]),
+        };
+        assert_ok!(StorageProvider::compact_partitions(
+            RuntimeOrigin::signed(account(ALICE)),
+            params.clone()
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(Event::PartitionsCompacted {
+                owner: account(ALICE),
+                deadline: 0,
+                partitions: params.partitions,
+            })]
+        );
+
+        // The untouched partition is renumbered and filled with the live sectors.
+        let deadline = load_deadline(0);
+        assert_eq!(deadline.partitions.len(), 2);
+        assert_eq!(deadline.partitions[&0].sectors, sector_set(&[1, 40]));
+        assert_eq!(deadline.partitions[&1].sectors, sector_set(&[21]));
+        assert_eq!(deadline.live_sectors, 3);
+        assert_eq!(deadline.total_sectors, 3);
+        for partition in deadline.partitions.values() {
+            // The sectors were already proven.
+            assert!(partition.unproven.is_empty());
+            assert!(partition.terminated.is_empty());
+        }
+        assert!(deadline
+            .expirations_blocks
+            .values()
+            .flatten()
+            .all(|p| *p < 2));
+    });
+}
+
+/// All the sectors expire at the same block, every compacted partition stays scheduled at it.
+#[test]
+fn compact_partitions_schedules_partitions_expiring_at_the_same_block() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+        terminate(0, 0, &[0]);
+        let expiration = StorageProviders::<Test>::get(account(ALICE))
+            .unwrap()
+            .sectors[&SectorNumber::from(1)]
+            .expiration;
+
+        assert_ok!(StorageProvider::compact_partitions(
+            RuntimeOrigin::signed(account(ALICE)),
+            CompactPartitionsParams {
+                deadline: 0,
+                partitions: partition_set(&[0]),
+            }
+        ));
+
+        // Partition 1 is renumbered to 0, the compacted sectors fill it and the next one.
+        let deadline = load_deadline(0);
+        assert_eq!(deadline.partitions.len(), 2);
+        assert_eq!(deadline.partitions[&0].sectors, sector_set(&[20, 21]));
+        assert_eq!(deadline.partitions[&1].sectors, sector_set(&[1, 40]));
+        assert_eq!(deadline.expirations_blocks.len(), 1);
+        assert_eq!(
+            deadline.expirations_blocks[&expiration],
+            partition_set(&[0, 1])
+        );
+    });
+}
+
+#[test]
+fn compact_partitions_keeps_faults_and_recoveries() {
+    new_test_ext().execute_with(|| {
+        // The faulty sectors expire early, before their on-time expiration
+        FaultMaxAge::set(10);
+        setup_proven_first_deadline();
+        let faults = DeclareFaultsBuilder::default()
+            .fault(0, 1, &[21.into()])
+            .build();
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            faults,
+        ));
+        let recoveries = DeclareFaultsRecoveredBuilder::default()
+            .fault_recovery(0, 1, &[21.into()])
+            .build();
+        assert_ok!(StorageProvider::declare_faults_recovered(
+            RuntimeOrigin::signed(account(ALICE)),
+            recoveries,
+        ));
+        terminate(0, 0, &[0]);
+        terminate(0, 1, &[20]);
+        let expected_fault_expiration = fault_expiration(&load_deadline(0).partitions[&1], 21);
+        assert!(expected_fault_expiration.is_some());
+
+        assert_ok!(StorageProvider::compact_partitions(
+            RuntimeOrigin::signed(account(ALICE)),
+            CompactPartitionsParams {
+                deadline: 0,
+                partitions: partition_set(&[0, 1]),
+            }
+        ));
+
+        let deadline = load_deadline(0);
+        let partition = &deadline.partitions[&1];
+        assert_eq!(partition.sectors, sector_set(&[21]));
+        assert_eq!(partition.faults, sector_set(&[21]));
+        assert_eq!(partition.recoveries, sector_set(&[21]));
+        assert_eq!(fault_expiration(partition, 21), expected_fault_expiration);
+        assert!(deadline.partitions[&0].faults.is_empty());
+    });
+}
+
+#[test]
+fn compact_partitions_fails_deadline_not_mutable() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+        // Deadline 1 opened right after deadline 0 closed
+
+        assert_noop!(
+            StorageProvider::compact_partitions(
+                RuntimeOrigin::signed(account(ALICE)),
+                CompactPartitionsParams {
+                    deadline: 1,
+                    partitions: partition_set(&[0]),
+                }
+            ),
+            Error::<Test>::CannotCompactImmutableDeadline
+        );
+    });
+}
+
+#[test]
+fn compact_partitions_fails_unproven_sectors() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_many_sectors_multiple_partitions(ALICE, BOB);
+
+        assert_noop!(
+            StorageProvider::compact_partitions(
+                RuntimeOrigin::signed(account(ALICE)),
+                CompactPartitionsParams {
+                    deadline: 0,
+                    partitions: partition_set(&[0]),
+                }
+            ),
+            Error::<Test>::GeneralPalletError(
+                GeneralPalletError::DeadlineErrorPartitionHasUnprovenSectors
+            )
+        );
+    });
+}
+
+#[test]
+fn move_partitions_carries_faults_and_recoveries() {
+    new_test_ext().execute_with(|| {
+        // The faulty sectors expire early, before their on-time expiration
+        FaultMaxAge::set(10);
+        setup_proven_first_deadline();
+        let faults = DeclareFaultsBuilder::default()
+            .fault(0, 1, &[20.into(), 21.into()])
+            .build();
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            faults,
+        ));
+        let recoveries = DeclareFaultsRecoveredBuilder::default()
+            .fault_recovery(0, 1, &[21.into()])
+            .build();
+        assert_ok!(StorageProvider::declare_faults_recovered(
+            RuntimeOrigin::signed(account(ALICE)),
+            recoveries,
+        ));
+        System::reset_events();
+        let moved_partition = load_deadline(0).partitions[&1].clone();
+        let fault_expiration = fault_expiration(&moved_partition, 20).unwrap();
+
+        // Deadline 3 is due before deadline 0 is due again
+        let params = MovePartitionsParams {
+            orig_deadline: 0,
+            dest_deadline: 3,
+            partitions: partition_set(&[1]),
+        };
+        assert_ok!(StorageProvider::move_partitions(
+            RuntimeOrigin::signed(account(ALICE)),
+            params.clone()
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(Event::PartitionsMoved {
+                owner: account(ALICE),
+                orig_deadline: 0,
+                dest_deadline: 3,
+                partitions: params.partitions,
+            })]
+        );
+
+        // The last partition of the original deadline is renumbered.
+        let orig_deadline = load_deadline(0);
+        assert_eq!(orig_deadline.partitions.len(), 2);
+        assert_eq!(orig_deadline.partitions[&0].sectors, sector_set(&[0, 1]));
+        assert_eq!(orig_deadline.partitions[&1].sectors, sector_set(&[40]));
+        assert_eq!(orig_deadline.live_sectors, 3);
+
+        // The partition is added after the partitions of the destination deadline, as it was.
+        let dest_deadline = load_deadline(3);
+        assert_eq!(dest_deadline.partitions.len(), 3);
+        assert_eq!(dest_deadline.partitions[&2], moved_partition);
+        assert_eq!(dest_deadline.partitions[&2].faults, sector_set(&[20, 21]));
+        assert_eq!(dest_deadline.partitions[&2].recoveries, sector_set(&[21]));
+        assert!(dest_deadline.expirations_blocks[&fault_expiration].contains(&2));
+        assert_eq!(dest_deadline.live_sectors, 6);
+    });
+}
+
+/// All the sectors expire at the same block, the moved partition is scheduled alongside
+/// the destination's partitions, and the remaining partitions of the original deadline stay scheduled.
+#[test]
+fn move_partitions_schedules_partitions_expiring_at_the_same_block() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+        let expiration = StorageProviders::<Test>::get(account(ALICE))
+            .unwrap()
+            .sectors[&SectorNumber::from(0)]
+            .expiration;
+        assert_eq!(
+            load_deadline(3).expirations_blocks[&expiration],
+            partition_set(&[0, 1])
+        );
+
+        assert_ok!(StorageProvider::move_partitions(
+            RuntimeOrigin::signed(account(ALICE)),
+            MovePartitionsParams {
+                orig_deadline: 0,
+                dest_deadline: 3,
+                partitions: partition_set(&[1]),
+            }
+        ));
+
+        assert_eq!(
+            load_deadline(0).expirations_blocks[&expiration],
+            partition_set(&[0, 1])
+        );
+        assert_eq!(
+            load_deadline(3).expirations_blocks[&expiration],
+            partition_set(&[0, 1, 2])
+        );
+    });
+}
+
+#[test]
+fn move_partitions_fails_destination_due_later() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+
+        // Deadline 3 is due before deadline 0 is due again,
+        // moving the other way the sectors would skip a proof.
+        for (orig_deadline, dest_deadline) in [(3, 0), (0, 0)] {
+            assert_noop!(
+                StorageProvider::move_partitions(
+                    RuntimeOrigin::signed(account(ALICE)),
+                    MovePartitionsParams {
+                        orig_deadline,
+                        dest_deadline,
+                        partitions: partition_set(&[0]),
+                    }
+                ),
+                Error::<Test>::InvalidDestinationDeadline
+            );
+        }
+    });
+}
+
+#[test]
+fn move_partitions_fails_deadline_not_mutable() {
+    new_test_ext().execute_with(|| {
+        setup_proven_first_deadline();
+
+        // Deadline 1 is open and deadline 2 opens within a challenge window
+        for dest_deadline in [1, 2] {
+            assert_noop!(
+                StorageProvider::move_partitions(
+                    RuntimeOrigin::signed(account(ALICE)),
+                    MovePartitionsParams {
+                        orig_deadline: 0,
+                        dest_deadline,
+                        partitions: partition_set(&[0]),
+                    }
+                ),
+                Error::<Test>::CannotMoveImmutableDeadline
+            );
+        }
+    });
+}
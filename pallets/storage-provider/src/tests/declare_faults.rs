@@ -122,12 +122,11 @@ fn declare_single_fault_before_proving_period_start() {
                 .iter()
                 .filter(|(_, partition)| partition.faults.len() == 1)
             {
-                assert_eq!(
-                    dl.expirations_blocks
-                        .get(&(test_dl.last() + <Test as Config>::FaultMaxAge::get()))
-                        .expect("should exist"),
-                    partition_number
-                );
+                assert!(dl
+                    .expirations_blocks
+                    .get(&(test_dl.last() + <Test as Config>::FaultMaxAge::get()))
+                    .expect("should exist")
+                    .contains(partition_number));
             }
         }
     });
@@ -196,12 +195,11 @@ fn declare_single_fault_from_proving_period(#[case] proving_period_multiple: f64
                 .iter()
                 .filter(|(_, partition)| partition.faults.len() == 1)
             {
-                assert_eq!(
-                    dl.expirations_blocks
-                        .get(&(test_dl.last() + <Test as Config>::FaultMaxAge::get()))
-                        .expect("should exist"),
-                    partition_number
-                );
+                assert!(dl
+                    .expirations_blocks
+                    .get(&(test_dl.last() + <Test as Config>::FaultMaxAge::get()))
+                    .expect("should exist")
+                    .contains(partition_number));
             }
         }
     });
@@ -0,0 +1,140 @@
+use frame_support::{assert_noop, assert_ok};
+use sp_core::bounded_vec;
+use sp_runtime::DispatchError;
+
+use crate::{
+    pallet::{
+        DeadlineChecks, Error, Event, PreCommitExpirations, StorageProviderAccounts,
+        StorageProviders,
+    },
+    sector::{TerminateSectorsParams, TerminationDeclaration},
+    tests::{
+        account, declare_faults::setup_sp_with_one_sector, events, new_test_ext,
+        register_storage_provider, sector_set, Balances, RuntimeEvent, RuntimeOrigin,
+        SectorPreCommitInfoBuilder, StorageProvider, System, Test, ALICE, BOB, CHARLIE,
+    },
+};
+
+#[test]
+fn successfully_deregister() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![account(CHARLIE)],
+        ));
+        System::reset_events();
+
+        assert_ok!(StorageProvider::deregister_storage_provider(
+            RuntimeOrigin::signed(account(ALICE))
+        ));
+
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::StorageProviderDeregistered {
+                    owner: account(ALICE),
+                    released: 0,
+                }
+            )]
+        );
+        assert!(!StorageProviders::<Test>::contains_key(account(ALICE)));
+        assert!(!DeadlineChecks::<Test>::iter_keys().any(|(_, sp)| sp == account(ALICE)));
+        // The control address is available again
+        assert!(!StorageProviderAccounts::<Test>::contains_key(account(
+            CHARLIE
+        )));
+        register_storage_provider(account(CHARLIE));
+    });
+}
+
+#[test]
+fn successfully_deregister_after_terminating_sectors() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let reserved_before = Balances::reserved_balance(account(ALICE));
+
+        assert_ok!(StorageProvider::terminate_sectors(
+            RuntimeOrigin::signed(account(ALICE)),
+            TerminateSectorsParams {
+                terminations: bounded_vec![TerminationDeclaration {
+                    deadline: 0,
+                    partition: 0,
+                    sectors: sector_set(&[0])
+                }],
+            }
+        ));
+        assert_ok!(StorageProvider::deregister_storage_provider(
+            RuntimeOrigin::signed(account(ALICE))
+        ));
+
+        assert!(!StorageProviders::<Test>::contains_key(account(ALICE)));
+        // The initial pledge of the terminated sector was released
+        assert!(Balances::reserved_balance(account(ALICE)) < reserved_before);
+        // Nothing is left scheduled for a later registration
+        assert!(!DeadlineChecks::<Test>::iter_keys().any(|(_, sp)| sp == account(ALICE)));
+        assert!(!PreCommitExpirations::<Test>::iter_keys().any(|(_, sp)| sp == account(ALICE)));
+    });
+}
+
+#[test]
+fn fails_not_registered() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            StorageProvider::deregister_storage_provider(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::StorageProviderNotFound,
+        );
+    });
+}
+
+#[test]
+fn fails_not_owner() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(StorageProvider::change_worker_address(
+            RuntimeOrigin::signed(account(ALICE)),
+            account(ALICE),
+            bounded_vec![account(CHARLIE)],
+        ));
+
+        assert_noop!(
+            StorageProvider::deregister_storage_provider(RuntimeOrigin::signed(account(CHARLIE))),
+            Error::<Test>::StorageProviderNotFound,
+        );
+        assert_noop!(
+            StorageProvider::deregister_storage_provider(RuntimeOrigin::none()),
+            DispatchError::BadOrigin,
+        );
+    });
+}
+
+#[test]
+fn fails_with_pre_committed_sectors() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(ALICE)),
+            bounded_vec![SectorPreCommitInfoBuilder::default()
+                .committed_capacity()
+                .build()]
+        ));
+
+        assert_noop!(
+            StorageProvider::deregister_storage_provider(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::StorageProviderHasSectors,
+        );
+    });
+}
+
+#[test]
+fn fails_with_live_sectors() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+
+        assert_noop!(
+            StorageProvider::deregister_storage_provider(RuntimeOrigin::signed(account(ALICE))),
+            Error::<Test>::StorageProviderHasSectors,
+        );
+    });
+}
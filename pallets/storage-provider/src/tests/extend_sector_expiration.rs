@@ -0,0 +1,268 @@
+extern crate alloc;
+
+use frame_support::{assert_err, assert_ok};
+use primitives::{sector::SectorNumber, MAX_SECTORS};
+use sp_core::bounded_vec;
+
+use crate::{
+    error::GeneralPalletError,
+    pallet::{Error, Event, StorageProviders},
+    sector::{ExpirationExtension, ExtendSectorExpirationParams, ProveCommitSector},
+    tests::{
+        account, events, new_test_ext, register_storage_provider, run_to_block, sector_set,
+        DealProposalBuilder, Market, RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder,
+        StorageProvider, SubmitWindowedPoStBuilder, System, Test, ALICE, BOB,
+    },
+};
+
+/// Expiration of the sector used in the tests.
+///
+/// It is lower than the default one, so the sector can be extended
+/// without exceeding the `SectorMaximumLifetime`.
+const SECTOR_EXPIRATION: u64 = 110;
+
+/// Setup the environment for the extend_sector_expiration tests.
+///
+/// 1. Registers Alice as a storage provider
+/// 2. Adds balances
+///     1. 60 for the storage provider
+///     2. 70 for the storage client
+/// 3. Publishes one storage deal
+/// 4. Pre-commits and proves sector 1, expiring at `SECTOR_EXPIRATION`
+fn setup() {
+    // Setup accounts
+    let storage_provider = ALICE;
+    let storage_client = BOB;
+
+    // Register storage provider
+    register_storage_provider(account(storage_provider));
+
+    // Add balance to the market pallet
+    assert_ok!(Market::add_balance(
+        RuntimeOrigin::signed(account(storage_provider)),
+        60
+    ));
+    assert_ok!(Market::add_balance(
+        RuntimeOrigin::signed(account(storage_client)),
+        70
+    ));
+
+    // Generate a deal proposal
+    let deal_proposal = DealProposalBuilder::default()
+        .client(storage_client)
+        .provider(storage_provider)
+        .signed(storage_client);
+
+    // Publish the deal proposal
+    assert_ok!(Market::publish_storage_deals(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![deal_proposal],
+    ));
+
+    // Sector to be pre-committed and proven
+    let sector_number = 1.into();
+
+    // Sector data
+    let sector = SectorPreCommitInfoBuilder::default()
+        .sector_number(sector_number)
+        .deals(vec![0])
+        .expiration(SECTOR_EXPIRATION)
+        .build();
+
+    // Run pre commit extrinsic
+    assert_ok!(StorageProvider::pre_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![sector.clone()]
+    ));
+
+    // Prove commit sector
+    let sector = ProveCommitSector {
+        sector_number,
+        proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+    };
+
+    assert_ok!(StorageProvider::prove_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![sector]
+    ));
+
+    // Remove any events that were triggered until now.
+    System::reset_events();
+}
+
+/// Submits the PoSt for the sector added in [`setup`], activating it,
+/// and runs until the deadline is mutable again.
+fn prove_sector() {
+    let proving_period_start = StorageProviders::<Test>::get(account(ALICE))
+        .unwrap()
+        .proving_period_start;
+    run_to_block(proving_period_start);
+
+    let windowed_post = SubmitWindowedPoStBuilder::default().partition(0).build();
+    assert_ok!(StorageProvider::submit_windowed_post(
+        RuntimeOrigin::signed(account(ALICE)),
+        windowed_post,
+    ));
+
+    // Run past the deadline's challenge window
+    run_to_block(proving_period_start + 5);
+
+    // Remove any events that were triggered until now.
+    System::reset_events();
+}
+
+/// Builds the params extending the given sectors in deadline 0, partition 0.
+fn extension(sectors: &[u32], new_expiration: u64) -> ExtendSectorExpirationParams<u64> {
+    ExtendSectorExpirationParams {
+        extensions: bounded_vec![ExpirationExtension {
+            deadline: 0,
+            partition: 0,
+            sectors: sector_set(sectors),
+            new_expiration,
+        }],
+    }
+}
+
+#[test]
+fn extend_sector_expiration_fails_sp_not_found() {
+    new_test_ext().execute_with(|| {
+        // Purposely run extrinsic without registration.
+        let params = ExtendSectorExpirationParams {
+            extensions: bounded_vec![],
+        };
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                params
+            ),
+            Error::<Test>::StorageProviderNotFound
+        );
+    });
+}
+
+/// Unproven sectors cannot be extended.
+#[test]
+fn extend_sector_expiration_fails_unproven_sector() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                extension(&[1], SECTOR_EXPIRATION + 5)
+            ),
+            Error::<Test>::GeneralPalletError(GeneralPalletError::PartitionErrorSectorsNotActive)
+        );
+    });
+}
+
+/// Tries to extend a sector in a deadline that is not mutable.
+#[test]
+fn extend_sector_expiration_fails_deadline_not_mutable() {
+    new_test_ext().execute_with(|| {
+        setup();
+        // Run to block where the deadline is not mutable
+        // Deadline is immutable after open - challenge window
+        let proving_period_start = StorageProviders::<Test>::get(account(ALICE))
+            .unwrap()
+            .proving_period_start;
+        run_to_block(proving_period_start - 2);
+
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                extension(&[1], SECTOR_EXPIRATION + 5)
+            ),
+            Error::<Test>::CannotExtendImmutableDeadline
+        );
+    });
+}
+
+#[test]
+fn extend_sector_expiration_fails_sector_not_found() {
+    new_test_ext().execute_with(|| {
+        setup();
+        prove_sector();
+
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                extension(&[2], SECTOR_EXPIRATION + 5)
+            ),
+            Error::<Test>::InvalidSector
+        );
+    });
+}
+
+#[test]
+fn extend_sector_expiration_fails_reduced_expiration() {
+    new_test_ext().execute_with(|| {
+        setup();
+        prove_sector();
+
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                extension(&[1], SECTOR_EXPIRATION - 5)
+            ),
+            Error::<Test>::CannotReduceSectorExpiration
+        );
+    });
+}
+
+/// The sector lifetime can't go over `SectorMaximumLifetime`, even when extended.
+#[test]
+fn extend_sector_expiration_fails_lifetime_exceeded() {
+    new_test_ext().execute_with(|| {
+        setup();
+        prove_sector();
+
+        assert_err!(
+            StorageProvider::extend_sector_expiration(
+                RuntimeOrigin::signed(account(ALICE)),
+                extension(&[1], 200)
+            ),
+            Error::<Test>::MaxSectorLifetimeExceeded
+        );
+    });
+}
+
+#[test]
+fn extend_sector_expiration_success() {
+    new_test_ext().execute_with(|| {
+        setup();
+        prove_sector();
+
+        let new_expiration = SECTOR_EXPIRATION + 5;
+        let params = extension(&[1], new_expiration);
+        assert_ok!(StorageProvider::extend_sector_expiration(
+            RuntimeOrigin::signed(account(ALICE)),
+            params.clone()
+        ));
+
+        // Check that the expected event is emitted
+        assert_eq!(
+            events(),
+            [RuntimeEvent::StorageProvider(
+                Event::SectorsExpirationExtended {
+                    owner: account(ALICE),
+                    extensions: params.extensions
+                }
+            )]
+        );
+
+        let mut sp = StorageProviders::<Test>::get(account(ALICE))
+            .expect("Should be able to get providers info");
+        let sector_number = SectorNumber::from(1);
+        assert_eq!(sp.sectors[&sector_number].expiration, new_expiration);
+
+        let deadline = sp.get_deadlines_mut().load_deadline_mut(0).unwrap();
+        assert!(deadline.expirations_blocks[&new_expiration].contains(&0));
+        let partition = &deadline.partitions[&0];
+        assert!(partition.expirations.map.get(&SECTOR_EXPIRATION).is_none());
+        assert_eq!(
+            partition.expirations.map[&new_expiration].on_time_sectors,
+            sector_set::<MAX_SECTORS>(&[1])
+        );
+    });
+}
@@ -0,0 +1,147 @@
+use frame_support::{assert_ok, pallet_prelude::Get};
+use primitives::sector::SectorNumber;
+
+use crate::{
+    pallet::{Config, Event, StorageProviders},
+    tests::{
+        account, declare_faults::setup_sp_with_one_sector, events, new_test_ext, run_to_block,
+        Balances, DeclareFaultsBuilder, RuntimeEvent, RuntimeOrigin, StorageProvider, System, Test,
+        ALICE, BOB,
+    },
+};
+
+/// Continued fault fee of a 2KiB sector, while the network power is below the baseline.
+const CONTINUED_FAULT_FEE: u64 = 1;
+
+/// Undeclared fault penalty of a 2KiB sector, while the network power is below the baseline.
+const UNDECLARED_FAULT_PENALTY: u64 = 2;
+
+/// Initial pledge of a 2KiB sector, while the network power is below the baseline.
+const INITIAL_PLEDGE: u64 = 2;
+
+/// Runs until the deadline 0 elapses in the proving period starting at the given block.
+fn run_past_deadline(proving_period_start: u64) {
+    let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+    run_to_block(proving_period_start + challenge_window + 1);
+}
+
+/// Returns the [`Event::FaultsPenalized`] events emitted so far.
+fn penalized_events() -> Vec<RuntimeEvent> {
+    events()
+        .into_iter()
+        .filter(|event| {
+            matches!(
+                event,
+                RuntimeEvent::StorageProvider(Event::<Test>::FaultsPenalized { .. })
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn declared_faults_pay_continued_fee() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsBuilder::default()
+                .fault(0, 0, &[SectorNumber::from(0)])
+                .build(),
+        ));
+        System::reset_events();
+
+        run_past_deadline(sp.proving_period_start);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE - CONTINUED_FAULT_FEE);
+        assert_eq!(
+            Balances::reserved_balance(account(ALICE)),
+            INITIAL_PLEDGE - CONTINUED_FAULT_FEE
+        );
+        assert_eq!(
+            penalized_events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::FaultsPenalized {
+                    owner: account(ALICE),
+                    continued_fault_fee: CONTINUED_FAULT_FEE,
+                    undeclared_fault_penalty: 0,
+                }
+            )]
+        );
+    });
+}
+
+/// The sector is charged again for every proving period it stays faulty.
+#[test]
+fn continued_fee_is_charged_every_proving_period() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsBuilder::default()
+                .fault(0, 0, &[SectorNumber::from(0)])
+                .build(),
+        ));
+        System::reset_events();
+
+        run_past_deadline(sp.proving_period_start);
+        run_past_deadline(sp.proving_period_start + proving_period);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE - 2 * CONTINUED_FAULT_FEE);
+        assert_eq!(penalized_events().len(), 2);
+    });
+}
+
+/// Faults detected without a prior declaration pay the larger penalty.
+#[test]
+fn undeclared_faults_pay_penalty() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+
+        run_past_deadline(sp.proving_period_start);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE - UNDECLARED_FAULT_PENALTY);
+        assert_eq!(
+            Balances::reserved_balance(account(ALICE)),
+            INITIAL_PLEDGE - UNDECLARED_FAULT_PENALTY
+        );
+        assert_eq!(
+            penalized_events(),
+            [RuntimeEvent::StorageProvider(
+                Event::<Test>::FaultsPenalized {
+                    owner: account(ALICE),
+                    continued_fault_fee: 0,
+                    undeclared_fault_penalty: UNDECLARED_FAULT_PENALTY,
+                }
+            )]
+        );
+    });
+}
+
+/// Only the locked funds are used to pay for the faults,
+/// nothing is charged once the initial pledge is used up.
+#[test]
+fn fees_are_capped_by_initial_pledge() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+        let free_balance = Balances::free_balance(account(ALICE));
+
+        run_past_deadline(sp.proving_period_start);
+        System::reset_events();
+        run_past_deadline(sp.proving_period_start + proving_period);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(Balances::reserved_balance(account(ALICE)), 0);
+        assert_eq!(Balances::free_balance(account(ALICE)), free_balance);
+        assert_eq!(penalized_events(), []);
+    });
+}
@@ -0,0 +1,174 @@
+use frame_support::{assert_ok, pallet_prelude::Get};
+use primitives::sector::SectorNumber;
+
+use crate::{
+    pallet::{Config, StorageProviderPower, StorageProviders},
+    tests::{
+        account,
+        declare_faults::{
+            setup_sp_with_many_sectors_multiple_partitions, setup_sp_with_one_sector,
+        },
+        events, new_test_ext, run_to_block, DeclareFaultsRecoveredBuilder, FaultMaxAge,
+        RuntimeEvent, RuntimeOrigin, StorageProvider, SubmitWindowedPoStBuilder, System, Test,
+        ALICE, BOB,
+    },
+};
+
+/// Sets up Alice with sector 0, the sector is assigned to deadline 0, partition 0 and expires at block 120.
+/// Doesn't submit the first PoSt, so the sector is detected as faulty once the deadline elapses.
+///
+/// Returns the start of the next proving period.
+fn setup_faulty_sector() -> u64 {
+    setup_sp_with_one_sector(ALICE, BOB);
+    let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+    let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+    let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+
+    run_to_block(sp.proving_period_start + challenge_window + 1);
+    let faulty_sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+    let partition = &faulty_sp.deadlines.due[0].partitions[&0];
+    assert!(partition.faults.contains(&SectorNumber::from(0)));
+    System::reset_events();
+
+    sp.proving_period_start + proving_period
+}
+
+/// Runs until the deadline 0 elapses in the proving period starting at the given block.
+fn run_past_deadline(proving_period_start: u64) {
+    let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+    run_to_block(proving_period_start + challenge_window + 1);
+}
+
+/// The sector misses two consecutive PoSts, by the time its deadline is processed again,
+/// it's been faulty for `FaultMaxAge` and is terminated along with its deal.
+#[test]
+fn terminates_sectors_faulty_for_too_long() {
+    new_test_ext().execute_with(|| {
+        FaultMaxAge::set(<<Test as Config>::WPoStProvingPeriod as Get<u64>>::get());
+        let next_proving_period_start = setup_faulty_sector();
+
+        run_past_deadline(next_proving_period_start);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let partition = &sp.deadlines.due[0].partitions[&0];
+        assert!(partition.terminated.contains(&SectorNumber::from(0)));
+        assert!(partition.faults.is_empty());
+        assert!(partition.early_terminations.is_empty());
+        assert!(sp.early_terminations.is_empty());
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)).raw_byte_power,
+            0
+        );
+
+        // The deal was terminated, refunding the client
+        assert!(pallet_market::Proposals::<Test>::get(0).is_none());
+        assert!(events().contains(&RuntimeEvent::Market(
+            pallet_market::Event::DealTerminated {
+                deal_id: 0,
+                client: account(BOB),
+                provider: account(ALICE),
+            }
+        )));
+    });
+}
+
+/// All the partitions of the deadline become faulty at the same time, so their sectors expire at the same block.
+/// Every partition is terminated, not just the last one scheduled.
+#[test]
+fn terminates_all_partitions_faulty_for_too_long() {
+    new_test_ext().execute_with(|| {
+        FaultMaxAge::set(<<Test as Config>::WPoStProvingPeriod as Get<u64>>::get());
+        setup_sp_with_many_sectors_multiple_partitions(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+
+        run_past_deadline(sp.proving_period_start);
+        let faulty_sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let deadline = &faulty_sp.deadlines.due[0];
+        assert_eq!(deadline.partitions.len(), 3);
+        for partition in deadline.partitions.values() {
+            assert_eq!(partition.faults, partition.sectors);
+        }
+        System::reset_events();
+
+        run_past_deadline(sp.proving_period_start + proving_period);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let deadline = &sp.deadlines.due[0];
+        for partition in deadline.partitions.values() {
+            assert_eq!(partition.terminated, partition.sectors);
+            assert!(partition.faults.is_empty());
+        }
+        assert_eq!(deadline.live_sectors, 0);
+
+        // The deals of every partition were terminated
+        for deal_id in [0, 1, 20, 21, 40] {
+            assert!(pallet_market::Proposals::<Test>::get(deal_id).is_none());
+            assert!(events().contains(&RuntimeEvent::Market(
+                pallet_market::Event::DealTerminated {
+                    deal_id,
+                    client: account(BOB),
+                    provider: account(ALICE),
+                }
+            )));
+        }
+    });
+}
+
+/// The sector is faulty at the end of its deadline, but it hasn't been faulty for `FaultMaxAge` yet.
+#[test]
+fn does_not_terminate_sectors_before_fault_max_age() {
+    new_test_ext().execute_with(|| {
+        FaultMaxAge::set(2 * <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get());
+        let next_proving_period_start = setup_faulty_sector();
+
+        run_past_deadline(next_proving_period_start);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let partition = &sp.deadlines.due[0].partitions[&0];
+        assert!(partition.faults.contains(&SectorNumber::from(0)));
+        assert!(partition.terminated.is_empty());
+        assert!(pallet_market::Proposals::<Test>::get(0).is_some());
+        assert!(!events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::Market(pallet_market::Event::DealTerminated { .. })
+        )));
+    });
+}
+
+/// Recovering the sector before `FaultMaxAge` reschedules it to its on-time expiration.
+#[test]
+fn does_not_terminate_recovered_sectors() {
+    new_test_ext().execute_with(|| {
+        FaultMaxAge::set(<<Test as Config>::WPoStProvingPeriod as Get<u64>>::get());
+        let next_proving_period_start = setup_faulty_sector();
+        let fault_declaration_cutoff =
+            <<Test as Config>::FaultDeclarationCutoff as Get<u64>>::get();
+
+        run_to_block(next_proving_period_start - fault_declaration_cutoff - 1);
+        assert_ok!(StorageProvider::declare_faults_recovered(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsRecoveredBuilder::default()
+                .fault_recovery(0, 0, &[SectorNumber::from(0)])
+                .build(),
+        ));
+        run_to_block(next_proving_period_start);
+        assert_ok!(StorageProvider::submit_windowed_post(
+            RuntimeOrigin::signed(account(ALICE)),
+            SubmitWindowedPoStBuilder::default().partition(0).build(),
+        ));
+
+        run_past_deadline(next_proving_period_start);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let partition = &sp.deadlines.due[0].partitions[&0];
+        assert!(partition.faults.is_empty());
+        assert!(partition.terminated.is_empty());
+        assert!(pallet_market::Proposals::<Test>::get(0).is_some());
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)).raw_byte_power,
+            2048
+        );
+    });
+}
@@ -0,0 +1,274 @@
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use codec::Encode;
+use frame_support::{
+    assert_ok,
+    storage::unhashed,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_core::bounded_vec;
+
+use crate::{
+    migrations::{
+        v1::{self, v0, MigrateV0ToV1},
+        v2::{self, MigrateV1ToV2},
+        v3::{self, MigrateV2ToV3},
+        v4::{self, MigrateV3ToV4},
+        v5::MigrateV4ToV5,
+        Tail,
+    },
+    pallet::{DeadlineChecks, PreCommitExpirations, StorageProviders},
+    tests::{
+        account, declare_faults::setup_sp_with_many_sectors_multiple_partitions, new_test_ext,
+        register_storage_provider, DealProposalBuilder, Market, MaxProveCommitDuration,
+        RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider, System, Test, ALICE, BOB,
+    },
+};
+
+/// Stores Alice's state as it was encoded before the expiration queues held every partition,
+/// only the last partition scheduled at each block is kept.
+fn put_v0_state() {
+    let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+    let deadlines = sp
+        .deadlines
+        .due
+        .into_iter()
+        .map(|deadline| v0::Deadline {
+            partitions: deadline.partitions,
+            expirations_blocks: deadline
+                .expirations_blocks
+                .into_iter()
+                .map(|(block, partitions)| (block, partitions.into_iter().last().unwrap()))
+                .collect::<BTreeMap<_, _>>()
+                .try_into()
+                .unwrap(),
+            partitions_posted: deadline.partitions_posted,
+            early_terminations: deadline.early_terminations,
+            live_sectors: deadline.live_sectors,
+            total_sectors: deadline.total_sectors,
+        })
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let old = v0::StorageProviderState {
+        info: v1::StorageProviderInfo {
+            peer_id: sp.info.peer_id,
+            window_post_proof_type: sp.info.window_post_proof_type,
+            sector_size: sp.info.sector_size,
+            window_post_partition_sectors: sp.info.window_post_partition_sectors,
+        },
+        sectors: sp.sectors,
+        pre_commit_deposits: sp.pre_commit_deposits,
+        pre_committed_sectors: sp.pre_committed_sectors,
+        proving_period_start: sp.proving_period_start,
+        current_deadline: sp.current_deadline,
+        deadlines,
+        early_terminations: Tail(sp.early_terminations.encode()),
+    };
+
+    unhashed::put(
+        &StorageProviders::<Test>::hashed_key_for(account(ALICE)),
+        &old,
+    );
+}
+
+#[test]
+fn migrates_expiration_queues_to_v1() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_many_sectors_multiple_partitions(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        put_v0_state();
+        StorageVersion::new(0).put::<StorageProvider>();
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageProvider::on_chain_storage_version(), 1);
+        let migrated = v1::StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        // The partitions sharing an expiration block are all scheduled again
+        assert_eq!(
+            migrated.deadlines[0]
+                .expirations_blocks
+                .values()
+                .map(|partitions| partitions.len())
+                .max(),
+            Some(3)
+        );
+        assert_eq!(migrated.deadlines.encode(), sp.deadlines.encode());
+        assert_eq!(
+            migrated.early_terminations.0,
+            sp.early_terminations.encode()
+        );
+    });
+}
+
+#[test]
+fn migrates_accounts_to_v2() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        // Everything after the info is kept as it is
+        let rest = sp.encode()[sp.info.encode().len()..].to_vec();
+        let old = v2::StorageProviderState {
+            info: v1::StorageProviderInfo {
+                peer_id: sp.info.peer_id.clone(),
+                window_post_proof_type: sp.info.window_post_proof_type,
+                sector_size: sp.info.sector_size,
+                window_post_partition_sectors: sp.info.window_post_partition_sectors,
+            },
+            rest: Tail(rest.clone()),
+        };
+        unhashed::put(
+            &StorageProviders::<Test>::hashed_key_for(account(ALICE)),
+            &old,
+        );
+        StorageVersion::new(1).put::<StorageProvider>();
+
+        MigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageProvider::on_chain_storage_version(), 2);
+        let migrated = v2::StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(migrated.info.owner, account(ALICE));
+        assert_eq!(migrated.info.worker, account(ALICE));
+        assert!(migrated.info.control_addresses.is_empty());
+        assert!(migrated.info.pending_worker_key.is_none());
+        assert_eq!(migrated.info.peer_id, sp.info.peer_id);
+        assert_eq!(
+            migrated.info.window_post_proof_type,
+            sp.info.window_post_proof_type
+        );
+        assert_eq!(migrated.rest.0, rest);
+    });
+}
+
+#[test]
+fn migrates_multiaddrs_to_v3() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        // Everything after the multiaddrs is kept as it is
+        let prefix = (
+            &sp.info.owner,
+            &sp.info.worker,
+            &sp.info.control_addresses,
+            &sp.info.pending_worker_key,
+            &sp.info.peer_id,
+            &sp.info.multiaddrs,
+        );
+        let rest = sp.encode()[prefix.encode().len()..].to_vec();
+        let old = v3::v2::StorageProviderState {
+            owner: sp.info.owner.clone(),
+            worker: sp.info.worker.clone(),
+            control_addresses: sp.info.control_addresses.clone(),
+            pending_worker_key: sp.info.pending_worker_key.clone(),
+            peer_id: sp.info.peer_id.clone(),
+            rest: Tail(rest.clone()),
+        };
+        unhashed::put(
+            &StorageProviders::<Test>::hashed_key_for(account(ALICE)),
+            &old,
+        );
+        StorageVersion::new(2).put::<StorageProvider>();
+
+        MigrateV2ToV3::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageProvider::on_chain_storage_version(), 3);
+        let migrated = v3::StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(migrated.owner, account(ALICE));
+        assert_eq!(migrated.peer_id, sp.info.peer_id);
+        assert!(migrated.multiaddrs.is_empty());
+        assert_eq!(migrated.rest.0, rest);
+    });
+}
+
+#[test]
+fn migrates_initial_pledge_to_v4() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_many_sectors_multiple_partitions(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        // Everything after the initial pledge is kept as it is
+        let prefix = (
+            &sp.info,
+            &sp.sectors,
+            &sp.pre_commit_deposits,
+            &sp.initial_pledge,
+        );
+        let rest = sp.encode()[prefix.encode().len()..].to_vec();
+        let old = v4::v3::StorageProviderState {
+            info: v4::StorageProviderInfo {
+                owner: sp.info.owner.clone(),
+                worker: sp.info.worker.clone(),
+                control_addresses: sp.info.control_addresses.clone(),
+                pending_worker_key: sp.info.pending_worker_key.clone(),
+                peer_id: sp.info.peer_id.clone(),
+                multiaddrs: sp.info.multiaddrs.clone(),
+                window_post_proof_type: sp.info.window_post_proof_type,
+                sector_size: sp.info.sector_size,
+                window_post_partition_sectors: sp.info.window_post_partition_sectors,
+            },
+            sectors: sp.sectors.clone(),
+            pre_commit_deposits: sp.pre_commit_deposits,
+            rest: Tail(rest.clone()),
+        };
+        unhashed::put(
+            &StorageProviders::<Test>::hashed_key_for(account(ALICE)),
+            &old,
+        );
+        StorageVersion::new(3).put::<StorageProvider>();
+
+        MigrateV3ToV4::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageProvider::on_chain_storage_version(), 4);
+        let migrated = v4::StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(migrated.info.owner, account(ALICE));
+        assert_eq!(migrated.sectors.encode(), sp.sectors.encode());
+        assert_eq!(migrated.pre_commit_deposits, sp.pre_commit_deposits);
+        assert_eq!(migrated.initial_pledge, 0);
+        assert_eq!(migrated.rest.0, rest);
+    });
+}
+
+#[test]
+fn schedules_storage_providers_in_v5() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        assert_ok!(Market::add_balance(
+            RuntimeOrigin::signed(account(ALICE)),
+            60
+        ));
+        assert_ok!(Market::add_balance(RuntimeOrigin::signed(account(BOB)), 70));
+        assert_ok!(Market::publish_storage_deals(
+            RuntimeOrigin::signed(account(ALICE)),
+            bounded_vec![DealProposalBuilder::default()
+                .client(BOB)
+                .provider(ALICE)
+                .signed(BOB)],
+        ));
+        let sector = SectorPreCommitInfoBuilder::default().deals(vec![0]).build();
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(ALICE)),
+            bounded_vec![sector]
+        ));
+        let prove_commit_due = System::block_number() + MaxProveCommitDuration::get();
+
+        // Nothing was scheduled before the upgrade
+        let deadline_checks = DeadlineChecks::<Test>::iter_keys().collect::<Vec<_>>();
+        let _ = DeadlineChecks::<Test>::clear(u32::MAX, None);
+        let _ = PreCommitExpirations::<Test>::clear(u32::MAX, None);
+        StorageVersion::new(4).put::<StorageProvider>();
+
+        MigrateV4ToV5::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageProvider::on_chain_storage_version(), 5);
+        assert_eq!(
+            DeadlineChecks::<Test>::iter_keys().collect::<Vec<_>>(),
+            deadline_checks
+        );
+        assert_eq!(deadline_checks.len(), 1);
+        assert_eq!(
+            PreCommitExpirations::<Test>::iter_keys().collect::<Vec<_>>(),
+            [(prove_commit_due, account(ALICE))]
+        );
+    });
+}
@@ -10,23 +10,29 @@ use frame_support::{
     parameter_types,
     sp_runtime::{BoundedBTreeMap, BoundedVec},
     traits::Hooks,
+    weights::Weight,
     PalletId,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_market::{BalanceOf, ClientDealProposal, DealProposal, DealState};
 use primitives::{
-    commitment::{CommP, Commitment, RawCommitment},
+    commitment::{CommD, CommP, Commitment, RawCommitment},
     pallets::ProofVerification,
-    proofs::{ProverId, PublicReplicaInfo, RegisteredPoStProof, RegisteredSealProof, Ticket},
+    proofs::{
+        ProverId, PublicReplicaInfo, PublicSealInfo, RegisteredPoStProof, RegisteredSealProof,
+        RegisteredUpdateProof, Ticket,
+    },
     sector::SectorNumber,
-    DealId, PartitionNumber, CID_SIZE_IN_BYTES, MAX_DEALS_PER_SECTOR, MAX_PARTITIONS_PER_DEADLINE,
-    MAX_POST_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS_PER_PROOF, MAX_TERMINATIONS_PER_CALL,
+    DealId, PartitionNumber, CID_SIZE_IN_BYTES, MAX_BATCHED_SECTORS, MAX_BATCH_PROOF_BYTES,
+    MAX_DEALS_PER_SECTOR, MAX_PARTITIONS_PER_DEADLINE, MAX_PARTITIONS_PER_POST,
+    MAX_REPLICA_UPDATE_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS_PER_PROOF,
+    MAX_TERMINATIONS_PER_CALL, MAX_WINDOWED_POST_PROOF_BYTES,
 };
 use sp_arithmetic::traits::Zero;
 use sp_core::{bounded_vec, Pair};
 use sp_runtime::{
     traits::{IdentifyAccount, IdentityLookup, Verify},
-    BoundedBTreeSet, BuildStorage, MultiSignature, MultiSigner,
+    BoundedBTreeSet, BuildStorage, MultiSignature, MultiSigner, Perbill,
 };
 
 use crate::{
@@ -39,14 +45,28 @@ use crate::{
     sector::SectorPreCommitInfo,
 };
 
+mod change_multiaddrs;
+mod change_peer_id;
+mod change_worker_address;
 mod deadline;
 mod declare_faults;
 mod declare_faults_recovered;
+mod deregister_storage_provider;
 mod expiration_queue;
+mod extend_sector_expiration;
+mod fault_fees;
+mod fault_max_age;
+mod migrations;
+mod pledge;
 mod post_hook;
+mod power;
 mod pre_commit_sector_hook;
 mod pre_commit_sectors;
+mod prove_commit_batch;
+mod prove_commit_ni_sectors;
 mod prove_commit_sectors;
+mod prove_replica_updates;
+mod runtime_api;
 mod state;
 mod storage_provider_registration;
 mod submit_windowed_post;
@@ -101,15 +121,72 @@ impl ProofVerification for DummyProofsVerification {
         Ok(())
     }
 
+    fn verify_ni_porep(
+        _prover_id: ProverId,
+        _seal_proof: RegisteredSealProof,
+        _comm_r: RawCommitment,
+        _comm_d: RawCommitment,
+        _sector: SectorNumber,
+        _ticket: Ticket,
+        proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
+    ) -> sp_runtime::DispatchResult {
+        if *proof == INVALID_PROOF {
+            return Err(sp_runtime::DispatchError::Other("invalid proof"));
+        }
+        Ok(())
+    }
+
+    fn verify_batch_porep(
+        _prover_id: ProverId,
+        _seal_proof: RegisteredSealProof,
+        _sectors: BoundedVec<PublicSealInfo, ConstU32<MAX_BATCHED_SECTORS>>,
+        proof: BoundedVec<u8, ConstU32<MAX_BATCH_PROOF_BYTES>>,
+    ) -> sp_runtime::DispatchResult {
+        if *proof == INVALID_PROOF {
+            return Err(sp_runtime::DispatchError::Other("invalid proof"));
+        }
+        Ok(())
+    }
+
     fn verify_post(
         _post_type: RegisteredPoStProof,
         _randomness: Ticket,
-        _replicas: BoundedBTreeMap<
-            SectorNumber,
-            PublicReplicaInfo,
-            ConstU32<MAX_SECTORS_PER_PROOF>,
+        partitions: BoundedVec<
+            BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>,
+            ConstU32<MAX_PARTITIONS_PER_POST>,
         >,
-        proof: BoundedVec<u8, ConstU32<MAX_POST_PROOF_BYTES>>,
+        proof: BoundedVec<u8, ConstU32<MAX_WINDOWED_POST_PROOF_BYTES>>,
+    ) -> sp_runtime::DispatchResult {
+        PoStReplicas::set(
+            partitions
+                .iter()
+                .map(|replicas| replicas.keys().copied().collect())
+                .collect(),
+        );
+        // Each of the partitions' proofs is checked, any of them being invalid fails the whole submission.
+        if proof
+            .chunks(INVALID_PROOF.len())
+            .any(|proof| proof == INVALID_PROOF)
+        {
+            return Err(sp_runtime::DispatchError::Other("invalid proof"));
+        }
+        Ok(())
+    }
+
+    fn verify_batch_porep_weight(_sectors: u32) -> Weight {
+        Weight::zero()
+    }
+
+    fn verify_post_weight(_partitions: u32) -> Weight {
+        Weight::zero()
+    }
+
+    fn verify_replica_update(
+        _update_proof: RegisteredUpdateProof,
+        _comm_r_old: RawCommitment,
+        _comm_r_new: RawCommitment,
+        _comm_d_new: RawCommitment,
+        proof: BoundedVec<u8, ConstU32<MAX_REPLICA_UPDATE_PROOF_BYTES>>,
     ) -> sp_runtime::DispatchResult {
         if *proof == INVALID_PROOF {
             return Err(sp_runtime::DispatchError::Other("invalid proof"));
@@ -129,6 +206,9 @@ impl pallet_market::Config for Test {
     type MinDealDuration = MinDealDuration;
     type MaxDealDuration = MaxDealDuration;
     type MaxDealsPerBlock = ConstU32<500>;
+    type DealSettlementInterval = DealSettlementInterval;
+    type MaxDealSettlementsPerBlock = ConstU32<500>;
+    type DealCancellationFee = DealCancellationFee;
 }
 
 parameter_types! {
@@ -142,18 +222,32 @@ parameter_types! {
     pub const SectorMaximumLifetime: BlockNumber = 120 * MINUTES;
     pub const MaxProveCommitDuration: BlockNumber = 5 * MINUTES;
     pub const MaxPartitionsPerDeadline: u64 = 3000;
-    pub const FaultMaxAge: BlockNumber = (5 * MINUTES) * 42;
+    // Static so tests can lower it below the sectors' lifetime
+    pub static FaultMaxAge: BlockNumber = (5 * MINUTES) * 42;
     pub const FaultDeclarationCutoff: BlockNumber = 2 * MINUTES;
     // 0 allows us to publish the prove-commit on the same block as the
     // pre-commit.
     pub const PreCommitChallengeDelay: BlockNumber = 0;
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L299>
     pub const AddressedSectorsMax: u64 = 25_000;
+    pub const WorkerKeyChangeDelay: BlockNumber = 10 * MINUTES;
+    pub const ExpectedBlockReward: u64 = 10;
+    // Power of 10 2KiB sectors, the deposit is 1 and the pledge is 2 until it's exceeded
+    pub const BaselinePower: u128 = 10 * 2048;
+    pub const PreCommitDepositProjectionPeriod: BlockNumber = 1;
+    pub const InitialPledgeProjectionPeriod: BlockNumber = 2;
+    // The fee is 1 and the penalty is 2 per faulty 2KiB sector, under the baseline
+    pub const ContinuedFaultProjectionPeriod: BlockNumber = 1;
+    pub const UndeclaredFaultProjectionPeriod: BlockNumber = 2;
+    // Sectors of each partition of the last verified Windowed PoSt
+    pub static PoStReplicas: Vec<BTreeSet<SectorNumber>> = Vec::new();
 
     // Market Pallet
     pub const MarketPalletId: PalletId = PalletId(*b"spMarket");
     pub const MinDealDuration: u64 = 2 * MINUTES;
     pub const MaxDealDuration: u64 = 30 * MINUTES;
+    pub const DealSettlementInterval: BlockNumber = 10 * MINUTES;
+    pub const DealCancellationFee: Perbill = Perbill::from_percent(20);
 }
 
 /// Randomness generator used by tests.
@@ -211,6 +305,13 @@ impl pallet_storage_provider::Config for Test {
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L295>
     type AddressedPartitionsMax = MaxPartitionsPerDeadline;
     type AddressedSectorsMax = AddressedSectorsMax;
+    type WorkerKeyChangeDelay = WorkerKeyChangeDelay;
+    type ExpectedBlockReward = ExpectedBlockReward;
+    type BaselinePower = BaselinePower;
+    type PreCommitDepositProjectionPeriod = PreCommitDepositProjectionPeriod;
+    type InitialPledgeProjectionPeriod = InitialPledgeProjectionPeriod;
+    type ContinuedFaultProjectionPeriod = ContinuedFaultProjectionPeriod;
+    type UndeclaredFaultProjectionPeriod = UndeclaredFaultProjectionPeriod;
 }
 
 type AccountIdOf<Test> = <Test as frame_system::Config>::AccountId;
@@ -233,7 +334,7 @@ const CHARLIE: &'static str = "//Charlie";
 const INITIAL_FUNDS: u64 = 50000;
 
 // Build genesis storage according to the mock runtime.
-fn new_test_ext() -> sp_io::TestExternalities {
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
     let _ = env_logger::try_init();
     let mut t = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
@@ -407,6 +508,11 @@ impl Default for SectorPreCommitInfoBuilder {
 }
 
 impl SectorPreCommitInfoBuilder {
+    pub fn seal_proof(mut self, seal_proof: RegisteredSealProof) -> Self {
+        self.seal_proof = seal_proof;
+        self
+    }
+
     pub fn sector_number(mut self, sector_number: SectorNumber) -> Self {
         self.sector_number = sector_number;
         self
@@ -422,12 +528,26 @@ impl SectorPreCommitInfoBuilder {
         self
     }
 
+    pub fn seal_randomness_height(mut self, seal_randomness_height: u64) -> Self {
+        self.seal_randomness_height = seal_randomness_height;
+        self
+    }
+
     pub fn unsealed_cid(mut self, unsealed_cid: &str) -> Self {
         let cid = Cid::from_str(unsealed_cid).expect("valid unsealed_cid");
         self.unsealed_cid = BoundedVec::try_from(cid.to_bytes()).unwrap();
         self
     }
 
+    /// Makes the sector a committed capacity sector, without deals and with the zero CommD.
+    pub fn committed_capacity(mut self) -> Self {
+        self.deal_ids = bounded_vec![];
+        self.unsealed_cid =
+            BoundedVec::try_from(Commitment::<CommD>::zero(self.seal_proof).cid().to_bytes())
+                .unwrap();
+        self
+    }
+
     pub fn build(self) -> SectorPreCommitInfo<u64> {
         SectorPreCommitInfo {
             seal_proof: self.seal_proof,
@@ -0,0 +1,204 @@
+use frame_support::{
+    assert_noop, assert_ok, dispatch::DispatchResult, pallet_prelude::Get,
+    traits::ReservableCurrency,
+};
+use primitives::{pallets::StoragePower, sector::SectorNumber};
+use sp_core::bounded_vec;
+
+use crate::{
+    pallet::{Config, Error, StorageProviders, TotalPower},
+    sector::{ProveCommitSector, TerminateSectorsParams, TerminationDeclaration},
+    tests::{
+        account, declare_faults::setup_sp_with_one_sector, new_test_ext, publish_deals,
+        register_storage_provider, run_to_block, sector_set, Balances, RuntimeOrigin,
+        SectorPreCommitInfoBuilder, StorageProvider, SubmitWindowedPoStBuilder, Test, ALICE, BOB,
+    },
+};
+
+/// Pre-commit deposit of a 2KiB sector, while the network power is below the baseline.
+const PRE_COMMIT_DEPOSIT: u64 = 1;
+
+/// Initial pledge of a 2KiB sector, while the network power is below the baseline.
+const INITIAL_PLEDGE: u64 = 2;
+
+/// Pre-commits sectors 1 and 2, containing the deals 0 and 1 respectively.
+fn pre_commit_two_sectors(storage_provider: &str) {
+    assert_ok!(StorageProvider::pre_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(SectorNumber::from(1))
+                .deals(vec![0])
+                .build(),
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(SectorNumber::from(2))
+                .deals(vec![1])
+                .build(),
+        ],
+    ));
+}
+
+/// Proves sectors 1 and 2, previously pre-committed by [`pre_commit_two_sectors`].
+fn prove_two_sectors(storage_provider: &str) -> DispatchResult {
+    StorageProvider::prove_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![
+            ProveCommitSector {
+                sector_number: SectorNumber::from(1),
+                proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+            },
+            ProveCommitSector {
+                sector_number: SectorNumber::from(2),
+                proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+            },
+        ],
+    )
+}
+
+/// Terminates the given sectors, all of them in deadline 0, partition 0.
+fn terminate(storage_provider: &str, sectors: &[u32]) {
+    assert_ok!(StorageProvider::terminate_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        TerminateSectorsParams {
+            terminations: bounded_vec![TerminationDeclaration {
+                deadline: 0,
+                partition: 0,
+                sectors: sector_set(sectors),
+            }],
+        },
+    ));
+}
+
+#[test]
+fn pre_commit_locks_deposit() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+
+        pre_commit_two_sectors(ALICE);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.pre_commit_deposits, 2 * PRE_COMMIT_DEPOSIT);
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(
+            Balances::reserved_balance(account(ALICE)),
+            2 * PRE_COMMIT_DEPOSIT
+        );
+    });
+}
+
+#[test]
+fn prove_commit_releases_deposit_and_locks_pledge() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+        pre_commit_two_sectors(ALICE);
+
+        assert_ok!(prove_two_sectors(ALICE));
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.pre_commit_deposits, 0);
+        assert_eq!(sp.initial_pledge, 2 * INITIAL_PLEDGE);
+        assert_eq!(
+            Balances::reserved_balance(account(ALICE)),
+            2 * INITIAL_PLEDGE
+        );
+    });
+}
+
+#[test]
+fn prove_commit_fails_not_enough_funds_for_pledge() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+        pre_commit_two_sectors(ALICE);
+
+        // Leave a single free token, the released deposit doesn't cover the pledge
+        let free_balance = Balances::free_balance(account(ALICE));
+        assert_ok!(Balances::reserve(&account(ALICE), free_balance - 1));
+
+        assert_noop!(prove_two_sectors(ALICE), Error::<Test>::NotEnoughFunds);
+    });
+}
+
+/// The pledge is shared equally by the proven sectors,
+/// terminating one of them releases its share.
+#[test]
+fn terminate_sectors_releases_pledge() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+        pre_commit_two_sectors(ALICE);
+        assert_ok!(prove_two_sectors(ALICE));
+
+        terminate(ALICE, &[1]);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE);
+        assert_eq!(Balances::reserved_balance(account(ALICE)), INITIAL_PLEDGE);
+
+        terminate(ALICE, &[2]);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(Balances::reserved_balance(account(ALICE)), 0);
+    });
+}
+
+/// The sector expires at block 120, its pledge is released when the deadline
+/// it's assigned to is processed after that block.
+#[test]
+fn expired_sectors_release_pledge() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE);
+        let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+        let sector_expiration = sp.sectors[&SectorNumber::from(0)].expiration;
+
+        // Keep proving the sector until the deadline after its expiration
+        let mut proving_period_start = sp.proving_period_start;
+        loop {
+            run_to_block(proving_period_start);
+            assert_ok!(StorageProvider::submit_windowed_post(
+                RuntimeOrigin::signed(account(ALICE)),
+                SubmitWindowedPoStBuilder::default().partition(0).build(),
+            ));
+            run_to_block(proving_period_start + challenge_window + 1);
+            if proving_period_start + challenge_window > sector_expiration {
+                break;
+            }
+            assert_eq!(Balances::reserved_balance(account(ALICE)), INITIAL_PLEDGE);
+            proving_period_start += proving_period;
+        }
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(Balances::reserved_balance(account(ALICE)), 0);
+    });
+}
+
+/// Once the network power exceeds the baseline, each sector earns a smaller share
+/// of the block reward, so the deposit and the pledge are lower.
+#[test]
+fn deposit_and_pledge_decrease_above_baseline_power() {
+    new_test_ext().execute_with(|| {
+        // Twice the baseline power
+        let network_power = 2 * <<Test as Config>::BaselinePower as Get<u128>>::get();
+        TotalPower::<Test>::put(StoragePower {
+            raw_byte_power: network_power,
+            quality_adj_power: network_power,
+        });
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+
+        // Half a token of deposit per sector is rounded down
+        pre_commit_two_sectors(ALICE);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.pre_commit_deposits, 0);
+
+        assert_ok!(prove_two_sectors(ALICE));
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(sp.initial_pledge, INITIAL_PLEDGE);
+        assert_eq!(Balances::reserved_balance(account(ALICE)), INITIAL_PLEDGE);
+    });
+}
@@ -5,7 +5,7 @@ use sp_runtime::{BoundedBTreeMap, BoundedBTreeSet};
 
 use super::new_test_ext;
 use crate::{
-    pallet::{Config, Event, StorageProviders},
+    pallet::{Config, DeadlineChecks, Event, StorageProviders},
     sector::ProveCommitSector,
     tests::{
         account, events, publish_deals, register_storage_provider, run_to_block, sector_set,
@@ -34,6 +34,34 @@ fn advances_deadline() {
     });
 }
 
+#[test]
+fn schedules_deadline_checks() {
+    new_test_ext().execute_with(|| {
+        let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        // The first deadline is checked when it closes.
+        let sp = StorageProviders::<Test>::get(account(storage_provider)).unwrap();
+        let first_deadline_end = sp.proving_period_start + challenge_window;
+        assert!(DeadlineChecks::<Test>::contains_key(
+            first_deadline_end,
+            account(storage_provider)
+        ));
+
+        // Once checked, the next deadline is scheduled.
+        run_to_block(first_deadline_end + 1);
+        assert!(!DeadlineChecks::<Test>::contains_key(
+            first_deadline_end,
+            account(storage_provider)
+        ));
+        assert!(DeadlineChecks::<Test>::contains_key(
+            first_deadline_end + challenge_window,
+            account(storage_provider)
+        ));
+    });
+}
+
 /// Publish 2 deals, by a 1 Storage Provider.
 /// Precommit both of them, prove both of them, but don't submit PoSt.
 /// It must detect partitions as faulty.
@@ -80,14 +108,36 @@ fn marks_partitions_as_faulty() {
         expected_faulty_partitions
             .try_insert(0, expected_faulty_sectors)
             .unwrap();
+        // The faults weren't declared, the penalty takes the whole pledge of both sectors
+        let undeclared_fault_penalty = 4;
+        assert_eq!(sp.initial_pledge, 0);
         assert_eq!(
             events(),
-            [RuntimeEvent::StorageProvider(
-                Event::<Test>::PartitionsFaulty {
+            [
+                RuntimeEvent::StorageProvider(Event::<Test>::PartitionsFaulty {
                     owner: account(storage_provider),
                     faulty_partitions: expected_faulty_partitions,
-                }
-            ),]
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Slashed {
+                    who: account(storage_provider),
+                    amount: undeclared_fault_penalty,
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
+                    amount: undeclared_fault_penalty
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Withdraw {
+                    who: account(storage_provider),
+                    amount: undeclared_fault_penalty,
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
+                    amount: undeclared_fault_penalty
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::FaultsPenalized {
+                    owner: account(storage_provider),
+                    continued_fault_fee: 0,
+                    undeclared_fault_penalty,
+                }),
+            ]
         );
     });
 }
@@ -0,0 +1,249 @@
+use frame_support::{assert_ok, pallet_prelude::Get};
+use primitives::{pallets::StoragePower, sector::SectorNumber};
+use sp_core::bounded_vec;
+
+use crate::{
+    pallet::{Config, StorageProviderPower, StorageProviders, TotalPower},
+    sector::{ProveCommitSector, TerminateSectorsParams, TerminationDeclaration},
+    tests::{
+        account, declare_faults::setup_sp_with_one_sector, new_test_ext, publish_deals,
+        register_storage_provider, run_to_block, sector_set, DeclareFaultsBuilder,
+        DeclareFaultsRecoveredBuilder, RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider,
+        SubmitWindowedPoStBuilder, Test, ALICE, BOB, CHARLIE,
+    },
+};
+
+/// Size of the sectors used in the tests, 2KiB.
+const SECTOR_SIZE: u128 = 2048;
+
+/// Power of the given amount of 2KiB sectors.
+fn power_of(sectors: u128) -> StoragePower {
+    StoragePower {
+        raw_byte_power: sectors * SECTOR_SIZE,
+        quality_adj_power: sectors * SECTOR_SIZE,
+    }
+}
+
+/// Pre-commits sector 1 containing the given deals.
+fn pre_commit_sector(storage_provider: &str, deals: Vec<u64>) {
+    let sector = SectorPreCommitInfoBuilder::default()
+        .sector_number(SectorNumber::from(1))
+        .deals(deals)
+        .build();
+    assert_ok!(StorageProvider::pre_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![sector],
+    ));
+}
+
+/// Proves sector 1, previously pre-committed by [`pre_commit_sector`].
+fn prove_commit_sector(storage_provider: &str) {
+    assert_ok!(StorageProvider::prove_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![ProveCommitSector {
+            sector_number: SectorNumber::from(1),
+            proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+        }],
+    ));
+}
+
+/// Proves the partition 0 of deadline 0 in the proving period starting at the given block.
+fn submit_post(storage_provider: &str, proving_period_start: u64) {
+    run_to_block(proving_period_start);
+    assert_ok!(StorageProvider::submit_windowed_post(
+        RuntimeOrigin::signed(account(storage_provider)),
+        SubmitWindowedPoStBuilder::default().partition(0).build(),
+    ));
+}
+
+#[test]
+fn power_is_none_for_unregistered_sp() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            StorageProvider::storage_provider_power(&account(ALICE)),
+            None
+        );
+        assert_eq!(StorageProvider::total_power(), power_of(0));
+    });
+}
+
+#[test]
+fn prove_commit_adds_power() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+        assert_eq!(
+            StorageProvider::storage_provider_power(&account(ALICE)),
+            Some(power_of(0))
+        );
+
+        // Pre-committed sectors don't have any power
+        pre_commit_sector(ALICE, vec![0, 1]);
+        assert_eq!(
+            StorageProvider::storage_provider_power(&account(ALICE)),
+            Some(power_of(0))
+        );
+
+        prove_commit_sector(ALICE);
+        assert_eq!(
+            StorageProvider::storage_provider_power(&account(ALICE)),
+            Some(power_of(1))
+        );
+        assert_eq!(StorageProvider::total_power(), power_of(1));
+    });
+}
+
+#[test]
+fn declare_faults_removes_power_and_post_restores_it() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sectors = vec![SectorNumber::from(0)];
+
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsBuilder::default()
+                .fault(0, 0, &sectors)
+                .build(),
+        ));
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(0)
+        );
+        assert_eq!(TotalPower::<Test>::get(), power_of(0));
+
+        // Recovering sectors don't count until they're proven
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+        let fault_declaration_cutoff =
+            <<Test as Config>::FaultDeclarationCutoff as Get<u64>>::get();
+        run_to_block(sp.proving_period_start + proving_period - fault_declaration_cutoff - 1);
+        assert_ok!(StorageProvider::declare_faults_recovered(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsRecoveredBuilder::default()
+                .fault_recovery(0, 0, &sectors)
+                .build(),
+        ));
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(0)
+        );
+
+        submit_post(ALICE, sp.proving_period_start + proving_period);
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(1)
+        );
+        assert_eq!(TotalPower::<Test>::get(), power_of(1));
+    });
+}
+
+#[test]
+fn terminate_sectors_removes_power() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+
+        assert_ok!(StorageProvider::terminate_sectors(
+            RuntimeOrigin::signed(account(ALICE)),
+            TerminateSectorsParams {
+                terminations: bounded_vec![TerminationDeclaration {
+                    deadline: 0,
+                    partition: 0,
+                    sectors: sector_set(&[0]),
+                }],
+            },
+        ));
+
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(0)
+        );
+        assert_eq!(TotalPower::<Test>::get(), power_of(0));
+    });
+}
+
+#[test]
+fn missed_post_removes_power() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+
+        // The partition is marked as faulty once the deadline elapses
+        run_to_block(sp.proving_period_start + challenge_window + 1);
+
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(0)
+        );
+        assert_eq!(TotalPower::<Test>::get(), power_of(0));
+    });
+}
+
+/// The sector expires at block 120, it's terminated when the deadline
+/// it's assigned to is processed after that block.
+#[test]
+fn expired_sectors_remove_power() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let challenge_window = <<Test as Config>::WPoStChallengeWindow as Get<u64>>::get();
+        let proving_period = <<Test as Config>::WPoStProvingPeriod as Get<u64>>::get();
+        let sector_expiration = sp.sectors[&SectorNumber::from(0)].expiration;
+
+        // Keep proving the sector until the deadline after its expiration
+        let mut proving_period_start = sp.proving_period_start;
+        while proving_period_start + challenge_window <= sector_expiration {
+            submit_post(ALICE, proving_period_start);
+            run_to_block(proving_period_start + challenge_window + 1);
+            assert_eq!(
+                StorageProviderPower::<Test>::get(account(ALICE)),
+                power_of(1)
+            );
+            proving_period_start += proving_period;
+        }
+
+        submit_post(ALICE, proving_period_start);
+        run_to_block(proving_period_start + challenge_window + 1);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let partition = &sp.deadlines.due[0].partitions[&0];
+        assert!(partition.terminated.contains(&SectorNumber::from(0)));
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(0)
+        );
+        assert_eq!(TotalPower::<Test>::get(), power_of(0));
+    });
+}
+
+#[test]
+fn total_power_sums_all_sps() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+
+        // Charlie proves a sector, with the deals published after Alice's one
+        register_storage_provider(account(CHARLIE));
+        publish_deals(CHARLIE);
+        pre_commit_sector(CHARLIE, vec![1, 2]);
+        prove_commit_sector(CHARLIE);
+
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(ALICE)),
+            power_of(1)
+        );
+        assert_eq!(
+            StorageProviderPower::<Test>::get(account(CHARLIE)),
+            power_of(1)
+        );
+        assert_eq!(StorageProvider::total_power(), power_of(2));
+
+        // Alice's power is removed from the total
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsBuilder::default()
+                .fault(0, 0, &[SectorNumber::from(0)])
+                .build(),
+        ));
+        assert_eq!(StorageProvider::total_power(), power_of(1));
+    });
+}
@@ -4,12 +4,12 @@ use sp_runtime::{BoundedBTreeMap, BoundedBTreeSet};
 
 use super::new_test_ext;
 use crate::{
-    pallet::{Event, StorageProviders},
+    pallet::{Event, PreCommitExpirations, StorageProviders},
     sector::ProveCommitSector,
     tests::{
         account, events, publish_deals, register_storage_provider, run_to_block, Balances,
-        RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider, System, Test,
-        CHARLIE,
+        MaxProveCommitDuration, RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder,
+        StorageProvider, System, Test, CHARLIE,
     },
 };
 
@@ -17,7 +17,8 @@ use crate::{
 /// Precommit both of them, but prove only the 2nd one.
 /// First one should be slashed -> pre_commit_deposit slashed & burned and removed from state + emitted event.
 /// Second one should **NOT** be slashed -> just removed during proving and not touched by the hook.
-/// There is a balance in pre_commit_deposit after proving, because we release balance after termination.
+/// The pre-commit deposit of the second one is released after proving, the initial pledge is locked instead.
+/// The second one misses its PoSt, so its initial pledge is taken by the undeclared fault penalty.
 #[test]
 fn pre_commit_hook_slashed_deal() {
     new_test_ext().execute_with(|| {
@@ -26,8 +27,8 @@ fn pre_commit_hook_slashed_deal() {
         publish_deals(storage_provider);
         let first_deal = 0;
         let second_deal = 1;
-        // TODO(@aidan46, #106, 2024-06-24): Set a logical value or calculation
         let deal_precommit_deposit = 1;
+        let sector_initial_pledge = 2;
 
         let first_sector = SectorPreCommitInfoBuilder::default()
             .sector_number(1.into())
@@ -49,6 +50,7 @@ fn pre_commit_hook_slashed_deal() {
             bounded_vec![second_sector.clone()],
         )
         .unwrap();
+        let prove_commit_due = System::block_number() + MaxProveCommitDuration::get();
 
         StorageProvider::prove_commit_sectors(
             RuntimeOrigin::signed(account(storage_provider)),
@@ -63,19 +65,55 @@ fn pre_commit_hook_slashed_deal() {
 
         // Running to block after it should have been slashed.
         // It wouldn't if we had proven it before.
-        run_to_block(first_sector.expiration + 1);
+        run_to_block(prove_commit_due + 1);
 
         let sp = StorageProviders::<Test>::get(account(storage_provider))
             .expect("SP should be present because of the pre-check");
         assert!(sp.sectors.contains_key(&second_sector.sector_number));
         // First sector removed from here because it was slashed, second one because it was proven.
         assert!(sp.pre_committed_sectors.is_empty());
-        // Pre-commit from the second deal is still there, as pre-commit deposits are until sector expired.
-        assert_eq!(sp.pre_commit_deposits, deal_precommit_deposit);
+        // Pre-commit deposit from the second deal was released when proving, the pledge was locked instead.
+        assert_eq!(sp.pre_commit_deposits, 0);
+        assert_eq!(sp.initial_pledge, sector_initial_pledge);
         assert_eq!(
             Balances::reserved_balance(account(storage_provider)),
-            deal_precommit_deposit
+            sector_initial_pledge
         );
+        assert_eq!(
+            events(),
+            [
+                // the slash -> withdraw is related to the usage of slash_and_burn
+                // when slashing the SP for a failed pre_commit
+                // this usage may need review for a proper economic balance
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Slashed {
+                    who: account(storage_provider),
+                    amount: deal_precommit_deposit,
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
+                    amount: deal_precommit_deposit
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Withdraw {
+                    who: account(storage_provider),
+                    amount: deal_precommit_deposit,
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
+                    amount: deal_precommit_deposit
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::SectorsSlashed {
+                    owner: account(storage_provider),
+                    sector_numbers: bounded_vec![1.into()],
+                }),
+            ]
+        );
+
+        // The second sector misses its PoSt.
+        run_to_block(first_sector.expiration + 1);
+
+        let sp = StorageProviders::<Test>::get(account(storage_provider))
+            .expect("SP should be present because of the pre-check");
+        // The pledge was charged as undeclared fault penalty.
+        assert_eq!(sp.initial_pledge, 0);
+        assert_eq!(Balances::reserved_balance(account(storage_provider)), 0);
         let mut expected_faulty_sectors = BoundedBTreeSet::new();
         expected_faulty_sectors
             .try_insert(SectorNumber::new(2).unwrap())
@@ -91,28 +129,114 @@ fn pre_commit_hook_slashed_deal() {
                     owner: account(storage_provider),
                     faulty_partitions: expected_faulty_partitions,
                 }),
-                // the slash -> withdraw is related to the usage of slash_and_burn
-                // when slashing the SP for a failed pre_commit
-                // this usage may need review for a proper economic balance
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Slashed {
                     who: account(storage_provider),
-                    amount: deal_precommit_deposit,
+                    amount: sector_initial_pledge,
                 }),
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
-                    amount: deal_precommit_deposit
+                    amount: sector_initial_pledge
                 }),
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Withdraw {
                     who: account(storage_provider),
-                    amount: deal_precommit_deposit,
+                    amount: sector_initial_pledge,
                 }),
                 RuntimeEvent::Balances(pallet_balances::Event::<Test>::Rescinded {
-                    amount: deal_precommit_deposit
+                    amount: sector_initial_pledge
                 }),
-                RuntimeEvent::StorageProvider(Event::<Test>::SectorsSlashed {
+                RuntimeEvent::StorageProvider(Event::<Test>::FaultsPenalized {
                     owner: account(storage_provider),
-                    sector_numbers: bounded_vec![1.into()],
+                    continued_fault_fee: 0,
+                    undeclared_fault_penalty: sector_initial_pledge,
                 }),
             ]
         );
     });
 }
+
+#[test]
+fn pre_commit_hook_schedules_expiration() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        publish_deals(storage_provider);
+
+        let sector = SectorPreCommitInfoBuilder::default()
+            .sector_number(1.into())
+            .deals(bounded_vec![0])
+            .build();
+        StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![sector.clone()],
+        )
+        .unwrap();
+
+        // The storage provider is checked once the pre-commit can no longer be proven.
+        let prove_commit_due = System::block_number() + MaxProveCommitDuration::get();
+        assert!(PreCommitExpirations::<Test>::contains_key(
+            prove_commit_due,
+            account(storage_provider)
+        ));
+
+        run_to_block(prove_commit_due + 1);
+
+        let sp = StorageProviders::<Test>::get(account(storage_provider))
+            .expect("SP should be present because of the pre-check");
+        assert!(sp.pre_committed_sectors.is_empty());
+        assert!(!PreCommitExpirations::<Test>::contains_key(
+            prove_commit_due,
+            account(storage_provider)
+        ));
+    });
+}
+
+#[test]
+fn prove_commit_unschedules_expiration() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        publish_deals(storage_provider);
+
+        let first_sector = SectorPreCommitInfoBuilder::default()
+            .sector_number(1.into())
+            .deals(bounded_vec![0])
+            .build();
+        let second_sector = SectorPreCommitInfoBuilder::default()
+            .sector_number(2.into())
+            .deals(bounded_vec![1])
+            .build();
+        StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![first_sector, second_sector],
+        )
+        .unwrap();
+        let prove_commit_due = System::block_number() + MaxProveCommitDuration::get();
+
+        // The first sector still needs to be checked.
+        StorageProvider::prove_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![ProveCommitSector {
+                sector_number: 2.into(),
+                proof: bounded_vec![0xde],
+            }],
+        )
+        .unwrap();
+        assert!(PreCommitExpirations::<Test>::contains_key(
+            prove_commit_due,
+            account(storage_provider)
+        ));
+
+        // Nothing is left to check.
+        StorageProvider::prove_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![ProveCommitSector {
+                sector_number: 1.into(),
+                proof: bounded_vec![0xde],
+            }],
+        )
+        .unwrap();
+        assert!(!PreCommitExpirations::<Test>::contains_key(
+            prove_commit_due,
+            account(storage_provider)
+        ));
+    });
+}
@@ -79,8 +79,8 @@ fn successfully_precommited_no_deals() {
 
         // Sector to be pre-committed.
         let sector = SectorPreCommitInfoBuilder::default()
-            // No sectors -> No CommD verification
-            .deals(bounded_vec![])
+            // No deals -> Committed capacity sector, the market is not involved
+            .committed_capacity()
             .build();
 
         // Run pre commit extrinsic
@@ -270,6 +270,31 @@ fn fails_declared_commd_not_matching() {
     });
 }
 
+/// Committed capacity sectors can only contain zeroes.
+#[test]
+fn fails_committed_capacity_commd_not_zero() {
+    new_test_ext().execute_with(|| {
+        // Register CHARLIE as a storage provider.
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        // Sector to be pre-committed
+        let sector = SectorPreCommitInfoBuilder::default()
+            .deals(vec![])
+            // CommD of the published deals
+            .unsealed_cid("baga6ea4seaqhdbbdnon7gkuquzw6waekzqx5lbuio6a6wjie22pgfmwnv3a3wfi")
+            .build();
+
+        assert_noop!(
+            StorageProvider::pre_commit_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![sector]
+            ),
+            Error::<Test>::InvalidUnsealedCidForSector,
+        );
+    });
+}
+
 #[test]
 fn fails_invalid_cid() {
     new_test_ext().execute_with(|| {
@@ -0,0 +1,161 @@
+use frame_support::{assert_noop, assert_ok, pallet_prelude::*};
+use primitives::{sector::SectorNumber, MAX_BATCHED_SECTORS, MAX_SECTORS_PER_CALL};
+use sp_core::bounded_vec;
+
+use super::{new_test_ext, MaxProveCommitDuration, INVALID_PROOF};
+use crate::{
+    pallet::{Error, Event, StorageProviders},
+    sector::ProveCommitBatch,
+    tests::{
+        account, events, register_storage_provider, run_to_block, Balances, RuntimeEvent,
+        RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider, System, Test, CHARLIE,
+        INITIAL_FUNDS,
+    },
+};
+
+/// Pre-commits committed capacity sectors `0..count`, respecting the per-call limit.
+fn pre_commit_cc_sectors(storage_provider: &str, count: u32) {
+    let sector_numbers = (0..count).collect::<Vec<_>>();
+    for chunk in sector_numbers.chunks(MAX_SECTORS_PER_CALL as usize) {
+        let sectors = chunk
+            .iter()
+            .map(|&sector_number| {
+                SectorPreCommitInfoBuilder::default()
+                    .sector_number(sector_number.try_into().unwrap())
+                    .committed_capacity()
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            sectors.try_into().unwrap(),
+        ));
+    }
+}
+
+fn sector_numbers(count: u32) -> BoundedVec<SectorNumber, ConstU32<MAX_BATCHED_SECTORS>> {
+    (0..count)
+        .map(|sector_number| sector_number.try_into().unwrap())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn successfully_prove_batch() {
+    new_test_ext().execute_with(|| {
+        // More sectors than fit in a single `prove_commit_sectors` call
+        const SECTORS_TO_COMMIT: u32 = MAX_SECTORS_PER_CALL + 8;
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        pre_commit_cc_sectors(storage_provider, SECTORS_TO_COMMIT);
+        System::reset_events();
+
+        run_to_block(System::block_number() + 2);
+        assert_ok!(StorageProvider::prove_commit_batch(
+            RuntimeOrigin::signed(account(storage_provider)),
+            ProveCommitBatch {
+                sector_numbers: sector_numbers(SECTORS_TO_COMMIT),
+                batch_proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+            }
+        ));
+
+        let events = events();
+        assert_eq!(
+            events[..2],
+            [
+                // The pre-commit deposits are released and the initial pledges are locked
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Unreserved {
+                    who: account(storage_provider),
+                    amount: SECTORS_TO_COMMIT as u64
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Reserved {
+                    who: account(storage_provider),
+                    amount: 2 * SECTORS_TO_COMMIT as u64
+                }),
+            ]
+        );
+        let RuntimeEvent::StorageProvider(Event::<Test>::SectorsProven { owner, sectors }) =
+            &events[2]
+        else {
+            panic!("expected SectorsProven event, got {:?}", events[2]);
+        };
+        assert_eq!(*owner, account(storage_provider));
+        assert_eq!(sectors.len(), SECTORS_TO_COMMIT as usize);
+
+        assert_eq!(
+            Balances::free_balance(account(storage_provider)),
+            INITIAL_FUNDS - 2 * SECTORS_TO_COMMIT as u64
+        );
+
+        let sp_state = StorageProviders::<Test>::get(account(storage_provider))
+            .expect("Should be able to get providers info");
+        assert!(sp_state.pre_committed_sectors.is_empty());
+        for sector_number in 0..SECTORS_TO_COMMIT {
+            let sector_number = sector_number.try_into().unwrap();
+            assert!(sp_state.sectors.contains_key(&sector_number));
+        }
+    });
+}
+
+#[test]
+fn fails_no_sectors_in_batch() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        assert_noop!(
+            StorageProvider::prove_commit_batch(
+                RuntimeOrigin::signed(account(storage_provider)),
+                ProveCommitBatch {
+                    sector_numbers: bounded_vec![],
+                    batch_proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }
+            ),
+            Error::<Test>::NoSectorsInBatch,
+        );
+    });
+}
+
+#[test]
+fn fails_invalid_batch_proof() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        pre_commit_cc_sectors(storage_provider, 2);
+
+        run_to_block(System::block_number() + 2);
+        assert_noop!(
+            StorageProvider::prove_commit_batch(
+                RuntimeOrigin::signed(account(storage_provider)),
+                ProveCommitBatch {
+                    sector_numbers: sector_numbers(2),
+                    batch_proof: BoundedVec::truncate_from(INVALID_PROOF.to_vec()),
+                }
+            ),
+            sp_runtime::DispatchError::Other("invalid proof"),
+        );
+    });
+}
+
+#[test]
+fn fails_prove_commit_batch_after_deadline() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        pre_commit_cc_sectors(storage_provider, 2);
+
+        run_to_block(1 + MaxProveCommitDuration::get());
+        assert_noop!(
+            StorageProvider::prove_commit_batch(
+                RuntimeOrigin::signed(account(storage_provider)),
+                ProveCommitBatch {
+                    sector_numbers: sector_numbers(2),
+                    batch_proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }
+            ),
+            Error::<Test>::ProveCommitAfterDeadline,
+        );
+    });
+}
@@ -0,0 +1,244 @@
+use frame_support::{assert_noop, assert_ok, pallet_prelude::*};
+use primitives::proofs::RegisteredSealProof;
+use sp_core::bounded_vec;
+use sp_runtime::{ArithmeticError, DispatchError};
+
+use super::{new_test_ext, MaxProveCommitDuration, INVALID_PROOF};
+use crate::{
+    pallet::{Error, Event, StorageProviders},
+    sector::{ProveCommitNiSector, ProveCommitResult},
+    tests::{
+        account, events, publish_deals, register_storage_provider, run_to_block, Balances,
+        RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider, Test, ALICE, BOB,
+        CHARLIE, INITIAL_FUNDS,
+    },
+};
+
+fn ni_sector_builder() -> SectorPreCommitInfoBuilder {
+    SectorPreCommitInfoBuilder::default()
+        .seal_proof(RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep)
+}
+
+#[test]
+fn successfully_prove_ni_sectors() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        publish_deals(storage_provider);
+
+        let deal_sector = ni_sector_builder()
+            .sector_number(1.into())
+            .unsealed_cid("baga6ea4seaqhdbbdnon7gkuquzw6waekzqx5lbuio6a6wjie22pgfmwnv3a3wfi")
+            .build();
+        let cc_sector = ni_sector_builder()
+            .sector_number(2.into())
+            .committed_capacity()
+            .build();
+
+        run_to_block(4);
+        assert_ok!(StorageProvider::prove_commit_ni_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![
+                ProveCommitNiSector {
+                    sector: deal_sector,
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                },
+                ProveCommitNiSector {
+                    sector: cc_sector,
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                },
+            ]
+        ));
+
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 0,
+                    client: account(ALICE),
+                    provider: account(storage_provider)
+                }),
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 1,
+                    client: account(BOB),
+                    provider: account(storage_provider)
+                }),
+                // No pre-commit deposit was locked, only the initial pledge is
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Reserved {
+                    who: account(storage_provider),
+                    amount: 4
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::SectorsProven {
+                    owner: account(storage_provider),
+                    sectors: bounded_vec![
+                        ProveCommitResult {
+                            sector_number: 1.into(),
+                            deadline_idx: 0,
+                            partition_number: 0,
+                        },
+                        ProveCommitResult {
+                            sector_number: 2.into(),
+                            deadline_idx: 0,
+                            partition_number: 0,
+                        }
+                    ]
+                })
+            ]
+        );
+
+        // Provider reserved 70 tokens in the market pallet and the initial pledge of 2 tokens per sector
+        assert_eq!(
+            Balances::free_balance(account(storage_provider)),
+            INITIAL_FUNDS - 70 - 4
+        );
+        let sp_state = StorageProviders::<Test>::get(account(storage_provider))
+            .expect("Should be able to get providers info");
+        assert!(sp_state.pre_committed_sectors.is_empty());
+        assert_eq!(sp_state.pre_commit_deposits, 0);
+        assert!(sp_state.sectors.contains_key(&1.into()));
+        assert!(sp_state.sectors.contains_key(&2.into()));
+    });
+}
+
+#[test]
+fn fails_interactive_seal_proof() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        let sector = SectorPreCommitInfoBuilder::default()
+            .committed_capacity()
+            .build();
+
+        run_to_block(4);
+        assert_noop!(
+            StorageProvider::prove_commit_ni_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![ProveCommitNiSector {
+                    sector,
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }]
+            ),
+            Error::<Test>::InvalidProofType,
+        );
+    });
+}
+
+#[test]
+fn fails_pre_commit_non_interactive_seal_proof() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        let sector = ni_sector_builder().committed_capacity().build();
+
+        assert_noop!(
+            StorageProvider::pre_commit_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![sector]
+            ),
+            Error::<Test>::InvalidProofType,
+        );
+    });
+}
+
+#[test]
+fn fails_invalid_proof() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        let sector = ni_sector_builder().committed_capacity().build();
+
+        run_to_block(4);
+        assert_noop!(
+            StorageProvider::prove_commit_ni_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![ProveCommitNiSector {
+                    sector,
+                    proof: BoundedVec::truncate_from(INVALID_PROOF.to_vec()),
+                }]
+            ),
+            sp_runtime::DispatchError::Other("invalid proof"),
+        );
+    });
+}
+
+#[test]
+fn fails_seal_randomness_too_old() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        // The randomness is taken from block 1
+        let sector = ni_sector_builder()
+            .committed_capacity()
+            .expiration(120 + MaxProveCommitDuration::get())
+            .build();
+
+        run_to_block(1 + MaxProveCommitDuration::get());
+        assert_noop!(
+            StorageProvider::prove_commit_ni_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![ProveCommitNiSector {
+                    sector,
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }]
+            ),
+            Error::<Test>::SealRandomnessTooOld,
+        );
+    });
+}
+
+#[test]
+fn fails_seal_randomness_height_overflow() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        let sector = ni_sector_builder()
+            .committed_capacity()
+            .seal_randomness_height(u64::MAX)
+            .build();
+
+        run_to_block(4);
+        assert_noop!(
+            StorageProvider::prove_commit_ni_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![ProveCommitNiSector {
+                    sector,
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }]
+            ),
+            DispatchError::Arithmetic(ArithmeticError::Overflow),
+        );
+    });
+}
+
+#[test]
+fn fails_sector_number_repeated() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+
+        let sector = ni_sector_builder().committed_capacity().build();
+
+        run_to_block(4);
+        assert_noop!(
+            StorageProvider::prove_commit_ni_sectors(
+                RuntimeOrigin::signed(account(storage_provider)),
+                bounded_vec![
+                    ProveCommitNiSector {
+                        sector: sector.clone(),
+                        proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                    },
+                    ProveCommitNiSector {
+                        sector,
+                        proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                    },
+                ]
+            ),
+            Error::<Test>::SectorNumberAlreadyUsed,
+        );
+    });
+}
@@ -71,6 +71,15 @@ fn successfully_prove_sector() {
                     client: account(BOB),
                     provider: account(storage_provider)
                 }),
+                // The pre-commit deposit is released and the initial pledge is locked
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Unreserved {
+                    who: account(storage_provider),
+                    amount: 1
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Reserved {
+                    who: account(storage_provider),
+                    amount: 2
+                }),
                 RuntimeEvent::StorageProvider(Event::<Test>::SectorsProven {
                     owner: account(storage_provider),
                     sectors: bounded_vec![ProveCommitResult {
@@ -85,8 +94,9 @@ fn successfully_prove_sector() {
         // check that the funds are still locked
         assert_eq!(
             Balances::free_balance(account(storage_provider)),
-            // Provider reserved 70 tokens in the market pallet and 1 token is used for the pre-commit
-            INITIAL_FUNDS - 70 - 1
+            // Provider reserved 70 tokens in the market pallet and the pre-commit deposit
+            // was replaced by the initial pledge of 2 tokens
+            INITIAL_FUNDS - 70 - 2
         );
         let sp_state = StorageProviders::<Test>::get(account(storage_provider))
             .expect("Should be able to get providers info");
@@ -101,6 +111,90 @@ fn successfully_prove_sector() {
     });
 }
 
+/// Committed capacity sectors are proven along with sectors with deals,
+/// only the deals of the latter are activated.
+#[test]
+fn successfully_prove_committed_capacity_sector() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = CHARLIE;
+        register_storage_provider(account(storage_provider));
+        publish_deals(storage_provider);
+
+        let deal_sector = SectorPreCommitInfoBuilder::default()
+            .sector_number(1.into())
+            .unsealed_cid("baga6ea4seaqhdbbdnon7gkuquzw6waekzqx5lbuio6a6wjie22pgfmwnv3a3wfi")
+            .build();
+        let cc_sector = SectorPreCommitInfoBuilder::default()
+            .sector_number(2.into())
+            .committed_capacity()
+            .build();
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![deal_sector, cc_sector]
+        ));
+        System::reset_events();
+
+        run_to_block(4);
+        assert_ok!(StorageProvider::prove_commit_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            bounded_vec![
+                ProveCommitSector {
+                    sector_number: 1.into(),
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                },
+                ProveCommitSector {
+                    sector_number: 2.into(),
+                    proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+                }
+            ]
+        ));
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 0,
+                    client: account(ALICE),
+                    provider: account(storage_provider)
+                }),
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 1,
+                    client: account(BOB),
+                    provider: account(storage_provider)
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Unreserved {
+                    who: account(storage_provider),
+                    amount: 2
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Reserved {
+                    who: account(storage_provider),
+                    amount: 4
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::SectorsProven {
+                    owner: account(storage_provider),
+                    sectors: bounded_vec![
+                        ProveCommitResult {
+                            sector_number: 1.into(),
+                            deadline_idx: 0,
+                            partition_number: 0,
+                        },
+                        ProveCommitResult {
+                            sector_number: 2.into(),
+                            deadline_idx: 0,
+                            partition_number: 0,
+                        }
+                    ]
+                })
+            ]
+        );
+
+        // Both sectors are included in the deadlines and have to be proven
+        let sp_state = StorageProviders::<Test>::get(account(storage_provider))
+            .expect("Should be able to get providers info");
+        let assigned_partition = &sp_state.deadlines.due[0].partitions[&0];
+        assert_eq!(assigned_partition.sectors.len(), 2);
+    });
+}
+
 #[test]
 fn successfully_prove_multiple_sectors() {
     new_test_ext().execute_with(|| {
@@ -183,6 +277,15 @@ fn successfully_prove_multiple_sectors() {
                     client: account(BOB),
                     provider: account(storage_provider)
                 }),
+                // The pre-commit deposits are released and the initial pledges are locked
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Unreserved {
+                    who: account(storage_provider),
+                    amount: SECTORS_TO_COMMIT as u64
+                }),
+                RuntimeEvent::Balances(pallet_balances::Event::<Test>::Reserved {
+                    who: account(storage_provider),
+                    amount: 2 * SECTORS_TO_COMMIT as u64
+                }),
                 RuntimeEvent::StorageProvider(Event::<Test>::SectorsProven {
                     owner: account(storage_provider),
                     sectors: expected_sector_results
@@ -193,8 +296,9 @@ fn successfully_prove_multiple_sectors() {
         // check that the funds are still locked
         assert_eq!(
             Balances::free_balance(account(storage_provider)),
-            // Provider reserved 70 tokens in the market pallet and 1 token is used per the pre-commit
-            INITIAL_FUNDS - 70 - SECTORS_TO_COMMIT as u64
+            // Provider reserved 70 tokens in the market pallet and the pre-commit deposits
+            // were replaced by the initial pledge of 2 tokens per sector
+            INITIAL_FUNDS - 70 - 2 * SECTORS_TO_COMMIT as u64
         );
         let sp_state = StorageProviders::<Test>::get(account(storage_provider))
             .expect("Should be able to get providers info");
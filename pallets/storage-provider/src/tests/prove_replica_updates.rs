@@ -0,0 +1,195 @@
+use core::str::FromStr;
+
+use cid::Cid;
+use frame_support::{assert_noop, assert_ok, pallet_prelude::ConstU32, BoundedVec};
+use primitives::{
+    commitment::{CommR, Commitment},
+    sector::SectorNumber,
+    CID_SIZE_IN_BYTES,
+};
+use sp_core::bounded_vec;
+use sp_runtime::DispatchError;
+
+use crate::{
+    pallet::{Error, Event, StorageProviders},
+    sector::{ProveCommitSector, ReplicaUpdate, SectorPreCommitInfo},
+    tests::{
+        account, events, new_test_ext, publish_deals, register_storage_provider, run_to_block,
+        RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider,
+        SubmitWindowedPoStBuilder, System, Test, ALICE, BOB, CHARLIE, INVALID_PROOF,
+    },
+};
+
+/// CommD of the deals published by [`publish_deals`].
+const DEALS_UNSEALED_CID: &str = "baga6ea4seaqhdbbdnon7gkuquzw6waekzqx5lbuio6a6wjie22pgfmwnv3a3wfi";
+
+/// Registers Charlie as a storage provider, publishes the deals 0 and 1,
+/// then pre-commits and proves the given sector 1.
+///
+/// The sector is assigned to deadline 0, partition 0,
+/// it's activated by the first PoSt and then its deadline is mutable again.
+fn setup(sector: SectorPreCommitInfo<u64>) {
+    let storage_provider = CHARLIE;
+    register_storage_provider(account(storage_provider));
+    publish_deals(storage_provider);
+
+    assert_ok!(StorageProvider::pre_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![sector]
+    ));
+    assert_ok!(StorageProvider::prove_commit_sectors(
+        RuntimeOrigin::signed(account(storage_provider)),
+        bounded_vec![ProveCommitSector {
+            sector_number: 1.into(),
+            proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+        }]
+    ));
+
+    let proving_period_start = StorageProviders::<Test>::get(account(storage_provider))
+        .unwrap()
+        .proving_period_start;
+    run_to_block(proving_period_start);
+    assert_ok!(StorageProvider::submit_windowed_post(
+        RuntimeOrigin::signed(account(storage_provider)),
+        SubmitWindowedPoStBuilder::default().partition(0).build(),
+    ));
+    // Run past the deadline's challenge window
+    run_to_block(proving_period_start + 5);
+
+    System::reset_events();
+}
+
+/// Builds an update of sector 1 in deadline 0, partition 0, putting the deals 0 and 1 into it.
+fn replica_update(new_unsealed_cid: &str) -> ReplicaUpdate {
+    ReplicaUpdate {
+        sector_number: 1.into(),
+        deadline: 0,
+        partition: 0,
+        new_sealed_cid: new_sealed_cid(),
+        new_unsealed_cid: cid_bytes(new_unsealed_cid),
+        deal_ids: bounded_vec![0, 1],
+        proof: bounded_vec![0xd, 0xe, 0xa, 0xd],
+    }
+}
+
+fn cid_bytes(cid: &str) -> BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>> {
+    BoundedVec::try_from(Cid::from_str(cid).unwrap().to_bytes()).unwrap()
+}
+
+/// CommR of the updated replica.
+fn new_sealed_cid() -> BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>> {
+    BoundedVec::try_from(Commitment::<CommR>::from([1; 32]).cid().to_bytes()).unwrap()
+}
+
+#[test]
+fn successfully_update_committed_capacity_sector() {
+    new_test_ext().execute_with(|| {
+        setup(
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(1.into())
+                .committed_capacity()
+                .build(),
+        );
+
+        assert_ok!(StorageProvider::prove_replica_updates(
+            RuntimeOrigin::signed(account(CHARLIE)),
+            bounded_vec![replica_update(DEALS_UNSEALED_CID)]
+        ));
+
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 0,
+                    client: account(ALICE),
+                    provider: account(CHARLIE)
+                }),
+                RuntimeEvent::Market(pallet_market::Event::DealActivated {
+                    deal_id: 1,
+                    client: account(BOB),
+                    provider: account(CHARLIE)
+                }),
+                RuntimeEvent::StorageProvider(Event::<Test>::ReplicasUpdated {
+                    owner: account(CHARLIE),
+                    sectors: bounded_vec![SectorNumber::from(1)],
+                })
+            ]
+        );
+
+        // The sector now refers to the updated replica
+        let sp = StorageProviders::<Test>::get(account(CHARLIE)).unwrap();
+        let sector = &sp.sectors[&SectorNumber::from(1)];
+        assert_eq!(sector.sealed_cid, new_sealed_cid());
+        assert_eq!(sector.unsealed_cid, cid_bytes(DEALS_UNSEALED_CID));
+        let partition = &sp.deadlines.due[0].partitions[&0];
+        assert!(partition.sectors.contains(&SectorNumber::from(1)));
+    });
+}
+
+/// Sectors that already contain deals cannot be updated.
+#[test]
+fn fails_sector_not_committed_capacity() {
+    new_test_ext().execute_with(|| {
+        setup(
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(1.into())
+                .deals(vec![0])
+                .build(),
+        );
+
+        let mut update = replica_update(DEALS_UNSEALED_CID);
+        update.deal_ids = bounded_vec![1];
+        assert_noop!(
+            StorageProvider::prove_replica_updates(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                bounded_vec![update]
+            ),
+            Error::<Test>::SectorNotCommittedCapacity
+        );
+    });
+}
+
+/// The declared CommD must match the one calculated from the deals.
+#[test]
+fn fails_invalid_unsealed_cid() {
+    new_test_ext().execute_with(|| {
+        setup(
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(1.into())
+                .committed_capacity()
+                .build(),
+        );
+
+        assert_noop!(
+            StorageProvider::prove_replica_updates(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                bounded_vec![replica_update(
+                    "baga6ea4seaqmruupwrxaeck7m3f5jtswpr7jv6bvwqeu5jinzjlcybh6er3ficq"
+                )]
+            ),
+            Error::<Test>::InvalidUnsealedCidForSector
+        );
+    });
+}
+
+#[test]
+fn fails_invalid_proof() {
+    new_test_ext().execute_with(|| {
+        setup(
+            SectorPreCommitInfoBuilder::default()
+                .sector_number(1.into())
+                .committed_capacity()
+                .build(),
+        );
+
+        let mut update = replica_update(DEALS_UNSEALED_CID);
+        update.proof = BoundedVec::try_from(INVALID_PROOF.to_vec()).unwrap();
+        assert_noop!(
+            StorageProvider::prove_replica_updates(
+                RuntimeOrigin::signed(account(CHARLIE)),
+                bounded_vec![update]
+            ),
+            DispatchError::Other("invalid proof")
+        );
+    });
+}
@@ -0,0 +1,183 @@
+use frame_support::assert_ok;
+use primitives::{
+    pallets::{LockedFunds, PreCommittedSector, SectorFaults},
+    sector::SectorNumber,
+};
+use sp_core::bounded_vec;
+
+use crate::{
+    pallet::StorageProviders,
+    tests::{
+        account, declare_faults::setup_sp_with_one_sector, new_test_ext, publish_deals,
+        register_storage_provider, run_to_block, sector_set, DeclareFaultsBuilder,
+        DeclareFaultsRecoveredBuilder, MaxProveCommitDuration, RuntimeOrigin,
+        SectorPreCommitInfoBuilder, StorageProvider, SubmitWindowedPoStBuilder, System, Test,
+        ALICE, BOB, CHARLIE,
+    },
+    Config,
+};
+
+#[test]
+fn queries_return_none_for_unregistered_sp() {
+    new_test_ext().execute_with(|| {
+        assert!(StorageProvider::sector_info(&account(ALICE), 0.into()).is_none());
+        assert!(StorageProvider::sector_faults(&account(ALICE)).is_none());
+        assert!(StorageProvider::pre_committed_sectors(&account(ALICE)).is_none());
+        assert!(StorageProvider::next_deadline(&account(ALICE)).is_none());
+        assert!(StorageProvider::locked_funds(&account(ALICE)).is_none());
+    });
+}
+
+#[test]
+fn sector_info_of_proven_sector() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        let sector = &sp.sectors[&SectorNumber::from(0)];
+        let info = StorageProvider::sector_info(&account(ALICE), 0.into())
+            .expect("sector should have been proven");
+
+        assert_eq!(info.sector_number, 0.into());
+        assert_eq!(info.sealed_cid, sector.sealed_cid);
+        assert_eq!(info.unsealed_cid, sector.unsealed_cid);
+        assert_eq!(info.activation, sector.activation);
+        assert_eq!(info.expiration, sector.expiration);
+        assert_eq!(info.deadline_index, 0);
+        assert_eq!(info.partition_number, 0);
+
+        // Sectors that were not proven have no information
+        assert!(StorageProvider::sector_info(&account(ALICE), 1.into()).is_none());
+    });
+}
+
+#[test]
+fn sector_faults_lists_faulty_and_recovering_sectors() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+        assert_eq!(
+            StorageProvider::sector_faults(&account(ALICE)),
+            Some(SectorFaults {
+                faulty: sector_set(&[]),
+                recovering: sector_set(&[]),
+                terminated: sector_set(&[]),
+            })
+        );
+
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsBuilder::default()
+                .fault(0, 0, &[0.into()])
+                .build(),
+        ));
+        assert_eq!(
+            StorageProvider::sector_faults(&account(ALICE)),
+            Some(SectorFaults {
+                faulty: sector_set(&[0]),
+                recovering: sector_set(&[]),
+                terminated: sector_set(&[]),
+            })
+        );
+
+        assert_ok!(StorageProvider::declare_faults_recovered(
+            RuntimeOrigin::signed(account(ALICE)),
+            DeclareFaultsRecoveredBuilder::default()
+                .fault_recovery(0, 0, &[0.into()])
+                .build(),
+        ));
+        assert_eq!(
+            StorageProvider::sector_faults(&account(ALICE)),
+            Some(SectorFaults {
+                faulty: sector_set(&[0]),
+                recovering: sector_set(&[0]),
+                terminated: sector_set(&[]),
+            })
+        );
+    });
+}
+
+#[test]
+fn pre_committed_sectors_with_prove_commit_due() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(ALICE));
+        publish_deals(ALICE);
+        assert_eq!(
+            StorageProvider::pre_committed_sectors(&account(ALICE)),
+            Some(bounded_vec![])
+        );
+
+        let sector = SectorPreCommitInfoBuilder::default().build();
+        assert_ok!(StorageProvider::pre_commit_sectors(
+            RuntimeOrigin::signed(account(ALICE)),
+            bounded_vec![sector.clone()]
+        ));
+
+        let pre_commit_block_number = System::block_number();
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert_eq!(
+            StorageProvider::pre_committed_sectors(&account(ALICE)),
+            Some(bounded_vec![PreCommittedSector {
+                sector_number: sector.sector_number,
+                pre_commit_deposit: sp.pre_commit_deposits,
+                pre_commit_block_number,
+                prove_commit_due: pre_commit_block_number + MaxProveCommitDuration::get(),
+                expiration: sector.expiration,
+            }])
+        );
+    });
+}
+
+#[test]
+fn next_deadline_skips_proven_deadline() {
+    new_test_ext().execute_with(|| {
+        register_storage_provider(account(CHARLIE));
+        // Nothing to prove without sectors
+        assert!(StorageProvider::next_deadline(&account(CHARLIE)).is_none());
+
+        setup_sp_with_one_sector(ALICE, BOB);
+        let proving_period_start = StorageProviders::<Test>::get(account(ALICE))
+            .unwrap()
+            .proving_period_start;
+
+        let deadline = StorageProvider::next_deadline(&account(ALICE)).unwrap();
+        assert_eq!(deadline.deadline_index, 0);
+        assert_eq!(deadline.start, proving_period_start);
+        assert!(!deadline.open);
+
+        run_to_block(proving_period_start);
+        let deadline = StorageProvider::next_deadline(&account(ALICE)).unwrap();
+        assert_eq!(deadline.start, proving_period_start);
+        assert!(deadline.open);
+
+        assert_ok!(StorageProvider::submit_windowed_post(
+            RuntimeOrigin::signed(account(ALICE)),
+            SubmitWindowedPoStBuilder::default().partition(0).build(),
+        ));
+
+        // The deadline was proven, the next one is in the following proving period
+        let deadline = StorageProvider::next_deadline(&account(ALICE)).unwrap();
+        assert_eq!(deadline.deadline_index, 0);
+        assert_eq!(
+            deadline.start,
+            proving_period_start + <Test as Config>::WPoStProvingPeriod::get()
+        );
+        assert!(!deadline.open);
+    });
+}
+
+#[test]
+fn locked_funds_of_proven_sector() {
+    new_test_ext().execute_with(|| {
+        setup_sp_with_one_sector(ALICE, BOB);
+
+        let sp = StorageProviders::<Test>::get(account(ALICE)).unwrap();
+        assert!(sp.initial_pledge > 0);
+        assert_eq!(
+            StorageProvider::locked_funds(&account(ALICE)),
+            Some(LockedFunds {
+                pre_commit_deposits: 0,
+                initial_pledge: sp.initial_pledge,
+            })
+        );
+    });
+}
@@ -18,7 +18,8 @@ fn successful_registration() {
         let window_post_type = RegisteredPoStProof::StackedDRGWindow2KiBV1P1;
         let expected_sector_size = window_post_type.sector_size();
         let expected_partition_sectors = window_post_type.window_post_partitions_sector();
-        let expected_sp_info = StorageProviderInfo::new(peer_id.clone(), window_post_type);
+        let expected_sp_info =
+            StorageProviderInfo::new(account(BOB), peer_id.clone(), window_post_type);
 
         // Register BOB as a storage provider.
         assert_ok!(StorageProvider::register_storage_provider(
@@ -31,6 +32,9 @@ fn successful_registration() {
         // `unwrap()` should be safe because of the above check.
         let sp_bob = StorageProviders::<Test>::get(account(BOB)).unwrap();
         // Check that storage provider information is correct.
+        assert_eq!(sp_bob.info.owner, account(BOB));
+        assert_eq!(sp_bob.info.worker, account(BOB));
+        assert!(sp_bob.info.control_addresses.is_empty());
         assert_eq!(sp_bob.info.peer_id, peer_id);
         assert_eq!(sp_bob.info.window_post_proof_type, window_post_type);
         assert_eq!(sp_bob.info.sector_size, expected_sector_size);
@@ -1,3 +1,7 @@
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+
 use frame_support::{assert_noop, assert_ok};
 use primitives::PartitionNumber;
 use rstest::rstest;
@@ -6,13 +10,14 @@ use sp_runtime::{BoundedVec, DispatchError};
 
 use crate::{
     error::GeneralPalletError,
+    fault::{DeclareFaultsParams, FaultDeclaration},
     pallet::{Error, Event, StorageProviders},
-    sector::ProveCommitSector,
+    sector::{ProveCommitSector, TerminateSectorsParams, TerminationDeclaration},
     tests::{
         account, declare_faults::setup_sp_with_many_sectors_multiple_partitions, events,
-        new_test_ext, register_storage_provider, run_to_block, DealProposalBuilder, Market,
-        RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder, StorageProvider,
-        SubmitWindowedPoStBuilder, System, Test, ALICE, BOB, INVALID_PROOF,
+        new_test_ext, register_storage_provider, run_to_block, sector_set, DealProposalBuilder,
+        Market, PoStReplicas, RuntimeEvent, RuntimeOrigin, SectorPreCommitInfoBuilder,
+        StorageProvider, SubmitWindowedPoStBuilder, System, Test, ALICE, BOB, INVALID_PROOF,
     },
     Config,
 };
@@ -191,6 +196,136 @@ fn submit_windowed_post_multiple_partitions() {
     });
 }
 
+#[test]
+fn fail_windowed_post_multiple_partitions_one_invalid() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = ALICE;
+        let storage_client = BOB;
+        setup_sp_with_many_sectors_multiple_partitions(storage_provider, storage_client);
+        // Run to block where the window post proof is to be submitted
+        let proving_period_start = StorageProviders::<Test>::get(account(storage_provider))
+            .unwrap()
+            .proving_period_start;
+        run_to_block(proving_period_start);
+
+        // The proof of the second partition is invalid
+        let windowed_post = SubmitWindowedPoStBuilder::default()
+            .partitions(bounded_vec![0, 1])
+            .proof_bytes([[0x1, 0x2], INVALID_PROOF].concat())
+            .build();
+
+        // Run extrinsic
+        assert_noop!(
+            StorageProvider::submit_windowed_post(
+                RuntimeOrigin::signed(account(ALICE)),
+                windowed_post,
+            ),
+            DispatchError::Other("invalid proof")
+        );
+    });
+}
+
+#[test]
+fn fail_windowed_post_proof_not_split_between_partitions() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = ALICE;
+        let storage_client = BOB;
+        setup_sp_with_many_sectors_multiple_partitions(storage_provider, storage_client);
+        // Run to block where the window post proof is to be submitted
+        let proving_period_start = StorageProviders::<Test>::get(account(storage_provider))
+            .unwrap()
+            .proving_period_start;
+        run_to_block(proving_period_start);
+
+        // 4 bytes of proofs can't be split evenly between 3 partitions
+        let windowed_post = SubmitWindowedPoStBuilder::default()
+            .partitions(bounded_vec![0, 1, 2])
+            .proof_bytes(vec![0x1, 0x2, 0x3, 0x4])
+            .build();
+
+        // Run extrinsic
+        assert_noop!(
+            StorageProvider::submit_windowed_post(
+                RuntimeOrigin::signed(account(ALICE)),
+                windowed_post,
+            ),
+            Error::<Test>::PoStProofInvalid
+        );
+    });
+}
+
+#[test]
+fn submit_windowed_post_without_terminated_and_faulty_sectors() {
+    new_test_ext().execute_with(|| {
+        let storage_provider = ALICE;
+        let storage_client = BOB;
+        setup_sp_with_many_sectors_multiple_partitions(storage_provider, storage_client);
+        let sp = StorageProviders::<Test>::get(account(storage_provider)).unwrap();
+        let partition_sectors = |partition: PartitionNumber| {
+            sp.deadlines.due[0].partitions[&partition]
+                .sectors
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        let (first_partition, second_partition) = (partition_sectors(0), partition_sectors(1));
+
+        // The first sector of the first partition is terminated, the first sector of the second one is faulty
+        assert_ok!(StorageProvider::terminate_sectors(
+            RuntimeOrigin::signed(account(storage_provider)),
+            TerminateSectorsParams {
+                terminations: bounded_vec![TerminationDeclaration {
+                    deadline: 0,
+                    partition: 0,
+                    sectors: sector_set(&[first_partition[0].into()]),
+                }],
+            },
+        ));
+        assert_ok!(StorageProvider::declare_faults(
+            RuntimeOrigin::signed(account(storage_provider)),
+            DeclareFaultsParams {
+                faults: bounded_vec![FaultDeclaration {
+                    deadline: 0,
+                    partition: 1,
+                    sectors: sector_set(&[second_partition[0].into()]),
+                }],
+            },
+        ));
+
+        // Run to block where the window post proof is to be submitted
+        let proving_period_start = StorageProviders::<Test>::get(account(storage_provider))
+            .unwrap()
+            .proving_period_start;
+        run_to_block(proving_period_start);
+
+        let windowed_post = SubmitWindowedPoStBuilder::default()
+            .partitions(bounded_vec![0, 1])
+            .proof_bytes(vec![0x1, 0x2])
+            .build();
+        assert_ok!(StorageProvider::submit_windowed_post(
+            RuntimeOrigin::signed(account(storage_provider)),
+            windowed_post,
+        ));
+
+        // Only the remaining sectors are proven, the same ones returned by `deadline_state`
+        let expected = [&first_partition[1..], &second_partition[1..]]
+            .map(|sectors| sectors.iter().copied().collect::<BTreeSet<_>>())
+            .to_vec();
+        assert_eq!(PoStReplicas::get(), expected);
+        let deadline_state =
+            StorageProvider::deadline_state(&account(storage_provider), 0).unwrap();
+        assert_eq!(
+            deadline_state
+                .partitions
+                .values()
+                .take(2)
+                .map(|partition| partition.sectors.clone().into_inner())
+                .collect::<Vec<_>>(),
+            expected
+        );
+    });
+}
+
 #[test]
 fn submit_windowed_post_for_sector_twice() {
     new_test_ext().execute_with(|| {
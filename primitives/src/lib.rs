@@ -10,6 +10,21 @@ pub type DealId = u64;
 
 pub type PartitionNumber = u32;
 
+/// A storage provider's dialable address, i.e. the bytes of a multiaddr string such as `/ip4/127.0.0.1/tcp/8001`.
+pub type Multiaddr = sp_runtime::BoundedVec<u8, sp_core::ConstU32<MAX_MULTIADDR_BYTES>>;
+
+/// Maximum length, in bytes, of a single multiaddr.
+/// It fits the longest IP address a provider can be dialed at, with its port and peer ID,
+/// e.g. `/ip6/<39 characters>/tcp/65535/p2p/<52 characters>` is 111 bytes.
+/// DNS names are limited to what's left, which covers the host names used in practice.
+pub const MAX_MULTIADDR_BYTES: u32 = 128;
+
+/// Maximum number of multiaddrs a storage provider can advertise.
+/// A provider needs one address per transport and IP version it listens on, i.e. a handful in practice.
+/// The multiaddrs are part of the provider's info, so this caps them at 2KiB (16 * [`MAX_MULTIADDR_BYTES`]),
+/// twice the 1024 bytes of multiaddr data Filecoin's miner actor allows (`MAX_MULTIADDR_DATA`).
+pub const MAX_MULTIADDRS: u32 = 16;
+
 /// Merkle tree node size in bytes.
 pub const NODE_SIZE: usize = 32;
 
@@ -51,13 +66,28 @@ pub const MAX_TERMINATIONS_PER_CALL: u32 = 32; // TODO(@jmg-duarte,25/07/2024):
 pub const MAX_SECTORS_PER_PROOF: u32 = 2349;
 
 /// The absolute maximum length, in bytes, a seal proof should be for the largest sector size.
-/// NOTE: Taken the value from `StackedDRG32GiBV1`,
-/// which is not the biggest seal proof type but we do not plan on supporting non-interactive proof types at this time.
+/// NOTE: Taken the value from `StackedDRG32GiBV1`, which is not the biggest seal proof type.
+/// Non-interactive proofs of big sectors use many more partitions and would need a larger bound,
+/// the non-interactive types supported at this time, up to 512MiB sectors, have two partitions, 384 bytes.
 ///
 /// References:
 /// * <https://github.com/filecoin-project/ref-fvm/blob/32583cc05aa422c8e1e7ba81d56a888ac9d90e61/shared/src/sector/registered_proof.rs#L90>
 pub const MAX_SEAL_PROOF_BYTES: u32 = 1_920;
 
+/// Number of sectors that can be proven by a single batched prove commit.
+/// Required for BoundedVec.
+///
+/// A batch carries the whole seal proof of every sector, so at [`MAX_SEAL_PROOF_BYTES`]
+/// a full batch is 480KiB, which leaves plenty of room in the 5MiB parachain block
+/// (3.75MiB for normal extrinsics) for the rest of the block's extrinsics.
+/// Filecoin's `MAX_AGGREGATED_SECTORS` allows up to 819 sectors, but SnarkPack aggregated proofs
+/// grow logarithmically with the amount of sectors, while our batches grow linearly.
+pub const MAX_BATCHED_SECTORS: u32 = 256;
+
+/// The absolute maximum length, in bytes, of a batch of seal proofs,
+/// made of the seal proofs of all of the batched sectors.
+pub const MAX_BATCH_PROOF_BYTES: u32 = MAX_SEAL_PROOF_BYTES * MAX_BATCHED_SECTORS;
+
 /// The fixed length, in bytes, of a PoSt proof.
 /// This value is the same as `PROOF_BYTES` in the `polka-storage-proofs` library.
 /// It is redefined to avoid import the whole library for 1 constant.
@@ -65,3 +95,30 @@ pub const MAX_SEAL_PROOF_BYTES: u32 = 1_920;
 /// References:
 /// * <https://github.com/filecoin-project/ref-fvm/blob/32583cc05aa422c8e1e7ba81d56a888ac9d90e61/shared/src/sector/registered_proof.rs#L159>
 pub const MAX_POST_PROOF_BYTES: u32 = 192;
+
+/// Max amount of partitions that can be proven by a single Windowed PoSt submission.
+/// Each of the partitions is proven by its own PoSt proof, all of them are verified in a single batch.
+///
+/// Filecoin bounds the partitions of a submission as well, through `load_partitions_sectors_max`,
+/// so the cost of verifying a single message stays bounded. Here the verification weight grows linearly
+/// with the partitions, a Groth16 proof and the public inputs of its sectors each, while the batch shares the final pairing check.
+/// 16 partitions keep the submission's proofs at 3KiB ([`MAX_WINDOWED_POST_PROOF_BYTES`]) and its verification a small part of a block,
+/// while a deadline with more partitions only needs a few more submissions.
+pub const MAX_PARTITIONS_PER_POST: u32 = 16;
+
+/// The absolute maximum length, in bytes, of the proofs of a single Windowed PoSt submission,
+/// one PoSt proof for each of the partitions being proven.
+pub const MAX_WINDOWED_POST_PROOF_BYTES: u32 = MAX_POST_PROOF_BYTES * MAX_PARTITIONS_PER_POST;
+
+/// The absolute maximum length, in bytes, a replica update proof should be for the largest sector size.
+/// Taken from `StackedDRG32GiBV1`'s update proof, made of 16 partition proofs, 192 bytes each.
+///
+/// References:
+/// * <https://github.com/filecoin-project/ref-fvm/blob/32583cc05aa422c8e1e7ba81d56a888ac9d90e61/shared/src/sector/registered_proof.rs>
+pub const MAX_REPLICA_UPDATE_PROOF_BYTES: u32 = 3_072;
+
+/// Max amount of deal IDs returned by a single page of a client's or a storage provider's deals.
+/// Each deal ID of the page is a storage read of the deal index, and 8 bytes of the runtime API's response,
+/// so a page is at most 1024 reads and an 8KiB response, cheap for a node to serve over RPC.
+/// It still covers 8 sectors full of deals ([`MAX_DEALS_PER_SECTOR`]), so most providers are listed in a few pages.
+pub const MAX_DEALS_PER_PAGE: u32 = 1024;
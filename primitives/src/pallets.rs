@@ -1,21 +1,45 @@
+extern crate alloc;
+use alloc::vec::Vec;
+
 use cid::Cid;
-use codec::{Codec, Decode, Encode};
+use codec::{Codec, Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 use sp_core::{ConstU32, RuntimeDebug};
-use sp_runtime::{BoundedBTreeMap, BoundedBTreeSet, BoundedVec, DispatchError, DispatchResult};
+use sp_runtime::{
+    BoundedBTreeMap, BoundedBTreeSet, BoundedVec, DispatchError, DispatchResult, Weight,
+};
 
 use crate::{
     commitment::RawCommitment,
-    proofs::{ProverId, PublicReplicaInfo, RegisteredPoStProof, RegisteredSealProof, Ticket},
+    proofs::{
+        ProverId, PublicReplicaInfo, PublicSealInfo, RegisteredPoStProof, RegisteredSealProof,
+        RegisteredUpdateProof, Ticket,
+    },
     sector::SectorNumber,
-    DealId, PartitionNumber, MAX_DEALS_PER_SECTOR, MAX_PARTITIONS_PER_DEADLINE,
-    MAX_POST_PROOF_BYTES, MAX_SEAL_PROOF_BYTES, MAX_SECTORS, MAX_SECTORS_PER_CALL,
-    MAX_SECTORS_PER_PROOF,
+    DealId, Multiaddr, PartitionNumber, CID_SIZE_IN_BYTES, MAX_BATCHED_SECTORS,
+    MAX_BATCH_PROOF_BYTES, MAX_DEALS_PER_PAGE, MAX_DEALS_PER_SECTOR, MAX_MULTIADDRS,
+    MAX_PARTITIONS_PER_DEADLINE, MAX_PARTITIONS_PER_POST, MAX_REPLICA_UPDATE_PROOF_BYTES,
+    MAX_SEAL_PROOF_BYTES, MAX_SECTORS, MAX_SECTORS_PER_CALL, MAX_SECTORS_PER_PROOF,
+    MAX_WINDOWED_POST_PROOF_BYTES,
 };
 
-pub trait StorageProviderValidation<AccountId> {
+pub trait StorageProviderValidation<AccountId, BlockNumber> {
     /// Checks that the storage provider is registered.
     fn is_registered_storage_provider(storage_provider: &AccountId) -> bool;
+
+    /// Resolves the owner of the storage provider the account acts for,
+    /// the account can be the owner itself, its worker or one of its control addresses.
+    ///
+    /// Returns [`None`] if the account doesn't act for any storage provider.
+    fn storage_provider_owner(account: &AccountId) -> Option<AccountId>;
+
+    /// Returns the block at which the storage provider's proven sector expires.
+    ///
+    /// Returns [`None`] if the storage provider or the sector don't exist.
+    fn sector_expiration(
+        storage_provider: &AccountId,
+        sector_number: SectorNumber,
+    ) -> Option<BlockNumber>;
 }
 
 /// Entrypoint for proof verification implemented by Pallet Proofs.
@@ -31,11 +55,53 @@ pub trait ProofVerification {
         proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
     ) -> DispatchResult;
 
+    /// Verifies a non-interactive PoRep proof, its challenges are derived from the sector's
+    /// commitments, so there's no interactive seed.
+    fn verify_ni_porep(
+        prover_id: ProverId,
+        seal_proof: RegisteredSealProof,
+        comm_r: RawCommitment,
+        comm_d: RawCommitment,
+        sector: SectorNumber,
+        ticket: Ticket,
+        proof: BoundedVec<u8, ConstU32<MAX_SEAL_PROOF_BYTES>>,
+    ) -> DispatchResult;
+
+    /// Verifies a batch of PoRep proofs of many sectors, sealed with the same seal proof type.
+    /// The proof is made of the sectors' proofs, one after another, in the same order as `sectors`.
+    fn verify_batch_porep(
+        prover_id: ProverId,
+        seal_proof: RegisteredSealProof,
+        sectors: BoundedVec<PublicSealInfo, ConstU32<MAX_BATCHED_SECTORS>>,
+        proof: BoundedVec<u8, ConstU32<MAX_BATCH_PROOF_BYTES>>,
+    ) -> DispatchResult;
+
+    /// Weight of [`ProofVerification::verify_batch_porep`] for the given amount of sectors.
+    fn verify_batch_porep_weight(sectors: u32) -> Weight;
+
+    /// Verifies the PoSt proofs of many partitions at once, each of the partitions has its own replicas.
+    /// The proof is made of the partitions' proofs, one after another, in the same order as `partitions`.
     fn verify_post(
         post_type: RegisteredPoStProof,
         randomness: Ticket,
-        replicas: BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>,
-        proof: BoundedVec<u8, ConstU32<MAX_POST_PROOF_BYTES>>,
+        partitions: BoundedVec<
+            BoundedBTreeMap<SectorNumber, PublicReplicaInfo, ConstU32<MAX_SECTORS_PER_PROOF>>,
+            ConstU32<MAX_PARTITIONS_PER_POST>,
+        >,
+        proof: BoundedVec<u8, ConstU32<MAX_WINDOWED_POST_PROOF_BYTES>>,
+    ) -> DispatchResult;
+
+    /// Weight of [`ProofVerification::verify_post`] for the given amount of partitions.
+    fn verify_post_weight(partitions: u32) -> Weight;
+
+    /// Verifies that the replica `comm_r_old` of a committed capacity sector
+    /// was updated into `comm_r_new`, containing the data committed to by `comm_d_new`.
+    fn verify_replica_update(
+        update_proof: RegisteredUpdateProof,
+        comm_r_old: RawCommitment,
+        comm_r_new: RawCommitment,
+        comm_d_new: RawCommitment,
+        proof: BoundedVec<u8, ConstU32<MAX_REPLICA_UPDATE_PROOF_BYTES>>,
     ) -> DispatchResult;
 }
 
@@ -115,6 +181,50 @@ pub struct ActiveDeal<AccountId> {
     pub piece_size: u64,
 }
 
+/// Client's consent to cancel a published deal.
+/// The client signs its [`DealCancellation::signing_payload`], so the provider can cancel the deal without forfeiting a fee.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Copy)]
+pub struct DealCancellation<Hash> {
+    /// Hash of the chain's genesis block, so the consent can't be replayed on another chain.
+    pub genesis_hash: Hash,
+    /// Deal to be cancelled, deal IDs are never reused, so the consent can't be replayed.
+    pub deal_id: DealId,
+}
+
+impl<Hash: Encode> DealCancellation<Hash> {
+    /// Tag the signed payload starts with, so the signature can't be passed off as the signature of another message.
+    pub const DOMAIN_TAG: &'static [u8] = b"polka-storage/market/deal-cancellation";
+
+    /// Bytes signed by the client, the SCALE encoding of [`Self::DOMAIN_TAG`] followed by the cancellation.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        (Self::DOMAIN_TAG, self).encode()
+    }
+}
+
+/// Client's agreement to extend an active deal, with a new end block and price per block.
+/// The client signs its [`DealExtension::signing_payload`], for the provider to submit it along with the extension.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Copy)]
+pub struct DealExtension<Hash, Balance, BlockNumber> {
+    /// Hash of the chain's genesis block, so the agreement can't be replayed on another chain.
+    pub genesis_hash: Hash,
+    /// Deal to be extended.
+    pub deal_id: DealId,
+    /// The deal's new end block, the agreement can't be replayed once the deal ends after it.
+    pub end_block: BlockNumber,
+    /// The price per block of the storage from the extension onwards.
+    pub storage_price_per_block: Balance,
+}
+
+impl<Hash: Encode, Balance: Encode, BlockNumber: Encode> DealExtension<Hash, Balance, BlockNumber> {
+    /// Tag the signed payload starts with, so the signature can't be passed off as the signature of another message.
+    pub const DOMAIN_TAG: &'static [u8] = b"polka-storage/market/deal-extension";
+
+    /// Bytes signed by the client, the SCALE encoding of [`Self::DOMAIN_TAG`] followed by the extension.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        (Self::DOMAIN_TAG, self).encode()
+    }
+}
+
 /// Current deadline in a proving period of a Storage Provider.
 #[derive(Encode, Decode, TypeInfo)]
 pub struct DeadlineInfo<BlockNumber> {
@@ -144,11 +254,114 @@ pub struct DeadlineState {
 
 #[derive(Encode, Decode, TypeInfo)]
 pub struct PartitionState {
+    /// Sectors to be proven by a Windowed PoSt, i.e. live sectors that are not faulty, or are declared as recovered.
     pub sectors: BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>>,
 }
 
+/// Information clients need to reach a storage provider.
+#[derive(Encode, Decode, TypeInfo)]
+pub struct StorageProviderPeerInfo<PeerId> {
+    /// Libp2p identity of the storage provider.
+    pub peer_id: PeerId,
+    /// Addresses the storage provider can be dialed at.
+    pub multiaddrs: BoundedVec<Multiaddr, ConstU32<MAX_MULTIADDRS>>,
+}
+
+/// Power of a storage provider, or of the whole network, in bytes.
+///
+/// References:
+/// * <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/power/src/state.rs>
+#[derive(Clone, Copy, Default, RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct StoragePower {
+    /// Sum of the sizes of the sectors, regardless of their content.
+    pub raw_byte_power: u128,
+    /// Sum of the sizes of the sectors, weighted by the quality of their content.
+    pub quality_adj_power: u128,
+}
+
+/// On-chain information about a proven sector.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct SectorInfo<BlockNumber> {
+    pub sector_number: SectorNumber,
+    /// Seal proof type used to seal the sector.
+    pub seal_proof: RegisteredSealProof,
+    /// Commitment of replication (CommR).
+    pub sealed_cid: BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>>,
+    /// Commitment of data (CommD).
+    pub unsealed_cid: BoundedVec<u8, ConstU32<CID_SIZE_IN_BYTES>>,
+    /// Block at which the sector was proven.
+    pub activation: BlockNumber,
+    /// Block at which the sector expires.
+    pub expiration: BlockNumber,
+    /// Deadline the sector is assigned to.
+    pub deadline_index: u64,
+    /// Partition of the deadline the sector is assigned to.
+    pub partition_number: PartitionNumber,
+}
+
+/// Sectors of a storage provider that are not being proven, across all of its deadlines.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct SectorFaults {
+    /// Sectors declared or detected faulty, including the recovering ones.
+    pub faulty: BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>>,
+    /// Faulty sectors declared as recovered, expected to be proven in their next deadline.
+    pub recovering: BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>>,
+    /// Sectors terminated but not yet removed from their partitions.
+    pub terminated: BoundedBTreeSet<SectorNumber, ConstU32<MAX_SECTORS>>,
+}
+
+/// A sector that has been pre-committed but not yet proven.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct PreCommittedSector<Balance, BlockNumber> {
+    pub sector_number: SectorNumber,
+    /// Funds locked until the sector is proven.
+    pub pre_commit_deposit: Balance,
+    /// Block at which the sector was pre-committed.
+    pub pre_commit_block_number: BlockNumber,
+    /// First block at which the sector can no longer be proven, its deposit is slashed then.
+    pub prove_commit_due: BlockNumber,
+    /// Block at which the sector expires.
+    pub expiration: BlockNumber,
+}
+
+/// Funds locked by a storage provider.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct LockedFunds<Balance> {
+    /// Sum of the deposits of the pre-committed sectors.
+    pub pre_commit_deposits: Balance,
+    /// Sum of the initial pledges of the proven sectors.
+    pub initial_pledge: Balance,
+}
+
+/// Funds required to publish a deal.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct DealQuote<Balance> {
+    /// Total storage fee, paid by the client over the deal's duration, it's locked when the deal is published.
+    pub storage_fee: Balance,
+    /// Collateral the storage provider locks when the deal is published.
+    pub provider_collateral: Balance,
+}
+
+/// Funds held by a market participant.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct MarketBalance<Balance> {
+    /// Funds that can be withdrawn or locked for new deals.
+    pub free: Balance,
+    /// Funds locked for published and active deals.
+    pub locked: Balance,
+}
+
+/// Identifies a verifying key set on chain, so the proving parameters can be checked against it.
+#[derive(RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone, Copy)]
+pub struct VerifyingKeyInfo {
+    /// Blake2b-256 hash of the SCALE encoded verifying key.
+    pub hash: [u8; 32],
+    /// Number of times the key of this proof type has been set, starting at 1.
+    pub version: u32,
+}
+
 sp_api::decl_runtime_apis! {
-    pub trait StorageProviderApi<AccountId> where AccountId: Codec
+    pub trait StorageProviderApi<AccountId, PeerId, Balance> where AccountId: Codec, PeerId: Codec, Balance: Codec
     {
         /// Gets the information about the specified deadline of the storage provider.
         ///
@@ -164,5 +377,105 @@ sp_api::decl_runtime_apis! {
         /// Returns snapshot information about the deadline, i.e. which sectors are assigned to which partitions.
         /// When the deadline has not opened yet (deadline_start - WPoStChallengeWindow), it can change!
         fn deadline_state(storage_provider: AccountId, deadline_index: u64) -> Option<DeadlineState>;
+
+        /// Returns the peer ID and multiaddrs clients use to reach the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        fn peer_info(storage_provider: AccountId) -> Option<StorageProviderPeerInfo<PeerId>>;
+
+        /// Returns the power of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        fn storage_provider_power(storage_provider: AccountId) -> Option<StoragePower>;
+
+        /// Returns the total power of the network, i.e. the sum of the power of all storage providers.
+        fn total_power() -> StoragePower;
+
+        /// Returns the on-chain information about a proven sector and where it is assigned.
+        ///
+        /// If there is no Storage Provider of given AccountId or it has no such sector returns [`Option::None`].
+        fn sector_info(storage_provider: AccountId, sector_number: SectorNumber) -> Option<
+            SectorInfo<
+                <<Block as sp_runtime::traits::Block>::Header as sp_runtime::traits::Header>::Number
+            >
+        >;
+
+        /// Returns the faulty, recovering and terminated sectors of the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        fn sector_faults(storage_provider: AccountId) -> Option<SectorFaults>;
+
+        /// Returns the pre-committed sectors of the storage provider, along with the block by which they must be proven.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        fn pre_committed_sectors(storage_provider: AccountId) -> Option<
+            BoundedVec<
+                PreCommittedSector<
+                    Balance,
+                    <<Block as sp_runtime::traits::Block>::Header as sp_runtime::traits::Header>::Number
+                >,
+                ConstU32<MAX_SECTORS>
+            >
+        >;
+
+        /// Returns the next deadline in which the storage provider needs to submit a Windowed PoSt,
+        /// i.e. the earliest not elapsed deadline with live sectors that have not been proven yet.
+        ///
+        /// If there is no Storage Provider of given AccountId or it has no sectors to prove returns [`Option::None`].
+        fn next_deadline(storage_provider: AccountId) -> Option<
+            DeadlineInfo<
+                <<Block as sp_runtime::traits::Block>::Header as sp_runtime::traits::Header>::Number
+            >
+        >;
+
+        /// Returns the funds locked by the storage provider.
+        ///
+        /// If there is no Storage Provider of given AccountId returns [`Option::None`].
+        fn locked_funds(storage_provider: AccountId) -> Option<LockedFunds<Balance>>;
+    }
+
+    pub trait MarketApi<AccountId, Balance, ClientDealProposal> where AccountId: Codec, Balance: Codec, ClientDealProposal: Codec
+    {
+        /// Returns the storage fee and collateral needed to publish the signed deal proposal,
+        /// or the reason why publishing it would currently fail. The deal is not published.
+        fn quote_deal(deal: ClientDealProposal) -> Result<DealQuote<Balance>, DispatchError>;
+
+        /// Returns the free and locked funds of the market participant.
+        fn balance(account: AccountId) -> MarketBalance<Balance>;
+
+        /// Returns the payment the storage provider can currently claim by settling the deal.
+        ///
+        /// If the deal doesn't exist returns [`Option::None`].
+        fn claimable_settlement(deal_id: DealId) -> Option<Balance>;
+
+        /// Returns a page of the client's deals, with at most `limit` deal IDs, capped at [`MAX_DEALS_PER_PAGE`].
+        ///
+        /// The first page starts with `start_after` set to [`Option::None`],
+        /// the next ones start after the last deal ID returned in the previous page.
+        fn client_deals(client: AccountId, start_after: Option<DealId>, limit: u32) -> BoundedVec<DealId, ConstU32<MAX_DEALS_PER_PAGE>>;
+
+        /// Returns a page of the storage provider's deals, with at most `limit` deal IDs, capped at [`MAX_DEALS_PER_PAGE`].
+        ///
+        /// Pages are requested like in [`MarketApi::client_deals`].
+        fn provider_deals(storage_provider: AccountId, start_after: Option<DealId>, limit: u32) -> BoundedVec<DealId, ConstU32<MAX_DEALS_PER_PAGE>>;
+    }
+
+    pub trait ProofsApi
+    {
+        /// Returns the hash and version of the verifying key used for PoRep proofs of the seal proof type,
+        /// non-interactive seal proof types return the information of the non-interactive key.
+        ///
+        /// If there is no verifying key set for the seal proof type returns [`Option::None`].
+        fn porep_verifying_key_info(seal_proof: RegisteredSealProof) -> Option<VerifyingKeyInfo>;
+
+        /// Returns the hash and version of the verifying key used for PoSt proofs of the PoSt proof type.
+        ///
+        /// If there is no verifying key set for the PoSt proof type returns [`Option::None`].
+        fn post_verifying_key_info(post_type: RegisteredPoStProof) -> Option<VerifyingKeyInfo>;
+
+        /// Returns the hash and version of the verifying key used for replica update proofs of the update proof type.
+        ///
+        /// If there is no verifying key set for the update proof type returns [`Option::None`].
+        fn replica_update_verifying_key_info(update_proof: RegisteredUpdateProof) -> Option<VerifyingKeyInfo>;
     }
 }
@@ -4,7 +4,10 @@ use scale_encode::EncodeAsType;
 use scale_info::TypeInfo;
 use sp_core::blake2_256;
 
-use crate::{commitment::RawCommitment, sector::SectorSize};
+use crate::{
+    commitment::RawCommitment,
+    sector::{SectorNumber, SectorSize},
+};
 
 /// Byte representation of the entity that was signing the proof.
 /// It must match the ProverId used for Proving.
@@ -38,6 +41,21 @@ pub struct PublicReplicaInfo {
     pub comm_r: RawCommitment,
 }
 
+/// The public information about a sector, required to verify its seal proof.
+#[derive(Clone, core::fmt::Debug, PartialEq, Eq)]
+pub struct PublicSealInfo {
+    /// The sector number.
+    pub sector: SectorNumber,
+    /// The replica commitment (CommR).
+    pub comm_r: RawCommitment,
+    /// The data commitment (CommD).
+    pub comm_d: RawCommitment,
+    /// The randomness the sector was sealed with.
+    pub ticket: Ticket,
+    /// The randomness the sector's challenges were generated with.
+    pub seed: Ticket,
+}
+
 #[allow(non_camel_case_types)]
 #[derive(
     Debug, Decode, Encode, DecodeAsType, EncodeAsType, TypeInfo, Eq, PartialEq, Clone, Copy,
@@ -53,20 +71,69 @@ pub enum RegisteredSealProof {
     #[cfg_attr(feature = "clap", clap(name = "2KiB"))]
     #[cfg_attr(feature = "serde", serde(alias = "2KiB"))]
     StackedDRG2KiBV1P1,
+    /// Non-interactive PoRep, the challenges are derived from the sector's commitments instead of
+    /// randomness drawn after the pre-commit, so the sector can be proven right after it's sealed.
+    ///
+    /// References:
+    /// * <https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0090.md>
+    #[cfg_attr(feature = "clap", clap(name = "2KiB-NI"))]
+    #[cfg_attr(feature = "serde", serde(alias = "2KiB-NI"))]
+    StackedDRG2KiBV1P2_Feat_NiPoRep,
+    #[cfg_attr(feature = "clap", clap(name = "8MiB"))]
+    #[cfg_attr(feature = "serde", serde(alias = "8MiB"))]
+    StackedDRG8MiBV1P1,
+    #[cfg_attr(feature = "clap", clap(name = "8MiB-NI"))]
+    #[cfg_attr(feature = "serde", serde(alias = "8MiB-NI"))]
+    StackedDRG8MiBV1P2_Feat_NiPoRep,
+    #[cfg_attr(feature = "clap", clap(name = "512MiB"))]
+    #[cfg_attr(feature = "serde", serde(alias = "512MiB"))]
+    StackedDRG512MiBV1P1,
+    #[cfg_attr(feature = "clap", clap(name = "512MiB-NI"))]
+    #[cfg_attr(feature = "serde", serde(alias = "512MiB-NI"))]
+    StackedDRG512MiBV1P2_Feat_NiPoRep,
 }
 
 impl RegisteredSealProof {
     pub fn sector_size(&self) -> SectorSize {
-        SectorSize::_2KiB
+        match self {
+            RegisteredSealProof::StackedDRG2KiBV1P1
+            | RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep => SectorSize::_2KiB,
+            RegisteredSealProof::StackedDRG8MiBV1P1
+            | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep => SectorSize::_8MiB,
+            RegisteredSealProof::StackedDRG512MiBV1P1
+            | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => SectorSize::_512MiB,
+        }
+    }
+
+    /// Whether the proof is generated without interactive randomness,
+    /// in which case the sector is onboarded in a single step, without a pre-commit.
+    pub fn is_non_interactive(&self) -> bool {
+        match self {
+            RegisteredSealProof::StackedDRG2KiBV1P1
+            | RegisteredSealProof::StackedDRG8MiBV1P1
+            | RegisteredSealProof::StackedDRG512MiBV1P1 => false,
+            RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep
+            | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep
+            | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => true,
+        }
     }
 
     /// Produces the windowed PoSt-specific RegisteredProof corresponding
     /// to the receiving RegisteredProof.
     pub fn registered_window_post_proof(&self) -> RegisteredPoStProof {
         match self {
-            RegisteredSealProof::StackedDRG2KiBV1P1 => {
+            RegisteredSealProof::StackedDRG2KiBV1P1
+            | RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep => {
                 RegisteredPoStProof::StackedDRGWindow2KiBV1P1
             }
+            RegisteredSealProof::StackedDRG8MiBV1P1
+            | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep => {
+                RegisteredPoStProof::StackedDRGWindow8MiBV1P1
+            }
+            RegisteredSealProof::StackedDRG512MiBV1P1
+            | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => {
+                RegisteredPoStProof::StackedDRGWindow512MiBV1P1
+            }
         }
     }
 
@@ -76,7 +143,73 @@ impl RegisteredSealProof {
     /// * <https://github.com/filecoin-project/ref-fvm/blob/b72a51084f3b65f8bd41f4a9a733d43bb4b1d6f7/shared/src/sector/registered_proof.rs#L90>
     pub fn proof_size(self) -> usize {
         match self {
-            RegisteredSealProof::StackedDRG2KiBV1P1 => 192,
+            // A single partition.
+            RegisteredSealProof::StackedDRG2KiBV1P1
+            | RegisteredSealProof::StackedDRG8MiBV1P1
+            | RegisteredSealProof::StackedDRG512MiBV1P1 => 192,
+            // Two partitions of 192 bytes each, as generated by `filecoin-proofs` for the test sector sizes.
+            RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep
+            | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep
+            | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => 384,
+        }
+    }
+
+    /// Produces the replica update-specific RegisteredProof corresponding
+    /// to the receiving RegisteredProof.
+    pub fn registered_update_proof(&self) -> RegisteredUpdateProof {
+        match self {
+            RegisteredSealProof::StackedDRG2KiBV1P1
+            | RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep => {
+                RegisteredUpdateProof::StackedDRG2KiBV1
+            }
+            RegisteredSealProof::StackedDRG8MiBV1P1
+            | RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep => {
+                RegisteredUpdateProof::StackedDRG8MiBV1
+            }
+            RegisteredSealProof::StackedDRG512MiBV1P1
+            | RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep => {
+                RegisteredUpdateProof::StackedDRG512MiBV1
+            }
+        }
+    }
+}
+
+/// Replica update (a.k.a. SnapDeals) proof type, indicating version and sector size of the proof.
+///
+/// References:
+/// * <https://github.com/filecoin-project/rust-filecoin-proofs-api/blob/b44e7cecf2a120aa266b6886628e869ba67252af/src/registry.rs>
+#[derive(
+    Debug, Decode, Encode, DecodeAsType, EncodeAsType, TypeInfo, PartialEq, Eq, Clone, Copy,
+)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+#[codec(crate = ::codec)]
+#[decode_as_type(crate_path = "::scale_decode")]
+#[encode_as_type(crate_path = "::scale_encode")]
+pub enum RegisteredUpdateProof {
+    StackedDRG2KiBV1,
+    StackedDRG8MiBV1,
+    StackedDRG512MiBV1,
+}
+
+impl RegisteredUpdateProof {
+    /// Returns the sector size of the proof type, which is measured in bytes.
+    pub fn sector_size(&self) -> SectorSize {
+        match self {
+            RegisteredUpdateProof::StackedDRG2KiBV1 => SectorSize::_2KiB,
+            RegisteredUpdateProof::StackedDRG8MiBV1 => SectorSize::_8MiB,
+            RegisteredUpdateProof::StackedDRG512MiBV1 => SectorSize::_512MiB,
+        }
+    }
+
+    /// Proof size in bytes, a single partition proof for each of the update partitions.
+    ///
+    /// Reference:
+    /// * <https://github.com/filecoin-project/ref-fvm/blob/32583cc05aa422c8e1e7ba81d56a888ac9d90e61/shared/src/sector/registered_proof.rs>
+    pub fn proof_size(&self) -> usize {
+        match self {
+            RegisteredUpdateProof::StackedDRG2KiBV1 => 192,
+            RegisteredUpdateProof::StackedDRG8MiBV1 => 192 * 4,
+            RegisteredUpdateProof::StackedDRG512MiBV1 => 192 * 16,
         }
     }
 }
@@ -94,6 +227,12 @@ pub enum RegisteredPoStProof {
     #[cfg_attr(feature = "clap", clap(name = "2KiB"))]
     #[cfg_attr(feature = "serde", serde(alias = "2KiB"))]
     StackedDRGWindow2KiBV1P1,
+    #[cfg_attr(feature = "clap", clap(name = "8MiB"))]
+    #[cfg_attr(feature = "serde", serde(alias = "8MiB"))]
+    StackedDRGWindow8MiBV1P1,
+    #[cfg_attr(feature = "clap", clap(name = "512MiB"))]
+    #[cfg_attr(feature = "serde", serde(alias = "512MiB"))]
+    StackedDRGWindow512MiBV1P1,
 }
 
 impl RegisteredPoStProof {
@@ -101,6 +240,8 @@ impl RegisteredPoStProof {
     pub fn sector_size(&self) -> SectorSize {
         match self {
             RegisteredPoStProof::StackedDRGWindow2KiBV1P1 => SectorSize::_2KiB,
+            RegisteredPoStProof::StackedDRGWindow8MiBV1P1 => SectorSize::_8MiB,
+            RegisteredPoStProof::StackedDRGWindow512MiBV1P1 => SectorSize::_512MiB,
         }
     }
 
@@ -109,7 +250,9 @@ impl RegisteredPoStProof {
     pub fn window_post_partitions_sector(&self) -> u64 {
         // Resolve to post proof and then compute size from that.
         match self {
-            RegisteredPoStProof::StackedDRGWindow2KiBV1P1 => 2,
+            RegisteredPoStProof::StackedDRGWindow2KiBV1P1
+            | RegisteredPoStProof::StackedDRGWindow8MiBV1P1
+            | RegisteredPoStProof::StackedDRGWindow512MiBV1P1 => 2,
         }
     }
 
@@ -119,7 +262,9 @@ impl RegisteredPoStProof {
     /// * <https://github.com/filecoin-project/rust-fil-proofs/blob/266acc39a3ebd6f3d28c6ee335d78e2b7cea06bc/filecoin-proofs/src/constants.rs#L102>
     pub fn sector_count(&self) -> usize {
         match self {
-            RegisteredPoStProof::StackedDRGWindow2KiBV1P1 => 2,
+            RegisteredPoStProof::StackedDRGWindow2KiBV1P1
+            | RegisteredPoStProof::StackedDRGWindow8MiBV1P1
+            | RegisteredPoStProof::StackedDRGWindow512MiBV1P1 => 2,
         }
     }
 }
@@ -142,6 +287,18 @@ mod serde_tests {
             serde_json::from_str::<RegisteredSealProof>(r#""StackedDRG2KiBV1P1""#).unwrap(),
             RegisteredSealProof::StackedDRG2KiBV1P1
         );
+        assert_eq!(
+            serde_json::from_str::<RegisteredSealProof>(r#""2KiB-NI""#).unwrap(),
+            RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep
+        );
+        assert_eq!(
+            serde_json::from_str::<RegisteredSealProof>(r#""8MiB""#).unwrap(),
+            RegisteredSealProof::StackedDRG8MiBV1P1
+        );
+        assert_eq!(
+            serde_json::from_str::<RegisteredSealProof>(r#""512MiB-NI""#).unwrap(),
+            RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep
+        );
     }
 
     #[test]
@@ -154,5 +311,9 @@ mod serde_tests {
             serde_json::from_str::<RegisteredPoStProof>(r#""StackedDRGWindow2KiBV1P1""#).unwrap(),
             RegisteredPoStProof::StackedDRGWindow2KiBV1P1
         );
+        assert_eq!(
+            serde_json::from_str::<RegisteredPoStProof>(r#""512MiB""#).unwrap(),
+            RegisteredPoStProof::StackedDRGWindow512MiBV1P1
+        );
     }
 }
@@ -34,7 +34,7 @@ use frame_support::{
     parameter_types,
     traits::{ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, TransformOrigin},
     weights::{ConstantMultiplier, Weight},
-    BoundedVec, PalletId,
+    PalletId,
 };
 use frame_system::{
     limits::{BlockLength, BlockWeights},
@@ -55,12 +55,12 @@ use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 use super::{
     weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight},
     AccountId, Aura, Balance, Balances, Block, BlockNumber, CollatorSelection, ConsensusHook, Hash,
-    MessageQueue, Nonce, PalletInfo, ParachainSystem, Runtime, RuntimeCall, RuntimeEvent,
+    MessageQueue, Nonce, PalletInfo, ParachainSystem, PeerId, Runtime, RuntimeCall, RuntimeEvent,
     RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session, SessionKeys,
     System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT, HOURS,
     MAXIMUM_BLOCK_WEIGHT, MICROUNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
 };
-use crate::{BabeDataGetter, DAYS, MINUTES};
+use crate::{BabeDataGetter, DAYS, MINUTES, UNIT};
 
 parameter_types! {
     pub const Version: RuntimeVersion = VERSION;
@@ -328,12 +328,26 @@ parameter_types! {
     pub const PreCommitChallengeDelay: BlockNumber = 75 * MINUTES;
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L299>
     pub const AddressedSectorsMax: u64 = 25_000;
+    /// Filecoin uses the chain finality (900 epochs of 30 seconds), adapted to substrate block time.
+    pub const WorkerKeyChangeDelay: BlockNumber = 7 * HOURS + 30 * MINUTES;
+    pub const ExpectedBlockReward: Balance = UNIT;
+    /// 1 PiB
+    pub const BaselinePower: u128 = 1 << 50;
+    /// Filecoin locks 20 days worth of the expected reward for both.
+    /// <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L36-L41>
+    pub const PreCommitDepositProjectionPeriod: BlockNumber = 20 * DAYS;
+    pub const InitialPledgeProjectionPeriod: BlockNumber = 20 * DAYS;
+    /// <https://github.com/filecoin-project/builtin-actors/blob/17ede2b256bc819dc309edf38e031e246a516486/actors/miner/src/monies.rs#L74-L85>
+    pub const ContinuedFaultProjectionPeriod: BlockNumber = 351 * DAYS / 100;
+    /// <https://github.com/filecoin-project/specs-actors/blob/v2.3.6/actors/builtin/miner/monies.go>
+    pub const UndeclaredFaultProjectionPeriod: BlockNumber = 551 * DAYS / 100;
 
     // Market Pallet
     /// Deal duration values copied from FileCoin.
     /// <https://github.com/filecoin-project/builtin-actors/blob/c32c97229931636e3097d92cf4c43ac36a7b4b47/actors/market/src/policy.rs#L28>
     pub const MinDealDuration: u64 = 20 * DAYS;
     pub const MaxDealDuration: u64 = 1278 * DAYS;
+    pub const DealSettlementInterval: BlockNumber = DAYS;
 
 }
 
@@ -354,10 +368,19 @@ parameter_types! {
     pub const PreCommitChallengeDelay: BlockNumber = 1 * MINUTES;
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L299>
     pub const AddressedSectorsMax: u64 = 25_000;
+    pub const WorkerKeyChangeDelay: BlockNumber = 2 * MINUTES;
+    pub const ExpectedBlockReward: Balance = UNIT;
+    /// 1 GiB
+    pub const BaselinePower: u128 = 1 << 30;
+    pub const PreCommitDepositProjectionPeriod: BlockNumber = 20 * MINUTES;
+    pub const InitialPledgeProjectionPeriod: BlockNumber = 20 * MINUTES;
+    pub const ContinuedFaultProjectionPeriod: BlockNumber = 351 * MINUTES / 100;
+    pub const UndeclaredFaultProjectionPeriod: BlockNumber = 551 * MINUTES / 100;
 
     // Market Pallet
     pub const MinDealDuration: u64 = 5 * MINUTES;
     pub const MaxDealDuration: u64 = 180 * MINUTES;
+    pub const DealSettlementInterval: BlockNumber = 10 * MINUTES;
 
     // Faucet pallet
     pub const FaucetDripAmount: Balance = 10_000_000_000_000;
@@ -368,7 +391,7 @@ impl pallet_storage_provider::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Randomness = crate::Randomness;
     type AuthorVrfHistory = crate::Randomness;
-    type PeerId = BoundedVec<u8, ConstU32<32>>; // Max length of SHA256 hash
+    type PeerId = PeerId;
     type Currency = Balances;
     type Market = crate::Market;
     type ProofVerification = crate::Proofs;
@@ -387,11 +410,20 @@ impl pallet_storage_provider::Config for Runtime {
     // <https://github.com/filecoin-project/builtin-actors/blob/8d957d2901c0f2044417c268f0511324f591cb92/runtime/src/runtime/policy.rs#L295>
     type AddressedPartitionsMax = MaxPartitionsPerDeadline;
     type AddressedSectorsMax = AddressedSectorsMax;
+    type WorkerKeyChangeDelay = WorkerKeyChangeDelay;
+    type ExpectedBlockReward = ExpectedBlockReward;
+    type BaselinePower = BaselinePower;
+    type PreCommitDepositProjectionPeriod = PreCommitDepositProjectionPeriod;
+    type InitialPledgeProjectionPeriod = InitialPledgeProjectionPeriod;
+    type ContinuedFaultProjectionPeriod = ContinuedFaultProjectionPeriod;
+    type UndeclaredFaultProjectionPeriod = UndeclaredFaultProjectionPeriod;
 }
 
 parameter_types! {
     /// PalletId of Market Pallet, used to convert it to AccountId which holds the Market funds
     pub const MarketPalletId: PalletId = PalletId(*b"spMarket");
+    /// Share of the collateral a provider forfeits to the client when cancelling a deal on its own.
+    pub const DealCancellationFee: Perbill = Perbill::from_percent(10);
 }
 
 pub type AccountPublic = <MultiSignature as Verify>::Signer;
@@ -407,10 +439,15 @@ impl pallet_market::Config for Runtime {
     type MaxDealsPerBlock = ConstU32<128>;
     type MinDealDuration = MinDealDuration;
     type MaxDealDuration = MaxDealDuration;
+    type DealSettlementInterval = DealSettlementInterval;
+    type MaxDealSettlementsPerBlock = ConstU32<128>;
+    type DealCancellationFee = DealCancellationFee;
 }
 
 impl pallet_proofs::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
+    type UpdateOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = pallet_proofs::weights::SubstrateWeight<Runtime>;
 }
 
 #[cfg(feature = "testnet")]
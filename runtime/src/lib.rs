@@ -60,6 +60,9 @@ pub type Hash = sp_core::H256;
 /// An index to a block.
 pub type BlockNumber = u64;
 
+/// Libp2p identity of a storage provider.
+pub type PeerId = sp_runtime::BoundedVec<u8, sp_core::ConstU32<32>>; // Max length of SHA256 hash
+
 /// The address format for describing accounts.
 pub type Address = MultiAddress<AccountId, ()>;
 
@@ -93,6 +96,20 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic =
     generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+    pallet_storage_provider::migrations::v1::MigrateV0ToV1<Runtime>,
+    pallet_storage_provider::migrations::v2::MigrateV1ToV2<Runtime>,
+    pallet_storage_provider::migrations::v3::MigrateV2ToV3<Runtime>,
+    pallet_storage_provider::migrations::v4::MigrateV3ToV4<Runtime>,
+    pallet_storage_provider::migrations::v5::MigrateV4ToV5<Runtime>,
+    pallet_proofs::migrations::v1::MigrateV0ToV1<Runtime>,
+    pallet_proofs::migrations::v2::MigrateV1ToV2<Runtime>,
+    pallet_proofs::migrations::v3::MigrateV2ToV3<Runtime>,
+    pallet_market::migrations::v1::MigrateV0ToV1<Runtime>,
+    pallet_market::migrations::v2::MigrateV1ToV2<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -100,6 +117,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
@@ -407,7 +425,7 @@ impl Runtime {
 }
 
 impl_runtime_apis! {
-    impl primitives::pallets::StorageProviderApi<Block, AccountId> for Runtime {
+    impl primitives::pallets::StorageProviderApi<Block, AccountId, PeerId, Balance> for Runtime {
         fn deadline_state(storage_provider: AccountId, deadline_index: u64) -> Option<primitives::pallets::DeadlineState> {
             StorageProvider::deadline_state(&storage_provider, deadline_index)
         }
@@ -415,6 +433,74 @@ impl_runtime_apis! {
         fn deadline_info(storage_provider: AccountId, deadline_index: u64) -> Option<primitives::pallets::DeadlineInfo<BlockNumber>> {
             StorageProvider::deadline_info(&storage_provider, deadline_index)
         }
+
+        fn peer_info(storage_provider: AccountId) -> Option<primitives::pallets::StorageProviderPeerInfo<PeerId>> {
+            StorageProvider::peer_info(&storage_provider)
+        }
+
+        fn storage_provider_power(storage_provider: AccountId) -> Option<primitives::pallets::StoragePower> {
+            StorageProvider::storage_provider_power(&storage_provider)
+        }
+
+        fn total_power() -> primitives::pallets::StoragePower {
+            StorageProvider::total_power()
+        }
+
+        fn sector_info(storage_provider: AccountId, sector_number: primitives::sector::SectorNumber) -> Option<primitives::pallets::SectorInfo<BlockNumber>> {
+            StorageProvider::sector_info(&storage_provider, sector_number)
+        }
+
+        fn sector_faults(storage_provider: AccountId) -> Option<primitives::pallets::SectorFaults> {
+            StorageProvider::sector_faults(&storage_provider)
+        }
+
+        fn pre_committed_sectors(storage_provider: AccountId) -> Option<sp_runtime::BoundedVec<primitives::pallets::PreCommittedSector<Balance, BlockNumber>, sp_core::ConstU32<{ primitives::MAX_SECTORS }>>> {
+            StorageProvider::pre_committed_sectors(&storage_provider)
+        }
+
+        fn next_deadline(storage_provider: AccountId) -> Option<primitives::pallets::DeadlineInfo<BlockNumber>> {
+            StorageProvider::next_deadline(&storage_provider)
+        }
+
+        fn locked_funds(storage_provider: AccountId) -> Option<primitives::pallets::LockedFunds<Balance>> {
+            StorageProvider::locked_funds(&storage_provider)
+        }
+    }
+
+    impl primitives::pallets::MarketApi<Block, AccountId, Balance, pallet_market::ClientDealProposal<AccountId, Balance, BlockNumber, Signature>> for Runtime {
+        fn quote_deal(deal: pallet_market::ClientDealProposal<AccountId, Balance, BlockNumber, Signature>) -> Result<primitives::pallets::DealQuote<Balance>, sp_runtime::DispatchError> {
+            Market::quote_deal(deal)
+        }
+
+        fn balance(account: AccountId) -> primitives::pallets::MarketBalance<Balance> {
+            Market::balance(&account)
+        }
+
+        fn claimable_settlement(deal_id: primitives::DealId) -> Option<Balance> {
+            Market::claimable_settlement(deal_id)
+        }
+
+        fn client_deals(client: AccountId, start_after: Option<primitives::DealId>, limit: u32) -> sp_runtime::BoundedVec<primitives::DealId, sp_core::ConstU32<{ primitives::MAX_DEALS_PER_PAGE }>> {
+            Market::client_deals(&client, start_after, limit)
+        }
+
+        fn provider_deals(storage_provider: AccountId, start_after: Option<primitives::DealId>, limit: u32) -> sp_runtime::BoundedVec<primitives::DealId, sp_core::ConstU32<{ primitives::MAX_DEALS_PER_PAGE }>> {
+            Market::provider_deals(&storage_provider, start_after, limit)
+        }
+    }
+
+    impl primitives::pallets::ProofsApi<Block> for Runtime {
+        fn porep_verifying_key_info(seal_proof: primitives::proofs::RegisteredSealProof) -> Option<primitives::pallets::VerifyingKeyInfo> {
+            Proofs::porep_verifying_key_info(seal_proof)
+        }
+
+        fn post_verifying_key_info(post_type: primitives::proofs::RegisteredPoStProof) -> Option<primitives::pallets::VerifyingKeyInfo> {
+            Proofs::post_verifying_key_info(post_type)
+        }
+
+        fn replica_update_verifying_key_info(update_proof: primitives::proofs::RegisteredUpdateProof) -> Option<primitives::pallets::VerifyingKeyInfo> {
+            Proofs::replica_update_verifying_key_info(update_proof)
+        }
     }
 
     impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
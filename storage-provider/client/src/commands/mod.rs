@@ -89,6 +89,14 @@ pub(crate) enum Cli {
         client_deal_proposal: SxtClientDealProposal,
     },
 
+    /// Pledge a committed capacity sector, without any deals.
+    /// The sector is sealed, pre-committed and proven by the provider in the background.
+    PledgeSector {
+        /// URL of the providers RPC server.
+        #[arg(long, default_value = DEFAULT_RPC_SERVER_URL)]
+        rpc_server_url: Url,
+    },
+
     /// Sign a storage deal using the provided key, will output the deal as a JSON
     /// — no information is shared across the network.
     SignDeal {
@@ -127,6 +135,7 @@ impl Cli {
                 rpc_server_url,
                 client_deal_proposal,
             } => Self::publish_deal(rpc_server_url, client_deal_proposal).await,
+            Self::PledgeSector { rpc_server_url } => Self::pledge_sector(rpc_server_url).await,
             Self::SignDeal {
                 deal_proposal,
                 signer_key,
@@ -165,6 +174,13 @@ impl Cli {
         Ok(())
     }
 
+    async fn pledge_sector(rpc_server_url: Url) -> Result<(), CliError> {
+        let client = PolkaStorageRpcClient::new(&rpc_server_url).await?;
+        let result = client.pledge_sector().await?;
+        println!("Successfully pledged sector of number: {}", result);
+        Ok(())
+    }
+
     fn sign_deal(
         deal_proposal: SxtDealProposal,
         signer_key: MultiPairArgs,
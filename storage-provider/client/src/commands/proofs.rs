@@ -10,7 +10,7 @@ use mater::CarV2Reader;
 use polka_storage_proofs::{
     porep::{self, sealer::Sealer},
     post::{self, ReplicaInfo},
-    ZeroPaddingReader,
+    update, ZeroPaddingReader,
 };
 use polka_storage_provider_common::commp::{calculate_piece_commitment, CommPError};
 use primitives::{
@@ -83,9 +83,10 @@ pub enum ProofsCommand {
         /// The height at which we draw the randomness for deriving a sealed cid.
         #[arg(long)]
         seal_randomness_height: u64,
-        /// Precommit block number
+        /// Precommit block number, the interactive seed is drawn from it.
+        /// Not required by non-interactive seal proofs, as those sectors are not pre-committed.
         #[arg(long)]
-        pre_commit_block_number: u64,
+        pre_commit_block_number: Option<u64>,
     },
     /// Generates PoSt verifying key and proving parameters for zk-SNARK workflows (submit windowed PoSt)
     #[clap(name = "post-params")]
@@ -130,6 +131,17 @@ pub enum ProofsCommand {
         /// CID - CommR of a replica (output of `porep` command)
         comm_r: String,
     },
+    /// Generates replica update verifying key and proving parameters for zk-SNARK workflows (prove replica updates)
+    #[clap(name = "update-params")]
+    GenerateUpdateParams {
+        /// Replica updates are dependent on the sector size of the updated sector.
+        /// Parameters are required for each sector size and its corresponding PoRep.
+        #[arg(short, long, default_value = "2KiB")]
+        seal_proof: RegisteredSealProof,
+        /// Directory where the params files will be put. Defaults to the current directory.
+        #[arg(short, long)]
+        output_path: Option<PathBuf>,
+    },
 }
 
 const POREP_PARAMS_EXT: &str = "porep.params";
@@ -140,6 +152,10 @@ const POST_PARAMS_EXT: &str = "post.params";
 const POST_VK_EXT: &str = "post.vk";
 const POST_VK_EXT_SCALE: &str = "post.vk.scale";
 
+const UPDATE_PARAMS_EXT: &str = "update.params";
+const UPDATE_VK_EXT: &str = "update.vk";
+const UPDATE_VK_EXT_SCALE: &str = "update.vk.scale";
+
 const POREP_PROOF_EXT: &str = "sector.proof.porep.scale";
 const POST_PROOF_EXT: &str = "sector.proof.post.scale";
 
@@ -187,7 +203,12 @@ impl ProofsCommand {
                     std::env::current_dir()?
                 };
 
-                let file_name: String = seal_proof.sector_size().to_string();
+                // Non-interactive proofs use their own circuit, hence their own parameters.
+                let file_name: String = if seal_proof.is_non_interactive() {
+                    format!("{}-ni", seal_proof.sector_size())
+                } else {
+                    seal_proof.sector_size().to_string()
+                };
 
                 let (parameters_file_name, mut parameters_file) =
                     file_with_extension(&output_path, file_name.as_str(), POREP_PARAMS_EXT)?;
@@ -248,18 +269,27 @@ impl ProofsCommand {
                     hex::encode(ticket)
                 );
 
-                // The number added is configured in runtime:
-                // https://github.com/eigerco/polka-storage/blob/18207759d7c6c175916d5bed70246d94a8f028f4/runtime/src/configs/mod.rs#L360
-                let interactive_block_number = pre_commit_block_number + 10;
-                let seed = get_randomness(
-                    DomainSeparationTag::InteractiveSealChallengeSeed,
-                    interactive_block_number,
-                    &entropy,
-                );
-                println!(
-                    "[{interactive_block_number}] Seed randomness: {}",
-                    hex::encode(seed)
-                );
+                // Non-interactive proofs derive their challenges from the replica, without a seed.
+                let seed = if seal_proof.is_non_interactive() {
+                    None
+                } else {
+                    let Some(pre_commit_block_number) = pre_commit_block_number else {
+                        return Err(UtilsCommandError::MissingPreCommitBlockNumber)?;
+                    };
+                    // The number added is configured in runtime:
+                    // https://github.com/eigerco/polka-storage/blob/18207759d7c6c175916d5bed70246d94a8f028f4/runtime/src/configs/mod.rs#L360
+                    let interactive_block_number = pre_commit_block_number + 10;
+                    let seed = get_randomness(
+                        DomainSeparationTag::InteractiveSealChallengeSeed,
+                        interactive_block_number,
+                        &entropy,
+                    );
+                    println!(
+                        "[{interactive_block_number}] Seed randomness: {}",
+                        hex::encode(seed)
+                    );
+                    Some(seed)
+                };
 
                 let output_path = if let Some(output_path) = output_path {
                     output_path
@@ -344,7 +374,7 @@ impl ProofsCommand {
                         prover_id,
                         sector_number,
                         ticket,
-                        Some(seed),
+                        seed,
                         precommit,
                         &piece_infos,
                     )
@@ -353,13 +383,16 @@ impl ProofsCommand {
                 println!("CommD: {}", precommit.comm_d.cid());
                 println!("CommR: {}", precommit.comm_r.cid());
                 println!("Proof: {:?}", proofs);
-                // We use sector size 2KiB only at this point, which guarantees to have 1 proof, because it has 1 partition in the config.
-                // That's why `prove_commit` will always generate a 1 proof.
-                let proof_scale: polka_storage_proofs::Proof<bls12_381::Bls12> = proofs[0]
-                    .clone()
-                    .try_into()
-                    .expect("converstion between rust-fil-proofs and polka-storage-proofs to work");
-                let scale_encoded_proof = codec::Encode::encode(&proof_scale);
+                // Interactive proofs of all the supported sector sizes, up to 512MiB, have 1 partition, hence 1 proof,
+                // while non-interactive ones have a proof for each of their partitions, one after another.
+                let mut scale_encoded_proof = Vec::new();
+                for partition_proof in proofs {
+                    let proof_scale: polka_storage_proofs::Proof<bls12_381::Bls12> =
+                        partition_proof.try_into().expect(
+                            "converstion between rust-fil-proofs and polka-storage-proofs to work",
+                        );
+                    scale_encoded_proof.extend(codec::Encode::encode(&proof_scale));
+                }
                 proof_scale_file.write_all(&scale_encoded_proof)?;
 
                 println!("Proof as HEX: {}", hex::encode(scale_encoded_proof));
@@ -404,6 +437,45 @@ impl ProofsCommand {
                 println!("{}", vk_file_name.display());
                 println!("{}", vk_scale_file_name.display());
             }
+            ProofsCommand::GenerateUpdateParams {
+                seal_proof,
+                output_path,
+            } => {
+                let output_path = if let Some(output_path) = output_path {
+                    output_path
+                } else {
+                    std::env::current_dir()?
+                };
+
+                let file_name: String = seal_proof.sector_size().to_string();
+
+                let (parameters_file_name, mut parameters_file) =
+                    file_with_extension(&output_path, file_name.as_str(), UPDATE_PARAMS_EXT)?;
+                let (vk_file_name, mut vk_file) =
+                    file_with_extension(&output_path, file_name.as_str(), UPDATE_VK_EXT)?;
+                let (vk_scale_file_name, mut vk_scale_file) =
+                    file_with_extension(&output_path, file_name.as_str(), UPDATE_VK_EXT_SCALE)?;
+
+                println!(
+                    "Generating replica update params for {} sectors... It can take a couple of minutes ⌛",
+                    file_name
+                );
+                let parameters = update::generate_random_groth16_parameters(seal_proof)
+                    .map_err(|e| UtilsCommandError::GeneratePoRepError(e))?;
+                parameters.write(&mut parameters_file)?;
+                parameters.vk.write(&mut vk_file)?;
+
+                let vk =
+                    polka_storage_proofs::VerifyingKey::<bls12_381::Bls12>::try_from(parameters.vk)
+                        .map_err(|e| UtilsCommandError::FromBytesError(e))?;
+                let bytes = codec::Encode::encode(&vk);
+                vk_scale_file.write_all(&bytes)?;
+
+                println!("Generated parameters: ");
+                println!("{}", parameters_file_name.display());
+                println!("{}", vk_file_name.display());
+                println!("{}", vk_scale_file_name.display());
+            }
             ProofsCommand::PoSt {
                 signer_key,
                 post_type,
@@ -510,6 +582,8 @@ pub enum UtilsCommandError {
     InvalidCARv2(PathBuf, mater::Error),
     #[error("no signer key was provider")]
     NoSigner,
+    #[error("the pre-commit block number is required by interactive seal proofs")]
+    MissingPreCommitBlockNumber,
 }
 
 fn file_with_extension(
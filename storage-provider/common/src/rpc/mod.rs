@@ -4,7 +4,10 @@ use std::fmt;
 
 use chrono::{DateTime, Utc};
 use jsonrpsee::proc_macros::rpc;
-use primitives::proofs::{RegisteredPoStProof, RegisteredSealProof};
+use primitives::{
+    proofs::{RegisteredPoStProof, RegisteredSealProof},
+    sector::SectorNumber,
+};
 use serde::{Deserialize, Serialize};
 use storagext::types::market::{
     ClientDealProposal as SxtClientDealProposal, DealProposal as SxtDealProposal,
@@ -27,6 +30,11 @@ pub trait StorageProviderRpc {
     /// Publish a deal, the published deal ID will be returned.
     #[method(name = "publish_deal")]
     async fn publish_deal(&self, deal: SxtClientDealProposal) -> Result<u64, RpcError>;
+
+    /// Pledge a committed capacity sector, without any deals, the sector number will be returned.
+    /// The sector is sealed, pre-committed and proven in the background.
+    #[method(name = "pledge_sector")]
+    async fn pledge_sector(&self) -> Result<SectorNumber, RpcError>;
 }
 
 /// Storage Provider server information, such as start time and on-chain address.
@@ -31,8 +31,13 @@ pub enum DBError {
 
 const ACCEPTED_DEAL_PROPOSALS_CF: &str = "accepted_deal_proposals";
 const SECTORS_CF: &str = "sectors";
+const COMMITTED_CAPACITY_SECTORS_CF: &str = "committed_capacity_sectors";
 
-const COLUMN_FAMILIES: [&str; 2] = [ACCEPTED_DEAL_PROPOSALS_CF, SECTORS_CF];
+const COLUMN_FAMILIES: [&str; 3] = [
+    ACCEPTED_DEAL_PROPOSALS_CF,
+    SECTORS_CF,
+    COMMITTED_CAPACITY_SECTORS_CF,
+];
 
 pub struct DealDB {
     database: RocksDB,
@@ -152,6 +157,56 @@ impl DealDB {
         Ok(())
     }
 
+    /// Marks the proven sector as committed capacity, so deals can be put into it with a replica update.
+    pub fn add_committed_capacity_sector(
+        &self,
+        sector_number: SectorNumber,
+        expiration: u64,
+    ) -> Result<(), DBError> {
+        let cf_handle = self.cf_handle(COMMITTED_CAPACITY_SECTORS_CF);
+        let key = u32::from(sector_number).to_le_bytes();
+
+        self.database
+            .put_cf(cf_handle, key, expiration.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    /// Takes the first committed capacity sector which expires at or after `min_expiration`.
+    /// The sector is removed from the committed capacity sectors, so it's not handed out twice.
+    pub fn take_committed_capacity_sector(
+        &self,
+        min_expiration: u64,
+    ) -> Result<Option<SectorNumber>, DBError> {
+        let cf_handle = self.cf_handle(COMMITTED_CAPACITY_SECTORS_CF);
+        for item in self
+            .database
+            .iterator_cf(cf_handle, rocksdb::IteratorMode::Start)
+        {
+            let (key, value) = item?;
+            let expiration: [u8; 8] = value
+                .as_ref()
+                .try_into()
+                .expect("sector's expiration to be u64 le bytes");
+            if u64::from_le_bytes(expiration) < min_expiration {
+                continue;
+            }
+
+            self.database.delete_cf(cf_handle, &key)?;
+            let key: [u8; 4] = key
+                .as_ref()
+                .try_into()
+                .expect("sector's key to be u32 le bytes");
+            // Unwrap safe. Can only fail if the sector number was manually
+            // inserted in the database.
+            let sector_number =
+                SectorNumber::new(u32::from_le_bytes(key)).expect("valid sector number");
+            return Ok(Some(sector_number));
+        }
+
+        Ok(None)
+    }
+
     /// Takes all of the existing sectors, finds the maximum sector id.
     /// The simplest way possible of generating an id.
     /// This function is private for a reason. It should only be called once at the DealDB initialization.
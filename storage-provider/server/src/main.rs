@@ -14,6 +14,7 @@ use pipeline::types::PipelineMessage;
 use polka_storage_proofs::{
     porep::{self, PoRepParameters},
     post::{self, PoStParameters},
+    Bls12, FromBytesError, VerifyingKey,
 };
 use polka_storage_provider_common::rpc::ServerInfo;
 use primitives::proofs::{RegisteredPoStProof, RegisteredSealProof};
@@ -23,14 +24,17 @@ use storagext::{
     runtime::runtime_types::{
         bounded_collections::bounded_vec::BoundedVec,
         pallet_storage_provider::storage_provider::StorageProviderState,
+        primitives::pallets::VerifyingKeyInfo,
     },
-    MarketClientExt, StorageProviderClientExt,
+    MarketClientExt, ProofsClientExt, StorageProviderClientExt,
 };
 use subxt::{
     ext::sp_core::{
-        ecdsa::Pair as ECDSAPair, ed25519::Pair as Ed25519Pair, sr25519::Pair as Sr25519Pair,
+        blake2_256, ecdsa::Pair as ECDSAPair, ed25519::Pair as Ed25519Pair,
+        sr25519::Pair as Sr25519Pair,
     },
     tx::Signer,
+    utils::AccountId32,
 };
 use tokio::{sync::mpsc::UnboundedReceiver, task::JoinError};
 use tokio_util::sync::CancellationToken;
@@ -136,9 +140,18 @@ pub enum ServerError {
     #[error("failed to load PoRep parameters from: {0}, because: {1}")]
     InvalidPoRepParameters(std::path::PathBuf, porep::PoRepError),
 
+    #[error("failed to load replica update parameters from: {0}, because: {1}")]
+    InvalidUpdateParameters(std::path::PathBuf, porep::PoRepError),
+
     #[error("failed to load PoSt parameters from: {0}, because: {1}")]
     InvalidPoStParameters(std::path::PathBuf, post::PoStError),
 
+    #[error("failed to convert the verifying key of the {0} parameters: {1:?}")]
+    InvalidVerifyingKey(&'static str, FromBytesError),
+
+    #[error("{0} parameters do not match the verifying key set on chain")]
+    VerifyingKeyMismatch(&'static str),
+
     #[error("FromEnv error: {0}")]
     EnvFilter(#[from] tracing_subscriber::filter::FromEnvError),
 
@@ -182,18 +195,25 @@ pub struct ServerArguments {
 
     /// Sr25519 keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
     ///
+    /// The key can belong to the storage provider's owner, worker or control addresses.
+    /// Using the worker key allows the owner key to be kept cold.
+    ///
     /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
     #[arg(long, value_parser = DebugPair::<Sr25519Pair>::value_parser)]
     sr25519_key: Option<DebugPair<Sr25519Pair>>,
 
     /// ECDSA keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
     ///
+    /// The key can belong to the storage provider's owner, worker or control addresses.
+    ///
     /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
     #[arg(long, value_parser = DebugPair::<ECDSAPair>::value_parser)]
     ecdsa_key: Option<DebugPair<ECDSAPair>>,
 
     /// Ed25519 keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
     ///
+    /// The key can belong to the storage provider's owner, worker or control addresses.
+    ///
     /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
     #[arg(long, value_parser = DebugPair::<Ed25519Pair>::value_parser)]
     ed25519_key: Option<DebugPair<Ed25519Pair>>,
@@ -233,6 +253,15 @@ pub struct ServerArguments {
     /// **they need to be set** via an extrinsic pallet-proofs::set_post_verifyingkey.
     #[arg(long)]
     post_parameters: PathBuf,
+
+    /// Proving Parameters for replica update proof, corresponding to given `seal_proof` sector size.
+    /// When set, deals are put into the existing committed capacity sectors, instead of sealing new sectors.
+    ///
+    /// Testing/temporary parameters can be generated via `polka-storage-provider-client proofs update-params` command.
+    /// Note that when you generate keys, for local testnet,
+    /// **they need to be set** via an extrinsic pallet-proofs::set_replica_update_verifying_key.
+    #[arg(long)]
+    update_parameters: Option<PathBuf>,
 }
 
 /// A valid server configuration. To be created using [`ServerConfiguration::try_from`].
@@ -249,7 +278,7 @@ pub struct ServerConfiguration {
     /// Parachain node RPC url.
     node_url: Url,
 
-    /// Storage provider key pair.
+    /// Storage provider key pair, used to sign the extrinsics.
     multi_pair_signer: MultiPairSigner,
 
     /// Deal database directory.
@@ -272,13 +301,17 @@ pub struct ServerConfiguration {
     /// Proving Parameters for PoSt proof.
     /// For 2KiB sectors they're ~11MiB of data.
     post_parameters: PoStParameters,
+
+    /// Proving Parameters for replica update proof, if replica updates are enabled.
+    update_parameters: Option<PoRepParameters>,
 }
 
 impl TryFrom<ServerArguments> for ServerConfiguration {
     type Error = ServerError;
 
     fn try_from(value: ServerArguments) -> Result<Self, Self::Error> {
-        if value.post_proof.sector_size() != value.seal_proof.sector_size() {
+        // The PoSt needs to prove the sectors sealed with the seal proof
+        if value.seal_proof.registered_window_post_proof() != value.post_proof {
             return Err(ServerError::SectorSizeMismatch);
         }
 
@@ -316,6 +349,14 @@ impl TryFrom<ServerArguments> for ServerConfiguration {
         let post_parameters = post::load_groth16_parameters(value.post_parameters.clone())
             .map_err(|e| ServerError::InvalidPoStParameters(value.post_parameters, e))?;
 
+        let update_parameters = value
+            .update_parameters
+            .map(|path| {
+                porep::load_groth16_parameters(path.clone())
+                    .map_err(|e| ServerError::InvalidUpdateParameters(path, e))
+            })
+            .transpose()?;
+
         Ok(Self {
             upload_listen_address: value.upload_listen_address,
             rpc_listen_address: value.rpc_listen_address,
@@ -327,6 +368,7 @@ impl TryFrom<ServerArguments> for ServerConfiguration {
             post_proof: value.post_proof,
             porep_parameters,
             post_parameters,
+            update_parameters,
         })
     }
 }
@@ -396,12 +438,24 @@ impl ServerConfiguration {
     }
 
     async fn setup(self) -> Result<SetupOutput, ServerError> {
-        let (xt_client, storage_provider_info) = ServerConfiguration::setup_storagext_client(
-            self.node_url,
-            &self.multi_pair_signer,
-            &self.post_proof,
-        )
-        .await?;
+        let (xt_client, owner, storage_provider_info) =
+            ServerConfiguration::setup_storagext_client(
+                self.node_url,
+                &self.multi_pair_signer,
+                &self.post_proof,
+            )
+            .await?;
+        // Proofs generated with parameters other than the chain's would be rejected
+        ServerConfiguration::check_verifying_key(
+            "PoRep",
+            self.porep_parameters.vk.clone(),
+            xt_client.porep_verifying_key_info(self.seal_proof).await?,
+        )?;
+        ServerConfiguration::check_verifying_key(
+            "PoSt",
+            self.post_parameters.vk.clone(),
+            xt_client.post_verifying_key_info(self.post_proof).await?,
+        )?;
         let xt_client = Arc::new(xt_client);
         let deal_database = Arc::new(DealDB::new(self.database_directory)?);
 
@@ -434,7 +488,7 @@ impl ServerConfiguration {
 
         let rpc_state = RpcServerState {
             server_info: ServerInfo::new(
-                self.multi_pair_signer.account_id(),
+                owner,
                 self.seal_proof,
                 self.post_proof,
                 storage_provider_info.proving_period_start,
@@ -455,6 +509,7 @@ impl ServerConfiguration {
             sealing_cache_dir,
             porep_parameters: Arc::new(self.porep_parameters),
             post_parameters: Arc::new(self.post_parameters),
+            update_parameters: self.update_parameters.map(Arc::new),
             xt_client,
             xt_keypair: self.multi_pair_signer,
             pipeline_sender: pipeline_tx,
@@ -468,6 +523,33 @@ impl ServerConfiguration {
         })
     }
 
+    /// Checks that the verifying key of the proving parameters matches the one set on chain.
+    ///
+    /// A key that's not set yet is only reported, as keys can be set after the server starts.
+    fn check_verifying_key(
+        kind: &'static str,
+        vkey: impl TryInto<VerifyingKey<Bls12>, Error = FromBytesError>,
+        key_info: Option<VerifyingKeyInfo>,
+    ) -> Result<(), ServerError> {
+        let Some(key_info) = key_info else {
+            tracing::warn!("{kind} verifying key is not set on chain, proofs can't be verified");
+            return Ok(());
+        };
+
+        let vkey = vkey
+            .try_into()
+            .map_err(|e| ServerError::InvalidVerifyingKey(kind, e))?;
+        if blake2_256(&codec::Encode::encode(&vkey)) != key_info.hash {
+            return Err(ServerError::VerifyingKeyMismatch(kind));
+        }
+        tracing::info!(
+            "{kind} parameters match the verifying key version {} set on chain",
+            key_info.version
+        );
+
+        Ok(())
+    }
+
     async fn setup_storagext_client(
         rpc_address: impl AsRef<str>,
         xt_keypair: &MultiPairSigner,
@@ -475,26 +557,34 @@ impl ServerConfiguration {
     ) -> Result<
         (
             storagext::Client,
-            StorageProviderState<BoundedVec<u8>, u128, u64>,
+            <storagext::PolkaStorageConfig as subxt::Config>::AccountId,
+            StorageProviderState<AccountId32, BoundedVec<u8>, u128, u64>,
         ),
         ServerError,
     > {
         let xt_client = storagext::Client::new(rpc_address, RETRY_NUMBER, RETRY_INTERVAL).await?;
 
-        let storage_provider_account_id = xt_keypair.account_id().into();
+        let signer_account_id = xt_keypair.account_id().into();
+
+        // The key may belong to the owner or to one of the worker and control addresses,
+        // either way, the storage provider is identified by its owner
+        let storage_provider_account_id = xt_client
+            .resolve_storage_provider_owner(&signer_account_id)
+            .await?
+            .unwrap_or(signer_account_id);
 
         // Check if the storage provider has been registered to the chain
         let storage_provider_info = xt_client
             .retrieve_storage_provider(&storage_provider_account_id)
             .await?;
 
+        // Once subxt breaks our code with https://github.com/paritytech/subxt/pull/1850
+        // we'll be able to make all this uniform
+        let owner = subxt::ext::sp_runtime::AccountId32::new(storage_provider_account_id.0);
+
         // Check if the account exists on the market
         xt_client
-            // Once subxt breaks our code with https://github.com/paritytech/subxt/pull/1850
-            // we'll be able to make all this uniform
-            .retrieve_balance(subxt::ext::sp_runtime::AccountId32::new(
-                storage_provider_account_id.0,
-            ))
+            .retrieve_balance(owner.clone())
             .await?
             .ok_or(ServerError::NoMarketAccountStorageProvider)?;
 
@@ -509,7 +599,7 @@ impl ServerConfiguration {
                     return Err(ServerError::ProofMismatch);
                 }
 
-                Ok((xt_client, storage_provider_info))
+                Ok((xt_client, owner, storage_provider_info))
             }
             None => {
                 tracing::error!(concat!(
@@ -4,37 +4,45 @@ use std::{path::PathBuf, sync::Arc};
 
 use polka_storage_proofs::{
     porep::{
-        sealer::{prepare_piece, BlstrsProof, PreCommitOutput, Sealer, SubstrateProof},
+        sealer::{
+            filler_pieces, prepare_piece, BlstrsProof, PreCommitOutput, Sealer, SubstrateProof,
+        },
         PoRepError, PoRepParameters,
     },
     post::{self, PoStError, PoStParameters, ReplicaInfo},
+    update::ReplicaUpdater,
 };
 use polka_storage_provider_common::rpc::ServerInfo;
 use primitives::{
-    commitment::{CommD, CommP, CommR, Commitment},
+    commitment::{
+        piece::{PaddedPieceSize, PieceInfo},
+        CommD, CommP, CommR, Commitment,
+    },
     proofs::derive_prover_id,
     randomness::{draw_randomness, DomainSeparationTag},
     sector::SectorNumber,
+    PartitionNumber, MAX_PARTITIONS_PER_POST,
 };
 use storagext::{
     types::{
         market::DealProposal,
         storage_provider::{
-            PartitionState, PoStProof, ProveCommitSector, SectorPreCommitInfo,
-            SubmitWindowedPoStParams,
+            PartitionState, PoStProof, ProveCommitNiSector, ProveCommitSector, ReplicaUpdate,
+            SectorPreCommitInfo, SubmitWindowedPoStParams,
         },
     },
     RandomnessClientExt, StorageProviderClientExt, SystemClientExt,
 };
-use subxt::{ext::codec::Encode, tx::Signer};
+use subxt::ext::codec::Encode;
 use tokio::{
     sync::mpsc::{error::SendError, UnboundedReceiver, UnboundedSender},
     task::{JoinError, JoinHandle},
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use types::{
-    AddPieceMessage, PipelineMessage, PreCommitMessage, PreCommittedSector, ProveCommitMessage,
-    ProvenSector, SubmitWindowedPoStMessage, UnsealedSector,
+    AddPieceMessage, PipelineMessage, PledgeSectorMessage, PreCommitMessage, PreCommittedSector,
+    ProveCommitMessage, ProveReplicaUpdateMessage, ProvenSector, SubmitWindowedPoStMessage,
+    UnsealedSector, UpdatingSector,
 };
 
 use crate::db::{DBError, DealDB};
@@ -42,6 +50,11 @@ use crate::db::{DBError, DealDB};
 // TODO(@th7nder,#622,02/12/2024): query it from the chain.
 const SECTOR_EXPIRATION_MARGIN: u64 = 20;
 
+// Lifetime of a committed capacity sector, 30 minutes on the testnet,
+// like the margin above, it should be queried from the chain (#622),
+// it must fit between the minimum and maximum sector expiration of the chain.
+const CC_SECTOR_LIFETIME: u64 = 300;
+
 #[derive(Debug, thiserror::Error)]
 pub enum PipelineError {
     #[error(transparent)]
@@ -64,6 +77,10 @@ pub enum PipelineError {
     DeadlineNotFound,
     #[error("deadline of given index does not have a state")]
     DeadlineStateNotFound,
+    #[error("sector is not assigned to any deadline and partition")]
+    SectorLocationNotFound,
+    #[error("replica updates are disabled, no update parameters were provided")]
+    ReplicaUpdatesDisabled,
     #[error(transparent)]
     SendError(#[from] SendError<PipelineMessage>),
     #[error("failed to schedule windowed PoSt")]
@@ -82,6 +99,8 @@ pub struct PipelineState {
     pub sealing_cache_dir: Arc<PathBuf>,
     pub porep_parameters: Arc<PoRepParameters>,
     pub post_parameters: Arc<PoStParameters>,
+    /// Replica updates are enabled only when the parameters are provided.
+    pub update_parameters: Option<Arc<PoRepParameters>>,
 
     pub xt_client: Arc<storagext::Client>,
     pub xt_keypair: storagext::multipair::MultiPairSigner,
@@ -125,6 +144,7 @@ pub async fn start_pipeline(
 
 trait PipelineOperations {
     fn add_piece(&self, state: Arc<PipelineState>, msg: AddPieceMessage, token: CancellationToken);
+    fn pledge_sector(&self, state: Arc<PipelineState>, msg: PledgeSectorMessage);
     fn precommit(&self, state: Arc<PipelineState>, msg: PreCommitMessage);
     fn prove_commit(
         &self,
@@ -132,6 +152,12 @@ trait PipelineOperations {
         msg: ProveCommitMessage,
         token: CancellationToken,
     );
+    fn prove_replica_update(
+        &self,
+        state: Arc<PipelineState>,
+        msg: ProveReplicaUpdateMessage,
+        token: CancellationToken,
+    );
     fn submit_windowed_post(
         &self,
         state: Arc<PipelineState>,
@@ -165,6 +191,20 @@ impl PipelineOperations for TaskTracker {
         });
     }
 
+    fn pledge_sector(&self, state: Arc<PipelineState>, msg: PledgeSectorMessage) {
+        let PledgeSectorMessage { sector_number } = msg;
+        self.spawn(async move {
+            match pledge_sector(state, sector_number).await {
+                Ok(_) => {
+                    tracing::info!("Pledge for sector {} finished successfully.", sector_number)
+                }
+                Err(err) => {
+                    tracing::error!(%err, "Failed Pledge for Sector: {}", sector_number)
+                }
+            }
+        });
+    }
+
     fn precommit(&self, state: Arc<PipelineState>, msg: PreCommitMessage) {
         let PreCommitMessage { sector_number } = msg;
         self.spawn(async move {
@@ -208,6 +248,28 @@ impl PipelineOperations for TaskTracker {
         });
     }
 
+    fn prove_replica_update(
+        &self,
+        state: Arc<PipelineState>,
+        msg: ProveReplicaUpdateMessage,
+        token: CancellationToken,
+    ) {
+        let ProveReplicaUpdateMessage { sector_number } = msg;
+        self.spawn(async move {
+            match prove_replica_update(state, sector_number, token).await {
+                Ok(_) => {
+                    tracing::info!(
+                        "ProveReplicaUpdate for sector {} finished successfully.",
+                        sector_number
+                    )
+                }
+                Err(err) => {
+                    tracing::error!(%err, "Failed ProveReplicaUpdate for Sector: {}", sector_number)
+                }
+            }
+        });
+    }
+
     fn submit_windowed_post(
         &self,
         state: Arc<PipelineState>,
@@ -260,10 +322,14 @@ fn process(
 ) {
     match msg {
         PipelineMessage::AddPiece(msg) => tracker.add_piece(state.clone(), msg, token.clone()),
+        PipelineMessage::PledgeSector(msg) => tracker.pledge_sector(state.clone(), msg),
         PipelineMessage::PreCommit(msg) => tracker.precommit(state.clone(), msg),
         PipelineMessage::ProveCommit(msg) => {
             tracker.prove_commit(state.clone(), msg, token.clone())
         }
+        PipelineMessage::ProveReplicaUpdate(msg) => {
+            tracker.prove_replica_update(state.clone(), msg, token.clone())
+        }
         PipelineMessage::SubmitWindowedPoStMessage(msg) => {
             tracker.submit_windowed_post(state.clone(), msg, token.clone())
         }
@@ -271,9 +337,34 @@ fn process(
     }
 }
 
+/// Finds a sector for a piece of the given deal.
+///
+/// When replica updates are enabled, a proven committed capacity sector outliving the deal is preferred,
+/// the pieces are then staged in a new unsealed sector with the same number and returned along with the CC sector.
+/// Otherwise a new sector is created.
 async fn find_sector_for_piece(
     state: &Arc<PipelineState>,
-) -> Result<UnsealedSector, PipelineError> {
+    deal: &DealProposal,
+) -> Result<(UnsealedSector, Option<ProvenSector>), PipelineError> {
+    if state.update_parameters.is_some() {
+        if let Some(sector_number) = state.db.take_committed_capacity_sector(deal.end_block)? {
+            let Some(cc_sector) = state.db.get_sector::<ProvenSector>(sector_number)? else {
+                tracing::error!("Committed capacity sector {} does not exist", sector_number);
+                return Err(PipelineError::SectorNotFound);
+            };
+            tracing::info!(
+                "Putting the piece into committed capacity sector {}",
+                sector_number
+            );
+
+            // The unsealed file of the CC sector has been removed after its pre-commit.
+            let unsealed_path = state.unsealed_sectors_dir.join(sector_number.to_string());
+            let staged = UnsealedSector::create(sector_number, unsealed_path).await?;
+
+            return Ok((staged, Some(cc_sector)));
+        }
+    }
+
     // TODO(@th7nder,30/10/2024): simplification, we're always creating a new sector for storing a piece.
     // It should not work like that, sectors should be filled with pieces according to *some* algorithm.
     let sector_number = state
@@ -283,7 +374,7 @@ async fn find_sector_for_piece(
     let unsealed_path = state.unsealed_sectors_dir.join(sector_number.to_string());
     let sector = UnsealedSector::create(sector_number, unsealed_path).await?;
 
-    Ok(sector)
+    Ok((sector, None))
 }
 
 /// Finds a sector to which a piece will fit and adds it to the sector.
@@ -298,7 +389,7 @@ async fn add_piece(
     deal: DealProposal,
     deal_id: u64,
 ) -> Result<(), PipelineError> {
-    let mut sector = find_sector_for_piece(&state).await?;
+    let (mut sector, cc_sector) = find_sector_for_piece(&state, &deal).await?;
     sector.deals.push((deal_id, deal));
 
     tracing::info!("Adding a piece...");
@@ -328,6 +419,27 @@ async fn add_piece(
     let sector: UnsealedSector = handle.await??;
 
     tracing::info!("Finished adding a piece");
+
+    // The piece has been staged for a committed capacity sector, its replica gets updated instead of sealing a new one.
+    if let Some(cc_sector) = cc_sector {
+        let sector_number = sector.sector_number;
+        state.db.save_sector(
+            sector_number,
+            &UpdatingSector {
+                sector: cc_sector,
+                staged: sector,
+            },
+        )?;
+
+        state
+            .pipeline_sender
+            .send(PipelineMessage::ProveReplicaUpdate(
+                ProveReplicaUpdateMessage { sector_number },
+            ))?;
+
+        return Ok(());
+    }
+
     state.db.save_sector(sector.sector_number, &sector)?;
 
     // TODO(@th7nder,30/10/2024): simplification, as we're always scheduling a precommit just after adding a piece and creating a new sector.
@@ -341,6 +453,41 @@ async fn add_piece(
     Ok(())
 }
 
+/// Creates a committed capacity sector, without any deals, and schedules its pre-commit.
+///
+/// The sector consists only of filler pieces, the unsealed file is left empty,
+/// as it's extended with zeroes to the full sector size when the replica is created.
+#[tracing::instrument(skip(state))]
+async fn pledge_sector(
+    state: Arc<PipelineState>,
+    sector_number: SectorNumber,
+) -> Result<(), PipelineError> {
+    tracing::info!("Creating a committed capacity sector");
+
+    let unsealed_path = state.unsealed_sectors_dir.join(sector_number.to_string());
+    let mut sector = UnsealedSector::create(sector_number, unsealed_path).await?;
+
+    let sector_size = PaddedPieceSize::new(state.server_info.seal_proof.sector_size().bytes())
+        .expect("sector size to be a valid padded piece size")
+        .unpadded();
+    sector.piece_infos = filler_pieces(sector_size.into())
+        .into_iter()
+        .map(PieceInfo::from)
+        .collect();
+    // The whole sector is occupied by the filler pieces, so it won't be padded any further.
+    sector.occupied_sector_space = *sector_size;
+
+    state.db.save_sector(sector.sector_number, &sector)?;
+
+    state
+        .pipeline_sender
+        .send(PipelineMessage::PreCommit(PreCommitMessage {
+            sector_number: sector.sector_number,
+        }))?;
+
+    Ok(())
+}
+
 #[tracing::instrument(skip(state))]
 /// Creates a replica and calls pre-commit on-chain.
 ///
@@ -374,7 +521,7 @@ async fn precommit(
         .await?
         .expect("randomness to be available as we wait for it");
 
-    let entropy = state.xt_keypair.account_id().encode();
+    let entropy = state.server_info.address.encode();
     // Must match pallet's logic or otherwise proof won't be verified:
     // https://github.com/eigerco/polka-storage/blob/af51a9b121c9b02e0bf6f02f5e835091ab46af76/pallets/storage-provider/src/lib.rs#L1539
     let ticket = draw_randomness(
@@ -391,7 +538,7 @@ async fn precommit(
 
     // TODO(@th7nder,31/10/2024): what happens if some of the process fails? SP will be slashed, and there is no error reporting? what about retries?
     let sealing_handle: JoinHandle<Result<PreCommitOutput, _>> = {
-        let prover_id = derive_prover_id(state.xt_keypair.account_id());
+        let prover_id = derive_prover_id(state.server_info.address.clone());
         let cache_dir = cache_path.clone();
         let unsealed_path = sector.unsealed_path.clone();
         let sealed_path = sealed_path.clone();
@@ -419,6 +566,28 @@ async fn precommit(
     let sealing_output_commr = Commitment::<CommR>::from(sealing_output.comm_r);
     let sealing_output_commd = Commitment::<CommD>::from(sealing_output.comm_d);
 
+    // Committed capacity sectors don't have any deals to outlive,
+    // they're committed for a fixed amount of time instead.
+    let expiration = match sector.deals.iter().map(|(_, deal)| deal.end_block).max() {
+        Some(deals_end_block) => deals_end_block + SECTOR_EXPIRATION_MARGIN,
+        None => current_block + CC_SECTOR_LIFETIME,
+    };
+
+    if state.server_info.seal_proof.is_non_interactive() {
+        // Non-interactive proofs don't need the interactive seed, the sector is proven right away.
+        let sector = PreCommittedSector::create(
+            sector,
+            cache_path,
+            sealed_path,
+            sealing_output_commr,
+            sealing_output_commd,
+            current_block,
+            current_block,
+        )
+        .await?;
+        return prove_commit_ni(state, sector, ticket, expiration).await;
+    }
+
     tracing::debug!("Precommiting at block: {}", current_block);
     let result = state
         .xt_client
@@ -426,13 +595,7 @@ async fn precommit(
             &state.xt_keypair,
             vec![SectorPreCommitInfo {
                 deal_ids: sector.deals.iter().map(|(id, _)| *id).collect(),
-                expiration: sector
-                    .deals
-                    .iter()
-                    .map(|(_, deal)| deal.end_block)
-                    .max()
-                    .expect("always at least 1 deal in a sector")
-                    + SECTOR_EXPIRATION_MARGIN,
+                expiration,
                 sector_number: sector_number,
                 seal_proof: state.server_info.seal_proof,
                 sealed_cid: sealing_output_commr.cid(),
@@ -502,7 +665,7 @@ async fn prove_commit(
         tracing::error!("Out-of-the-state transition, this SHOULD NOT happen");
         return Err(PipelineError::RandomnessNotAvailable);
     };
-    let entropy = state.xt_keypair.account_id().encode();
+    let entropy = state.server_info.address.encode();
     // Must match pallet's logic or otherwise proof won't be verified:
     // https://github.com/eigerco/polka-storage/blob/af51a9b121c9b02e0bf6f02f5e835091ab46af76/pallets/storage-provider/src/lib.rs#L1539
     let ticket = draw_randomness(
@@ -540,7 +703,7 @@ async fn prove_commit(
         &entropy,
     );
 
-    let prover_id = derive_prover_id(state.xt_keypair.account_id());
+    let prover_id = derive_prover_id(state.server_info.address.clone());
     tracing::debug!("Performing prove commit for, seal_randomness_height {}, pre_commit_block: {}, prove_commit_block: {}, entropy: {}, ticket: {}, seed: {}, prover id: {}, sector_number: {}",
         seal_randomness_height, sector.precommit_block, prove_commit_block, hex::encode(entropy), hex::encode(ticket), hex::encode(seed), hex::encode(prover_id), sector_number);
 
@@ -579,7 +742,7 @@ async fn prove_commit(
         }
     };
 
-    // We use sector size 2KiB only at this point, which guarantees to have 1 proof, because it has 1 partition in the config.
+    // All of the supported sector sizes, up to 512MiB, guarantee to have 1 proof, because they have 1 partition in the config.
     // That's why `prove_commit` will always generate a 1 proof.
     let proof: SubstrateProof = proofs[0]
         .clone()
@@ -609,9 +772,337 @@ async fn prove_commit(
 
     tracing::info!("Successfully proven sectors on-chain: {:?}", proven_sectors);
 
+    // Committed capacity sectors can later receive deals through a replica update.
+    // Their expiration is fixed at pre-commit, the randomness is drawn at the pre-commit block.
+    let committed_capacity = sector.deals.is_empty();
+    let expiration = sector.seal_randomness_height + CC_SECTOR_LIFETIME;
+
     let sector = ProvenSector::create(sector);
     state.db.save_sector(sector.sector_number, &sector)?;
 
+    if committed_capacity {
+        state
+            .db
+            .add_committed_capacity_sector(sector.sector_number, expiration)?;
+    }
+
+    Ok(())
+}
+
+/// Proves a sector sealed with a non-interactive seal proof and onboards it in a single step,
+/// there's no pre-commit and no interactive seed to wait for.
+///
+/// Like [`precommit`], it is not cancellation safe.
+async fn prove_commit_ni(
+    state: Arc<PipelineState>,
+    sector: PreCommittedSector,
+    ticket: [u8; 32],
+    expiration: u64,
+) -> Result<(), PipelineError> {
+    let sector_number = sector.sector_number;
+    let sealer = Sealer::new(state.server_info.seal_proof);
+    let prover_id = derive_prover_id(state.server_info.address.clone());
+    tracing::debug!("Performing non-interactive prove commit for, seal_randomness_height {}, ticket: {}, prover id: {}, sector_number: {}",
+        sector.seal_randomness_height, hex::encode(ticket), hex::encode(prover_id), sector_number);
+
+    let sealing_handle: JoinHandle<Result<Vec<BlstrsProof>, _>> = {
+        let porep_params = state.porep_parameters.clone();
+        let cache_dir = sector.cache_path.clone();
+        let sealed_path = sector.sealed_path.clone();
+        let piece_infos = sector.piece_infos.clone();
+        let comm_r = sector.comm_r;
+        let comm_d = sector.comm_d;
+
+        tokio::task::spawn_blocking(move || {
+            sealer.prove_sector(
+                porep_params.as_ref(),
+                cache_dir,
+                sealed_path,
+                prover_id,
+                sector_number,
+                ticket,
+                None,
+                PreCommitOutput { comm_r, comm_d },
+                &piece_infos,
+            )
+        })
+    };
+    let proofs = sealing_handle.await??;
+
+    // Non-interactive proofs consist of a proof for each of the partitions, one after another.
+    let mut proof = Vec::new();
+    for partition_proof in proofs {
+        let partition_proof: SubstrateProof = partition_proof
+            .try_into()
+            .expect("converstion between rust-fil-proofs and polka-storage-proofs to work");
+        proof.extend(codec::Encode::encode(&partition_proof));
+    }
+    tracing::info!("Proven sector: {}", sector_number);
+
+    let result = state
+        .xt_client
+        .prove_commit_ni_sectors(
+            &state.xt_keypair,
+            vec![ProveCommitNiSector {
+                sector: SectorPreCommitInfo {
+                    deal_ids: sector.deals.iter().map(|(id, _)| *id).collect(),
+                    expiration,
+                    sector_number,
+                    seal_proof: state.server_info.seal_proof,
+                    sealed_cid: sector.comm_r.cid(),
+                    unsealed_cid: sector.comm_d.cid(),
+                    seal_randomness_height: sector.seal_randomness_height,
+                },
+                proof,
+            }],
+            true,
+        )
+        .await?
+        .expect("waiting for finalization should always give results");
+
+    let proven_sectors = result
+        .events
+        .find::<storagext::runtime::storage_provider::events::SectorsProven>()
+        .map(|result| result.map_err(|err| subxt::Error::from(err)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tracing::info!("Successfully proven sectors on-chain: {:?}", proven_sectors);
+
+    let committed_capacity = sector.deals.is_empty();
+    let sector = ProvenSector::create(sector);
+    state.db.save_sector(sector.sector_number, &sector)?;
+
+    if committed_capacity {
+        state
+            .db
+            .add_committed_capacity_sector(sector.sector_number, expiration)?;
+    }
+
+    Ok(())
+}
+
+/// Encodes the deals staged for a committed capacity sector into a new replica,
+/// proves the replica update and submits it on-chain.
+///
+/// Everything is retryable until the proof is submitted, the committed capacity sector is kept untouched.
+#[tracing::instrument(skip(state, token))]
+async fn prove_replica_update(
+    state: Arc<PipelineState>,
+    sector_number: SectorNumber,
+    token: CancellationToken,
+) -> Result<(), PipelineError> {
+    tracing::info!("Starting replica update");
+
+    let Some(update_parameters) = state.update_parameters.clone() else {
+        tracing::error!("Tried to update a replica without the update parameters");
+        return Err(PipelineError::ReplicaUpdatesDisabled);
+    };
+    let Some(UpdatingSector { sector, mut staged }) =
+        state.db.get_sector::<UpdatingSector>(sector_number)?
+    else {
+        tracing::error!("Tried to update non-existing sector");
+        return Err(PipelineError::SectorNotFound);
+    };
+
+    let seal_proof = state.server_info.seal_proof;
+    // Pad sector so CommD can be properly calculated.
+    let sealer = Sealer::new(seal_proof);
+    staged.piece_infos = sealer.pad_sector(&staged.piece_infos, staged.occupied_sector_space)?;
+    tracing::debug!("piece_infos: {:?}", staged.piece_infos);
+
+    // The committed capacity replica is kept, it's the sector key needed to prove the update.
+    let cache_path = state
+        .sealing_cache_dir
+        .join(format!("{}-update", sector_number));
+    let sealed_path = state
+        .sealed_sectors_dir
+        .join(format!("{}-update", sector_number));
+    tokio::fs::create_dir_all(&cache_path).await?;
+    tokio::fs::File::create(&sealed_path).await?;
+
+    let updating_handle: JoinHandle<
+        Result<(Commitment<CommR>, Commitment<CommD>, Vec<BlstrsProof>), PipelineError>,
+    > = {
+        let updater = ReplicaUpdater::new(seal_proof);
+        let sector_size = seal_proof.sector_size().bytes();
+        let cache_path = cache_path.clone();
+        let sealed_path = sealed_path.clone();
+        let sector_key_path = sector.sealed_path.clone();
+        let sector_key_cache_path = sector.cache_path.clone();
+        let staged_path = staged.unsealed_path.clone();
+        let piece_infos = staged.piece_infos.clone();
+        let comm_r_old = sector.comm_r;
+
+        tokio::task::spawn_blocking(move || {
+            // The staged data needs to span the whole sector,
+            // the space left after the pieces is zeroes, same as the padding pieces.
+            std::fs::File::options()
+                .write(true)
+                .open(&staged_path)?
+                .set_len(sector_size)?;
+
+            let (comm_r_new, comm_d_new) = updater.encode_into(
+                sealed_path.clone(),
+                cache_path.clone(),
+                sector_key_path.clone(),
+                sector_key_cache_path.clone(),
+                staged_path,
+                &piece_infos,
+            )?;
+            tracing::info!(
+                "Created updated replica, CommD: {}, CommR: {}",
+                comm_d_new.cid(),
+                comm_r_new.cid()
+            );
+
+            let proofs = updater.prove(
+                update_parameters.as_ref(),
+                comm_r_old,
+                comm_r_new,
+                comm_d_new,
+                sector_key_path,
+                sector_key_cache_path,
+                sealed_path,
+                cache_path,
+            )?;
+
+            Ok((comm_r_new, comm_d_new, proofs))
+        })
+    };
+
+    let (comm_r_new, comm_d_new, proofs) = tokio::select! {
+        res = updating_handle => {
+            res??
+        },
+        () = token.cancelled() => {
+            return Err(PipelineError::ProvingCancelled);
+        }
+    };
+
+    // The update proof consists of a proof for each of the update partitions, one after another.
+    let mut proof = Vec::new();
+    for partition_proof in proofs {
+        let partition_proof: SubstrateProof = partition_proof
+            .try_into()
+            .expect("converstion between rust-fil-proofs and polka-storage-proofs to work");
+        proof.extend(codec::Encode::encode(&partition_proof));
+    }
+    tracing::info!("Proven replica update of sector: {}", sector_number);
+
+    let (deadline_index, partition) = find_sector_location(&state, sector_number).await?;
+    wait_for_mutable_deadline(&state, deadline_index, &token).await?;
+
+    let result = state
+        .xt_client
+        .prove_replica_updates(
+            &state.xt_keypair,
+            vec![ReplicaUpdate {
+                sector_number,
+                deadline: deadline_index,
+                partition,
+                new_sealed_cid: comm_r_new.cid(),
+                new_unsealed_cid: comm_d_new.cid(),
+                deal_ids: staged.deals.iter().map(|(id, _)| *id).collect(),
+                proof,
+            }],
+            true,
+        )
+        .await?
+        .expect("waiting for finalization should always give results");
+
+    let updated_replicas = result
+        .events
+        .find::<storagext::runtime::storage_provider::events::ReplicasUpdated>()
+        .map(|result| result.map_err(|err| subxt::Error::from(err)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tracing::info!(
+        "Successfully updated replicas on-chain: {:?}",
+        updated_replicas
+    );
+
+    let sector =
+        ProvenSector::update(staged, cache_path, sealed_path, comm_r_new, comm_d_new).await?;
+    state.db.save_sector(sector.sector_number, &sector)?;
+
+    Ok(())
+}
+
+/// Finds the deadline and the partition the sector is assigned to.
+async fn find_sector_location(
+    state: &Arc<PipelineState>,
+    sector_number: SectorNumber,
+) -> Result<(u64, PartitionNumber), PipelineError> {
+    let proving_period = state.xt_client.proving_period_info()?;
+
+    for deadline_index in 0..proving_period.deadlines {
+        let Some(deadline_state) = state
+            .xt_client
+            .deadline_state(&state.server_info.address.clone().into(), deadline_index)
+            .await?
+        else {
+            continue;
+        };
+
+        let partition =
+            deadline_state
+                .partitions
+                .iter()
+                .find_map(|(partition, PartitionState { sectors })| {
+                    sectors.contains(&sector_number).then_some(*partition)
+                });
+        if let Some(partition) = partition {
+            return Ok((deadline_index, partition));
+        }
+    }
+
+    Err(PipelineError::SectorLocationNotFound)
+}
+
+/// Waits until the sectors in the deadline can be modified on-chain,
+/// i.e. the deadline is not open and it won't open in the next challenge window.
+async fn wait_for_mutable_deadline(
+    state: &Arc<PipelineState>,
+    deadline_index: u64,
+    token: &CancellationToken,
+) -> Result<(), PipelineError> {
+    let proving_period = state.xt_client.proving_period_info()?;
+    let current_block = state.xt_client.height(true).await?;
+    let Some(deadline) = state
+        .xt_client
+        .deadline_info(&state.server_info.address.clone().into(), deadline_index)
+        .await?
+    else {
+        tracing::error!("there is no such deadline...");
+        return Err(PipelineError::DeadlineNotFound);
+    };
+
+    // `deadline.start` is the next opening of the deadline, it may be still open from the previous proving period.
+    let previous_close = (deadline.start + proving_period.challenge_window)
+        .saturating_sub(proving_period.proving_period);
+    let mutable_at = if current_block < previous_close {
+        previous_close
+    } else if current_block + proving_period.challenge_window >= deadline.start {
+        deadline.start + proving_period.challenge_window
+    } else {
+        return Ok(());
+    };
+
+    tracing::info!(
+        "Deadline {} is immutable, wait for block {}",
+        deadline_index,
+        mutable_at
+    );
+    tokio::select! {
+        res = state.xt_client.wait_for_height(mutable_at, true) => {
+            res?;
+        },
+        () = token.cancelled() => {
+            tracing::warn!("Cancelled while waiting for deadline {} to be mutable", deadline_index);
+            return Err(PipelineError::ProvingCancelled);
+        }
+    };
+
     Ok(())
 }
 
@@ -623,7 +1114,7 @@ async fn submit_windowed_post(
     tracing::info!("Getting deadline info for {} deadline", deadline_index);
     let deadline = state
         .xt_client
-        .deadline_info(&state.xt_keypair.account_id().into(), deadline_index)
+        .deadline_info(&state.server_info.address.clone().into(), deadline_index)
         .await?;
     let Some(deadline) = deadline else {
         tracing::error!("there is no such deadline...");
@@ -650,7 +1141,7 @@ async fn submit_windowed_post(
         tracing::error!("Randomness for the block not available.");
         return Err(PipelineError::RandomnessNotAvailable);
     };
-    let entropy = state.xt_keypair.account_id().encode();
+    let entropy = state.server_info.address.encode();
     let randomness = draw_randomness(
         &digest,
         DomainSeparationTag::WindowedPoStChallengeSeed,
@@ -660,70 +1151,82 @@ async fn submit_windowed_post(
 
     let Some(deadline_state) = state
         .xt_client
-        .deadline_state(&state.xt_keypair.account_id().into(), deadline_index)
+        .deadline_state(&state.server_info.address.clone().into(), deadline_index)
         .await?
     else {
         tracing::error!("Something went catastrophic, there is no current deadline state");
         return Err(PipelineError::DeadlineStateNotFound);
     };
 
-    if deadline_state.partitions.len() > 1 {
-        todo!("I don't know what to do: polka-storage#595");
-    }
     if deadline_state.partitions.len() == 0 {
         tracing::info!("There are not partitions in this deadline yet. Nothing to prove here.");
         schedule_post(state, deadline_index)?;
         return Ok(());
     }
 
-    let partitions = deadline_state.partitions.keys().cloned().collect();
-    let (_partition_number, PartitionState { sectors }) = deadline_state
-        .partitions
-        .first_key_value()
-        .expect("1 partition to be there");
+    let prover_id = derive_prover_id(state.server_info.address.clone());
+    // Each partition is proven separately and verified on-chain against its own proof.
+    let mut partitions = Vec::new();
+    let mut proofs = Vec::new();
+    for (partition_number, PartitionState { sectors }) in deadline_state.partitions.iter() {
+        if sectors.len() == 0 {
+            tracing::info!(
+                "Every sector in partition {} expired... Nothing to prove here.",
+                partition_number
+            );
+            continue;
+        }
+
+        let mut replicas = Vec::new();
+        for sector_number in sectors {
+            let sector = state
+                .db
+                .get_sector::<ProvenSector>(*sector_number)?
+                .ok_or(PipelineError::SectorNotFound)?;
+
+            replicas.push(ReplicaInfo {
+                sector_id: *sector_number,
+                comm_r: sector.comm_r.raw(),
+                cache_path: sector.cache_path.clone(),
+                replica_path: sector.sealed_path.clone(),
+            });
+        }
+
+        tracing::info!("Proving PoSt partition... {}", partition_number);
+        let handle: JoinHandle<Result<Vec<BlstrsProof>, _>> = {
+            let post_params = state.post_parameters.clone();
+            let post_proof = state.server_info.post_proof;
+
+            tokio::task::spawn_blocking(move || {
+                post::generate_window_post(
+                    post_proof,
+                    &post_params,
+                    randomness,
+                    prover_id,
+                    replicas,
+                )
+            })
+        };
+        let partition_proofs = handle.await??;
+
+        // A partition on-chain holds at most as many sectors as a single PoSt proof covers,
+        // so there is exactly one proof generated for it.
+        let proof: SubstrateProof = partition_proofs[0]
+            .clone()
+            .try_into()
+            .expect("converstion between rust-fil-proofs and polka-storage-proofs to work");
+
+        partitions.push(*partition_number);
+        proofs.push(codec::Encode::encode(&proof));
+    }
 
-    if sectors.len() == 0 {
+    if partitions.is_empty() {
         tracing::info!("Every sector expired... Nothing to prove here.");
         schedule_post(state, deadline_index)?;
         return Ok(());
     }
 
-    let mut replicas = Vec::new();
-    for sector_number in sectors {
-        let sector = state
-            .db
-            .get_sector::<ProvenSector>(*sector_number)?
-            .ok_or(PipelineError::SectorNotFound)?;
-
-        replicas.push(ReplicaInfo {
-            sector_id: *sector_number,
-            comm_r: sector.comm_r.raw(),
-            cache_path: sector.cache_path.clone(),
-            replica_path: sector.sealed_path.clone(),
-        });
-    }
-    let prover_id = derive_prover_id(state.xt_keypair.account_id());
-
-    tracing::info!("Proving PoSt partitions... {:?}", partitions);
-    let handle: JoinHandle<Result<Vec<BlstrsProof>, _>> = {
-        let post_params = state.post_parameters.clone();
-        let post_proof = state.server_info.post_proof;
-
-        tokio::task::spawn_blocking(move || {
-            post::generate_window_post(post_proof, &post_params, randomness, prover_id, replicas)
-        })
-    };
-    let proofs = handle.await??;
-
-    // TODO(@th7nder,#595,06/12/2024): how many proofs are for how many partitions and why
-    // don't now why yet, need to figure this out
-    let proof: SubstrateProof = proofs[0]
-        .clone()
-        .try_into()
-        .expect("converstion between rust-fil-proofs and polka-storage-proofs to work");
-    let proof = codec::Encode::encode(&proof);
-
-    tracing::info!("Generated PoSt proof for partitions: {:?}", partitions);
+    tracing::info!("Generated PoSt proofs for partitions: {:?}", partitions);
 
     tracing::info!("Wait for block {} for open deadline", deadline.start,);
     state
@@ -731,30 +1234,42 @@ async fn submit_windowed_post(
         .wait_for_height(deadline.start, true)
         .await?;
 
-    let result = state
-        .xt_client
-        .submit_windowed_post(
-            &state.xt_keypair,
-            SubmitWindowedPoStParams {
-                deadline: deadline_index,
-                partitions: partitions,
-                proof: PoStProof {
-                    post_proof: state.server_info.post_proof,
-                    proof_bytes: proof,
+    // A single submission can prove only a limited amount of partitions,
+    // the proofs are concatenated in the same order as the partitions.
+    let max_partitions = MAX_PARTITIONS_PER_POST as usize;
+    for (partitions, proofs) in partitions
+        .chunks(max_partitions)
+        .zip(proofs.chunks(max_partitions))
+    {
+        let result = state
+            .xt_client
+            .submit_windowed_post(
+                &state.xt_keypair,
+                SubmitWindowedPoStParams {
+                    deadline: deadline_index,
+                    partitions: partitions.to_vec(),
+                    proof: PoStProof {
+                        post_proof: state.server_info.post_proof,
+                        proof_bytes: proofs.concat(),
+                    },
                 },
-            },
-            true,
-        )
-        .await?
-        .expect("waiting for finalization should always give results");
-
-    let posts = result
-        .events
-        .find::<storagext::runtime::storage_provider::events::ValidPoStSubmitted>()
-        .map(|result| result.map_err(|err| subxt::Error::from(err)))
-        .collect::<Result<Vec<_>, _>>()?;
-
-    tracing::info!("Successfully submitted PoSt on-chain: {:?}", posts);
+                true,
+            )
+            .await?
+            .expect("waiting for finalization should always give results");
+
+        let posts = result
+            .events
+            .find::<storagext::runtime::storage_provider::events::ValidPoStSubmitted>()
+            .map(|result| result.map_err(|err| subxt::Error::from(err)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        tracing::info!(
+            "Successfully submitted PoSt for partitions {:?} on-chain: {:?}",
+            partitions,
+            posts
+        );
+    }
 
     schedule_post(state, deadline_index)?;
 
@@ -13,10 +13,14 @@ use storagext::types::market::DealProposal;
 pub enum PipelineMessage {
     /// Adds a deal to a sector selected by the storage provider.
     AddPiece(AddPieceMessage),
+    /// Creates a committed capacity sector, filled only with zeroes, and schedules its pre-commit.
+    PledgeSector(PledgeSectorMessage),
     /// Pads, seals a sector and pre-commits it on-chain.
     PreCommit(PreCommitMessage),
     /// Generates a PoRep for a sector and verifies the proof on-chain.
     ProveCommit(ProveCommitMessage),
+    /// Encodes the staged deals into a committed capacity sector and proves the replica update on-chain.
+    ProveReplicaUpdate(ProveReplicaUpdateMessage),
     /// Fetches partitions and sectors from the chain and generates a Windowed PoSt proof.
     SubmitWindowedPoStMessage(SubmitWindowedPoStMessage),
     /// Schedules WindowPoSt for each deadline in the proving period.
@@ -36,6 +40,13 @@ pub struct AddPieceMessage {
     pub commitment: Commitment<CommP>,
 }
 
+/// Committed capacity sector to be created, without any deals.
+#[derive(Debug)]
+pub struct PledgeSectorMessage {
+    /// Number of the sector to be created
+    pub sector_number: SectorNumber,
+}
+
 /// Sector to be sealed and pre-commited to the chain
 #[derive(Debug)]
 pub struct PreCommitMessage {
@@ -49,6 +60,13 @@ pub struct ProveCommitMessage {
     pub sector_number: SectorNumber,
 }
 
+/// Committed capacity sector to be updated with the deals staged for it.
+#[derive(Debug)]
+pub struct ProveReplicaUpdateMessage {
+    /// Number of an existing, proven sector with staged deals
+    pub sector_number: SectorNumber,
+}
+
 #[derive(Debug)]
 pub struct SubmitWindowedPoStMessage {
     pub deadline_index: u64,
@@ -223,4 +241,43 @@ impl ProvenSector {
             comm_d: sector.comm_d,
         }
     }
+
+    /// Creates a [`ProvenSector`] from the deals staged for a committed capacity sector
+    /// and removes the staged data, once the replica update has been proven.
+    ///
+    /// Expects that file at `sealed_path` contains the updated replica.
+    pub async fn update(
+        staged: UnsealedSector,
+        cache_path: std::path::PathBuf,
+        sealed_path: std::path::PathBuf,
+        comm_r: Commitment<CommR>,
+        comm_d: Commitment<CommD>,
+    ) -> Result<Self, std::io::Error> {
+        tokio::fs::remove_file(staged.unsealed_path).await?;
+
+        Ok(Self {
+            sector_number: staged.sector_number,
+            piece_infos: staged.piece_infos,
+            deals: staged.deals,
+            cache_path,
+            sealed_path,
+            comm_r,
+            comm_d,
+        })
+    }
+}
+
+/// Proven committed capacity sector, with the deals staged to be put into it by a replica update.
+///
+/// It's stored under the same key as the [`ProvenSector`] and can be read as one,
+/// so the sector keeps being proven while the update is in progress.
+/// When the update is proven, it's converted back into a [`ProvenSector`].
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdatingSector {
+    /// The committed capacity sector being updated.
+    #[serde(flatten)]
+    pub sector: ProvenSector,
+
+    /// Deals and their data, to be encoded into the sector.
+    pub staged: UnsealedSector,
 }
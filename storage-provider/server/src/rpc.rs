@@ -5,12 +5,14 @@ use jsonrpsee::server::Server;
 use polka_storage_provider_common::rpc::{
     CidString, RpcError, ServerInfo, StorageProviderRpcServer,
 };
-use primitives::commitment::{CommP, Commitment, CommitmentKind};
+use primitives::{
+    commitment::{CommP, Commitment, CommitmentKind},
+    sector::SectorNumber,
+};
 use storagext::{
     types::market::{ClientDealProposal as SxtClientDealProposal, DealProposal as SxtDealProposal},
     MarketClientExt,
 };
-use subxt::tx::Signer;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::sync::CancellationToken;
 use tower_http::cors::{Any, CorsLayer};
@@ -18,7 +20,7 @@ use tracing::{info, instrument};
 
 use crate::{
     db::DealDB,
-    pipeline::types::{AddPieceMessage, PipelineMessage},
+    pipeline::types::{AddPieceMessage, PipelineMessage, PledgeSectorMessage},
 };
 
 /// RPC server shared state.
@@ -59,7 +61,7 @@ impl StorageProviderRpcServer for RpcServerState {
             ));
         }
 
-        if deal.provider != self.xt_keypair.account_id() {
+        if deal.provider != self.server_info.address {
             return Err(RpcError::invalid_params(
                 "deal's provider ID does not match the current provider ID",
                 None,
@@ -89,7 +91,7 @@ impl StorageProviderRpcServer for RpcServerState {
 
         let storage_provider_balance = self
             .xt_client
-            .retrieve_balance(self.xt_keypair.account_id())
+            .retrieve_balance(self.server_info.address.clone())
             .await?
             .ok_or_else(|| RpcError::internal_error("Storage Provider not found", None))?;
 
@@ -214,6 +216,21 @@ impl StorageProviderRpcServer for RpcServerState {
 
         Ok(deal_id)
     }
+
+    async fn pledge_sector(&self) -> Result<SectorNumber, RpcError> {
+        let sector_number = self
+            .deal_db
+            .next_sector_number()
+            .map_err(|err| RpcError::internal_error(err, None))?;
+
+        self.pipeline_sender
+            .send(PipelineMessage::PledgeSector(PledgeSectorMessage {
+                sector_number,
+            }))
+            .map_err(|e| RpcError::internal_error(e, None))?;
+
+        Ok(sector_number)
+    }
 }
 
 /// Start the RPC server.
@@ -67,6 +67,61 @@ pub(crate) enum MarketCommand {
         client_ed25519_key: Option<DebugPair<Ed25519Pair>>,
     },
 
+    /// Cancel a published deal, before it's activated.
+    ///
+    /// Without the client's key, the provider forfeits part of the deal's collateral to the client.
+    #[command(group(ArgGroup::new("client_keypair").args(&["client_sr25519_key", "client_ecdsa_key", "client_ed25519_key"])))]
+    CancelStorageDeal {
+        /// The ID of the deal to cancel.
+        deal_id: DealId,
+        /// Client's Sr25519 keypair, signs the client's consent to the cancellation.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<Sr25519Pair>::value_parser)]
+        client_sr25519_key: Option<DebugPair<Sr25519Pair>>,
+
+        /// Client's ECDSA keypair, signs the client's consent to the cancellation.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<ECDSAPair>::value_parser)]
+        client_ecdsa_key: Option<DebugPair<ECDSAPair>>,
+
+        /// Client's Ed25519 keypair, signs the client's consent to the cancellation.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<Ed25519Pair>::value_parser)]
+        client_ed25519_key: Option<DebugPair<Ed25519Pair>>,
+    },
+
+    /// Extend an active deal, with a new end block and price per block, signed by client_<key_type>_key.
+    #[command(group(ArgGroup::new("client_keypair").required(true).args(&["client_sr25519_key", "client_ecdsa_key", "client_ed25519_key"])))]
+    ExtendDeal {
+        /// The ID of the deal to extend.
+        deal_id: DealId,
+        /// The deal's new end block.
+        end_block: storagext::BlockNumber,
+        /// The price per block of the storage from the extension onwards.
+        #[arg(value_parser=parse_without_underscore::<storagext::Currency>)]
+        storage_price_per_block: storagext::Currency,
+        /// Client's Sr25519 keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<Sr25519Pair>::value_parser)]
+        client_sr25519_key: Option<DebugPair<Sr25519Pair>>,
+
+        /// Client's ECDSA keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<ECDSAPair>::value_parser)]
+        client_ecdsa_key: Option<DebugPair<ECDSAPair>>,
+
+        /// Client's Ed25519 keypair, encoded as hex, BIP-39 or a dev phrase like `//Alice`.
+        ///
+        /// See `sp_core::crypto::Pair::from_string_with_seed` for more information.
+        #[arg(long, value_parser = DebugPair::<Ed25519Pair>::value_parser)]
+        client_ed25519_key: Option<DebugPair<Ed25519Pair>>,
+    },
+
     /// Settle deal payments.
     SettleDealPayments {
         /// The IDs for the deals to settle.
@@ -197,6 +252,52 @@ impl MarketCommand {
                 )
                 .await?
             }
+            MarketCommand::CancelStorageDeal {
+                deal_id,
+                client_sr25519_key,
+                client_ecdsa_key,
+                client_ed25519_key,
+            } => {
+                let client_keypair = MultiPairSigner::new(
+                    client_sr25519_key.map(DebugPair::into_inner),
+                    client_ecdsa_key.map(DebugPair::into_inner),
+                    client_ed25519_key.map(DebugPair::into_inner),
+                );
+                Self::cancel_storage_deal(
+                    client,
+                    account_keypair,
+                    deal_id,
+                    client_keypair,
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            MarketCommand::ExtendDeal {
+                deal_id,
+                end_block,
+                storage_price_per_block,
+                client_sr25519_key,
+                client_ecdsa_key,
+                client_ed25519_key,
+            } => {
+                let client_keypair =
+                    MultiPairSigner::new(
+                        client_sr25519_key.map(DebugPair::into_inner),
+                        client_ecdsa_key.map(DebugPair::into_inner),
+                        client_ed25519_key.map(DebugPair::into_inner)
+                    )
+                    .expect("client is required to submit at least one key, this should've been handled by clap's ArgGroup");
+                Self::extend_deal(
+                    client,
+                    account_keypair,
+                    client_keypair,
+                    deal_id,
+                    end_block,
+                    storage_price_per_block,
+                    wait_for_finalization,
+                )
+                .await?
+            }
             _unsigned => unreachable!("unsigned commands should have been previously handled"),
         };
         let Some(submission_result) = submission_result else {
@@ -277,6 +378,60 @@ impl MarketCommand {
         Ok(submission_result)
     }
 
+    async fn cancel_storage_deal<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        deal_id: DealId,
+        client_keypair: Option<MultiPairSigner>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: MarketClientExt,
+    {
+        let submission_result = client
+            .cancel_storage_deal(
+                &account_keypair,
+                deal_id,
+                client_keypair.as_ref(),
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!("[{}] Successfully cancelled deal {}", result.hash, deal_id);
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn extend_deal<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        client_keypair: MultiPairSigner,
+        deal_id: DealId,
+        end_block: storagext::BlockNumber,
+        storage_price_per_block: storagext::Currency,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: MarketClientExt,
+    {
+        let submission_result = client
+            .extend_deal(
+                &account_keypair,
+                &client_keypair,
+                deal_id,
+                end_block,
+                storage_price_per_block,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!("[{}] Successfully extended deal {}", result.hash, deal_id);
+            });
+
+        Ok(submission_result)
+    }
+
     async fn settle_deal_payments<Client>(
         client: Client,
         account_keypair: MultiPairSigner,
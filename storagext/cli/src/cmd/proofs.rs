@@ -1,25 +1,71 @@
 use std::time::Duration;
 
 use clap::Subcommand;
+use primitives::proofs::{RegisteredPoStProof, RegisteredSealProof, RegisteredUpdateProof};
 use storagext::{
     clients::ProofsClientExt, multipair::MultiPairSigner, runtime::SubmissionResult,
     types::proofs::VerifyingKey, PolkaStorageConfig,
 };
 use url::Url;
 
-use crate::{missing_keypair_error, OutputFormat};
+use crate::{cmd::storage_provider::parse_post_proof, missing_keypair_error, OutputFormat};
+
+fn parse_seal_proof(src: &str) -> Result<RegisteredSealProof, String> {
+    match src {
+        "2KiB" => Ok(RegisteredSealProof::StackedDRG2KiBV1P1),
+        "2KiB-NI" => Ok(RegisteredSealProof::StackedDRG2KiBV1P2_Feat_NiPoRep),
+        "8MiB" => Ok(RegisteredSealProof::StackedDRG8MiBV1P1),
+        "8MiB-NI" => Ok(RegisteredSealProof::StackedDRG8MiBV1P2_Feat_NiPoRep),
+        "512MiB" => Ok(RegisteredSealProof::StackedDRG512MiBV1P1),
+        "512MiB-NI" => Ok(RegisteredSealProof::StackedDRG512MiBV1P2_Feat_NiPoRep),
+        unknown => Err(format!("Unknown Seal Proof type: {}", unknown)),
+    }
+}
+
+fn parse_update_proof(src: &str) -> Result<RegisteredUpdateProof, String> {
+    match src {
+        "2KiB" => Ok(RegisteredUpdateProof::StackedDRG2KiBV1),
+        "8MiB" => Ok(RegisteredUpdateProof::StackedDRG8MiBV1),
+        "512MiB" => Ok(RegisteredUpdateProof::StackedDRG512MiBV1),
+        unknown => Err(format!("Unknown Update Proof type: {}", unknown)),
+    }
+}
 
 #[derive(Debug, Subcommand)]
 #[command(name = "proofs", about = "CLI Client to the Proofs Pallet", version)]
 pub(crate) enum ProofsCommand {
     /// Set PoRep verifying key
     SetPorepVerifyingKey {
+        /// Seal proof type the key verifies proofs for: 2KiB, 8MiB or 512MiB.
+        #[arg(long, value_parser = parse_seal_proof, default_value = "2KiB")]
+        seal_proof: RegisteredSealProof,
         /// Verifying key. Either hex encoded as string or, if prepended with @, a path to a file containing the key's raw bytes.
         #[arg(value_parser = VerifyingKey::value_parser)]
         verifying_key: VerifyingKey,
     },
-    /// Set PoRep verifying key
+    /// Set non-interactive PoRep verifying key
+    SetNiPorepVerifyingKey {
+        /// Seal proof type the key verifies proofs for: 2KiB-NI, 8MiB-NI or 512MiB-NI.
+        #[arg(long, value_parser = parse_seal_proof, default_value = "2KiB-NI")]
+        seal_proof: RegisteredSealProof,
+        /// Verifying key. Either hex encoded as string or, if prepended with @, a path to a file containing the key's raw bytes.
+        #[arg(value_parser = VerifyingKey::value_parser)]
+        verifying_key: VerifyingKey,
+    },
+    /// Set PoSt verifying key
     SetPostVerifyingKey {
+        /// PoSt proof type the key verifies proofs for: 2KiB, 8MiB or 512MiB.
+        #[arg(long, value_parser = parse_post_proof, default_value = "2KiB")]
+        post_proof: RegisteredPoStProof,
+        /// Verifying key. Either hex encoded as string or, if prepended with @, a path to a file containing the key's raw bytes.
+        #[arg(value_parser = VerifyingKey::value_parser)]
+        verifying_key: VerifyingKey,
+    },
+    /// Set replica update verifying key
+    SetReplicaUpdateVerifyingKey {
+        /// Update proof type the key verifies proofs for: 2KiB, 8MiB or 512MiB.
+        #[arg(long, value_parser = parse_update_proof, default_value = "2KiB")]
+        update_proof: RegisteredUpdateProof,
         /// Verifying key. Either hex encoded as string or, if prepended with @, a path to a file containing the key's raw bytes.
         #[arg(value_parser = VerifyingKey::value_parser)]
         verifying_key: VerifyingKey,
@@ -46,7 +92,10 @@ impl ProofsCommand {
             // NOTE: subcommand_negates_reqs does not work for this since it only negates the parents'
             // requirements, and the global arguments (keys) are at the grandparent level
             // https://users.rust-lang.org/t/clap-ignore-global-argument-in-sub-command/101701/8
-            ProofsCommand::SetPorepVerifyingKey { verifying_key } => {
+            ProofsCommand::SetPorepVerifyingKey {
+                seal_proof,
+                verifying_key,
+            } => {
                 let Some(account_keypair) = account_keypair else {
                     return Err(missing_keypair_error::<Self>().into());
                 };
@@ -54,12 +103,33 @@ impl ProofsCommand {
                 Self::set_porep_verifying_key(
                     client,
                     account_keypair,
+                    seal_proof,
                     verifying_key,
                     wait_for_finalization,
                 )
                 .await?
             }
-            ProofsCommand::SetPostVerifyingKey { verifying_key } => {
+            ProofsCommand::SetNiPorepVerifyingKey {
+                seal_proof,
+                verifying_key,
+            } => {
+                let Some(account_keypair) = account_keypair else {
+                    return Err(missing_keypair_error::<Self>().into());
+                };
+
+                Self::set_ni_porep_verifying_key(
+                    client,
+                    account_keypair,
+                    seal_proof,
+                    verifying_key,
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            ProofsCommand::SetPostVerifyingKey {
+                post_proof,
+                verifying_key,
+            } => {
                 let Some(account_keypair) = account_keypair else {
                     return Err(missing_keypair_error::<Self>().into());
                 };
@@ -67,6 +137,24 @@ impl ProofsCommand {
                 Self::set_post_verifying_key(
                     client,
                     account_keypair,
+                    post_proof,
+                    verifying_key,
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            ProofsCommand::SetReplicaUpdateVerifyingKey {
+                update_proof,
+                verifying_key,
+            } => {
+                let Some(account_keypair) = account_keypair else {
+                    return Err(missing_keypair_error::<Self>().into());
+                };
+
+                Self::set_replica_update_verifying_key(
+                    client,
+                    account_keypair,
+                    update_proof,
                     verifying_key,
                     wait_for_finalization,
                 )
@@ -107,6 +195,7 @@ impl ProofsCommand {
     async fn set_porep_verifying_key<Client>(
         client: Client,
         account_keypair: MultiPairSigner,
+        seal_proof: RegisteredSealProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
@@ -114,7 +203,12 @@ impl ProofsCommand {
         Client: ProofsClientExt,
     {
         let submission_result = client
-            .set_porep_verifying_key(&account_keypair, verifying_key, wait_for_finalization)
+            .set_porep_verifying_key(
+                &account_keypair,
+                seal_proof,
+                verifying_key,
+                wait_for_finalization,
+            )
             .await?
             .inspect(|result| {
                 tracing::debug!("[{}] PoRep Key successfully set", result.hash);
@@ -123,9 +217,35 @@ impl ProofsCommand {
         Ok(submission_result)
     }
 
+    async fn set_ni_porep_verifying_key<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        seal_proof: RegisteredSealProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: ProofsClientExt,
+    {
+        let submission_result = client
+            .set_ni_porep_verifying_key(
+                &account_keypair,
+                seal_proof,
+                verifying_key,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!("[{}] NI-PoRep Key successfully set", result.hash);
+            });
+
+        Ok(submission_result)
+    }
+
     async fn set_post_verifying_key<Client>(
         client: Client,
         account_keypair: MultiPairSigner,
+        post_proof: RegisteredPoStProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
@@ -133,7 +253,12 @@ impl ProofsCommand {
         Client: ProofsClientExt,
     {
         let submission_result = client
-            .set_post_verifying_key(&account_keypair, verifying_key, wait_for_finalization)
+            .set_post_verifying_key(
+                &account_keypair,
+                post_proof,
+                verifying_key,
+                wait_for_finalization,
+            )
             .await?
             .inspect(|result| {
                 tracing::debug!("[{}] PoSt Key successfully set", result.hash);
@@ -141,4 +266,29 @@ impl ProofsCommand {
 
         Ok(submission_result)
     }
+
+    async fn set_replica_update_verifying_key<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        update_proof: RegisteredUpdateProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: ProofsClientExt,
+    {
+        let submission_result = client
+            .set_replica_update_verifying_key(
+                &account_keypair,
+                update_proof,
+                verifying_key,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!("[{}] Replica update Key successfully set", result.hash);
+            });
+
+        Ok(submission_result)
+    }
 }
@@ -1,15 +1,16 @@
-use std::time::Duration;
+use std::{collections::BTreeSet, time::Duration};
 
 use clap::Subcommand;
-use primitives::{proofs::RegisteredPoStProof, sector::SectorNumber};
+use primitives::{proofs::RegisteredPoStProof, sector::SectorNumber, PartitionNumber};
 use storagext::{
     deser::DeserializablePath,
     multipair::MultiPairSigner,
     runtime::SubmissionResult,
     types::storage_provider::{
-        FaultDeclaration as SxtFaultDeclaration, ProveCommitSector as SxtProveCommitSector,
-        RecoveryDeclaration as SxtRecoveryDeclaration,
-        SectorPreCommitInfo as SxtSectorPreCommitInfo,
+        ExpirationExtension as SxtExpirationExtension, FaultDeclaration as SxtFaultDeclaration,
+        ProveCommitBatch as SxtProveCommitBatch, ProveCommitNiSector as SxtProveCommitNiSector,
+        ProveCommitSector as SxtProveCommitSector, RecoveryDeclaration as SxtRecoveryDeclaration,
+        ReplicaUpdate as SxtReplicaUpdate, SectorPreCommitInfo as SxtSectorPreCommitInfo,
         SubmitWindowedPoStParams as SxtSubmitWindowedPoStParams,
         TerminationDeclaration as SxtTerminationDeclaration,
     },
@@ -19,9 +20,11 @@ use url::Url;
 
 use crate::{missing_keypair_error, operation_takes_a_while, OutputFormat};
 
-fn parse_post_proof(src: &str) -> Result<RegisteredPoStProof, String> {
+pub(crate) fn parse_post_proof(src: &str) -> Result<RegisteredPoStProof, String> {
     match src {
         "2KiB" => Ok(RegisteredPoStProof::StackedDRGWindow2KiBV1P1),
+        "8MiB" => Ok(RegisteredPoStProof::StackedDRGWindow8MiBV1P1),
+        "512MiB" => Ok(RegisteredPoStProof::StackedDRGWindow512MiBV1P1),
         unknown => Err(format!("Unknown PoSt Proof type: {}", unknown)),
     }
 }
@@ -39,11 +42,16 @@ pub enum StorageProviderCommand {
         /// PeerId in Storage Provider P2P network, can be any String.
         peer_id: String,
         /// Proof of Space Time type.
-        /// Can only be "2KiB" meaning `RegisteredPoStProof::StackedDRGWindow2KiBV1P1`.
+        /// Can be "2KiB", "8MiB" or "512MiB", e.g. "2KiB" means `RegisteredPoStProof::StackedDRGWindow2KiBV1P1`.
         #[arg(long, value_parser = parse_post_proof, default_value = "2KiB")]
         post_proof: RegisteredPoStProof,
     },
 
+    /// Deregister the Storage Provider, releasing its remaining locked funds.
+    /// It must not have live or pre-committed sectors.
+    #[command(name = "deregister")]
+    DeregisterStorageProvider,
+
     /// Retrieve all registered Storage Providers.
     RetrieveStorageProviders,
 
@@ -61,6 +69,19 @@ pub enum StorageProviderCommand {
         prove_commit_sectors: std::vec::Vec<SxtProveCommitSector>,
     },
 
+    /// Proves many previously pre-committed sectors with a single batch of proofs.
+    ProveCommitBatch {
+        #[arg(value_parser = <SxtProveCommitBatch as DeserializablePath>::deserialize_json)]
+        batch: SxtProveCommitBatch,
+    },
+
+    /// Onboards sectors sealed with a non-interactive seal proof in a single step,
+    /// without pre-committing them first.
+    ProveCommitNi {
+        #[arg(value_parser = <Vec<SxtProveCommitNiSector> as DeserializablePath>::deserialize_json)]
+        prove_commit_ni_sectors: std::vec::Vec<SxtProveCommitNiSector>,
+    },
+
     /// Submit a Proof-of-SpaceTime (PoST).
     #[command(name = "submit-windowed-post")]
     SubmitWindowedProofOfSpaceTime {
@@ -86,6 +107,81 @@ pub enum StorageProviderCommand {
         #[arg(value_parser = <Vec<SxtTerminationDeclaration> as DeserializablePath>::deserialize_json)]
         terminations: std::vec::Vec<SxtTerminationDeclaration>,
     },
+
+    /// Extend the expiration of sectors.
+    ExtendSectorExpiration {
+        #[arg(value_parser = <Vec<SxtExpirationExtension> as DeserializablePath>::deserialize_json)]
+        extensions: std::vec::Vec<SxtExpirationExtension>,
+    },
+
+    /// Put deals into committed capacity sectors, by proving their updated replicas.
+    ProveReplicaUpdates {
+        #[arg(value_parser = <Vec<SxtReplicaUpdate> as DeserializablePath>::deserialize_json)]
+        updates: std::vec::Vec<SxtReplicaUpdate>,
+    },
+
+    /// Compact partitions of a deadline, re-packing their live sectors
+    /// and dropping the terminated and expired ones.
+    CompactPartitions {
+        /// The deadline the partitions belong to.
+        deadline: u64,
+        /// The partitions to compact.
+        #[arg(required = true)]
+        partitions: std::vec::Vec<PartitionNumber>,
+    },
+
+    /// Move partitions from a deadline to another, which must be due before it.
+    MovePartitions {
+        /// The deadline the partitions belong to.
+        #[arg(long)]
+        orig_deadline: u64,
+        /// The deadline the partitions are moved to.
+        #[arg(long)]
+        dest_deadline: u64,
+        /// The partitions to move.
+        #[arg(required = true)]
+        partitions: std::vec::Vec<PartitionNumber>,
+    },
+
+    /// Change the worker and control addresses, only the owner can change them.
+    /// The control addresses are replaced immediately,
+    /// the worker change needs to be confirmed after a delay.
+    ChangeWorkerAddress {
+        /// The new worker account.
+        new_worker: <PolkaStorageConfig as subxt::Config>::AccountId,
+        /// The new control accounts, replacing the current ones.
+        #[arg(long = "control-address")]
+        control_addresses: std::vec::Vec<<PolkaStorageConfig as subxt::Config>::AccountId>,
+    },
+
+    /// Confirm a pending worker address change.
+    ConfirmChangeWorkerAddress,
+
+    /// Change the PeerId of the Storage Provider.
+    ChangePeerId {
+        /// PeerId in Storage Provider P2P network, can be any String.
+        peer_id: String,
+    },
+
+    /// Change the multiaddrs clients use to reach the Storage Provider.
+    /// Passing no multiaddrs removes the current ones.
+    ChangeMultiaddrs {
+        /// The new multiaddrs, replacing the current ones — e.g. `/ip4/127.0.0.1/tcp/8001`.
+        multiaddrs: std::vec::Vec<String>,
+    },
+
+    /// Retrieve the PeerId and multiaddrs of a Storage Provider.
+    RetrievePeerInfo {
+        /// The Storage Provider's owner account.
+        storage_provider: <PolkaStorageConfig as subxt::Config>::AccountId,
+    },
+
+    /// Retrieve the power of a Storage Provider, or the total power of the network.
+    RetrievePower {
+        /// The Storage Provider's owner account, the total power is retrieved when missing.
+        #[arg(long)]
+        storage_provider: Option<<PolkaStorageConfig as subxt::Config>::AccountId>,
+    },
 }
 
 impl StorageProviderCommand {
@@ -105,7 +201,7 @@ impl StorageProviderCommand {
         let client = storagext::Client::new(node_rpc, n_retries, retry_interval).await?;
 
         match self {
-            // Only command that doesn't need a key.
+            // Only commands that don't need a key.
             //
             // NOTE: subcommand_negates_reqs does not work for this since it only negates the parents'
             // requirements, and the global arguments (keys) are at the grandparent level
@@ -123,6 +219,28 @@ impl StorageProviderCommand {
                     }
                 }
             }
+            StorageProviderCommand::RetrievePeerInfo { storage_provider } => {
+                if let Some(peer_info) = client.peer_info(&storage_provider.into()).await? {
+                    println!("{}", output_format.format(&peer_info)?);
+                } else {
+                    tracing::error!("Could not find the Storage Provider");
+                }
+            }
+            StorageProviderCommand::RetrievePower { storage_provider } => {
+                if let Some(storage_provider) = storage_provider {
+                    if let Some(power) = client
+                        .storage_provider_power(&storage_provider.into())
+                        .await?
+                    {
+                        println!("{}", output_format.format(&power)?);
+                    } else {
+                        tracing::error!("Could not find the Storage Provider");
+                    }
+                } else {
+                    let power = client.total_power().await?;
+                    println!("{}", output_format.format(&power)?);
+                }
+            }
             else_ => {
                 let Some(account_keypair) = account_keypair else {
                     return Err(missing_keypair_error::<Self>().into());
@@ -167,6 +285,10 @@ impl StorageProviderCommand {
                 )
                 .await?
             }
+            StorageProviderCommand::DeregisterStorageProvider => {
+                Self::deregister_storage_provider(client, account_keypair, wait_for_finalization)
+                    .await?
+            }
             StorageProviderCommand::PreCommit { pre_commit_sectors } => {
                 Self::pre_commit(
                     client,
@@ -187,6 +309,21 @@ impl StorageProviderCommand {
                 )
                 .await?
             }
+            StorageProviderCommand::ProveCommitBatch { batch } => {
+                Self::prove_commit_batch(client, account_keypair, batch, wait_for_finalization)
+                    .await?
+            }
+            StorageProviderCommand::ProveCommitNi {
+                prove_commit_ni_sectors,
+            } => {
+                Self::prove_commit_ni(
+                    client,
+                    account_keypair,
+                    prove_commit_ni_sectors,
+                    wait_for_finalization,
+                )
+                .await?
+            }
             StorageProviderCommand::SubmitWindowedProofOfSpaceTime { windowed_post } => {
                 Self::submit_windowed_post(
                     client,
@@ -217,6 +354,72 @@ impl StorageProviderCommand {
                 )
                 .await?
             }
+            StorageProviderCommand::ExtendSectorExpiration { extensions } => {
+                Self::extend_sector_expiration(
+                    client,
+                    account_keypair,
+                    extensions,
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            StorageProviderCommand::ProveReplicaUpdates { updates } => {
+                Self::prove_replica_updates(client, account_keypair, updates, wait_for_finalization)
+                    .await?
+            }
+            StorageProviderCommand::CompactPartitions {
+                deadline,
+                partitions,
+            } => {
+                Self::compact_partitions(
+                    client,
+                    account_keypair,
+                    deadline,
+                    partitions.into_iter().collect(),
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            StorageProviderCommand::MovePartitions {
+                orig_deadline,
+                dest_deadline,
+                partitions,
+            } => {
+                Self::move_partitions(
+                    client,
+                    account_keypair,
+                    orig_deadline,
+                    dest_deadline,
+                    partitions.into_iter().collect(),
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            StorageProviderCommand::ChangeWorkerAddress {
+                new_worker,
+                control_addresses,
+            } => {
+                Self::change_worker_address(
+                    client,
+                    account_keypair,
+                    new_worker,
+                    control_addresses,
+                    wait_for_finalization,
+                )
+                .await?
+            }
+            StorageProviderCommand::ConfirmChangeWorkerAddress => {
+                Self::confirm_change_worker_address(client, account_keypair, wait_for_finalization)
+                    .await?
+            }
+            StorageProviderCommand::ChangePeerId { peer_id } => {
+                Self::change_peer_id(client, account_keypair, peer_id, wait_for_finalization)
+                    .await?
+            }
+            StorageProviderCommand::ChangeMultiaddrs { multiaddrs } => {
+                Self::change_multiaddrs(client, account_keypair, multiaddrs, wait_for_finalization)
+                    .await?
+            }
             _unsigned => unreachable!("unsigned commands should have been previously handled"),
         };
 
@@ -343,6 +546,61 @@ impl StorageProviderCommand {
         Ok(submission_result)
     }
 
+    async fn prove_commit_batch<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        batch: SxtProveCommitBatch,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let sector_numbers = batch.sector_numbers.clone();
+        let submission_result = client
+            .prove_commit_batch(&account_keypair, batch, wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully proven sectors {:?}.",
+                    result.hash,
+                    sector_numbers
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn prove_commit_ni<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        prove_commit_ni_sectors: Vec<SxtProveCommitNiSector>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let sector_numbers = prove_commit_ni_sectors
+            .iter()
+            .map(|s| s.sector.sector_number)
+            .collect::<Vec<_>>();
+        let submission_result = client
+            .prove_commit_ni_sectors(
+                &account_keypair,
+                prove_commit_ni_sectors,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully proven sectors {:?}.",
+                    result.hash,
+                    sector_numbers
+                )
+            });
+
+        Ok(submission_result)
+    }
+
     async fn submit_windowed_post<Client>(
         client: Client,
         account_keypair: MultiPairSigner,
@@ -416,4 +674,218 @@ impl StorageProviderCommand {
 
         Ok(submission_result)
     }
+
+    async fn extend_sector_expiration<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        extensions: Vec<SxtExpirationExtension>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .extend_sector_expiration(&account_keypair, extensions, wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully extended sector expirations.",
+                    result.hash
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn prove_replica_updates<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        updates: Vec<SxtReplicaUpdate>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .prove_replica_updates(&account_keypair, updates, wait_for_finalization)
+            .await?
+            .inspect(|result| tracing::debug!("[{}] Successfully updated replicas.", result.hash));
+
+        Ok(submission_result)
+    }
+
+    async fn compact_partitions<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .compact_partitions(
+                &account_keypair,
+                deadline,
+                partitions,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully compacted partitions of deadline {}.",
+                    result.hash,
+                    deadline
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn move_partitions<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        orig_deadline: u64,
+        dest_deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .move_partitions(
+                &account_keypair,
+                orig_deadline,
+                dest_deadline,
+                partitions,
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully moved partitions from deadline {} to {}.",
+                    result.hash,
+                    orig_deadline,
+                    dest_deadline
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn change_worker_address<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        new_worker: <PolkaStorageConfig as subxt::Config>::AccountId,
+        control_addresses: Vec<<PolkaStorageConfig as subxt::Config>::AccountId>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .change_worker_address(
+                &account_keypair,
+                new_worker.clone().into(),
+                control_addresses.into_iter().map(Into::into).collect(),
+                wait_for_finalization,
+            )
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully changed the control addresses and requested worker {}.",
+                    result.hash,
+                    new_worker
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn deregister_storage_provider<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .deregister_storage_provider(&account_keypair, wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully deregistered from Storage Provider Pallet",
+                    result.hash
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn confirm_change_worker_address<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .confirm_change_worker_address(&account_keypair, wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!("[{}] Successfully changed the worker address.", result.hash)
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn change_peer_id<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        peer_id: String,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .change_peer_id(&account_keypair, peer_id.clone(), wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully changed the peer ID to {}.",
+                    result.hash,
+                    peer_id
+                )
+            });
+
+        Ok(submission_result)
+    }
+
+    async fn change_multiaddrs<Client>(
+        client: Client,
+        account_keypair: MultiPairSigner,
+        multiaddrs: Vec<String>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Client: StorageProviderClientExt,
+    {
+        let submission_result = client
+            .change_multiaddrs(&account_keypair, multiaddrs.clone(), wait_for_finalization)
+            .await?
+            .inspect(|result| {
+                tracing::debug!(
+                    "[{}] Successfully changed the multiaddrs to {:?}.",
+                    result.hash,
+                    multiaddrs
+                )
+            });
+
+        Ok(submission_result)
+    }
 }
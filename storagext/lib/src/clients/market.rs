@@ -1,14 +1,21 @@
 use std::future::Future;
 
-use primitives::DealId;
+use primitives::{
+    pallets::{DealCancellation, DealExtension},
+    DealId,
+};
 use subxt::{ext::sp_core::crypto::Ss58Codec, utils::Static};
 
 use crate::{
     runtime::{
         self,
         client::SubmissionResult,
-        runtime_types::pallet_market::pallet::{
-            BalanceEntry, ClientDealProposal as RuntimeClientDealProposal,
+        runtime_types::{
+            pallet_market::pallet::{
+                BalanceEntry, ClientDealProposal as RuntimeClientDealProposal,
+            },
+            primitives::pallets::{DealQuote, MarketBalance},
+            sp_runtime::DispatchError,
         },
     },
     types::market::{ClientDealProposal, DealProposal},
@@ -87,6 +94,37 @@ pub trait MarketClientExt {
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>;
 
+    /// Cancel the given published deal, before it's activated.
+    ///
+    /// When the `client_keypair` is provided, the client consents to the cancellation by signing it,
+    /// otherwise, the provider forfeits part of the deal's collateral to the client.
+    fn cancel_storage_deal<Keypair, ClientKeypair>(
+        &self,
+        account_keypair: &Keypair,
+        deal_id: DealId,
+        client_keypair: Option<&ClientKeypair>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+        ClientKeypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    /// Extend the given active deal to the `end_block`, at `storage_price_per_block` from now on.
+    ///
+    /// The `client_keypair` signs the client's agreement to the extension.
+    fn extend_deal<Keypair, ClientKeypair>(
+        &self,
+        account_keypair: &Keypair,
+        client_keypair: &ClientKeypair,
+        deal_id: DealId,
+        end_block: BlockNumber,
+        storage_price_per_block: Currency,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+        ClientKeypair: subxt::tx::Signer<PolkaStorageConfig>;
+
     /// Retrieve the balance for a given account (includes the `free` and `locked` balance).
     fn retrieve_balance(
         &self,
@@ -98,6 +136,47 @@ pub trait MarketClientExt {
         &self,
         deal_id: DealId,
     ) -> impl Future<Output = Result<Option<DealProposal>, subxt::Error>>;
+
+    /// Quote the funds needed to publish the given *signed* deal, without publishing it.
+    ///
+    /// Returns the reason why publishing the deal would currently fail, instead of the quote.
+    fn quote_deal(
+        &self,
+        deal: ClientDealProposal,
+    ) -> impl Future<Output = Result<Result<DealQuote<Currency>, DispatchError>, subxt::Error>>;
+
+    /// Retrieve the `free` and `locked` balance of a given account.
+    fn balance_summary(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+    ) -> impl Future<Output = Result<MarketBalance<Currency>, subxt::Error>>;
+
+    /// Retrieve the payment the storage provider can currently claim by settling the given deal.
+    fn claimable_settlement(
+        &self,
+        deal_id: DealId,
+    ) -> impl Future<Output = Result<Option<Currency>, subxt::Error>>;
+
+    /// Retrieve a page of the client's deal IDs, with at most `limit` deals.
+    ///
+    /// The first page is retrieved with `start_after` set to [`None`],
+    /// the next ones by setting it to the last deal ID of the previous page, until an empty page is returned.
+    fn client_deals(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+        start_after: Option<DealId>,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<DealId>, subxt::Error>>;
+
+    /// Retrieve a page of the storage provider's deal IDs, with at most `limit` deals.
+    ///
+    /// Pages are retrieved like in [`MarketClientExt::client_deals`].
+    fn provider_deals(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+        start_after: Option<DealId>,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<DealId>, subxt::Error>>;
 }
 
 impl MarketClientExt for crate::runtime::client::Client {
@@ -264,6 +343,83 @@ impl MarketClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+            deal_id = deal_id
+        )
+    )]
+    async fn cancel_storage_deal<Keypair, ClientKeypair>(
+        &self,
+        account_keypair: &Keypair,
+        deal_id: DealId,
+        client_keypair: Option<&ClientKeypair>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+        ClientKeypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let client_signature = client_keypair.map(|client_keypair| {
+            let cancellation = DealCancellation {
+                genesis_hash: self.client.genesis_hash(),
+                deal_id,
+            };
+            Static(client_keypair.sign(&cancellation.signing_payload()))
+        });
+
+        let payload = runtime::tx()
+            .market()
+            .cancel_storage_deal(deal_id, client_signature);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+            deal_id = deal_id,
+            end_block = end_block,
+            storage_price_per_block = storage_price_per_block
+        )
+    )]
+    async fn extend_deal<Keypair, ClientKeypair>(
+        &self,
+        account_keypair: &Keypair,
+        client_keypair: &ClientKeypair,
+        deal_id: DealId,
+        end_block: BlockNumber,
+        storage_price_per_block: Currency,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+        ClientKeypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let extension = DealExtension {
+            genesis_hash: self.client.genesis_hash(),
+            deal_id,
+            end_block,
+            storage_price_per_block,
+        };
+        let client_signature = Static(client_keypair.sign(&extension.signing_payload()));
+
+        let payload = runtime::tx().market().extend_deal(
+            deal_id,
+            end_block,
+            storage_price_per_block,
+            client_signature,
+        );
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -306,4 +462,119 @@ impl MarketClientExt for crate::runtime::client::Client {
 
         Ok(Some(deal))
     }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn quote_deal(
+        &self,
+        deal: ClientDealProposal,
+    ) -> Result<Result<DealQuote<Currency>, DispatchError>, subxt::Error> {
+        let payload = runtime::apis()
+            .market_api()
+            .quote_deal(SpecializedRuntimeClientDealProposal::from(deal));
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_id.to_ss58check()
+        )
+    )]
+    async fn balance_summary(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+    ) -> Result<MarketBalance<Currency>, subxt::Error> {
+        let payload = runtime::apis()
+            .market_api()
+            .balance(subxt::utils::AccountId32::from(account_id));
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(deal_id))]
+    async fn claimable_settlement(
+        &self,
+        deal_id: DealId,
+    ) -> Result<Option<Currency>, subxt::Error> {
+        let payload = runtime::apis().market_api().claimable_settlement(deal_id);
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_id.to_ss58check(),
+            start_after,
+            limit
+        )
+    )]
+    async fn client_deals(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+        start_after: Option<DealId>,
+        limit: u32,
+    ) -> Result<Vec<DealId>, subxt::Error> {
+        let payload = runtime::apis().market_api().client_deals(
+            subxt::utils::AccountId32::from(account_id),
+            start_after,
+            limit,
+        );
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+            .map(|deal_ids| deal_ids.0)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_id.to_ss58check(),
+            start_after,
+            limit
+        )
+    )]
+    async fn provider_deals(
+        &self,
+        account_id: <PolkaStorageConfig as subxt::Config>::AccountId,
+        start_after: Option<DealId>,
+        limit: u32,
+    ) -> Result<Vec<DealId>, subxt::Error> {
+        let payload = runtime::apis().market_api().provider_deals(
+            subxt::utils::AccountId32::from(account_id),
+            start_after,
+            limit,
+        );
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+            .map(|deal_ids| deal_ids.0)
+    }
 }
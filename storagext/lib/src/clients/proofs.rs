@@ -1,8 +1,16 @@
 use futures::Future;
+use primitives::proofs::{RegisteredPoStProof, RegisteredSealProof, RegisteredUpdateProof};
 use subxt::ext::sp_core::crypto::Ss58Codec;
 
 use crate::{
-    runtime::{self, SubmissionResult},
+    runtime::{
+        self,
+        runtime_types::{
+            pallet_proofs::pallet::Call as ProofsCall, polka_storage_runtime::RuntimeCall,
+            primitives::pallets::VerifyingKeyInfo,
+        },
+        SubmissionResult,
+    },
     types::proofs::VerifyingKey,
     PolkaStorageConfig,
 };
@@ -11,6 +19,17 @@ pub trait ProofsClientExt {
     fn set_porep_verifying_key<Keypair>(
         &self,
         account_keypair: &Keypair,
+        seal_proof: RegisteredSealProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn set_ni_porep_verifying_key<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        seal_proof: RegisteredSealProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
@@ -20,11 +39,33 @@ pub trait ProofsClientExt {
     fn set_post_verifying_key<Keypair>(
         &self,
         account_keypair: &Keypair,
+        post_type: RegisteredPoStProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn set_replica_update_verifying_key<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        update_proof: RegisteredUpdateProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+    /// Retrieves the hash and version of the PoRep verifying key set for the seal proof type.
+    fn porep_verifying_key_info(
+        &self,
+        seal_proof: RegisteredSealProof,
+    ) -> impl Future<Output = Result<Option<VerifyingKeyInfo>, subxt::Error>>;
+
+    /// Retrieves the hash and version of the PoSt verifying key set for the PoSt proof type.
+    fn post_verifying_key_info(
+        &self,
+        post_type: RegisteredPoStProof,
+    ) -> impl Future<Output = Result<Option<VerifyingKeyInfo>, subxt::Error>>;
 }
 
 impl ProofsClientExt for crate::runtime::client::Client {
@@ -38,15 +79,49 @@ impl ProofsClientExt for crate::runtime::client::Client {
     async fn set_porep_verifying_key<Keypair>(
         &self,
         account_keypair: &Keypair,
+        seal_proof: RegisteredSealProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>,
     {
-        let payload = runtime::tx()
-            .proofs()
-            .set_porep_verifying_key(verifying_key.into());
+        // Verifying keys can only be set by the update origin, the sudo key on the parachain
+        let payload =
+            runtime::tx()
+                .sudo()
+                .sudo(RuntimeCall::Proofs(ProofsCall::set_porep_verifying_key {
+                    seal_proof,
+                    verifying_key: verifying_key.into(),
+                }));
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn set_ni_porep_verifying_key<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        seal_proof: RegisteredSealProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx().sudo().sudo(RuntimeCall::Proofs(
+            ProofsCall::set_ni_porep_verifying_key {
+                seal_proof,
+                verifying_key: verifying_key.into(),
+            },
+        ));
 
         self.traced_submission(&payload, account_keypair, wait_for_finalization)
             .await
@@ -62,17 +137,82 @@ impl ProofsClientExt for crate::runtime::client::Client {
     async fn set_post_verifying_key<Keypair>(
         &self,
         account_keypair: &Keypair,
+        post_type: RegisteredPoStProof,
+        verifying_key: VerifyingKey,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload =
+            runtime::tx()
+                .sudo()
+                .sudo(RuntimeCall::Proofs(ProofsCall::set_post_verifying_key {
+                    post_type,
+                    verifying_key: verifying_key.into(),
+                }));
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn set_replica_update_verifying_key<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        update_proof: RegisteredUpdateProof,
         verifying_key: VerifyingKey,
         wait_for_finalization: bool,
     ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>,
     {
-        let payload = runtime::tx()
-            .proofs()
-            .set_post_verifying_key(verifying_key.into());
+        let payload = runtime::tx().sudo().sudo(RuntimeCall::Proofs(
+            ProofsCall::set_replica_update_verifying_key {
+                update_proof,
+                verifying_key: verifying_key.into(),
+            },
+        ));
 
         self.traced_submission(&payload, account_keypair, wait_for_finalization)
             .await
     }
+
+    async fn porep_verifying_key_info(
+        &self,
+        seal_proof: RegisteredSealProof,
+    ) -> Result<Option<VerifyingKeyInfo>, subxt::Error> {
+        let payload = runtime::apis()
+            .proofs_api()
+            .porep_verifying_key_info(seal_proof);
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    async fn post_verifying_key_info(
+        &self,
+        post_type: RegisteredPoStProof,
+    ) -> Result<Option<VerifyingKeyInfo>, subxt::Error> {
+        let payload = runtime::apis()
+            .proofs_api()
+            .post_verifying_key_info(post_type);
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
 }
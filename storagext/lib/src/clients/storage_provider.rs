@@ -1,7 +1,9 @@
-use std::future::Future;
+use std::{collections::BTreeSet, future::Future};
 
-use primitives::proofs::RegisteredPoStProof;
-use runtime::runtime_types::bounded_collections::bounded_vec::BoundedVec;
+use primitives::{proofs::RegisteredPoStProof, sector::SectorNumber, PartitionNumber};
+use runtime::runtime_types::bounded_collections::{
+    bounded_btree_set::BoundedBTreeSet, bounded_vec::BoundedVec,
+};
 use subxt::{
     ext::{futures::TryStreamExt, sp_core::crypto::Ss58Codec},
     utils::AccountId32,
@@ -13,13 +15,20 @@ use crate::{
         bounded_vec::IntoBoundedByteVec,
         client::SubmissionResult,
         runtime_types::{
-            pallet_storage_provider::storage_provider::StorageProviderState,
-            primitives::pallets::DeadlineInfo,
+            pallet_storage_provider::{
+                deadline::{CompactPartitionsParams, MovePartitionsParams},
+                storage_provider::StorageProviderState,
+            },
+            primitives::pallets::{
+                DeadlineInfo, LockedFunds, PreCommittedSector, SectorFaults, SectorInfo,
+                StoragePower, StorageProviderPeerInfo,
+            },
         },
         storage_provider::calls::types::register_storage_provider::PeerId,
     },
     types::storage_provider::{
-        DeadlineState, FaultDeclaration, ProveCommitSector, RecoveryDeclaration,
+        DeadlineState, ExpirationExtension, FaultDeclaration, ProveCommitBatch,
+        ProveCommitNiSector, ProveCommitSector, RecoveryDeclaration, ReplicaUpdate,
         SectorPreCommitInfo, SubmitWindowedPoStParams, TerminationDeclaration,
     },
     BlockNumber, Currency, PolkaStorageConfig,
@@ -35,6 +44,15 @@ pub trait StorageProviderClientExt {
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>;
 
+    /// Deregisters the storage provider, releasing its remaining locked funds.
+    fn deregister_storage_provider<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
     fn pre_commit_sectors<Keypair>(
         &self,
         account_keypair: &Keypair,
@@ -53,6 +71,26 @@ pub trait StorageProviderClientExt {
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>;
 
+    /// Proves many pre-committed sectors at once, with a single batch of proofs.
+    fn prove_commit_batch<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        batch: ProveCommitBatch,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    /// Onboards sectors sealed with a non-interactive seal proof, without pre-committing them.
+    fn prove_commit_ni_sectors<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        sectors: Vec<ProveCommitNiSector>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
     fn submit_windowed_post<Keypair>(
         &self,
         account_keypair: &Keypair,
@@ -89,13 +127,102 @@ pub trait StorageProviderClientExt {
     where
         Keypair: subxt::tx::Signer<PolkaStorageConfig>;
 
+    fn extend_sector_expiration<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        extensions: Vec<ExpirationExtension>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn change_worker_address<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        new_worker: AccountId32,
+        new_control_addresses: Vec<AccountId32>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn confirm_change_worker_address<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn change_peer_id<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        peer_id: String,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    fn change_multiaddrs<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        multiaddrs: Vec<String>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    /// Puts deals into committed capacity sectors, by proving their updated replicas.
+    fn prove_replica_updates<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        updates: Vec<ReplicaUpdate>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    /// Compacts partitions of a deadline, dropping their terminated and expired sectors.
+    fn compact_partitions<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
+    /// Moves partitions from a deadline to another, which must be due before it.
+    fn move_partitions<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        orig_deadline: u64,
+        dest_deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> impl Future<Output = Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>;
+
     fn retrieve_storage_provider(
         &self,
         account_id: &AccountId32,
     ) -> impl Future<
-        Output = Result<Option<StorageProviderState<PeerId, Currency, BlockNumber>>, subxt::Error>,
+        Output = Result<
+            Option<StorageProviderState<AccountId32, PeerId, Currency, BlockNumber>>,
+            subxt::Error,
+        >,
     >;
 
+    /// Resolves the owner of the storage provider the account acts for.
+    ///
+    /// The account can be the owner itself, the worker or one of the control addresses.
+    fn resolve_storage_provider_owner(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<AccountId32>, subxt::Error>>;
+
     fn retrieve_registered_storage_providers(
         &self,
     ) -> impl Future<Output = Result<Vec<String>, subxt::Error>>;
@@ -112,12 +239,62 @@ pub trait StorageProviderClientExt {
         deadline_index: u64,
     ) -> impl Future<Output = Result<Option<DeadlineState>, subxt::Error>>;
 
+    /// Retrieves the peer ID and multiaddrs clients use to reach the storage provider.
+    fn peer_info(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<StorageProviderPeerInfo<PeerId>>, subxt::Error>>;
+
+    /// Retrieves the raw byte and quality adjusted power of the storage provider.
+    fn storage_provider_power(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<StoragePower>, subxt::Error>>;
+
+    /// Retrieves the total raw byte and quality adjusted power of the network.
+    fn total_power(&self) -> impl Future<Output = Result<StoragePower, subxt::Error>>;
+
+    /// Retrieves the on-chain information about a proven sector and the deadline and partition it's assigned to.
+    fn sector_info(
+        &self,
+        account_id: &AccountId32,
+        sector_number: SectorNumber,
+    ) -> impl Future<Output = Result<Option<SectorInfo<BlockNumber>>, subxt::Error>>;
+
+    /// Retrieves the faulty, recovering and terminated sectors of the storage provider.
+    fn sector_faults(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<SectorFaults>, subxt::Error>>;
+
+    /// Retrieves the pre-committed sectors of the storage provider, along with the block by which they must be proven.
+    fn pre_committed_sectors(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<Vec<PreCommittedSector<Currency, BlockNumber>>>, subxt::Error>>;
+
+    /// Retrieves the next deadline in which the storage provider needs to submit a Windowed PoSt.
+    fn next_deadline(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<DeadlineInfo<BlockNumber>>, subxt::Error>>;
+
+    /// Retrieves the pre-commit deposits and initial pledge locked by the storage provider.
+    fn locked_funds(
+        &self,
+        account_id: &AccountId32,
+    ) -> impl Future<Output = Result<Option<LockedFunds<Currency>>, subxt::Error>>;
+
     fn proving_period_info(&self) -> Result<ProvingPeriodInfo, subxt::Error>;
 }
 
 pub struct ProvingPeriodInfo {
     /// Number of deadlines in a proving period,
     pub deadlines: u64,
+    /// Length of a proving period, in blocks.
+    pub proving_period: u64,
+    /// Length of a deadline's challenge window, in blocks.
+    pub challenge_window: u64,
 }
 
 impl StorageProviderClientExt for crate::runtime::client::Client {
@@ -126,8 +303,20 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .storage_provider()
             .w_po_st_period_deadlines();
         let deadlines = self.client.constants().at(&query)?;
-
-        Ok(ProvingPeriodInfo { deadlines })
+        let query = runtime::constants()
+            .storage_provider()
+            .w_po_st_proving_period();
+        let proving_period = self.client.constants().at(&query)?;
+        let query = runtime::constants()
+            .storage_provider()
+            .w_po_st_challenge_window();
+        let challenge_window = self.client.constants().at(&query)?;
+
+        Ok(ProvingPeriodInfo {
+            deadlines,
+            proving_period,
+            challenge_window,
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all, fields(deadline_index))]
@@ -166,6 +355,139 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn peer_info(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<StorageProviderPeerInfo<PeerId>>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .peer_info(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn storage_provider_power(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<StoragePower>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .storage_provider_power(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn total_power(&self) -> Result<StoragePower, subxt::Error> {
+        let payload = runtime::apis().storage_provider_api().total_power();
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(sector_number))]
+    async fn sector_info(
+        &self,
+        account_id: &AccountId32,
+        sector_number: SectorNumber,
+    ) -> Result<Option<SectorInfo<BlockNumber>>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .sector_info(account_id.clone(), sector_number);
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn sector_faults(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<SectorFaults>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .sector_faults(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn pre_committed_sectors(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<Vec<PreCommittedSector<Currency, BlockNumber>>>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .pre_committed_sectors(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+            .map(|sectors| sectors.map(|sectors| sectors.0))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn next_deadline(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<DeadlineInfo<BlockNumber>>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .next_deadline(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn locked_funds(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<LockedFunds<Currency>>, subxt::Error> {
+        let payload = runtime::apis()
+            .storage_provider_api()
+            .locked_funds(account_id.clone());
+
+        self.client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(payload)
+            .await
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -191,6 +513,29 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn deregister_storage_provider<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .deregister_storage_provider();
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -239,6 +584,55 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn prove_commit_batch<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        batch: ProveCommitBatch,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .prove_commit_batch(batch.into());
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn prove_commit_ni_sectors<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        sectors: Vec<ProveCommitNiSector>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let sectors = BoundedVec(sectors.into_iter().map(Into::into).collect());
+        let payload = runtime::tx()
+            .storage_provider()
+            .prove_commit_ni_sectors(sectors);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -329,11 +723,217 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn extend_sector_expiration<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        extensions: Vec<ExpirationExtension>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .extend_sector_expiration(extensions.into());
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+            new_worker = new_worker.to_ss58check(),
+        )
+    )]
+    async fn change_worker_address<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        new_worker: AccountId32,
+        new_control_addresses: Vec<AccountId32>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .change_worker_address(new_worker, BoundedVec(new_control_addresses));
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn confirm_change_worker_address<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .confirm_change_worker_address();
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+            peer_id,
+        )
+    )]
+    async fn change_peer_id<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        peer_id: String,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let payload = runtime::tx()
+            .storage_provider()
+            .change_peer_id(peer_id.into_bounded_byte_vec());
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn change_multiaddrs<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        multiaddrs: Vec<String>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let multiaddrs = BoundedVec(
+            multiaddrs
+                .into_iter()
+                .map(IntoBoundedByteVec::into_bounded_byte_vec)
+                .collect(),
+        );
+        let payload = runtime::tx()
+            .storage_provider()
+            .change_multiaddrs(multiaddrs);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn prove_replica_updates<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        updates: Vec<ReplicaUpdate>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let updates = BoundedVec(updates.into_iter().map(Into::into).collect());
+        let payload = runtime::tx()
+            .storage_provider()
+            .prove_replica_updates(updates);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn compact_partitions<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let params = CompactPartitionsParams {
+            deadline,
+            partitions: BoundedBTreeSet(partitions.into_iter().collect()),
+        };
+        let payload = runtime::tx().storage_provider().compact_partitions(params);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            address = account_keypair.account_id().to_ss58check(),
+        )
+    )]
+    async fn move_partitions<Keypair>(
+        &self,
+        account_keypair: &Keypair,
+        orig_deadline: u64,
+        dest_deadline: u64,
+        partitions: BTreeSet<PartitionNumber>,
+        wait_for_finalization: bool,
+    ) -> Result<Option<SubmissionResult<PolkaStorageConfig>>, subxt::Error>
+    where
+        Keypair: subxt::tx::Signer<PolkaStorageConfig>,
+    {
+        let params = MovePartitionsParams {
+            orig_deadline,
+            dest_deadline,
+            partitions: BoundedBTreeSet(partitions.into_iter().collect()),
+        };
+        let payload = runtime::tx().storage_provider().move_partitions(params);
+
+        self.traced_submission(&payload, account_keypair, wait_for_finalization)
+            .await
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     async fn retrieve_storage_provider(
         &self,
         account_id: &AccountId32,
-    ) -> Result<Option<StorageProviderState<PeerId, Currency, BlockNumber>>, subxt::Error> {
+    ) -> Result<
+        Option<StorageProviderState<AccountId32, PeerId, Currency, BlockNumber>>,
+        subxt::Error,
+    > {
         let storage_provider = runtime::storage()
             .storage_provider()
             .storage_providers(account_id);
@@ -346,6 +946,26 @@ impl StorageProviderClientExt for crate::runtime::client::Client {
             .await
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn resolve_storage_provider_owner(
+        &self,
+        account_id: &AccountId32,
+    ) -> Result<Option<AccountId32>, subxt::Error> {
+        let storage = self.client.storage().at_latest().await?;
+
+        let storage_provider = runtime::storage()
+            .storage_provider()
+            .storage_providers(account_id);
+        if storage.fetch(&storage_provider).await?.is_some() {
+            return Ok(Some(account_id.clone()));
+        }
+
+        let owner = runtime::storage()
+            .storage_provider()
+            .storage_provider_accounts(account_id);
+        storage.fetch(&owner).await
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     async fn retrieve_registered_storage_providers(&self) -> Result<Vec<String>, subxt::Error> {
         let storage_providers = runtime::storage()
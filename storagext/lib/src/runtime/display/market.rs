@@ -104,6 +104,44 @@ impl std::fmt::Display for Event {
                     successful, unsuccessful
                 ))
             }
+            Event::DealPaymentSettled {
+                deal_id,
+                client,
+                provider,
+                amount,
+            } => f.write_fmt(format_args!(
+                "Deal Payment Settled: {{ deal_id: {}, amount: {}, provider_account: {}, client_account: {} }}",
+                deal_id,
+                amount,
+                provider,
+                client
+            )),
+            Event::DealCancelled {
+                deal_id,
+                client,
+                provider,
+                fee,
+            } => f.write_fmt(format_args!(
+                "Deal Cancelled: {{ deal_id: {}, fee: {}, provider_account: {}, client_account: {} }}",
+                deal_id,
+                fee,
+                provider,
+                client
+            )),
+            Event::DealExtended {
+                deal_id,
+                client,
+                provider,
+                end_block,
+                storage_price_per_block,
+            } => f.write_fmt(format_args!(
+                "Deal Extended: {{ deal_id: {}, end_block: {}, storage_price_per_block: {}, provider_account: {}, client_account: {} }}",
+                deal_id,
+                end_block,
+                storage_price_per_block,
+                provider,
+                client
+            )),
             Event::DealSlashed {
                 deal_id,
                 amount,
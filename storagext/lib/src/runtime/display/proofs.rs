@@ -3,12 +3,46 @@ use crate::runtime::proofs::Event;
 impl std::fmt::Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Event::PoRepVerifyingKeyChanged { .. } => {
-                f.write_fmt(format_args!("PoRep verifying key changed"))
-            }
-            Event::PoStVerifyingKeyChanged { .. } => {
-                f.write_fmt(format_args!("PoSt verifying key changed"))
-            }
+            Event::PoRepVerifyingKeyChanged {
+                seal_proof,
+                hash,
+                version,
+            } => f.write_fmt(format_args!(
+                "PoRep verifying key changed for {:?}: {{ hash: {}, version: {} }}",
+                seal_proof,
+                hex::encode(hash),
+                version
+            )),
+            Event::NiPoRepVerifyingKeyChanged {
+                seal_proof,
+                hash,
+                version,
+            } => f.write_fmt(format_args!(
+                "NI-PoRep verifying key changed for {:?}: {{ hash: {}, version: {} }}",
+                seal_proof,
+                hex::encode(hash),
+                version
+            )),
+            Event::PoStVerifyingKeyChanged {
+                post_type,
+                hash,
+                version,
+            } => f.write_fmt(format_args!(
+                "PoSt verifying key changed for {:?}: {{ hash: {}, version: {} }}",
+                post_type,
+                hex::encode(hash),
+                version
+            )),
+            Event::ReplicaUpdateVerifyingKeyChanged {
+                update_proof,
+                hash,
+                version,
+            } => f.write_fmt(format_args!(
+                "Replica update verifying key changed for {:?}: {{ hash: {}, version: {} }}",
+                update_proof,
+                hex::encode(hash),
+                version
+            )),
         }
     }
 }
@@ -1,5 +1,9 @@
 use crate::runtime::{
-    runtime_types::pallet_storage_provider::{fault, sector},
+    runtime_types::{
+        bounded_collections::bounded_vec::BoundedVec,
+        pallet_storage_provider::{fault, sector},
+        primitives::pallets::{StoragePower, StorageProviderPeerInfo},
+    },
     storage_provider::{events, Event},
 };
 
@@ -33,6 +37,25 @@ impl std::fmt::Display for fault::RecoveryDeclaration {
     }
 }
 
+impl<T> std::fmt::Display for sector::ExpirationExtension<T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Expiration Extension: {{ deadline: {}, partition: {}, sectors: [{}], new_expiration: {} }}",
+            self.deadline,
+            self.partition,
+            itertools::Itertools::intersperse(
+                self.sectors.0.iter().map(|sector| format!("{}", sector)),
+                ", ".to_string()
+            )
+            .collect::<String>(),
+            self.new_expiration,
+        ))
+    }
+}
+
 impl<T> std::fmt::Display for sector::SectorPreCommitInfo<T>
 where
     T: std::fmt::Display,
@@ -49,14 +72,52 @@ where
     }
 }
 
+/// Displays the multiaddrs as a comma separated list of strings.
+fn display_multiaddrs(multiaddrs: &[BoundedVec<u8>]) -> String {
+    itertools::Itertools::intersperse(
+        multiaddrs
+            .iter()
+            .map(|multiaddr| String::from_utf8_lossy(&multiaddr.0).into_owned()),
+        ", ".to_string(),
+    )
+    .collect::<String>()
+}
+
+impl std::fmt::Display for StorageProviderPeerInfo<BoundedVec<u8>> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Storage Provider Peer Info: {{ peer_id: {}, multiaddrs: [{}] }}",
+            bs58::encode(self.peer_id.0.as_slice()).into_string(),
+            display_multiaddrs(&self.multiaddrs.0),
+        ))
+    }
+}
+
+impl std::fmt::Display for StoragePower {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Storage Power: {{ raw_byte_power: {}, quality_adj_power: {} }}",
+            self.raw_byte_power, self.quality_adj_power,
+        ))
+    }
+}
+
 // This type is a generated specialization of a more generic type,
 // using this one is easier for Display rather than coping with ultra-generic bounds
 impl std::fmt::Display for events::storage_provider_registered::Info {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Storage Provider Info: {{ peer_id: {}, window_post_proof_type: {:?}, sector_size: {:?}, window_post_partition_sectors: {} }}",
+            "Storage Provider Info: {{ owner: {}, worker: {}, control_addresses: [{}], peer_id: {}, multiaddrs: [{}], window_post_proof_type: {:?}, sector_size: {:?}, window_post_partition_sectors: {} }}",
+            self.owner,
+            self.worker,
+            itertools::Itertools::intersperse(
+                self.control_addresses.0.iter().map(ToString::to_string),
+                ", ".to_string()
+            )
+            .collect::<String>(),
             // This matches the libp2p implementation without requiring such a big dependency
             bs58::encode(self.peer_id.0.as_slice()).into_string(),
+            display_multiaddrs(&self.multiaddrs.0),
             self.window_post_proof_type,
             self.sector_size,
             self.window_post_partition_sectors,
@@ -75,6 +136,10 @@ impl std::fmt::Display for Event {
                 "Storage Provider Registered: {{ owner: {}, info: {}, proving_period_start: {} }}",
                 owner, info, proving_period_start,
             )),
+            Event::StorageProviderDeregistered { owner, released } => f.write_fmt(format_args!(
+                "Storage Provider Deregistered: {{ owner: {}, released: {} }}",
+                owner, released,
+            )),
             Event::SectorsPreCommitted {
                 block,
                 owner,
@@ -143,6 +208,14 @@ impl std::fmt::Display for Event {
                 )
                 .collect::<String>()
             )),
+            Event::FaultsPenalized {
+                owner,
+                continued_fault_fee,
+                undeclared_fault_penalty,
+            } => f.write_fmt(format_args!(
+                "Faults Penalized: {{ owner: {}, continued_fault_fee: {}, undeclared_fault_penalty: {} }}",
+                owner, continued_fault_fee, undeclared_fault_penalty,
+            )),
             Event::SectorsTerminated {
                 owner,
                 terminations,
@@ -158,6 +231,91 @@ impl std::fmt::Display for Event {
                 )
                 .collect::<String>()
             )),
+            Event::SectorsExpirationExtended { owner, extensions } => f.write_fmt(format_args!(
+                "Sectors Expiration Extended: {{ owner: {}, extensions: [{}] }}",
+                owner,
+                itertools::Itertools::intersperse(
+                    extensions
+                        .0
+                        .iter()
+                        .map(|extension| format!("{}", extension)),
+                    ", ".to_string()
+                )
+                .collect::<String>()
+            )),
+            Event::ReplicasUpdated { owner, sectors } => f.write_fmt(format_args!(
+                "Replicas Updated: {{ owner: {}, sectors: [{}] }}",
+                owner,
+                itertools::Itertools::intersperse(
+                    sectors.0.iter().map(ToString::to_string),
+                    ", ".to_string()
+                )
+                .collect::<String>(),
+            )),
+            Event::PartitionsCompacted {
+                owner,
+                deadline,
+                partitions,
+            } => f.write_fmt(format_args!(
+                "Partitions Compacted: {{ owner: {}, deadline: {}, partitions: [{}] }}",
+                owner,
+                deadline,
+                itertools::Itertools::intersperse(
+                    partitions.0.iter().map(ToString::to_string),
+                    ", ".to_string()
+                )
+                .collect::<String>(),
+            )),
+            Event::PartitionsMoved {
+                owner,
+                orig_deadline,
+                dest_deadline,
+                partitions,
+            } => f.write_fmt(format_args!(
+                "Partitions Moved: {{ owner: {}, orig_deadline: {}, dest_deadline: {}, partitions: [{}] }}",
+                owner,
+                orig_deadline,
+                dest_deadline,
+                itertools::Itertools::intersperse(
+                    partitions.0.iter().map(ToString::to_string),
+                    ", ".to_string()
+                )
+                .collect::<String>(),
+            )),
+            Event::ControlAddressesChanged {
+                owner,
+                control_addresses,
+            } => f.write_fmt(format_args!(
+                "Control Addresses Changed: {{ owner: {}, control_addresses: [{}] }}",
+                owner,
+                itertools::Itertools::intersperse(
+                    control_addresses.0.iter().map(ToString::to_string),
+                    ", ".to_string()
+                )
+                .collect::<String>()
+            )),
+            Event::WorkerAddressChangeScheduled {
+                owner,
+                new_worker,
+                effective_at,
+            } => f.write_fmt(format_args!(
+                "Worker Address Change Scheduled: {{ owner: {}, new_worker: {}, effective_at: {} }}",
+                owner, new_worker, effective_at,
+            )),
+            Event::WorkerAddressChanged { owner, worker } => f.write_fmt(format_args!(
+                "Worker Address Changed: {{ owner: {}, worker: {} }}",
+                owner, worker,
+            )),
+            Event::PeerIdChanged { owner, peer_id } => f.write_fmt(format_args!(
+                "Peer ID Changed: {{ owner: {}, peer_id: {} }}",
+                owner,
+                bs58::encode(peer_id.0.as_slice()).into_string(),
+            )),
+            Event::MultiaddrsChanged { owner, multiaddrs } => f.write_fmt(format_args!(
+                "Multiaddrs Changed: {{ owner: {}, multiaddrs: [{}] }}",
+                owner,
+                display_multiaddrs(&multiaddrs.0),
+            )),
         }
     }
 }
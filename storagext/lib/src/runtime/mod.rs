@@ -24,6 +24,10 @@ pub mod display;
         path = "primitives::proofs::RegisteredPoStProof",
         with = "::primitives::proofs::RegisteredPoStProof",
     ),
+    substitute_type(
+        path = "primitives::proofs::RegisteredUpdateProof",
+        with = "::primitives::proofs::RegisteredUpdateProof",
+    ),
     substitute_type(
         path = "primitives::sector::SectorSize",
         with = "::primitives::sector::SectorSize",
@@ -88,6 +92,34 @@ pub mod display;
         path = "pallet_storage_provider::storage_provider::StorageProviderInfo",
         derive = "::serde::Serialize"
     ),
+    derive_for_type(
+        path = "pallet_storage_provider::storage_provider::WorkerKeyChange",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::StorageProviderPeerInfo",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::StoragePower",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::SectorInfo",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::SectorFaults",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::PreCommittedSector",
+        derive = "::serde::Serialize"
+    ),
+    derive_for_type(
+        path = "primitives::pallets::LockedFunds",
+        derive = "::serde::Serialize"
+    ),
     derive_for_type(
         path = "pallet_storage_provider::sector::SectorPreCommitInfo",
         derive = "::serde::Serialize"
@@ -112,6 +144,10 @@ pub mod display;
         path = "pallet_storage_provider::sector::TerminationDeclaration",
         derive = "::serde::Serialize"
     ),
+    derive_for_type(
+        path = "pallet_storage_provider::sector::ExpirationExtension",
+        derive = "::serde::Serialize"
+    ),
     derive_for_type(
         path = "pallet_market::pallet::ActiveDealState",
         derive = "::serde::Serialize"
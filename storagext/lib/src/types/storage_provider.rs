@@ -29,7 +29,12 @@ use crate::{
                     SubmitWindowedPoStParams as RuntimeSubmitWindowedPoStParams,
                 },
                 sector::{
+                    ExpirationExtension as RuntimeExpirationExtension,
+                    ExtendSectorExpirationParams as RuntimeExtendSectorExpirationParams,
+                    ProveCommitBatch as RuntimeProveCommitBatch,
+                    ProveCommitNiSector as RuntimeProveCommitNiSector,
                     ProveCommitSector as RuntimeProveCommitSector,
+                    ReplicaUpdate as RuntimeReplicaUpdate,
                     SectorPreCommitInfo as RuntimeSectorPreCommitInfo,
                     TerminateSectorsParams as RuntimeTerminateSectorsParams,
                     TerminationDeclaration as RuntimeTerminationDeclaration,
@@ -150,6 +155,81 @@ impl From<ProveCommitSector> for RuntimeProveCommitSector {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ProveCommitNiSector {
+    /// Sector sealed with a non-interactive seal proof, it isn't pre-committed.
+    #[serde(flatten)]
+    pub sector: SectorPreCommitInfo,
+    /// Raw proof bytes serialized with [`parity_scale_codec::Encode::encode`]
+    /// and using [`bls12_381::Bls12`] as a curve.
+    #[serde(with = "hex")]
+    pub proof: Vec<u8>,
+}
+
+impl From<ProveCommitNiSector> for RuntimeProveCommitNiSector<BlockNumber> {
+    fn from(value: ProveCommitNiSector) -> Self {
+        Self {
+            sector: value.sector.into(),
+            proof: value.proof.into_bounded_byte_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ProveCommitBatch {
+    /// Numbers of the sectors that have been previously pre-committed, in the same order as their proofs in the batch.
+    pub sector_numbers: Vec<SectorNumber>,
+    /// Proofs of the sectors, serialized with [`parity_scale_codec::Encode::encode`]
+    /// and concatenated in the order of `sector_numbers`.
+    #[serde(with = "hex")]
+    pub batch_proof: Vec<u8>,
+}
+
+impl From<ProveCommitBatch> for RuntimeProveCommitBatch {
+    fn from(value: ProveCommitBatch) -> Self {
+        Self {
+            sector_numbers: bounded_vec::BoundedVec(value.sector_numbers),
+            batch_proof: value.batch_proof.into_bounded_byte_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ReplicaUpdate {
+    /// Number of a committed capacity sector that has been previously proven.
+    pub sector_number: SectorNumber,
+    /// Deadline the sector is assigned to.
+    pub deadline: u64,
+    /// Partition the sector is assigned to.
+    pub partition: PartitionNumber,
+    /// CommR of the updated replica.
+    #[serde(deserialize_with = "crate::types::deserialize_string_to_cid")]
+    pub new_sealed_cid: cid::Cid,
+    /// CommD of the data in the updated replica.
+    #[serde(deserialize_with = "crate::types::deserialize_string_to_cid")]
+    pub new_unsealed_cid: cid::Cid,
+    /// Deals to be activated, their data must be in the updated replica.
+    pub deal_ids: Vec<DealId>,
+    /// Raw proof bytes serialized with [`parity_scale_codec::Encode::encode`]
+    /// and using [`bls12_381::Bls12`] as a curve.
+    #[serde(with = "hex")]
+    pub proof: Vec<u8>,
+}
+
+impl From<ReplicaUpdate> for RuntimeReplicaUpdate {
+    fn from(value: ReplicaUpdate) -> Self {
+        Self {
+            sector_number: value.sector_number,
+            deadline: value.deadline,
+            partition: value.partition,
+            new_sealed_cid: value.new_sealed_cid.into_bounded_byte_vec(),
+            new_unsealed_cid: value.new_unsealed_cid.into_bounded_byte_vec(),
+            deal_ids: bounded_vec::BoundedVec(value.deal_ids),
+            proof: value.proof.into_bounded_byte_vec(),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, serde::Deserialize)]
 pub struct FaultDeclaration {
     pub deadline: u64,
@@ -313,6 +393,34 @@ impl From<Vec<TerminationDeclaration>> for RuntimeTerminateSectorsParams {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ExpirationExtension {
+    pub deadline: u64,
+    pub partition: u32,
+    pub sectors: BTreeSet<SectorNumber>,
+    pub new_expiration: BlockNumber,
+}
+
+impl From<ExpirationExtension> for RuntimeExpirationExtension<BlockNumber> {
+    fn from(value: ExpirationExtension) -> Self {
+        Self {
+            deadline: value.deadline,
+            partition: value.partition,
+            // Converts from BTreeSet -> Vec -> BoundedBTreeSet because subxt...
+            sectors: bounded_btree_set::BoundedBTreeSet(value.sectors.into_iter().collect()),
+            new_expiration: value.new_expiration,
+        }
+    }
+}
+
+impl From<Vec<ExpirationExtension>> for RuntimeExtendSectorExpirationParams<BlockNumber> {
+    fn from(value: Vec<ExpirationExtension>) -> Self {
+        Self {
+            extensions: bounded_vec::BoundedVec(value.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
 pub struct PartitionState {
     pub sectors: BTreeSet<SectorNumber>,
 }
@@ -348,8 +456,8 @@ mod tests {
         types::{
             market::DealProposal,
             storage_provider::{
-                FaultDeclaration, PoStProof, RecoveryDeclaration, SubmitWindowedPoStParams,
-                TerminationDeclaration,
+                ExpirationExtension, FaultDeclaration, PoStProof, RecoveryDeclaration,
+                SubmitWindowedPoStParams, TerminationDeclaration,
             },
         },
         PolkaStorageConfig,
@@ -532,4 +640,26 @@ mod tests {
             }]
         )
     }
+
+    #[test]
+    fn ensure_serde_for_expiration_extension() {
+        let extension = serde_json::from_str::<Vec<ExpirationExtension>>(
+            r#"[{
+                "deadline": 69,
+                "partition": 420,
+                "sectors": [1, 2],
+                "new_expiration": 1000
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            extension,
+            vec![ExpirationExtension {
+                deadline: 69,
+                partition: 420,
+                sectors: BTreeSet::from([1.into(), 2.into()]),
+                new_expiration: 1000,
+            }]
+        )
+    }
 }